@@ -381,8 +381,6 @@ fn endpoint_c_10(s: EndpointC10, loops: i32) -> Result<(), Box<dyn Error>> {
     }
 }
 
-///
-
 fn endpoint_s_init(s: EndpointS0) -> Result<(), Box<dyn Error>> {
     endpoint_s_0(s, 100)
 }
@@ -127,6 +127,7 @@ fn client(s: EndpointCFull) -> Result<(), Box<dyn Error>> {
     client_recurs(s, xs, 1)
 }
 
+#[allow(clippy::only_used_in_recursion)]
 fn client_recurs(s: EndpointCRecurs, mut xs: Vec<i32>, index: i32) -> Result<(), Box<dyn Error>> {
     match xs.pop() {
         Option::Some(_) => {
@@ -0,0 +1,174 @@
+use crossbeam_channel::bounded;
+
+use criterion::{black_box, Criterion};
+
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::shm::channel::ShmChannel;
+use mpstthree::transport::shm::{close::close_shm, recv::recv_shm, send::send_shm};
+use mpstthree::transport::unix::{close::close_unix, recv::recv_unix, send::send_unix};
+use mpstthree::transport::wire::frame::{read_frame, write_frame};
+use mpstthree::{choose_shm, choose_unix, offer_shm, offer_unix};
+
+use std::error::Error;
+use std::os::unix::net::UnixStream;
+use std::thread::spawn;
+
+// The ping pong of ping_pong_1, between B and a process A
+// reached over a transport, the session of A staying
+// with B as with fork_shm and fork_unix.
+
+type Data = ((), [u8; 128]);
+
+// Types
+// A
+enum BinaryA {
+    More(Recv<Data, Send<Data, RecursA>>),
+    Done(End),
+}
+type RecursA = Recv<([u8; 128], BinaryA), End>;
+
+// B
+type RecursB = <RecursA as Session>::Dual;
+
+/////////////////////////
+// Shared memory
+
+fn shm_a_to_b(s: RecursA, channel: ShmChannel) -> Result<(), Box<dyn Error>> {
+    offer_shm!(s, {
+        BinaryA::Done(s) => {
+            close_shm(s, channel, false)
+        },
+        BinaryA::More(s) => {
+            let (x, s, data, _r, channel) = recv_shm(s, channel, false)?;
+            let (s, channel) = send_shm(x, &data, s, channel, false)?;
+            shm_a_to_b(s, channel)
+        },
+    })
+}
+
+fn shm_b_to_a(s: RecursB, channel: ShmChannel, index: i64) -> Result<(), Box<dyn Error>> {
+    if index == 0 {
+        let s = choose_shm!(BinaryA::Done, s, [0_u8; 128]);
+        return close_shm(s, channel, true);
+    }
+
+    let s = choose_shm!(BinaryA::More, s, [0_u8; 128]);
+    let (s, channel) = send_shm((), &[0_u8; 128], s, channel, true)?;
+    let ((), s, _data, _r, channel) = recv_shm(s, channel, true)?;
+    shm_b_to_a(s, channel, index - 1)
+}
+
+fn all_shm() {
+    let (channel_a, channel_b) = ShmChannel::pair().unwrap();
+
+    // The process of A echoes the data
+    let process_a = spawn(move || {
+        let mut data = [0_u8; 128];
+        while let Ok(size) = channel_a.recv(&mut data) {
+            channel_a.send(&data[..size]).unwrap();
+        }
+    });
+
+    let (s_a, s_b) = RecursA::new();
+    let copy_channel = channel_b.try_clone().unwrap();
+    let thread_a = spawn(move || shm_a_to_b(black_box(s_a), copy_channel).unwrap());
+
+    shm_b_to_a(s_b, channel_b, LOOPS).unwrap();
+
+    thread_a.join().unwrap();
+    process_a.join().unwrap();
+}
+
+/////////////////////////
+// Unix domain socket
+
+fn unix_a_to_b(s: RecursA, stream: UnixStream) -> Result<(), Box<dyn Error>> {
+    offer_unix!(s, {
+        BinaryA::Done(s) => {
+            close_unix(s, stream, false)
+        },
+        BinaryA::More(s) => {
+            let (x, s, data, _r, stream) = recv_unix(s, stream, false)?;
+            let (s, stream) = send_unix(x, &data, s, stream, false)?;
+            unix_a_to_b(s, stream)
+        },
+    })
+}
+
+fn unix_b_to_a(s: RecursB, stream: UnixStream, index: i64) -> Result<(), Box<dyn Error>> {
+    if index == 0 {
+        let s = choose_unix!(BinaryA::Done, s, [0_u8; 128]);
+        return close_unix(s, stream, true);
+    }
+
+    let s = choose_unix!(BinaryA::More, s, [0_u8; 128]);
+    let (s, stream) = send_unix((), &[0_u8; 128], s, stream, true)?;
+    let ((), s, _data, _r, stream) = recv_unix(s, stream, true)?;
+    unix_b_to_a(s, stream, index - 1)
+}
+
+fn all_unix() {
+    let (mut stream_a, stream_b) = UnixStream::pair().unwrap();
+
+    // The process of A echoes the frames
+    let process_a = spawn(move || {
+        while let Ok(frame) = read_frame(&mut stream_a) {
+            write_frame(&mut stream_a, &frame).unwrap();
+        }
+    });
+
+    let (s_a, s_b) = RecursA::new();
+    let copy_stream = stream_b.try_clone().unwrap();
+    let thread_a = spawn(move || unix_a_to_b(black_box(s_a), copy_stream).unwrap());
+
+    unix_b_to_a(s_b, stream_b, LOOPS).unwrap();
+
+    thread_a.join().unwrap();
+    process_a.join().unwrap();
+}
+
+/////////////////////////
+// Crossbeam, without any transport
+
+fn all_crossbeam() {
+    let (sender_b, receiver_a) = bounded::<[u8; 128]>(1);
+    let (sender_a, receiver_b) = bounded::<[u8; 128]>(1);
+
+    let thread_a = spawn(move || {
+        while let Ok(data) = receiver_a.recv() {
+            sender_a.send(data).unwrap();
+        }
+    });
+
+    for _ in 0..LOOPS {
+        sender_b.send(black_box([0_u8; 128])).unwrap();
+        receiver_b.recv().unwrap();
+    }
+    drop(sender_b);
+
+    thread_a.join().unwrap();
+}
+
+/////////////////////////
+
+static LOOPS: i64 = 100;
+
+pub fn ping_pong_protocol_shm(c: &mut Criterion) {
+    c.bench_function(
+        &format!("ping pong protocol shared memory {}", LOOPS),
+        |b| b.iter(all_shm),
+    );
+}
+
+pub fn ping_pong_protocol_unix(c: &mut Criterion) {
+    c.bench_function(&format!("ping pong protocol Unix socket {}", LOOPS), |b| {
+        b.iter(all_unix)
+    });
+}
+
+pub fn ping_pong_protocol_crossbeam_data(c: &mut Criterion) {
+    c.bench_function(
+        &format!("ping pong protocol crossbeam data {}", LOOPS),
+        |b| b.iter(all_crossbeam),
+    );
+}
@@ -0,0 +1,27 @@
+#![allow(clippy::type_complexity)]
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+// Only the ping pong of ping_pong_1, to compare with the transports
+mod ping_pong_all {
+    pub mod ping_pong_1;
+    pub mod ping_pong_shm_1;
+}
+
+criterion_main! {
+    ping_pong_shm
+}
+
+criterion_group! {
+    name = ping_pong_shm;
+    config = Criterion::default().significance_level(0.1).sample_size(1000);
+    targets =
+        // Basic
+        ping_pong_all::ping_pong_1::ping_pong_protocol_mpst,
+        ping_pong_all::ping_pong_1::ping_pong_protocol_binary,
+        ping_pong_all::ping_pong_1::ping_pong_protocol_crossbeam,
+        // Transports
+        ping_pong_all::ping_pong_shm_1::ping_pong_protocol_shm,
+        ping_pong_all::ping_pong_shm_1::ping_pong_protocol_unix,
+        ping_pong_all::ping_pong_shm_1::ping_pong_protocol_crossbeam_data,
+}
@@ -1,6 +1,7 @@
 #![allow(clippy::type_complexity)]
 
 use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::checking::temporal::check_property;
 use mpstthree::role::broadcast::RoleBroadcast;
 use mpstthree::role::end::RoleEnd;
 use mpstthree::{bundle_impl_with_enum_and_cancel, checker_concat};
@@ -173,7 +174,7 @@ fn endpoint_s(s: EndpointS) -> Result<(), Box<dyn Error>> {
 
 // Check for bottom-up approach
 fn checking() {
-    let _ = checker_concat!(
+    let (graphs, _) = checker_concat!(
         "o_auth_checking",
         EndpointA,
         EndpointC,
@@ -192,6 +193,13 @@ fn checking() {
     )
     .unwrap();
 
+    // Every `Login` is eventually followed by `Success` or `Fail`
+    assert!(
+        check_property(&graphs, "AG(Login -> AF(Success | Fail))")
+            .unwrap()
+            .holds
+    );
+
     assert_eq!(
         "CSA: \u{1b}[92mTrue\n\
         \u{1b}[0mBasic: \u{1b}[92mTrue\n\
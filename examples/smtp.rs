@@ -489,8 +489,6 @@ fn endpoint_c_10(s: EndpointC10) -> Result<(), Box<dyn Error>> {
     }
 }
 
-///
-
 fn endpoint_s_0(s: EndpointS0) -> Result<(), Box<dyn Error>> {
     let s = send_mpst_s_to_c((), s);
 
@@ -725,8 +723,6 @@ fn endpoint_s_10(s: EndpointS10) -> Result<(), Box<dyn Error>> {
     })
 }
 
-///
-
 fn main() {
     let (thread_c, thread_s) = fork_mpst(endpoint_c_0, endpoint_s_0);
 
@@ -1 +1,178 @@
-fn main() {}
+//! The `mpst` command-line tool, for working on the Scribble protocols
+//! without any external tool.
+//!
+//! ```text
+//! mpst project <file.scr> --role <role>
+//! mpst generate <file.scr> [--output <file.rs>]
+//! mpst check <file.scr>
+//! mpst graph <file.scr> [--format dot|graphml|json]... [--output <directory>]
+//! mpst rendezvous <address>
+//! ```
+//!
+//! `rendezvous` is available only if `mpst` is built with
+//! the `"transport"` feature or the `"transport_tcp"` feature.
+
+use mpstthree::checking::cancel::check_cancellation;
+use mpstthree::checking::deadlock::check_interleaved;
+use mpstthree::checking::export::{export, Format};
+use mpstthree::checking::product::synchronous_product;
+use mpstthree::scribble::Protocol;
+#[cfg(feature = "transport_tcp")]
+use mpstthree::transport::tcp::rendezvous::Coordinator;
+
+use std::error::Error;
+use std::fs::write;
+#[cfg(feature = "transport_tcp")]
+use std::io::Write;
+use std::process::exit;
+
+const USAGE: &str = "Usage:
+    mpst project <file.scr> --role <role>
+        Print the local type of <role>
+    mpst generate <file.scr> [--output <file.rs>]
+        Print, or write in <file.rs>, the Rust types of the protocol
+    mpst check <file.scr>
+        Check that the protocol is deadlock-free, with and without cancellation
+    mpst graph <file.scr> [--format dot|graphml|json]... [--output <directory>]
+        Write the CFSMs and the global graph of the protocol in <directory>,
+        by default `graphs`, in each format, by default dot
+    mpst rendezvous <address>
+        Listen on <address> for the roles joining their sessions,
+        and send each of them the addresses of the other roles";
+
+// The options following the file, such as `--role A`.
+fn options(args: &[String], allowed: &[&str]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let mut result = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(option) = args.next() {
+        if !allowed.contains(&option.as_str()) {
+            return Err(format!("Unexpected argument {}", option).into());
+        }
+        match args.next() {
+            Some(value) => result.push((option.to_string(), value.to_string())),
+            None => return Err(format!("Missing value for {}", option).into()),
+        }
+    }
+
+    Ok(result)
+}
+
+fn option<'a>(options: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    options
+        .iter()
+        .rev()
+        .find(|(option, _)| option == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// Run a command, returning whether the protocol is correct.
+fn run(args: &[String]) -> Result<bool, Box<dyn Error>> {
+    let (command, file, rest) = match args {
+        [command, file, rest @ ..] => (command.as_str(), file, rest),
+        _ => return Err(USAGE.into()),
+    };
+
+    match command {
+        "project" => {
+            let options = options(rest, &["--role"])?;
+            let role = option(&options, "--role").ok_or("Missing --role")?;
+            print!("{}", Protocol::load(file)?.project(role)?);
+        }
+        "generate" => {
+            let options = options(rest, &["--output"])?;
+            let code = Protocol::load(file)?.generate()?;
+            match option(&options, "--output") {
+                Some(output) => write(output, code)?,
+                None => print!("{}", code),
+            }
+        }
+        "check" => {
+            options(rest, &[])?;
+            let protocol = Protocol::load(file)?;
+            let graphs = protocol.graphs();
+            let global = synchronous_product(&graphs);
+
+            println!(
+                "Protocol {}: {} roles, {} global states, {} global transitions",
+                protocol.name(),
+                protocol.roles().len(),
+                global.node_count(),
+                global.edge_count()
+            );
+
+            let mut correct = true;
+
+            match check_interleaved(&[(protocol.name(), &graphs)], &[], 1)? {
+                None => println!("Deadlock-free: yes"),
+                Some(deadlock) => {
+                    println!("Deadlock-free: no\n{}", deadlock);
+                    correct = false;
+                }
+            }
+
+            match check_cancellation(&graphs) {
+                None => println!("Deadlock-free with cancellation: yes"),
+                Some(deadlock) => {
+                    println!("Deadlock-free with cancellation: no\n{}", deadlock);
+                    correct = false;
+                }
+            }
+
+            return Ok(correct);
+        }
+        "graph" => {
+            let options = options(rest, &["--format", "--output"])?;
+            let mut formats = Vec::new();
+            for (option, value) in options.iter() {
+                if option == "--format" {
+                    formats.push(match value.as_str() {
+                        "dot" => Format::Dot,
+                        "graphml" => Format::GraphML,
+                        "json" => Format::Json,
+                        _ => return Err(format!("Unknown format {}", value).into()),
+                    });
+                }
+            }
+            if formats.is_empty() {
+                formats.push(Format::Dot);
+            }
+
+            let protocol = Protocol::load(file)?;
+            let output = option(&options, "--output").unwrap_or("graphs");
+            for path in export(protocol.name(), output, &protocol.graphs(), &formats)? {
+                println!("{}", path.display());
+            }
+        }
+        #[cfg(feature = "transport_tcp")]
+        "rendezvous" => {
+            options(rest, &[])?;
+            let coordinator = Coordinator::bind(file)?;
+            println!("Listening on {}", coordinator.local_addr()?);
+            std::io::stdout().flush()?;
+            coordinator.serve()?;
+        }
+        #[cfg(not(feature = "transport_tcp"))]
+        "rendezvous" => {
+            return Err(
+                "mpst must be built with the transport_tcp feature to run rendezvous".into(),
+            )
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(true)
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match run(&args) {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(error) => {
+            eprintln!("{}", error);
+            exit(2);
+        }
+    }
+}
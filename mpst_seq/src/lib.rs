@@ -42,7 +42,13 @@ pub use mpst_seq_proc::fork_mpst_multi_solo;
 //////////////////////////////////////
 
 pub use mpst_seq_proc::choose_mpst_multi_http_to_all;
+pub use mpst_seq_proc::create_recv_http_payload_request_session;
+pub use mpst_seq_proc::create_recv_http_payload_session;
+pub use mpst_seq_proc::create_recv_http_server_session;
 pub use mpst_seq_proc::create_recv_http_session;
+pub use mpst_seq_proc::create_send_http_payload_response_session;
+pub use mpst_seq_proc::create_send_http_payload_session;
+pub use mpst_seq_proc::create_send_http_server_session;
 pub use mpst_seq_proc::create_send_http_session;
 
 //////////////////////////////////////
@@ -59,6 +65,15 @@ pub use mpst_seq_proc::baking_with_enum_and_cancel;
 
 //////////////////////////////////////
 
+pub use mpst_seq_proc::stub_bundle;
+
+//////////////////////////////////////
+
+pub use mpst_seq_proc::bundle_family;
+pub use mpst_seq_proc::template;
+
+//////////////////////////////////////
+
 #[proc_macro_hack]
 pub use mpst_seq_proc::e_recv_aux_simple;
 
@@ -160,6 +175,24 @@ pub use mpst_seq_proc::e_create_send_http_session;
 #[proc_macro_hack]
 pub use mpst_seq_proc::e_create_recv_http_session;
 
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_create_send_http_server_session;
+
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_create_recv_http_server_session;
+
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_create_send_http_payload_session;
+
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_create_recv_http_payload_session;
+
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_create_send_http_payload_response_session;
+
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_create_recv_http_payload_request_session;
+
 //////////////////////////////////////
 
 #[proc_macro_hack]
@@ -181,3 +214,16 @@ pub use mpst_seq_proc::e_baking_with_enum_and_cancel;
 
 #[proc_macro_hack]
 pub use mpst_seq_proc::e_baking_interleaved_with_enum_and_cancel;
+
+//////////////////////////////////////
+
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_stub_bundle;
+
+//////////////////////////////////////
+
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_bundle_family;
+
+#[proc_macro_hack]
+pub use mpst_seq_proc::e_template;
@@ -9,6 +9,7 @@ type VecOfTuple = Vec<(u64, u64, u64)>;
 #[derive(Debug)]
 pub struct BakingWithEnumAndCancel {
     meshedchannels_name: Ident,
+    recorded: bool,
     all_roles: Vec<TokenStream>,
     number_roles: u64,
 }
@@ -22,6 +23,7 @@ fn expand_token_stream(input: ParseStream) -> Result<Vec<TokenStream>> {
     for tt in token_stream.into_iter() {
         let elt = match tt {
             TokenTree::Group(g) => Some(g.stream()),
+            TokenTree::Ident(i) => Some(TokenTree::Ident(i).into()),
             _ => None,
         };
         if let Some(elt_tt) = elt {
@@ -34,7 +36,7 @@ fn expand_token_stream(input: ParseStream) -> Result<Vec<TokenStream>> {
 
 impl Parse for BakingWithEnumAndCancel {
     fn parse(input: ParseStream) -> Result<Self> {
-        let meshedchannels_name = Ident::parse(input)?;
+        let (meshedchannels_name, recorded) = super::parse_name(input)?;
         <Token![,]>::parse(input)?;
         let all_roles = expand_token_stream(<&syn::parse::ParseBuffer>::clone(&input))?;
 
@@ -42,6 +44,7 @@ impl Parse for BakingWithEnumAndCancel {
 
         Ok(BakingWithEnumAndCancel {
             meshedchannels_name,
+            recorded,
             all_roles,
             number_roles,
         })
@@ -248,8 +251,11 @@ impl BakingWithEnumAndCancel {
 
         let new_session = Ident::new(&format!("session{}", index), Span::call_site());
 
+        let payload_bound = super::payload_bound(self.recorded);
+        let send_hook = super::send_hook(self.recorded);
+
         quote! {
-            impl<#( #session_types_struct )* R: mpstthree::role::Role, T: std::marker::Send>
+            impl<#( #session_types_struct )* R: mpstthree::role::Role, T: #payload_bound>
                 #meshedchannels_name<
                     #( #send_sessions )*
                     #receiver_ident<R>,
@@ -264,6 +270,12 @@ impl BakingWithEnumAndCancel {
                     >,
                     Box<dyn std::error::Error>
                 > {
+                    let _span = #send_hook(
+                        stringify!(#meshedchannels_name),
+                        stringify!(#sender_ident),
+                        stringify!(#receiver_ident),
+                        &payload,
+                    );
                     let new_session = mpstthree::binary::send::send_canceled(payload, self.#new_session)?;
                     let new_stack = self.stack.continuation();
                     Ok(
@@ -278,6 +290,103 @@ impl BakingWithEnumAndCancel {
         }
     }
 
+    /// Expand send to all methods
+    fn expand_send_to_all(
+        &self,
+        all_roles: Vec<TokenStream>,
+        sender: u64,
+        session_types: Vec<Ident>,
+        session_types_struct: Vec<TokenStream>,
+    ) -> TokenStream {
+        let meshedchannels_name = self.meshedchannels_name.clone();
+        let send_hook = super::send_hook(self.recorded);
+
+        let sender_ident = if let Some(elt) = all_roles.get(usize::try_from(sender - 1).unwrap()) {
+            Ident::new(&format!("Role{}", elt), Span::call_site())
+        } else {
+            panic!("Not enough arguments for sender_ident in expand_send_to_all")
+        };
+
+        let send_sessions: Vec<TokenStream> = (1..self.number_roles)
+            .map(|k| {
+                let temp_type = Ident::new(&format!("S{}", k), Span::call_site());
+                quote! { mpstthree::binary::struct_trait::send::Send<T, #temp_type > , }
+            })
+            .collect();
+
+        let new_sessions: Vec<TokenStream> = (1..self.number_roles)
+            .map(|k| {
+                let receiver = if k >= sender { k + 1 } else { k };
+
+                let receiver_ident =
+                    if let Some(elt) = all_roles.get(usize::try_from(receiver - 1).unwrap()) {
+                        Ident::new(&format!("Role{}", elt), Span::call_site())
+                    } else {
+                        panic!("Not enough arguments for receiver_ident in expand_send_to_all")
+                    };
+
+                let temp_session = Ident::new(&format!("session{}", k), Span::call_site());
+
+                // The last receiver gets the payload itself
+                let temp_payload = if k == self.number_roles - 1 {
+                    quote! { payload }
+                } else {
+                    quote! { payload.clone() }
+                };
+
+                quote! {
+                    let #temp_session = {
+                        let _span = #send_hook(
+                            stringify!(#meshedchannels_name),
+                            stringify!(#sender_ident),
+                            stringify!(#receiver_ident),
+                            &payload,
+                        );
+                        mpstthree::binary::send::send_canceled(#temp_payload, self.#temp_session)?
+                    };
+                }
+            })
+            .collect();
+
+        let fields: Vec<TokenStream> = (1..self.number_roles)
+            .map(|k| {
+                let temp_session = Ident::new(&format!("session{}", k), Span::call_site());
+                quote! { #temp_session , }
+            })
+            .collect();
+
+        let payload_bound = super::payload_bound(self.recorded);
+
+        quote! {
+            impl<#( #session_types_struct )* R: mpstthree::role::Role, T: Clone + #payload_bound>
+                #meshedchannels_name<
+                    #( #send_sessions )*
+                    mpstthree::role::to_all::RoleToAll<R>,
+                    #sender_ident<mpstthree::role::end::RoleEnd>
+                >
+            {
+                pub fn send_to_all(self, payload: T) -> Result<
+                    #meshedchannels_name<
+                        #( #session_types , )*
+                        R,
+                        #sender_ident<mpstthree::role::end::RoleEnd>
+                    >,
+                    Box<dyn std::error::Error>
+                > {
+                    #( #new_sessions )*
+                    let new_stack = self.stack.continuation();
+                    Ok(
+                        #meshedchannels_name {
+                            #( #fields )*
+                            stack: new_stack,
+                            name: self.name,
+                        }
+                    )
+                }
+            }
+        }
+    }
+
     /// Expand receive methods
     fn expand_recv(
         &self,
@@ -355,6 +464,12 @@ impl BakingWithEnumAndCancel {
                     >),
                     Box<dyn std::error::Error>
                 > {
+                    let _span = mpstthree::instrument::recv(
+                        stringify!(#meshedchannels_name),
+                        stringify!(#receiver_ident),
+                        stringify!(#sender_ident),
+                        std::any::type_name::<T>(),
+                    );
                     let (v, new_session) = mpstthree::binary::recv::recv(self.#new_session)?;
                     let new_stack = self.stack.continuation();
                     Ok((
@@ -560,6 +675,11 @@ impl BakingWithEnumAndCancel {
                     ) -> Result<U, Box<dyn std::error::Error + 'a>>,
                 {
                     let (e, s) = self.recv_from_all()?;
+                    let _span = mpstthree::instrument::offer(
+                        stringify!(#meshedchannels_name),
+                        stringify!(#receiver_ident),
+                        if e.is_left() { "Left" } else { "Right" },
+                    );
                     mpstthree::binary::cancel::cancel(s);
                     e.either(f, g)
                 }
@@ -1049,6 +1169,12 @@ impl BakingWithEnumAndCancel {
                         #new_stacks_receivers_left
                     )*
 
+                    let _span = mpstthree::instrument::choose(
+                        stringify!(#meshedchannels_name),
+                        stringify!(#sender_ident),
+                        "Left",
+                    );
+
                     let (#new_stack_sender, _) = <#new_stack_sender_left as mpstthree::role::Role>::new();
 
                     #(
@@ -1099,6 +1225,12 @@ impl BakingWithEnumAndCancel {
                         #new_stacks_receivers_right
                     )*
 
+                    let _span = mpstthree::instrument::choose(
+                        stringify!(#meshedchannels_name),
+                        stringify!(#sender_ident),
+                        "Right",
+                    );
+
                     let (#new_stack_sender, _) = <#new_stack_sender_right as mpstthree::role::Role>::new();
 
                     #(
@@ -1181,6 +1313,10 @@ impl BakingWithEnumAndCancel {
                 >
             {
                 pub fn close(self) -> Result<(), Box<dyn std::error::Error>> {
+                    let _span = mpstthree::instrument::close(
+                        stringify!(#meshedchannels_name),
+                        stringify!(#sender_ident),
+                    );
 
                     #(
                         #close_session_send
@@ -1231,6 +1367,10 @@ impl BakingWithEnumAndCancel {
             {
                 /// Cancel the session
                 pub fn cancel(self) {
+                    let _span = mpstthree::instrument::cancel(
+                        stringify!(#meshedchannels_name),
+                        &<N as mpstthree::role::Role>::head_str(),
+                    );
                     std::mem::drop(self);
                 }
             }
@@ -2059,6 +2199,17 @@ impl BakingWithEnumAndCancel {
             })
             .collect();
 
+        let send_to_all_methods: Vec<TokenStream> = (1..=self.number_roles)
+            .map(|sender| {
+                self.expand_send_to_all(
+                    all_roles.clone(),
+                    sender,
+                    session_types.clone(),
+                    session_types_struct.clone(),
+                )
+            })
+            .collect();
+
         let recv_methods: Vec<TokenStream> = (1..=self.number_roles)
             .map(|receiver| {
                 (1..=self.number_roles)
@@ -2258,6 +2409,8 @@ impl BakingWithEnumAndCancel {
 
             #( #send_methods )*
 
+            #( #send_to_all_methods )*
+
             #( #recv_methods )*
 
             #( #recv_from_all_methods )*
@@ -2279,10 +2432,17 @@ impl BakingWithEnumAndCancel {
                 ($session: expr, { $( $pat: pat => $result: expr, )+ }) => {
                     (move || -> Result<_, _> {
                         let (l, s) = $session.recv()?;
-                        mpstthree::binary::cancel::cancel(s);
                         match l {
                             $(
-                                $pat => $result,
+                                $pat => {
+                                    let _span = mpstthree::instrument::offer_pattern(
+                                        &s,
+                                        &s.name,
+                                        stringify!($pat),
+                                    );
+                                    mpstthree::binary::cancel::cancel(s);
+                                    $result
+                                },
                             )+
                             _ => panic!("Unexpected payload") ,
                         }
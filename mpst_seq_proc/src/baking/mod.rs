@@ -3,3 +3,39 @@ pub mod baking_interleaved_with_enum_and_cancel;
 pub mod baking_with_cancel;
 pub mod baking_with_enum;
 pub mod baking_with_enum_and_cancel;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, Result};
+
+/// Parse the name of the *MeshedChannels*, which may be preceded by `recorded`
+/// for the payloads of its `send` methods to be given to the recording hook.
+pub(crate) fn parse_name(input: ParseStream) -> Result<(Ident, bool)> {
+    let name = Ident::parse(input)?;
+
+    if name == "recorded" && input.peek(Ident) {
+        Ok((Ident::parse(input)?, true))
+    } else {
+        Ok((name, false))
+    }
+}
+
+/// The bound of the payloads of the baked `send` methods,
+/// which must also be `'static` to be recorded.
+pub(crate) fn payload_bound(recorded: bool) -> TokenStream {
+    if recorded {
+        quote! { std::marker::Send + 'static }
+    } else {
+        quote! { std::marker::Send }
+    }
+}
+
+/// The hook called by the baked `send` methods.
+pub(crate) fn send_hook(recorded: bool) -> TokenStream {
+    if recorded {
+        quote! { mpstthree::instrument::send_recorded }
+    } else {
+        quote! { mpstthree::instrument::send }
+    }
+}
@@ -16,11 +16,24 @@ fn attempt_extraction(input: ParseStream) -> Result<Vec<String>> {
     let _parentheses = syn::braced!(content in input);
     let token_stream = TokenStream::parse(&content)?;
 
+    // Each field is given as `$field: fragment`, where `$field` may or may not
+    // be wrapped in an invisible group depending on the fragment and the compiler
     let mut temp_result: Vec<TokenStream> = Vec::new();
+    let mut after_colon = false;
     for tt in token_stream.into_iter() {
         let elt = match tt {
+            TokenTree::Punct(ref p) if p.as_char() == ':' => {
+                after_colon = true;
+                None
+            }
+            TokenTree::Punct(_) => None,
+            _ if after_colon => {
+                after_colon = false;
+                None
+            }
             TokenTree::Group(g) => Some(g.stream()),
-            _ => None,
+            TokenTree::Ident(i) => Some(TokenTree::Ident(i).into()),
+            TokenTree::Literal(_) => None,
         };
         if let Some(elt_tt) = elt {
             temp_result.push(elt_tt)
@@ -65,62 +78,48 @@ impl CheckingInput {
     fn expand(&self) -> TokenStream {
         let choices = self.choices.clone();
 
-        let mut display: Vec<proc_macro2::TokenStream> = Vec::new();
         let mut new_hashmap: Vec<proc_macro2::TokenStream> = Vec::new();
 
         for (key, value) in choices {
             let name_key = Ident::new(&key, Span::call_site());
             let fn_key = Ident::new(&key.to_lowercase(), Span::call_site());
 
+            // Label each branch with its name and the type of its payload,
+            // without implementing any trait for the `enum`, which would be
+            // a non-local `impl` when the macro is called inside a function
             let branches: Vec<proc_macro2::TokenStream> = value
                 .iter()
                 .map(|branch| {
                     let branch_ident = Ident::new(branch, Span::call_site());
                     quote! {
                         #name_key::#branch_ident(s) => {
-                            write!(
-                                f,
-                                "{}\n{}",
-                                stringify!(#branch_ident),
-                                type_of(&s)
-                            )
+                            (stringify!(#branch_ident), type_of(&s))
                         }
                     }
                 })
                 .collect();
 
-            display.push(quote! {
-                impl std::fmt::Display for #name_key {
-                    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                        match self {
-                            #( #branches )*
-                        }
-                    }
-                }
-            });
-
             let branches_hashmap: Vec<proc_macro2::TokenStream> = value.iter()
             .map(|branch| {
-                let temp = Ident::new(&format!("temp_{}", branch).to_lowercase(), Span::call_site());
                 let branch_ident = Ident::new(branch, Span::call_site());
                 let branch_name = Ident::new(&branch.to_lowercase(), Span::call_site());
                 quote! {
-                    let #temp =
-                        (#name_key::#branch_ident(<_ as mpstthree::binary::struct_trait::session::Session>::new().0))
-                            .to_string();
-
-                    let #branch_name = #temp
-                        .split('\n')
-                        .filter(|s| !s.is_empty())
-                        .collect::<Vec<_>>();
+                    let #branch_name =
+                        label(&#name_key::#branch_ident(<_ as mpstthree::binary::struct_trait::session::Session>::new().0));
 
-                    all_branches.insert(String::from(#branch_name[0]), String::from(#branch_name[1]));
+                    all_branches.insert(String::from(#branch_name.0), String::from(#branch_name.1));
                 }
             })
             .collect();
 
             new_hashmap.push(quote! {
                 fn #fn_key() -> std::collections::HashMap<String, String> {
+                    fn label(value: &#name_key) -> (&'static str, &'static str) {
+                        match value {
+                            #( #branches )*
+                        }
+                    }
+
                     let state_branches_receivers = std::collections::hash_map::RandomState::new();
                     let mut all_branches: std::collections::HashMap<String, String> =
                         std::collections::HashMap::with_hasher(state_branches_receivers);
@@ -143,7 +142,6 @@ impl CheckingInput {
             let mut branches_receivers: std::collections::HashMap<String, std::collections::HashMap<String, String>> =
                 std::collections::HashMap::with_hasher(state_branches);
 
-            #( #display )*
             #( #new_hashmap )*
         }
     }
@@ -0,0 +1,98 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, Path, Result, Token};
+
+use crate::family::{all_members, parse_roles, FamilyRole};
+
+#[derive(Debug)]
+pub struct BundleFamily {
+    bundle_macro: Path,
+    meshedchannels_name: Ident,
+    all_roles: Vec<FamilyRole>,
+}
+
+impl Parse for BundleFamily {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let bundle_macro = Path::parse(input)?;
+        <Token![,]>::parse(input)?;
+        let meshedchannels_name = Ident::parse(input)?;
+        <Token![,]>::parse(input)?;
+        let all_roles = parse_roles(input)?;
+
+        Ok(BundleFamily {
+            bundle_macro,
+            meshedchannels_name,
+            all_roles,
+        })
+    }
+}
+
+impl From<BundleFamily> for TokenStream {
+    fn from(input: BundleFamily) -> TokenStream {
+        input.expand()
+    }
+}
+
+impl BundleFamily {
+    /// Expand the marker of a family and its implementations
+    /// of `Family` and `Indexed`
+    fn expand_family(&self, role: &FamilyRole) -> TokenStream {
+        let size = match role.size {
+            Some(size) => size as usize,
+            None => return quote! {},
+        };
+
+        let family_name = role.name.clone();
+
+        let indexed: Vec<TokenStream> = role
+            .members()
+            .iter()
+            .enumerate()
+            .map(|(index, member)| {
+                let role_name = Ident::new(&format!("Role{}", member), Span::call_site());
+
+                quote! {
+                    impl mpstthree::role::family::Indexed<#index> for #family_name {
+                        type Role<R: mpstthree::role::Role> = #role_name<R>;
+                        type Name = #role_name<mpstthree::role::end::RoleEnd>;
+                    }
+                }
+            })
+            .collect();
+
+        let family_str = family_name.to_string();
+
+        quote! {
+            ////////////////////////////////////////////
+            /// The marker of the family of roles
+            #[derive(Debug)]
+            enum #family_name {}
+
+            impl mpstthree::role::family::Family for #family_name {
+                const NAME: &'static str = #family_str;
+                const SIZE: usize = #size;
+            }
+
+            #( #indexed )*
+        }
+    }
+
+    fn expand(&self) -> TokenStream {
+        let bundle_macro = self.bundle_macro.clone();
+        let meshedchannels_name = self.meshedchannels_name.clone();
+        let all_members = all_members(&self.all_roles);
+
+        let families: Vec<TokenStream> = self
+            .all_roles
+            .iter()
+            .map(|role| self.expand_family(role))
+            .collect();
+
+        quote! {
+            #bundle_macro!( #meshedchannels_name, #( #all_members , )* );
+
+            #( #families )*
+        }
+    }
+}
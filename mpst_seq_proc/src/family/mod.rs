@@ -0,0 +1,64 @@
+use proc_macro2::Span;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, LitInt, Result, Token};
+
+pub mod bundle_family;
+pub mod template;
+
+/// A role given to the family macros,
+/// either a single role `A` or a family `Worker[N]`
+#[derive(Debug)]
+pub struct FamilyRole {
+    name: Ident,
+    size: Option<u64>,
+}
+
+impl Parse for FamilyRole {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name = Ident::parse(input)?;
+
+        let size = if input.peek(syn::token::Bracket) {
+            let content;
+            let _brackets = syn::bracketed!(content in input);
+            let literal = LitInt::parse(&content)?;
+            let size = literal.base10_parse::<u64>()?;
+            if size == 0 {
+                return Err(syn::Error::new(
+                    literal.span(),
+                    "A family of roles must contain at least one role",
+                ));
+            }
+            Some(size)
+        } else {
+            None
+        };
+
+        Ok(FamilyRole { name, size })
+    }
+}
+
+impl FamilyRole {
+    /// Get the names of the roles, `Worker0` to `Worker{N-1}` for a family
+    fn members(&self) -> Vec<Ident> {
+        match self.size {
+            Some(size) => (0..size)
+                .map(|i| Ident::new(&format!("{}{}", self.name, i), Span::call_site()))
+                .collect(),
+            None => vec![self.name.clone()],
+        }
+    }
+}
+
+/// Parse the roles between parentheses, separated by commas
+fn parse_roles(input: ParseStream) -> Result<Vec<FamilyRole>> {
+    let content;
+    let _parentheses = syn::parenthesized!(content in input);
+    let roles = content.parse_terminated::<FamilyRole, Token![,]>(FamilyRole::parse)?;
+
+    Ok(roles.into_iter().collect())
+}
+
+/// Get the names of all the roles, in order, with the families expanded
+fn all_members(roles: &[FamilyRole]) -> Vec<Ident> {
+    roles.iter().flat_map(|role| role.members()).collect()
+}
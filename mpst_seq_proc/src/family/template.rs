@@ -0,0 +1,173 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, Result, Token, Type};
+
+use crate::family::{all_members, parse_roles, FamilyRole};
+
+/// The topologies which can be instantiated
+#[derive(Debug, Clone, Copy)]
+enum Topology {
+    Ring,
+    Star,
+    Mesh,
+}
+
+#[derive(Debug)]
+pub struct Template {
+    topology: Topology,
+    template_name: Ident,
+    meshedchannels_name: Ident,
+    payload: Type,
+    all_roles: Vec<FamilyRole>,
+}
+
+impl Parse for Template {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let topology_ident = Ident::parse(input)?;
+        let topology = match topology_ident.to_string().as_str() {
+            "ring" => Topology::Ring,
+            "star" => Topology::Star,
+            "mesh" => Topology::Mesh,
+            _ => {
+                return Err(syn::Error::new(
+                    topology_ident.span(),
+                    "Expected one of the topologies ring, star or mesh",
+                ))
+            }
+        };
+        <Token![,]>::parse(input)?;
+        let template_name = Ident::parse(input)?;
+        <Token![,]>::parse(input)?;
+        let meshedchannels_name = Ident::parse(input)?;
+        <Token![,]>::parse(input)?;
+        let payload = Type::parse(input)?;
+        <Token![,]>::parse(input)?;
+        let all_roles = parse_roles(input)?;
+
+        if all_members(&all_roles).len() < 2 {
+            return Err(input.error("A template needs at least two roles"));
+        }
+
+        Ok(Template {
+            topology,
+            template_name,
+            meshedchannels_name,
+            payload,
+            all_roles,
+        })
+    }
+}
+
+impl From<Template> for TokenStream {
+    fn from(input: Template) -> TokenStream {
+        input.expand()
+    }
+}
+
+impl Template {
+    /// The global protocol of the topology, as the ordered list
+    /// of (sender, receiver) among `number_roles` roles, starting at 0
+    fn interactions(&self, number_roles: usize) -> Vec<(usize, usize)> {
+        match self.topology {
+            // Each role sends to the next one, the last one back to the first one
+            Topology::Ring => (0..number_roles)
+                .map(|i| (i, (i + 1) % number_roles))
+                .collect(),
+            // The first role sends to all the others, which all reply
+            Topology::Star => (1..number_roles)
+                .map(|i| (0, i))
+                .chain((1..number_roles).map(|i| (i, 0)))
+                .collect(),
+            // Each pair of roles exchanges a payload both ways
+            Topology::Mesh => (0..number_roles)
+                .flat_map(|i| ((i + 1)..number_roles).flat_map(move |j| vec![(i, j), (j, i)]))
+                .collect(),
+        }
+    }
+
+    /// Project the global protocol on the binary session
+    /// between `subject` and `peer`
+    fn expand_session(
+        &self,
+        interactions: &[(usize, usize)],
+        subject: usize,
+        peer: usize,
+    ) -> TokenStream {
+        let payload = self.payload.clone();
+
+        interactions.iter().rev().fold(
+            quote! { mpstthree::binary::struct_trait::end::End },
+            |continuation, (sender, receiver)| {
+                if *sender == subject && *receiver == peer {
+                    quote! { mpstthree::binary::struct_trait::send::Send<#payload, #continuation> }
+                } else if *sender == peer && *receiver == subject {
+                    quote! { mpstthree::binary::struct_trait::recv::Recv<#payload, #continuation> }
+                } else {
+                    continuation
+                }
+            },
+        )
+    }
+
+    /// Project the global protocol on the stack of `subject`
+    fn expand_stack(
+        &self,
+        all_members: &[Ident],
+        interactions: &[(usize, usize)],
+        subject: usize,
+    ) -> TokenStream {
+        interactions.iter().rev().fold(
+            quote! { mpstthree::role::end::RoleEnd },
+            |continuation, (sender, receiver)| {
+                let peer = if *sender == subject {
+                    *receiver
+                } else if *receiver == subject {
+                    *sender
+                } else {
+                    return continuation;
+                };
+                let role_name =
+                    Ident::new(&format!("Role{}", all_members[peer]), Span::call_site());
+                quote! { #role_name<#continuation> }
+            },
+        )
+    }
+
+    fn expand(&self) -> TokenStream {
+        let template_name = self.template_name.clone();
+        let meshedchannels_name = self.meshedchannels_name.clone();
+        let all_members = all_members(&self.all_roles);
+        let interactions = self.interactions(all_members.len());
+
+        let endpoints: Vec<TokenStream> = all_members
+            .iter()
+            .enumerate()
+            .map(|(subject, member)| {
+                let endpoint_name =
+                    Ident::new(&format!("{}{}", template_name, member), Span::call_site());
+                let role_name = Ident::new(&format!("Role{}", member), Span::call_site());
+
+                let sessions: Vec<TokenStream> = (0..all_members.len())
+                    .filter(|peer| *peer != subject)
+                    .map(|peer| self.expand_session(&interactions, subject, peer))
+                    .collect();
+
+                let stack = self.expand_stack(&all_members, &interactions, subject);
+
+                quote! {
+                    #[allow(dead_code)]
+                    type #endpoint_name = #meshedchannels_name<
+                        #( #sessions , )*
+                        #stack,
+                        #role_name<mpstthree::role::end::RoleEnd>
+                    >;
+                }
+            })
+            .collect();
+
+        quote! {
+            #( #endpoints )*
+        }
+    }
+}
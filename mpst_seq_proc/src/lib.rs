@@ -2,7 +2,6 @@
 
 use proc_macro::TokenStream;
 use proc_macro_hack::proc_macro_hack;
-use std::usize;
 use syn::parse_macro_input;
 
 mod functionmpst;
@@ -52,7 +51,13 @@ use interleaved::fork_mpst_multi_solo::ForkMPSTMultiSolo;
 mod macros_http;
 
 use macros_http::choose::choose_mpst_multi_http_to_all::ChooseTypeMultiHttpToAll;
+use macros_http::recv::create_recv_http_payload_request_session::CreateRecvHttpPayloadRequestSession;
+use macros_http::recv::create_recv_http_payload_session::CreateRecvHttpPayloadSession;
+use macros_http::recv::create_recv_http_server_session::CreateRecvHttpServerSession;
 use macros_http::recv::create_recv_http_session::CreateRecvHttpSession;
+use macros_http::send::create_send_http_payload_response_session::CreateSendHttpPayloadResponseSession;
+use macros_http::send::create_send_http_payload_session::CreateSendHttpPayloadSession;
+use macros_http::send::create_send_http_server_session::CreateSendHttpServerSession;
 use macros_http::send::create_send_http_session::CreateSendHttpSession;
 
 mod checking;
@@ -68,6 +73,15 @@ use baking::baking_with_cancel::BakingWithCancel;
 use baking::baking_with_enum::BakingWithEnum;
 use baking::baking_with_enum_and_cancel::BakingWithEnumAndCancel;
 
+mod testing;
+
+use testing::stub_bundle::StubBundle;
+
+mod family;
+
+use family::bundle_family::BundleFamily;
+use family::template::Template;
+
 //////////////////////////////////////
 
 #[proc_macro]
@@ -504,6 +518,90 @@ pub fn e_create_send_http_session(input: TokenStream) -> TokenStream {
 
 //////////////////////////////////////
 
+#[proc_macro]
+pub fn create_recv_http_server_session(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as CreateRecvHttpServerSession);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_create_recv_http_server_session(input: TokenStream) -> TokenStream {
+    create_recv_http_server_session(input)
+}
+
+//////////////////////////////////////
+
+#[proc_macro]
+pub fn create_send_http_server_session(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as CreateSendHttpServerSession);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_create_send_http_server_session(input: TokenStream) -> TokenStream {
+    create_send_http_server_session(input)
+}
+
+//////////////////////////////////////
+
+#[proc_macro]
+pub fn create_recv_http_payload_session(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as CreateRecvHttpPayloadSession);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_create_recv_http_payload_session(input: TokenStream) -> TokenStream {
+    create_recv_http_payload_session(input)
+}
+
+//////////////////////////////////////
+
+#[proc_macro]
+pub fn create_send_http_payload_session(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as CreateSendHttpPayloadSession);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_create_send_http_payload_session(input: TokenStream) -> TokenStream {
+    create_send_http_payload_session(input)
+}
+
+//////////////////////////////////////
+
+#[proc_macro]
+pub fn create_recv_http_payload_request_session(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as CreateRecvHttpPayloadRequestSession);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_create_recv_http_payload_request_session(input: TokenStream) -> TokenStream {
+    create_recv_http_payload_request_session(input)
+}
+
+//////////////////////////////////////
+
+#[proc_macro]
+pub fn create_send_http_payload_response_session(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as CreateSendHttpPayloadResponseSession);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_create_send_http_payload_response_session(input: TokenStream) -> TokenStream {
+    create_send_http_payload_response_session(input)
+}
+
+//////////////////////////////////////
+
 #[proc_macro]
 pub fn checking(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as CheckingInput);
@@ -614,3 +712,45 @@ pub fn baking_interleaved_with_enum_and_cancel(input: TokenStream) -> TokenStrea
 pub fn e_baking_interleaved_with_enum_and_cancel(input: TokenStream) -> TokenStream {
     baking_interleaved_with_enum_and_cancel(input)
 }
+
+//////////////////////////////////////
+
+#[proc_macro]
+pub fn stub_bundle(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as StubBundle);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_stub_bundle(input: TokenStream) -> TokenStream {
+    stub_bundle(input)
+}
+
+//////////////////////////////////////
+
+#[proc_macro]
+pub fn bundle_family(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as BundleFamily);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_bundle_family(input: TokenStream) -> TokenStream {
+    bundle_family(input)
+}
+
+//////////////////////////////////////
+
+#[proc_macro]
+pub fn template(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Template);
+    let output: proc_macro2::TokenStream = proc_macro2::TokenStream::from(input);
+    output.into()
+}
+
+#[proc_macro_hack]
+pub fn e_template(input: TokenStream) -> TokenStream {
+    template(input)
+}
@@ -123,7 +123,8 @@ impl CreateRecvHttpSession {
                 #(
                     #session_types
                 )*
-                R
+                R,
+                H
             >(
                 s: #meshedchannels_name<
                     #(
@@ -132,7 +133,7 @@ impl CreateRecvHttpSession {
                     #sender<R>,
                     #receiver<mpstthree::role::end::RoleEnd>,
                 >,
-                http: bool,
+                http: H,
                 mut resp_future: Vec::<hyper::client::ResponseFuture>,
             ) -> Result<
                 (
@@ -154,18 +155,14 @@ impl CreateRecvHttpSession {
                     #session_types_struct
                 )*
                 R: mpstthree::role::Role,
+                H: mpstthree::transport::http::context::HttpMode,
             {
-                if ( resp_future.len() != 1 && http ) || ( !http && resp_future.len() != 0 ) {
+                if ( resp_future.len() != 1 && http.is_http() ) || ( !http.is_http() && resp_future.len() != 0 ) {
                     panic!("Too many futures: {:?}", resp_future.len())
                 }
 
-                let resp = match http {
-                    true => {
-                        let rt = tokio::runtime::Runtime::new()?;
-                        rt.block_on(async move {
-                            resp_future.remove(0).await
-                        })?
-                    },
+                let resp = match http.is_http() {
+                    true => http.wait_response(resp_future.remove(0))?,
                     false => hyper::Response::default(),
                 };
 
@@ -1 +1,4 @@
+pub mod create_recv_http_payload_request_session;
+pub mod create_recv_http_payload_session;
+pub mod create_recv_http_server_session;
 pub mod create_recv_http_session;
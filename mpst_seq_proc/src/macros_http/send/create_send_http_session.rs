@@ -123,7 +123,8 @@ impl CreateSendHttpSession {
                 #(
                     #session_types
                 )*
-                R
+                R,
+                H
             >(
                 x: T,
                 s: #meshedchannels_name<
@@ -133,7 +134,7 @@ impl CreateSendHttpSession {
                     #receiver<R>,
                     #sender<mpstthree::role::end::RoleEnd>,
                 >,
-                http: bool,
+                http: H,
                 req: hyper::Request<hyper::Body>
             ) -> Result<
                 (
@@ -154,13 +155,11 @@ impl CreateSendHttpSession {
                     #session_types_struct
                 )*
                 R: mpstthree::role::Role,
+                H: mpstthree::transport::http::context::HttpMode,
             {
-                let https = hyper_tls::HttpsConnector::new();
-                let client = hyper::Client::builder().build::<_, hyper::Body>(https);
-
-                let new_req = match http {
-                    true => req,
-                    false => hyper::Request::default(),
+                let resp_future = match http.is_http() {
+                    true => http.send_request(req)?,
+                    false => http.send_request(hyper::Request::default())?,
                 };
 
                 #(
@@ -177,7 +176,7 @@ impl CreateSendHttpSession {
                         stack: new_stack,
                         name: s.name,
                     },
-                    client.request(new_req)
+                    resp_future
                 ))
             }
         }
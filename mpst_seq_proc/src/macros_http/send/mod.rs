@@ -1 +1,4 @@
+pub mod create_send_http_payload_response_session;
+pub mod create_send_http_payload_session;
+pub mod create_send_http_server_session;
 pub mod create_send_http_session;
@@ -17,6 +17,7 @@ fn expand_parenthesized(stream: &TokenStream) -> Vec<TokenStream> {
     for tt in stream.clone().into_iter() {
         let elt = match tt {
             TokenTree::Group(g) => Some(g.stream()),
+            TokenTree::Ident(i) => Some(TokenTree::Ident(i).into()),
             _ => None,
         };
         if let Some(elt_tt) = elt {
@@ -84,14 +85,21 @@ impl ChooseMultiCreateToAll {
                     $session: expr,
                     $( $label: path ),+ $(,)?
                 ) => {
-                    mpstthree::choose_mpst_multi_to_all!(
-                        $session ,
-                        $( $label , )* =>
-                        #( #all_receivers , )* =>
-                        #sender ,
-                        #meshedchannels_name ,
-                        #exclusion
-                    )
+                    {
+                        let _span = mpstthree::instrument::choose(
+                            stringify!(#meshedchannels_name),
+                            stringify!(#sender),
+                            [ $( stringify!($label) , )* ][0],
+                        );
+                        mpstthree::choose_mpst_multi_to_all!(
+                            $session ,
+                            $( $label , )* =>
+                            #( #all_receivers , )* =>
+                            #sender ,
+                            #meshedchannels_name ,
+                            #exclusion
+                        )
+                    }
                 }
             }
         }
@@ -23,6 +23,7 @@ fn expand_parenthesized(stream: &TokenStream) -> Vec<TokenStream> {
     for tt in stream.clone().into_iter() {
         let elt = match tt {
             TokenTree::Group(g) => Some(g.stream()),
+            TokenTree::Ident(i) => Some(TokenTree::Ident(i).into()),
             _ => None,
         };
         if let Some(elt_tt) = elt {
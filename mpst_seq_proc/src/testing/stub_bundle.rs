@@ -0,0 +1,334 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use std::convert::TryFrom;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, Result, Token};
+
+#[derive(Debug)]
+pub struct StubBundle {
+    meshedchannels_name: Ident,
+    all_roles: Vec<TokenStream>,
+    number_roles: u64,
+}
+
+fn expand_token_stream(input: ParseStream) -> Result<Vec<TokenStream>> {
+    let content;
+    let _parentheses = syn::parenthesized!(content in input);
+    let token_stream = TokenStream::parse(&content)?;
+
+    let mut result: Vec<TokenStream> = Vec::new();
+    for tt in token_stream.into_iter() {
+        let elt = match tt {
+            TokenTree::Group(g) => Some(g.stream()),
+            TokenTree::Ident(i) => Some(TokenTree::Ident(i).into()),
+            _ => None,
+        };
+        if let Some(elt_tt) = elt {
+            result.push(elt_tt)
+        }
+    }
+
+    Ok(result)
+}
+
+impl Parse for StubBundle {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let meshedchannels_name = Ident::parse(input)?;
+        <Token![,]>::parse(input)?;
+        let all_roles = expand_token_stream(<&syn::parse::ParseBuffer>::clone(&input))?;
+
+        let number_roles = u64::try_from(all_roles.len()).unwrap();
+
+        Ok(StubBundle {
+            meshedchannels_name,
+            all_roles,
+            number_roles,
+        })
+    }
+}
+
+impl From<StubBundle> for TokenStream {
+    fn from(input: StubBundle) -> TokenStream {
+        input.expand()
+    }
+}
+
+impl StubBundle {
+    /// Get the name of the role at `index`, starting at 1
+    fn role_ident(&self, index: u64, caller: &str) -> Ident {
+        if let Some(elt) = self.all_roles.get(usize::try_from(index - 1).unwrap()) {
+            Ident::new(&format!("Role{}", elt), Span::call_site())
+        } else {
+            panic!("Not enough arguments for role_ident in {}", caller)
+        }
+    }
+
+    /// Expand the stubs of the roles which send or receive a payload.
+    /// `is_send` selects between `Send` and `Recv`.
+    fn expand_interaction(&self, subject: u64, peer: u64, is_send: bool) -> TokenStream {
+        let meshedchannels_name = self.meshedchannels_name.clone();
+
+        let subject_ident = self.role_ident(subject, "expand_interaction");
+        let peer_ident = self.role_ident(peer, "expand_interaction");
+
+        let index = if peer >= subject { peer - 1 } else { peer };
+
+        let session_types: Vec<Ident> = (1..self.number_roles)
+            .map(|i| Ident::new(&format!("S{}", i), Span::call_site()))
+            .collect();
+
+        let session_types_struct: Vec<TokenStream> = (1..self.number_roles)
+            .map(|i| {
+                let temp_ident = Ident::new(&format!("S{}", i), Span::call_site());
+                quote! { #temp_ident : mpstthree::binary::struct_trait::session::Session , }
+            })
+            .collect();
+
+        let current_sessions: Vec<TokenStream> = (1..self.number_roles)
+            .map(|k| {
+                let temp_type = Ident::new(&format!("S{}", k), Span::call_site());
+
+                if k == index && is_send {
+                    quote! { mpstthree::binary::struct_trait::send::Send<T, #temp_type > , }
+                } else if k == index {
+                    quote! { mpstthree::binary::struct_trait::recv::Recv<T, #temp_type > , }
+                } else {
+                    quote! { #temp_type , }
+                }
+            })
+            .collect();
+
+        let new_sessions: Vec<TokenStream> = (1..self.number_roles)
+            .map(|k| {
+                let temp_session = Ident::new(&format!("session{}", k), Span::call_site());
+
+                if k == index {
+                    quote! { #temp_session : new_session , }
+                } else {
+                    quote! { #temp_session : self.#temp_session , }
+                }
+            })
+            .collect();
+
+        let new_session = Ident::new(&format!("session{}", index), Span::call_site());
+
+        let continuation = quote! {
+            #meshedchannels_name<
+                #( #session_types , )*
+                R,
+                #subject_ident<mpstthree::role::end::RoleEnd>
+            >
+        };
+
+        let subject_str = subject_ident.to_string();
+        let peer_str = peer_ident.to_string();
+
+        if is_send {
+            quote! {
+                impl<#( #session_types_struct )* R: mpstthree::role::Role, T: mpstthree::testing::Payload>
+                    mpstthree::testing::Stub for
+                    #meshedchannels_name<
+                        #( #current_sessions )*
+                        #peer_ident<R>,
+                        #subject_ident<mpstthree::role::end::RoleEnd>
+                    >
+                where
+                    #continuation: mpstthree::testing::Stub,
+                {
+                    fn stub(
+                        self,
+                        script: &mut mpstthree::testing::Script
+                    ) -> Result<(), Box<dyn std::error::Error>> {
+                        let payload = script.payload::<T>()?;
+                        script.record(format!(
+                            "{}!{}: {}",
+                            #subject_str,
+                            #peer_str,
+                            mpstthree::testing::type_label::<T>()
+                        ));
+                        let new_session = mpstthree::binary::send::send(payload, self.#new_session);
+                        let new_stack = self.stack.continuation();
+                        let s = #meshedchannels_name {
+                            #( #new_sessions )*
+                            stack: new_stack,
+                            name: self.name,
+                        };
+                        s.stub(script)
+                    }
+                }
+            }
+        } else {
+            quote! {
+                impl<#( #session_types_struct )* R: mpstthree::role::Role, T: mpstthree::testing::Message<#continuation>>
+                    mpstthree::testing::Stub for
+                    #meshedchannels_name<
+                        #( #current_sessions )*
+                        #peer_ident<R>,
+                        #subject_ident<mpstthree::role::end::RoleEnd>
+                    >
+                {
+                    fn stub(
+                        self,
+                        script: &mut mpstthree::testing::Script
+                    ) -> Result<(), Box<dyn std::error::Error>> {
+                        let (v, new_session) = mpstthree::binary::recv::recv(self.#new_session)?;
+                        script.record(format!(
+                            "{}?{}: {}",
+                            #subject_str,
+                            #peer_str,
+                            v.label()
+                        ));
+                        let new_stack = self.stack.continuation();
+                        let s = #meshedchannels_name {
+                            #( #new_sessions )*
+                            stack: new_stack,
+                            name: self.name,
+                        };
+                        v.received(s, script)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Expand the stub of a role which sends a payload to all the other roles
+    fn expand_send_to_all(&self, subject: u64) -> TokenStream {
+        let meshedchannels_name = self.meshedchannels_name.clone();
+
+        let subject_ident = self.role_ident(subject, "expand_send_to_all");
+        let subject_str = subject_ident.to_string();
+
+        let session_types: Vec<Ident> = (1..self.number_roles)
+            .map(|i| Ident::new(&format!("S{}", i), Span::call_site()))
+            .collect();
+
+        let session_types_struct: Vec<TokenStream> = (1..self.number_roles)
+            .map(|i| {
+                let temp_ident = Ident::new(&format!("S{}", i), Span::call_site());
+                quote! { #temp_ident : mpstthree::binary::struct_trait::session::Session , }
+            })
+            .collect();
+
+        let current_sessions: Vec<TokenStream> = (1..self.number_roles)
+            .map(|k| {
+                let temp_type = Ident::new(&format!("S{}", k), Span::call_site());
+                quote! { mpstthree::binary::struct_trait::send::Send<T, #temp_type > , }
+            })
+            .collect();
+
+        let new_sessions: Vec<TokenStream> = (1..self.number_roles)
+            .map(|k| {
+                let peer = if k >= subject { k + 1 } else { k };
+                let peer_str = self.role_ident(peer, "expand_send_to_all").to_string();
+
+                let temp_session = Ident::new(&format!("session{}", k), Span::call_site());
+
+                quote! {
+                    script.record(format!(
+                        "{}!{}: {}",
+                        #subject_str,
+                        #peer_str,
+                        mpstthree::testing::type_label::<T>()
+                    ));
+                    let #temp_session = mpstthree::binary::send::send(payload.clone(), self.#temp_session);
+                }
+            })
+            .collect();
+
+        let fields: Vec<TokenStream> = (1..self.number_roles)
+            .map(|k| {
+                let temp_session = Ident::new(&format!("session{}", k), Span::call_site());
+                quote! { #temp_session , }
+            })
+            .collect();
+
+        quote! {
+            impl<#( #session_types_struct )* R: mpstthree::role::Role, T: mpstthree::testing::Payload + Clone>
+                mpstthree::testing::Stub for
+                #meshedchannels_name<
+                    #( #current_sessions )*
+                    mpstthree::role::to_all::RoleToAll<R>,
+                    #subject_ident<mpstthree::role::end::RoleEnd>
+                >
+            where
+                #meshedchannels_name<
+                    #( #session_types , )*
+                    R,
+                    #subject_ident<mpstthree::role::end::RoleEnd>
+                >: mpstthree::testing::Stub,
+            {
+                fn stub(
+                    self,
+                    script: &mut mpstthree::testing::Script
+                ) -> Result<(), Box<dyn std::error::Error>> {
+                    let payload = script.payload::<T>()?;
+                    #( #new_sessions )*
+                    let new_stack = self.stack.continuation();
+                    let s = #meshedchannels_name {
+                        #( #fields )*
+                        stack: new_stack,
+                        name: self.name,
+                    };
+                    s.stub(script)
+                }
+            }
+        }
+    }
+
+    /// Expand the stub of a role which has nothing left to do
+    fn expand_close(&self, subject: u64) -> TokenStream {
+        let meshedchannels_name = self.meshedchannels_name.clone();
+
+        let subject_ident = self.role_ident(subject, "expand_close");
+        let subject_str = subject_ident.to_string();
+
+        let close_session_types: Vec<TokenStream> = (1..self.number_roles)
+            .map(|_i| {
+                quote! { mpstthree::binary::struct_trait::end::End, }
+            })
+            .collect();
+
+        quote! {
+            impl mpstthree::testing::Stub for
+                #meshedchannels_name<
+                    #( #close_session_types )*
+                    mpstthree::role::end::RoleEnd,
+                    #subject_ident<mpstthree::role::end::RoleEnd>
+                >
+            {
+                fn stub(
+                    self,
+                    script: &mut mpstthree::testing::Script
+                ) -> Result<(), Box<dyn std::error::Error>> {
+                    script.record(format!("{}: close", #subject_str));
+                    self.close()
+                }
+            }
+        }
+    }
+
+    fn expand(&self) -> TokenStream {
+        let interactions: Vec<TokenStream> = (1..=self.number_roles)
+            .flat_map(|subject| {
+                (1..=self.number_roles)
+                    .filter(move |peer| *peer != subject)
+                    .flat_map(move |peer| vec![(subject, peer, true), (subject, peer, false)])
+            })
+            .map(|(subject, peer, is_send)| self.expand_interaction(subject, peer, is_send))
+            .collect();
+
+        let send_to_all_methods: Vec<TokenStream> = (1..=self.number_roles)
+            .map(|subject| self.expand_send_to_all(subject))
+            .collect();
+
+        let close_methods: Vec<TokenStream> = (1..=self.number_roles)
+            .map(|subject| self.expand_close(subject))
+            .collect();
+
+        quote! {
+            #( #interactions )*
+            #( #send_to_all_methods )*
+            #( #close_methods )*
+        }
+    }
+}
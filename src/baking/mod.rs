@@ -10,6 +10,8 @@
 ///
 /// # Arguments
 ///
+/// * Optionally, `recorded`, for the values of the payloads to be recorded
+///   with the `"recording"` feature. The payloads must then be `'static`.
 /// * Name of the new SessionMST
 /// * Names of the new roles. They are called as RoleX where X is the name provided.
 ///
@@ -23,6 +25,15 @@
 #[macro_export]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
 macro_rules! bundle_impl {
+    (
+        recorded $meshedchannels_name: ident,
+        $( $all_roles: ident),+ $(,)?
+    ) => {
+        mpst_seq::baking!(
+            recorded $meshedchannels_name,
+            ( $( $all_roles , )+ )
+        );
+    };
     (
         $meshedchannels_name: ident,
         $( $all_roles: ident),+ $(,)?
@@ -48,6 +59,8 @@ macro_rules! bundle_impl {
 ///
 /// # Arguments
 ///
+/// * Optionally, `recorded`, for the values of the payloads to be recorded
+///   with the `"recording"` feature. The payloads must then be `'static`.
 /// * Name of the new SessionMST
 /// * Names of the new roles. They are called as RoleX where X is the name provided.
 ///
@@ -61,6 +74,15 @@ macro_rules! bundle_impl {
 #[macro_export]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
 macro_rules! bundle_impl_with_enum {
+    (
+        recorded $meshedchannels_name: ident,
+        $( $all_roles: ident),+ $(,)?
+    ) => {
+        mpst_seq::baking_with_enum!(
+            recorded $meshedchannels_name,
+            ( $( $all_roles , )+ )
+        );
+    };
     (
         $meshedchannels_name: ident,
         $( $all_roles: ident),+ $(,)?
@@ -78,6 +100,8 @@ macro_rules! bundle_impl_with_enum {
 ///
 /// # Arguments
 ///
+/// * Optionally, `recorded`, for the values of the payloads to be recorded
+///   with the `"recording"` feature. The payloads must then be `'static`.
 /// * Name of the new SessionMST
 /// * Names of the new roles. They are called as RoleX where X is the name provided.
 ///
@@ -91,6 +115,15 @@ macro_rules! bundle_impl_with_enum {
 #[macro_export]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
 macro_rules! bundle_impl_with_cancel {
+    (
+        recorded $meshedchannels_name: ident,
+        $( $all_roles: ident),+ $(,)?
+    ) => {
+        mpst_seq::baking_with_cancel!(
+            recorded $meshedchannels_name,
+            ( $( $all_roles , )+ )
+        );
+    };
     (
         $meshedchannels_name: ident,
         $( $all_roles: ident),+ $(,)?
@@ -117,6 +150,8 @@ macro_rules! bundle_impl_with_cancel {
 ///
 /// # Arguments
 ///
+/// * Optionally, `recorded`, for the values of the payloads to be recorded
+///   with the `"recording"` feature. The payloads must then be `'static`.
 /// * Name of the new SessionMST
 /// * Names of the new roles. They are called as RoleX where X is the name provided.
 ///
@@ -130,6 +165,15 @@ macro_rules! bundle_impl_with_cancel {
 #[macro_export]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
 macro_rules! bundle_impl_with_enum_and_cancel {
+    (
+        recorded $meshedchannels_name: ident,
+        $( $all_roles: ident),+ $(,)?
+    ) => {
+        mpst_seq::baking_with_enum_and_cancel!(
+            recorded $meshedchannels_name,
+            ( $( $all_roles , )+ )
+        );
+    };
     (
         $meshedchannels_name: ident,
         $( $all_roles: ident),+ $(,)?
@@ -157,6 +201,8 @@ macro_rules! bundle_impl_with_enum_and_cancel {
 ///
 /// # Arguments
 ///
+/// * Optionally, `recorded`, for the values of the payloads to be recorded
+///   with the `"recording"` feature. The payloads must then be `'static`.
 /// * Name of the new SessionMST
 /// * Names of the new roles. They are called as RoleX where X is the name provided.
 ///
@@ -170,6 +216,15 @@ macro_rules! bundle_impl_with_enum_and_cancel {
 #[macro_export]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
 macro_rules! bundle_impl_interleaved_with_enum_and_cancel {
+    (
+        recorded $meshedchannels_name: ident,
+        $( $all_roles: ident),+ $(,)?
+    ) => {
+        mpst_seq::baking_interleaved_with_enum_and_cancel!(
+            recorded $meshedchannels_name,
+            ( $( $all_roles , )+ )
+        );
+    };
     (
         $meshedchannels_name: ident,
         $( $all_roles: ident),+ $(,)?
@@ -180,3 +235,351 @@ macro_rules! bundle_impl_interleaved_with_enum_and_cancel {
         );
     };
 }
+
+/// Create a new SessionMST structure, new roles and the baking environment,
+/// as [`bundle_impl!`], where the roles can be families of indexed roles.
+///
+/// A family `Worker[N]` creates the roles `RoleWorker0` to `RoleWorker{N-1}`,
+/// and the marker `Worker` of the family, which gives the role at index `I`
+/// with [`Indexed`]. `N` must be an integer literal.
+///
+/// # Arguments
+///
+/// * Name of the new SessionMST
+/// * Names of the new roles, or families of roles as `Worker[N]`.
+///
+/// # Basic example
+///
+/// ```
+/// use mpstthree::bundle_family;
+/// use mpstthree::role::family::{Family, Indexed};
+/// use mpstthree::role::Role;
+///
+/// bundle_family!(MeshedChannelsFive, Hub, Leaf[4]);
+///
+/// type NameLeaf2 = <Leaf as Indexed<2>>::Name;
+///
+/// assert_eq!(Leaf::SIZE, 4);
+/// assert_eq!(NameLeaf2::head_str(), "RoleLeaf2");
+/// ```
+///
+/// [`bundle_impl!`]: crate::bundle_impl
+/// [`Indexed`]: crate::role::family::Indexed
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
+macro_rules! bundle_family {
+    (
+        $meshedchannels_name: ident,
+        $( $all_roles: ident $( [ $size: literal ] )? ),+ $(,)?
+    ) => {
+        mpst_seq::bundle_family!(
+            mpstthree::bundle_impl,
+            $meshedchannels_name,
+            ( $( $all_roles $( [ $size ] )? , )+ )
+        );
+    };
+}
+
+/// Create a new SessionMST structure, new roles and the baking environment,
+/// as [`bundle_impl_with_enum!`], where the roles can be families of indexed roles,
+/// as for [`bundle_family!`].
+///
+/// # Basic example
+///
+/// ```
+/// use mpstthree::bundle_family_with_enum;
+///
+/// bundle_family_with_enum!(MeshedChannelsFour, Worker[4]);
+/// ```
+///
+/// [`bundle_impl_with_enum!`]: crate::bundle_impl_with_enum
+/// [`bundle_family!`]: crate::bundle_family
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
+macro_rules! bundle_family_with_enum {
+    (
+        $meshedchannels_name: ident,
+        $( $all_roles: ident $( [ $size: literal ] )? ),+ $(,)?
+    ) => {
+        mpst_seq::bundle_family!(
+            mpstthree::bundle_impl_with_enum,
+            $meshedchannels_name,
+            ( $( $all_roles $( [ $size ] )? , )+ )
+        );
+    };
+}
+
+/// Create a new SessionMST structure, new roles and the baking environment,
+/// as [`bundle_impl_with_cancel!`], where the roles can be families of indexed roles,
+/// as for [`bundle_family!`].
+///
+/// # Basic example
+///
+/// ```
+/// use mpstthree::bundle_family_with_cancel;
+///
+/// bundle_family_with_cancel!(MeshedChannelsFour, Worker[4]);
+/// ```
+///
+/// [`bundle_impl_with_cancel!`]: crate::bundle_impl_with_cancel
+/// [`bundle_family!`]: crate::bundle_family
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
+macro_rules! bundle_family_with_cancel {
+    (
+        $meshedchannels_name: ident,
+        $( $all_roles: ident $( [ $size: literal ] )? ),+ $(,)?
+    ) => {
+        mpst_seq::bundle_family!(
+            mpstthree::bundle_impl_with_cancel,
+            $meshedchannels_name,
+            ( $( $all_roles $( [ $size ] )? , )+ )
+        );
+    };
+}
+
+/// Create a new SessionMST structure, new roles and the baking environment,
+/// as [`bundle_impl_with_enum_and_cancel!`], where the roles can be families
+/// of indexed roles, as for [`bundle_family!`].
+///
+/// # Basic example
+///
+/// ```
+/// use mpstthree::bundle_family_with_enum_and_cancel;
+///
+/// bundle_family_with_enum_and_cancel!(MeshedChannelsFour, Worker[4]);
+/// ```
+///
+/// [`bundle_impl_with_enum_and_cancel!`]: crate::bundle_impl_with_enum_and_cancel
+/// [`bundle_family!`]: crate::bundle_family
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
+macro_rules! bundle_family_with_enum_and_cancel {
+    (
+        $meshedchannels_name: ident,
+        $( $all_roles: ident $( [ $size: literal ] )? ),+ $(,)?
+    ) => {
+        mpst_seq::bundle_family!(
+            mpstthree::bundle_impl_with_enum_and_cancel,
+            $meshedchannels_name,
+            ( $( $all_roles $( [ $size ] )? , )+ )
+        );
+    };
+}
+
+/// Instantiate the ring protocol for the roles of a bundle:
+/// each role sends a payload to the next one,
+/// and the last role sends a payload back to the first one.
+///
+/// Creates the type `TemplateX` of the endpoint of each role `X`,
+/// where `Template` is the name provided.
+///
+/// # Arguments
+///
+/// * Name of the template, prefixing the types of the endpoints
+/// * Name of the SessionMST
+/// * Type of the payloads
+/// * Names of the roles, or families of roles, in the order given to the bundle
+///
+/// # Basic example
+///
+/// ```
+/// use mpstthree::{bundle_family, template_ring};
+/// use std::error::Error;
+///
+/// bundle_family!(MeshedChannelsThree, Worker[3]);
+///
+/// template_ring!(Ring, MeshedChannelsThree, i32, Worker[3]);
+///
+/// fn endpoint_worker_0(s: RingWorker0) -> Result<(), Box<dyn Error>> {
+///     let s = s.send(0);
+///     let (payload, s) = s.recv()?;
+///     assert_eq!(payload, 2);
+///     s.close()
+/// }
+///
+/// // Each other role receives from the previous role, then sends to the next one
+/// fn endpoint_worker_1(s: RingWorker1) -> Result<(), Box<dyn Error>> {
+///     let (payload, s) = s.recv()?;
+///     let s = s.send(payload + 1);
+///     s.close()
+/// }
+///
+/// fn endpoint_worker_2(s: RingWorker2) -> Result<(), Box<dyn Error>> {
+///     let (payload, s) = s.recv()?;
+///     let s = s.send(payload + 1);
+///     s.close()
+/// }
+///
+/// let (thread_0, thread_1, thread_2) =
+///     fork_mpst(endpoint_worker_0, endpoint_worker_1, endpoint_worker_2);
+///
+/// assert!(thread_0.join().is_ok());
+/// assert!(thread_1.join().is_ok());
+/// assert!(thread_2.join().is_ok());
+/// ```
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
+macro_rules! template_ring {
+    (
+        $template_name: ident,
+        $meshedchannels_name: ident,
+        $payload: ty,
+        $( $all_roles: ident $( [ $size: literal ] )? ),+ $(,)?
+    ) => {
+        mpst_seq::template!(
+            ring,
+            $template_name,
+            $meshedchannels_name,
+            $payload,
+            ( $( $all_roles $( [ $size ] )? , )+ )
+        );
+    };
+}
+
+/// Instantiate the star protocol for the roles of a bundle:
+/// the first role, the hub, sends a payload to each other role,
+/// then each other role replies with a payload.
+///
+/// Creates the type `TemplateX` of the endpoint of each role `X`,
+/// where `Template` is the name provided.
+///
+/// # Arguments
+///
+/// * Name of the template, prefixing the types of the endpoints
+/// * Name of the SessionMST
+/// * Type of the payloads
+/// * Names of the roles, or families of roles, in the order given to the bundle
+///
+/// # Basic example
+///
+/// ```
+/// use mpstthree::{bundle_family, template_star};
+/// use std::error::Error;
+///
+/// bundle_family!(MeshedChannelsThree, Hub, Leaf[2]);
+///
+/// template_star!(Star, MeshedChannelsThree, i32, Hub, Leaf[2]);
+///
+/// fn endpoint_hub(s: StarHub) -> Result<(), Box<dyn Error>> {
+///     let s = s.send(1);
+///     let s = s.send(2);
+///     let (first, s) = s.recv()?;
+///     let (second, s) = s.recv()?;
+///     assert_eq!(first + second, 6);
+///     s.close()
+/// }
+///
+/// fn endpoint_leaf_0(s: StarLeaf0) -> Result<(), Box<dyn Error>> {
+///     let (payload, s) = s.recv()?;
+///     let s = s.send(payload * 2);
+///     s.close()
+/// }
+///
+/// fn endpoint_leaf_1(s: StarLeaf1) -> Result<(), Box<dyn Error>> {
+///     let (payload, s) = s.recv()?;
+///     let s = s.send(payload * 2);
+///     s.close()
+/// }
+///
+/// let (thread_hub, thread_0, thread_1) = fork_mpst(endpoint_hub, endpoint_leaf_0, endpoint_leaf_1);
+///
+/// assert!(thread_hub.join().is_ok());
+/// assert!(thread_0.join().is_ok());
+/// assert!(thread_1.join().is_ok());
+/// ```
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
+macro_rules! template_star {
+    (
+        $template_name: ident,
+        $meshedchannels_name: ident,
+        $payload: ty,
+        $( $all_roles: ident $( [ $size: literal ] )? ),+ $(,)?
+    ) => {
+        mpst_seq::template!(
+            star,
+            $template_name,
+            $meshedchannels_name,
+            $payload,
+            ( $( $all_roles $( [ $size ] )? , )+ )
+        );
+    };
+}
+
+/// Instantiate the all-to-all mesh protocol for the roles of a bundle:
+/// for each pair of roles, in order, the first role sends a payload
+/// to the second one, which replies with a payload.
+///
+/// Creates the type `TemplateX` of the endpoint of each role `X`,
+/// where `Template` is the name provided.
+///
+/// # Arguments
+///
+/// * Name of the template, prefixing the types of the endpoints
+/// * Name of the SessionMST
+/// * Type of the payloads
+/// * Names of the roles, or families of roles, in the order given to the bundle
+///
+/// # Basic example
+///
+/// ```
+/// use mpstthree::{bundle_family, template_mesh};
+/// use std::error::Error;
+///
+/// bundle_family!(MeshedChannelsThree, Worker[3]);
+///
+/// template_mesh!(Mesh, MeshedChannelsThree, i32, Worker[3]);
+///
+/// // RoleWorker0 exchanges with RoleWorker1, then with RoleWorker2
+/// fn endpoint_worker_0(s: MeshWorker0) -> Result<(), Box<dyn Error>> {
+///     let s = s.send(0);
+///     let (_, s) = s.recv()?;
+///     let s = s.send(0);
+///     let (_, s) = s.recv()?;
+///     s.close()
+/// }
+///
+/// // RoleWorker1 exchanges with RoleWorker0, then with RoleWorker2
+/// fn endpoint_worker_1(s: MeshWorker1) -> Result<(), Box<dyn Error>> {
+///     let (_, s) = s.recv()?;
+///     let s = s.send(1);
+///     let s = s.send(1);
+///     let (_, s) = s.recv()?;
+///     s.close()
+/// }
+///
+/// // RoleWorker2 exchanges with RoleWorker0, then with RoleWorker1
+/// fn endpoint_worker_2(s: MeshWorker2) -> Result<(), Box<dyn Error>> {
+///     let (_, s) = s.recv()?;
+///     let s = s.send(2);
+///     let (_, s) = s.recv()?;
+///     let s = s.send(2);
+///     s.close()
+/// }
+///
+/// let (thread_0, thread_1, thread_2) =
+///     fork_mpst(endpoint_worker_0, endpoint_worker_1, endpoint_worker_2);
+///
+/// assert!(thread_0.join().is_ok());
+/// assert!(thread_1.join().is_ok());
+/// assert!(thread_2.join().is_ok());
+/// ```
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "baking")))]
+macro_rules! template_mesh {
+    (
+        $template_name: ident,
+        $meshedchannels_name: ident,
+        $payload: ty,
+        $( $all_roles: ident $( [ $size: literal ] )? ),+ $(,)?
+    ) => {
+        mpst_seq::template!(
+            mesh,
+            $template_name,
+            $meshedchannels_name,
+            $payload,
+            ( $( $all_roles $( [ $size ] )? , )+ )
+        );
+    };
+}
@@ -20,5 +20,8 @@ use std::mem;
 /// cancel(s);
 /// ```
 pub fn cancel<T>(s: T) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(session = std::any::type_name::<T>(), "cancel");
+
     mem::drop(s);
 }
@@ -18,6 +18,9 @@ where
     S1: Session + 'a,
     S2: Session + 'a,
 {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(branch = "Left", "choose");
+
     let (here, there) = S1::new();
     let s = send(Either::Left(there), s);
     cancel(s);
@@ -31,6 +34,9 @@ where
     S1: Session + 'a,
     S2: Session + 'a,
 {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(branch = "Right", "choose");
+
     let (here, there) = S2::new();
     let s = send(Either::Right(there), s);
     cancel(s);
@@ -8,6 +8,9 @@ use std::error::Error;
 /// Closes a session. Synchronises with the partner, and
 /// fails if the partner has crashed.
 pub fn close(s: End) -> Result<(), Box<dyn Error>> {
+    #[cfg(feature = "tracing")]
+    tracing::trace!("close");
+
     s.sender.send(Signal::Stop).unwrap_or(());
     s.receiver.recv()?;
     Ok(())
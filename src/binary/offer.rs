@@ -27,6 +27,10 @@ where
 {
     let (e, s) = recv(s)?;
     cancel(s);
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(branch = if e.is_left() { "Left" } else { "Right" }, "offer");
+
     e.either(f, g)
 }
 
@@ -15,5 +15,9 @@ where
     S: Session,
 {
     let (v, s) = s.channel.recv()?;
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(payload = std::any::type_name::<T>(), "recv");
+
     Ok((v, s))
 }
@@ -15,6 +15,9 @@ where
     T: marker::Send,
     S: Session,
 {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(payload = std::any::type_name::<T>(), "send");
+
     let (here, there) = S::new();
     s.channel.send((x, there)).unwrap_or(());
     here
@@ -27,6 +30,9 @@ where
     T: marker::Send,
     S: Session,
 {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(payload = std::any::type_name::<T>(), "send");
+
     let (here, there) = S::new();
     match s.channel.send((x, there)) {
         Ok(_) => Ok(here),
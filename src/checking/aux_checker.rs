@@ -1,4 +1,4 @@
-use petgraph::graph::NodeIndex;
+use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::Graph;
 
 use regex::Regex;
@@ -11,6 +11,9 @@ type VecOfStr = Vec<String>;
 type HashMapStrVecOfStr = HashMap<String, VecOfStr>;
 type GraphOfStrStr = Graph<String, String>;
 type VecOfTuple = Vec<(String, usize)>;
+// The edges starting a branch, along with the name of the branch
+// and whether the branch is chosen by the role or offered to it
+type VecOfBranches = Vec<(EdgeIndex<u32>, String, bool)>;
 
 // Clean the provided session, which should be stringified.
 //
@@ -36,7 +39,7 @@ type VecOfTuple = Vec<(String, usize)>;
 #[doc(hidden)]
 pub(crate) fn clean_session(session: &str) -> Result<VecOfStr, Box<dyn Error>> {
     // The regex expression
-    let main_re = Regex::new(r"([^<,>\s]+)::([^<,>\s]+)")?;
+    let main_re = Regex::new(r"([^<,>()\s]+)::([^<,>()\s]+)")?;
     let mut temp = session.replace('&', "");
 
     // Replace with regex expression -> term1::term2::term3 by term3
@@ -141,8 +144,17 @@ pub(crate) fn get_blocks(full_block: &str) -> Result<VecOfStr, Box<dyn Error>> {
     // Start at -1 because we want to remove the first `<` and the term before
     let mut index = -1;
 
+    // The depth in tuples, such as `(End,Branching0fromCtoA)`
+    let mut parenthesis = 0;
+
     for i in full_block.chars() {
         if i == '&' || i.is_whitespace() {
+        } else if i == '(' && index >= 0 {
+            temp = format!("{}{}", temp, i);
+            parenthesis += 1;
+        } else if i == ')' && index >= 0 {
+            temp = format!("{}{}", temp, i);
+            parenthesis -= 1;
         } else if i == '>' && index == 0 {
             result.push(temp.to_string());
             temp = "".to_string();
@@ -152,7 +164,7 @@ pub(crate) fn get_blocks(full_block: &str) -> Result<VecOfStr, Box<dyn Error>> {
         } else if i == '>' && index >= 0 {
             temp = format!("{}{}", temp, i);
             index -= 1;
-        } else if i == ',' && index == 0 {
+        } else if i == ',' && index == 0 && parenthesis == 0 {
             result.push(temp);
             temp = "".to_string();
         } else if index >= 0 {
@@ -184,12 +196,24 @@ pub(crate) fn get_head_payload_continuation(full_block: &str) -> Result<VecOfStr
         // If the full block is a `End` type
         Ok(vec!["RoleEnd".to_string()])
     } else {
-        let mut result = vec![full_block.to_string().split('<').collect::<Vec<_>>()[0].to_string()];
+        let mut result = vec![full_block.split('<').collect::<Vec<_>>()[0].to_string()];
         result.append(&mut get_blocks(full_block)?);
         Ok(result)
     }
 }
 
+// Get the name of the `enum` sent for a choice, which may be paired
+// with the `End` session used for cancelling the session.
+//
+// From "(End,Branching0fromCtoA)" to "Branching0fromCtoA".
+#[doc(hidden)]
+pub(crate) fn get_branching_payload(payload: &str) -> &str {
+    payload
+        .strip_prefix("(End,")
+        .and_then(|payload| payload.strip_suffix(')'))
+        .unwrap_or(payload)
+}
+
 // Extract the correct label for a node from the index_node and the depth of the current node.
 //
 // From [0, 1, 0, 5] and 2 to "0.1.0".
@@ -232,6 +256,27 @@ pub(crate) fn build_dual(session: &str) -> Result<String, Box<dyn Error>> {
     }
 }
 
+// Record the edges leaving `previous_node` which were added since `first_edge`,
+// as starting the branch `current_branch`, such as "Branching0fromAtoC::Success",
+// which is named after the variant of the `enum`.
+// They are inserted at `first_branch`, before the branches of nested choices.
+fn record_branch(
+    g: &GraphOfStrStr,
+    branches: &mut VecOfBranches,
+    previous_node: NodeIndex<u32>,
+    (first_edge, first_branch): (usize, usize),
+    current_branch: &str,
+    chosen: bool,
+) {
+    let name_branch = current_branch.rsplit("::").next().unwrap_or("");
+
+    for edge in (first_edge..g.edge_count()).rev().map(EdgeIndex::new) {
+        if g.edge_endpoints(edge).map(|(source, _)| source) == Some(previous_node) {
+            branches.insert(first_branch, (edge, name_branch.to_string(), chosen));
+        }
+    }
+}
+
 #[doc(hidden)]
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn aux_get_graph(
@@ -249,6 +294,7 @@ pub(crate) fn aux_get_graph(
     branching_sessions: HashMapStrVecOfStr,
     group_branches: HashMap<String, i32>,
     mut cfsm: VecOfTuple,
+    branches: &mut VecOfBranches,
 ) -> Result<(GraphOfStrStr, VecOfTuple), Box<dyn Error>> {
     if compare_end == full_session {
         index_node[depth_level] += 1;
@@ -355,6 +401,7 @@ pub(crate) fn aux_get_graph(
                     branching_sessions.clone(),
                     group_branches.clone(),
                     cfsm,
+                    branches,
                 )?;
 
                 g = result.0;
@@ -377,6 +424,7 @@ pub(crate) fn aux_get_graph(
                     branching_sessions,
                     group_branches,
                     cfsm,
+                    branches,
                 )
             } else {
                 // If this is the active role
@@ -400,6 +448,7 @@ pub(crate) fn aux_get_graph(
                     branching_sessions.clone(),
                     group_branches.clone(),
                     cfsm,
+                    branches,
                 )?;
 
                 g = result.0;
@@ -420,8 +469,81 @@ pub(crate) fn aux_get_graph(
                     branching_sessions,
                     group_branches,
                     cfsm,
+                    branches,
                 )
             }
+        } else if stack.len() == 2 && stack[0] == "RoleToAll" {
+            // If it is a payload sent to all the other roles
+
+            for pos in 0..size_full_session {
+                // The index of the receiver among the roles
+                let index_head = pos + (pos >= index_current_role) as usize;
+                let head_stack = &roles[index_head];
+
+                // The running session, which should send the payload
+                let running_session = get_head_payload_continuation(&full_session[pos])?;
+
+                if running_session[0] != *"Send" {
+                    panic!(
+                        "Did not found a correct session for role {:?} sending to all. \
+                        Found session: {:?}",
+                        current_role, full_session
+                    )
+                }
+
+                // Increase the index for the nodes
+                index_node[depth_level] += 1;
+
+                // Add the new `step`
+                let new_node = g.add_node(extract_index_node(&index_node, depth_level)?);
+
+                // Add the new edge between the previous and the new node,
+                // and label it with the corresponding interaction
+                g.add_edge(
+                    previous_node,
+                    new_node,
+                    format!("{}!{}: {}", current_role, head_stack, &running_session[1]),
+                );
+
+                cfsm.push((
+                    format!(
+                        "{}{} {} ! {} {}",
+                        current_role,
+                        previous_node.index(),
+                        index_head,
+                        &running_session[1],
+                        current_role
+                    ),
+                    new_node.index(),
+                ));
+
+                // Replace the old binary session with the new one
+                full_session[pos] = running_session[2].to_string();
+
+                // Update the previous node
+                previous_node = new_node;
+            }
+
+            // Replace the old stack with the new one
+            full_session[size_full_session] = stack[1].to_string();
+
+            aux_get_graph(
+                current_role,
+                full_session,
+                roles,
+                index_node,
+                previous_node,
+                compare_end,
+                depth_level,
+                index_current_role,
+                g,
+                branches_receivers,
+                branches_already_seen,
+                branching_sessions,
+                group_branches,
+                cfsm,
+                branches,
+            )
         } else if stack.len() == 2 {
             // If it is a simple interaction
             let head_stack = &stack[0];
@@ -475,18 +597,17 @@ pub(crate) fn aux_get_graph(
                 // Update the previous node
                 previous_node = new_node;
             } else if running_session[0] == *"Recv" {
-                if let Some(choice) = branches_receivers.get(&running_session[1]) {
+                let payload = get_branching_payload(&running_session[1]);
+
+                if let Some(choice) = branches_receivers.get(payload) {
                     // If receive recursive choice
                     let mut all_branches = Vec::new();
                     let mut all_branches_vec = Vec::new();
 
                     for (branch, session) in choice {
-                        all_branches.push((
-                            format!("{}::{}", &running_session[1], &branch),
-                            session.to_vec(),
-                        ));
+                        all_branches.push((format!("{}::{}", payload, &branch), session.to_vec()));
 
-                        all_branches_vec.push(format!("{}::{}", &running_session[1], &branch));
+                        all_branches_vec.push(format!("{}::{}", payload, &branch));
                     }
 
                     all_branches_vec.sort();
@@ -524,6 +645,10 @@ pub(crate) fn aux_get_graph(
                                     .insert(temp_current_branch.0.clone(), previous_node);
                             }
 
+                            // The edges and branches added from now on belong to the current branch
+                            let first_edge = g.edge_count();
+                            let first_branch = branches.len();
+
                             let result = aux_get_graph(
                                 current_role,
                                 session[..(session.len() - 2)].to_vec(),
@@ -539,11 +664,21 @@ pub(crate) fn aux_get_graph(
                                 branching_sessions.clone(),
                                 group_branches.clone(),
                                 cfsm,
+                                branches,
                             )?;
 
                             g = result.0;
                             cfsm = result.1;
 
+                            record_branch(
+                                &g,
+                                branches,
+                                previous_node,
+                                (first_edge, first_branch),
+                                &current_branch,
+                                false,
+                            );
+
                             // Insert the new node/branch in the list of the ones already seen
                             let index_group =
                                 if let Some(index) = group_branches.get(&current_branch) {
@@ -613,6 +748,7 @@ pub(crate) fn aux_get_graph(
                 branching_sessions,
                 group_branches,
                 cfsm,
+                branches,
             )
         } else if stack.len() == 1 && stack[0] == "RoleBroadcast" {
             // If it is a broadcasting role
@@ -636,7 +772,8 @@ pub(crate) fn aux_get_graph(
                         number_of_send += 1;
 
                         // Should be a specific `enum`
-                        let payload = &get_head_payload_continuation(session)?[1];
+                        let payload = get_head_payload_continuation(session)?[1].to_string();
+                        let payload = get_branching_payload(&payload);
 
                         // Update all_choices
                         if let Some(choice) = branches_receivers.get(payload) {
@@ -689,6 +826,10 @@ pub(crate) fn aux_get_graph(
                             .insert(temp_current_branch.clone(), previous_node);
                     }
 
+                    // The edges and branches added from now on belong to the current branch
+                    let first_edge = g.edge_count();
+                    let first_branch = branches.len();
+
                     let result = aux_get_graph(
                         current_role,
                         session,
@@ -704,11 +845,21 @@ pub(crate) fn aux_get_graph(
                         branching_sessions.clone(),
                         group_branches.clone(),
                         cfsm,
+                        branches,
                     )?;
 
                     g = result.0;
                     cfsm = result.1;
 
+                    record_branch(
+                        &g,
+                        branches,
+                        previous_node,
+                        (first_edge, first_branch),
+                        &current_branch,
+                        true,
+                    );
+
                     // Insert the new node/branch in the list of the ones already seen
                     let index_group = if let Some(index) = group_branches.get(&current_branch) {
                         index
@@ -745,7 +896,7 @@ pub(crate) fn get_graph_session(
     branches_receivers: HashMap<String, HashMapStrVecOfStr>,
     branching_sessions: HashMapStrVecOfStr,
     group_branches: HashMap<String, i32>,
-) -> Result<(GraphOfStrStr, VecOfStr), Box<dyn Error>> {
+) -> Result<(GraphOfStrStr, VecOfStr, VecOfBranches), Box<dyn Error>> {
     // Create the new graph that will be returned in the end
     let mut g = Graph::<String, String>::new();
 
@@ -773,6 +924,9 @@ pub(crate) fn get_graph_session(
 
     let cfsm: VecOfTuple = Vec::new();
 
+    // The edges starting each branch chosen by the current role
+    let mut branches: VecOfBranches = Vec::new();
+
     let (result, cfsm) = aux_get_graph(
         current_role,
         full_session,
@@ -788,6 +942,7 @@ pub(crate) fn get_graph_session(
         branching_sessions,
         group_branches,
         cfsm,
+        &mut branches,
     )?;
 
     // The missing strings for starting cfsm
@@ -805,7 +960,7 @@ pub(crate) fn get_graph_session(
     cfsm_result.push(format!(".marking {}0", current_role));
     cfsm_result.push(".end".to_string());
 
-    Ok((result, cfsm_result))
+    Ok((result, cfsm_result, branches))
 }
 
 ///////////////////////
@@ -983,6 +1138,29 @@ mod tests {
             clean_random,
             get_head_payload_continuation(dirty_random).unwrap()
         );
+
+        // Cancellation
+        let dirty_cancel = "Recv<(End,Branching0fromCtoA),End>";
+
+        let clean_cancel = vec!["Recv", "(End,Branching0fromCtoA)", "End"];
+
+        assert_eq!(
+            clean_cancel,
+            get_head_payload_continuation(dirty_cancel).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_branching_payload() {
+        assert_eq!(
+            "Branching0fromCtoA",
+            get_branching_payload("(End,Branching0fromCtoA)")
+        );
+        assert_eq!(
+            "Branching0fromCtoA",
+            get_branching_payload("Branching0fromCtoA")
+        );
+        assert_eq!("(i32,i32)", get_branching_payload("(i32,i32)"));
     }
 
     #[test]
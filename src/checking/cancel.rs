@@ -0,0 +1,298 @@
+//! This module contains the functions for checking the protocols
+//! built with [`bundle_impl_with_cancel!`] and
+//! [`bundle_impl_with_enum_and_cancel!`] against cancellation.
+//!
+//! In such protocols, any role may cancel its session at any time,
+//! either by calling `cancel` or by failing.
+//! A role whose next action involves a cancelled role fails in turn,
+//! and is cancelled as well.
+//!
+//! The global graph built by [`cancellation_product`] extends the
+//! [`synchronous_product`] with these transitions, labelled
+//! `RoleA: cancel` when `RoleA` cancels its session and
+//! `RoleB: cancelled by RoleA` when the cancellation of `RoleA` reaches `RoleB`.
+//! In the labels of the nodes, a cancelled role is written as `RoleA:cancelled`.
+//!
+//! [`bundle_impl_with_cancel!`]: crate::bundle_impl_with_cancel
+//! [`bundle_impl_with_enum_and_cancel!`]: crate::bundle_impl_with_enum_and_cancel
+//! [`synchronous_product`]: crate::checking::product::synchronous_product
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"checking"` feature.*
+
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+
+use super::deadlock::{find_cycle, Deadlock};
+use super::product::{is_local_terminal, local_actions, Action};
+
+type GraphOfStrStr = Graph<String, String>;
+
+// A global state: the local node of each role, `None` if the role is cancelled.
+type State = Vec<Option<NodeIndex<u32>>>;
+
+// Label of a global node, such as "RoleA:1,RoleB:cancelled,RoleC:0".
+fn state_label(roles: &[String], state: &[Option<NodeIndex<u32>>]) -> String {
+    roles
+        .iter()
+        .zip(state.iter())
+        .map(|(role, node)| match node {
+            Some(node) => format!("{}:{}", role, node.index()),
+            None => format!("{}:cancelled", role),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// The peer involved in an action.
+fn peer(action: &Action) -> Option<&String> {
+    match action {
+        Action::Send(_, peer, _) | Action::Recv(_, peer, _) => Some(peer),
+        Action::Silent => None,
+    }
+}
+
+// All the transitions from `state`, along with whether they are a cancellation
+// decided by a role, and not a consequence of the protocol.
+fn transitions(
+    roles: &[String],
+    graphs: &HashMap<String, GraphOfStrStr>,
+    state: &[Option<NodeIndex<u32>>],
+) -> Vec<(State, String, bool)> {
+    let mut result = Vec::new();
+
+    for (index_sender, sender) in roles.iter().enumerate() {
+        let node_sender = match state[index_sender] {
+            Some(node) => node,
+            None => continue,
+        };
+
+        let actions = local_actions(&graphs[sender], node_sender);
+
+        for (action, next_sender) in actions.iter() {
+            let index_peer = match peer(action).and_then(|p| roles.iter().position(|r| r == p)) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            match (action, state[index_peer]) {
+                // The peer is cancelled: the role fails
+                (_, None) => {
+                    let mut next = state.to_vec();
+                    next[index_sender] = None;
+
+                    let label = format!("{}: cancelled by {}", sender, roles[index_peer]);
+
+                    if !result.iter().any(|(s, l, _)| s == &next && l == &label) {
+                        result.push((next, label, false));
+                    }
+                }
+                // Both roles are running: synchronous interaction
+                (Action::Send(_, receiver, payload), Some(node_receiver)) => {
+                    for (dual, next_receiver) in local_actions(&graphs[receiver], node_receiver) {
+                        if dual == Action::Recv(receiver.clone(), sender.clone(), payload.clone()) {
+                            let mut next = state.to_vec();
+                            next[index_sender] = Some(*next_sender);
+                            next[index_peer] = Some(next_receiver);
+
+                            result.push((
+                                next,
+                                format!("{}->{}: {}", sender, receiver, payload),
+                                false,
+                            ));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The role may cancel its session as long as it has not terminated
+        if !actions.is_empty() {
+            let mut next = state.to_vec();
+            next[index_sender] = None;
+            result.push((next, format!("{}: cancel", sender), true));
+        }
+    }
+
+    result
+}
+
+/// Build the global graph of a protocol where every role may cancel its session,
+/// from the CFSMs returned by [`checker_concat!`].
+///
+/// The node `0` of the returned graph is the starting state.
+/// Each node is labelled with the index of the local node of each role,
+/// or `cancelled`, such as `RoleA:1,RoleB:cancelled,RoleC:0`,
+/// and each edge either with an interaction, such as `RoleA->RoleB: i32`,
+/// or with a cancellation, such as `RoleA: cancel` or `RoleB: cancelled by RoleA`.
+///
+/// [`checker_concat!`]: crate::checker_concat
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn cancellation_product(graphs: &HashMap<String, GraphOfStrStr>) -> GraphOfStrStr {
+    let mut roles = graphs.keys().cloned().collect::<Vec<_>>();
+    roles.sort();
+
+    let state_seen = RandomState::new();
+    let mut seen: HashMap<State, NodeIndex<u32>> = HashMap::with_hasher(state_seen);
+
+    let mut graph = Graph::<String, String>::new();
+
+    let start = vec![Some(NodeIndex::new(0)); roles.len()];
+    let start_node = graph.add_node(state_label(&roles, &start));
+    seen.insert(start.clone(), start_node);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(state) = queue.pop_front() {
+        let current = seen[&state];
+
+        for (next, label, _) in transitions(&roles, graphs, &state) {
+            let target = if let Some(target) = seen.get(&next) {
+                *target
+            } else {
+                let target = graph.add_node(state_label(&roles, &next));
+                seen.insert(next.clone(), target);
+                queue.push_back(next);
+                target
+            };
+
+            if !graph
+                .edges_connecting(current, target)
+                .any(|e| e.weight() == &label)
+            {
+                graph.add_edge(current, target, label);
+            }
+        }
+    }
+
+    graph
+}
+
+/// Check that, whichever roles cancel their sessions and whenever they do,
+/// every role either terminates or is cancelled in turn,
+/// and that no role blocks forever after another one cancels.
+///
+/// The CFSMs are the ones returned by [`checker_concat!`].
+///
+/// Returns `None` if the protocol is safe, or the first state found
+/// where some roles are blocked, that is a state where no interaction
+/// is possible and no cancellation reaches the roles which
+/// have neither terminated nor been cancelled.
+/// The trace of the returned [`Deadlock`] follows the labels
+/// of the edges of the [`cancellation_product`].
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::checking::cancel::check_cancellation;
+///
+/// let (graphs, _) = mpstthree::checker_concat!(EndpointA, EndpointB, EndpointC).unwrap();
+///
+/// assert_eq!(check_cancellation(&graphs), None);
+/// ```
+///
+/// [`checker_concat!`]: crate::checker_concat
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn check_cancellation(graphs: &HashMap<String, GraphOfStrStr>) -> Option<Deadlock> {
+    let mut roles = graphs.keys().cloned().collect::<Vec<_>>();
+    roles.sort();
+
+    let state_parents = RandomState::new();
+    let mut parents: HashMap<State, Option<(State, String)>> = HashMap::with_hasher(state_parents);
+
+    let start = vec![Some(NodeIndex::new(0)); roles.len()];
+    parents.insert(start.clone(), None);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(state) = queue.pop_front() {
+        let successors = transitions(&roles, graphs, &state);
+
+        let done = roles
+            .iter()
+            .zip(state.iter())
+            .all(|(role, node)| match node {
+                Some(node) => is_local_terminal(&graphs[role], *node),
+                None => true,
+            });
+
+        // The cancellations decided by the roles do not count as progress
+        if !done && successors.iter().all(|(_, _, voluntary)| *voluntary) {
+            let mut trace = Vec::new();
+            let mut current = &state;
+            while let Some(Some((parent, step))) = parents.get(current) {
+                trace.push(step.clone());
+                current = parent;
+            }
+            trace.reverse();
+
+            let mut waits: Vec<Vec<(usize, String)>> = vec![Vec::new(); roles.len()];
+            let mut blocked = Vec::new();
+
+            for (index_role, role) in roles.iter().enumerate() {
+                let node = match state[index_role] {
+                    Some(node) => node,
+                    None => continue,
+                };
+
+                let mut expected = Vec::new();
+
+                for (action, _) in local_actions(&graphs[role], node) {
+                    let label = match &action {
+                        Action::Send(subject, peer, payload) => {
+                            format!("{}!{}: {}", subject, peer, payload)
+                        }
+                        Action::Recv(subject, peer, payload) => {
+                            format!("{}?{}: {}", subject, peer, payload)
+                        }
+                        Action::Silent => continue,
+                    };
+
+                    if let Some(index_peer) =
+                        peer(&action).and_then(|p| roles.iter().position(|r| r == p))
+                    {
+                        waits[index_role].push((index_peer, label.clone()));
+                    }
+
+                    expected.push(label);
+                }
+
+                if !expected.is_empty() {
+                    blocked.push(format!("{} waits for {}", role, expected.join(" or ")));
+                }
+            }
+
+            return Some(Deadlock {
+                trace,
+                blocked,
+                cycle: find_cycle(&waits)
+                    .into_iter()
+                    .map(|(from, to, label)| {
+                        format!("{} waits for {} on {}", roles[from], roles[to], label)
+                    })
+                    .collect(),
+            });
+        }
+
+        for (next, step, _) in successors {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some((state.clone(), step)));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,454 @@
+//! This module contains the functions for checking that
+//! several interleaved sessions, such as the ones created with
+//! [`bundle_impl_interleaved_with_enum_and_cancel!`] and
+//! [`fork_mpst_multi_interleaved!`], are deadlock-free together.
+//!
+//! Each bundle may be deadlock-free on its own while their composition
+//! deadlocks: a role shared by two bundles runs in a single thread, and can
+//! only wait on one of its sessions at a time.
+//! The checker builds the product of the CFSMs of all the bundles, where
+//! the roles shared between bundles are merged into a single process.
+//! Such a process commits to one of its sessions before each action,
+//! and every possible commitment is explored.
+//! As with the [`KMC`] tool, the sends are buffered: each channel, from a role
+//! to another one of the same bundle, is a FIFO queue holding at most `k` payloads,
+//! and only a send on a full queue or a receive on an empty one waits.
+//!
+//! When a reachable state is stuck while some roles have not terminated,
+//! the interactions leading to this state are returned,
+//! along with the cyclic wait between the processes.
+//!
+//! [`bundle_impl_interleaved_with_enum_and_cancel!`]: crate::bundle_impl_interleaved_with_enum_and_cancel
+//! [`fork_mpst_multi_interleaved!`]: crate::fork_mpst_multi_interleaved
+//! [`KMC`]: https://github.com/julien-lange/kmc
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"checking"` feature.*
+
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use super::product::{is_local_terminal, local_actions, Action};
+
+type GraphOfStrStr = Graph<String, String>;
+
+/// A deadlock found by [`check_interleaved`] or by [`check_cancellation`].
+///
+/// [`check_cancellation`]: crate::checking::cancel::check_cancellation
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub struct Deadlock {
+    /// The steps leading from the starting state to the deadlock,
+    /// such as `RoleA!RoleB: i32 (bundle)` for a send,
+    /// `RoleB?RoleA: i32 (bundle)` for a receive,
+    /// or `RoleA (bundle) is selected` when a shared role
+    /// commits to one of its sessions, for [`check_interleaved`],
+    /// and the labels of the edges of the cancellation product,
+    /// such as `RoleA->RoleB: i32`, for [`check_cancellation`].
+    ///
+    /// [`check_cancellation`]: crate::checking::cancel::check_cancellation
+    pub trace: Vec<String>,
+    /// The processes which have neither terminated nor been cancelled in the stuck state,
+    /// with the actions each of them is waiting for.
+    pub blocked: Vec<String>,
+    /// The cyclic wait between the processes,
+    /// such as `RoleA (bundle) waits for RoleB (bundle)`.
+    /// Empty if the processes are waiting on terminated processes.
+    pub cycle: Vec<String>,
+}
+
+impl fmt::Display for Deadlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Deadlock after:")?;
+        for step in self.trace.iter() {
+            writeln!(f, "    {}", step)?;
+        }
+        writeln!(f, "Blocked processes:")?;
+        for process in self.blocked.iter() {
+            writeln!(f, "    {}", process)?;
+        }
+        if self.cycle.is_empty() {
+            write!(f, "No cyclic wait: some processes wait on terminated ones")
+        } else {
+            write!(f, "Cyclic wait:")?;
+            for wait in self.cycle.iter() {
+                write!(f, "\n    {}", wait)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+// A role of a bundle.
+struct Component<'a> {
+    bundle: usize,
+    role: String,
+    graph: &'a GraphOfStrStr,
+    process: usize,
+}
+
+// A global state: the local node of each component,
+// for each process, the component it is committed to, if any,
+// and the payloads in the queue of each channel,
+// from the component `sender` to the component `receiver`
+// at `sender * components + receiver`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct State {
+    nodes: Vec<NodeIndex<u32>>,
+    focus: Vec<Option<usize>>,
+    queues: Vec<VecDeque<String>>,
+}
+
+/// Check that the sessions of several bundles are deadlock-free when the
+/// roles listed in `shared` are run by the same threads,
+/// and the channels hold at most `k` payloads each.
+///
+/// `bundles` gives a name to each bundle along with its CFSMs,
+/// as returned by [`checker_concat!`].
+/// Each element of `shared` is a group of roles, identified by the name of
+/// their bundle and their name, which are run by the same thread.
+/// A role cannot be part of several groups,
+/// and a group cannot contain two roles of the same bundle.
+/// `k` must be at least `1`, as for the [`KMC`] tool.
+///
+/// Returns `None` if no deadlock is reachable, whatever the order in which
+/// the shared roles handle their sessions, or the first deadlock found.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::checking::deadlock::check_interleaved;
+///
+/// let (graphs_cb, _) =
+///     mpstthree::checker_concat!(EndpointApi, EndpointControllerCB, EndpointStorage, EndpointUser)
+///         .unwrap();
+/// let (graphs_log, _) = mpstthree::checker_concat!(EndpointControllerLog, EndpointLogs).unwrap();
+///
+/// let deadlock = check_interleaved(
+///     &[("cb", &graphs_cb), ("log", &graphs_log)],
+///     &[&[("cb", "RoleControllerCB"), ("log", "RoleControllerLog")]],
+///     1,
+/// )
+/// .unwrap();
+///
+/// assert!(deadlock.is_none());
+/// ```
+///
+/// [`checker_concat!`]: crate::checker_concat
+/// [`KMC`]: https://github.com/julien-lange/kmc
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn check_interleaved(
+    bundles: &[(&str, &HashMap<String, GraphOfStrStr>)],
+    shared: &[&[(&str, &str)]],
+    k: usize,
+) -> Result<Option<Deadlock>, Box<dyn Error>> {
+    if k == 0 {
+        return Err("The channels must hold at least one payload".into());
+    }
+
+    // Build the components, sorted by bundle then by role
+    let mut components = Vec::new();
+
+    for (bundle, (_, graphs)) in bundles.iter().enumerate() {
+        let mut roles = graphs.keys().cloned().collect::<Vec<_>>();
+        roles.sort();
+
+        for role in roles {
+            components.push(Component {
+                bundle,
+                graph: &graphs[&role],
+                role,
+                process: 0,
+            });
+        }
+    }
+
+    let index_of = |bundle: &str, role: &str| -> Result<usize, Box<dyn Error>> {
+        let index_bundle = bundles
+            .iter()
+            .position(|(name, _)| *name == bundle)
+            .ok_or_else(|| format!("Unknown bundle {}", bundle))?;
+        components
+            .iter()
+            .position(|c| c.bundle == index_bundle && c.role == role)
+            .ok_or_else(|| format!("Unknown role {} in bundle {}", role, bundle).into())
+    };
+
+    // Group the components into processes
+    let mut processes: Vec<Vec<usize>> = Vec::new();
+    let mut assigned = vec![false; components.len()];
+
+    for group in shared.iter() {
+        let mut process = Vec::new();
+
+        for (bundle, role) in group.iter() {
+            let index = index_of(bundle, role)?;
+
+            if assigned[index] {
+                return Err(format!("The role {} of {} is shared twice", role, bundle).into());
+            }
+            if process
+                .iter()
+                .any(|other: &usize| components[*other].bundle == components[index].bundle)
+            {
+                return Err(format!("Two roles of {} are run by the same thread", bundle).into());
+            }
+
+            assigned[index] = true;
+            process.push(index);
+        }
+
+        processes.push(process);
+    }
+
+    for (index, is_assigned) in assigned.iter().enumerate() {
+        if !is_assigned {
+            processes.push(vec![index]);
+        }
+    }
+
+    for (index_process, process) in processes.iter().enumerate() {
+        for index in process.iter() {
+            components[*index].process = index_process;
+        }
+    }
+
+    let name = |index: usize| {
+        format!(
+            "{} ({})",
+            components[index].role, bundles[components[index].bundle].0
+        )
+    };
+
+    let process_name = |index_process: usize| {
+        processes[index_process]
+            .iter()
+            .map(|index| name(*index))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    };
+
+    // Whether a component can currently act.
+    let is_active = |state: &State, index: usize| {
+        let process = components[index].process;
+        processes[process].len() == 1 || state.focus[process] == Some(index)
+    };
+
+    // Explore the product
+    let state_seen = RandomState::new();
+    let mut parents: HashMap<State, Option<(State, String)>> = HashMap::with_hasher(state_seen);
+
+    let start = State {
+        nodes: vec![NodeIndex::new(0); components.len()],
+        focus: vec![None; processes.len()],
+        queues: vec![VecDeque::new(); components.len() * components.len()],
+    };
+    parents.insert(start.clone(), None);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(state) = queue.pop_front() {
+        let mut successors = Vec::new();
+
+        // Sends and receives
+        for index in 0..components.len() {
+            if !is_active(&state, index) {
+                continue;
+            }
+
+            for (action, next_node) in local_actions(components[index].graph, state.nodes[index]) {
+                let (peer_role, payload, sending) = match &action {
+                    Action::Send(_, peer, payload) => (peer, payload, true),
+                    Action::Recv(_, peer, payload) => (peer, payload, false),
+                    Action::Silent => continue,
+                };
+
+                let peer = match components
+                    .iter()
+                    .position(|c| c.bundle == components[index].bundle && &c.role == peer_role)
+                {
+                    Some(peer) => peer,
+                    None => continue,
+                };
+
+                let mut next = state.clone();
+
+                if sending {
+                    let queue = &mut next.queues[index * components.len() + peer];
+                    if queue.len() >= k {
+                        continue;
+                    }
+                    queue.push_back(payload.clone());
+                } else {
+                    let queue = &mut next.queues[peer * components.len() + index];
+                    if queue.front() != Some(payload) {
+                        continue;
+                    }
+                    queue.pop_front();
+                }
+
+                next.nodes[index] = next_node;
+                next.focus[components[index].process] = None;
+
+                successors.push((
+                    next,
+                    format!(
+                        "{}{}{}: {} ({})",
+                        components[index].role,
+                        if sending { '!' } else { '?' },
+                        peer_role,
+                        payload,
+                        bundles[components[index].bundle].0
+                    ),
+                ));
+            }
+        }
+
+        // Commitments of the shared roles
+        for (index_process, process) in processes.iter().enumerate() {
+            if process.len() == 1 || state.focus[index_process].is_some() {
+                continue;
+            }
+
+            for index in process.iter() {
+                if !local_actions(components[*index].graph, state.nodes[*index]).is_empty() {
+                    let mut next = state.clone();
+                    next.focus[index_process] = Some(*index);
+                    successors.push((next, format!("{} is selected", name(*index))));
+                }
+            }
+        }
+
+        let terminated = (0..components.len())
+            .all(|index| is_local_terminal(components[index].graph, state.nodes[index]));
+
+        if successors.is_empty() && !terminated {
+            // Rebuild the trace
+            let mut trace = Vec::new();
+            let mut current = &state;
+            while let Some(Some((parent, step))) = parents.get(current) {
+                trace.push(step.clone());
+                current = parent;
+            }
+            trace.reverse();
+
+            // The wait-for graph between the processes
+            let mut waits: Vec<Vec<(usize, String)>> = vec![Vec::new(); processes.len()];
+            let mut blocked = Vec::new();
+
+            for (index_process, process) in processes.iter().enumerate() {
+                let mut expected = Vec::new();
+
+                for index in process.iter() {
+                    if !is_active(&state, *index) && process.len() > 1 {
+                        continue;
+                    }
+
+                    for (action, _) in local_actions(components[*index].graph, state.nodes[*index])
+                    {
+                        let (peer, label) = match &action {
+                            Action::Send(subject, peer, payload) => {
+                                (peer, format!("{}!{}: {}", subject, peer, payload))
+                            }
+                            Action::Recv(subject, peer, payload) => {
+                                (peer, format!("{}?{}: {}", subject, peer, payload))
+                            }
+                            Action::Silent => continue,
+                        };
+
+                        if let Some(other) = components
+                            .iter()
+                            .position(|c| c.bundle == components[*index].bundle && &c.role == peer)
+                        {
+                            waits[index_process].push((components[other].process, label.clone()));
+                        }
+
+                        expected.push(label);
+                    }
+                }
+
+                if !expected.is_empty() {
+                    blocked.push(format!(
+                        "{} waits for {}",
+                        process_name(index_process),
+                        expected.join(" or ")
+                    ));
+                }
+            }
+
+            return Ok(Some(Deadlock {
+                trace,
+                blocked,
+                cycle: find_cycle(&waits)
+                    .into_iter()
+                    .map(|(from, to, label)| {
+                        format!(
+                            "{} waits for {} on {}",
+                            process_name(from),
+                            process_name(to),
+                            label
+                        )
+                    })
+                    .collect(),
+            }));
+        }
+
+        for (next, step) in successors {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some((state.clone(), step)));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// Find a cycle in the wait-for graph, as a list of (waiting, awaited, action).
+pub(crate) fn find_cycle(waits: &[Vec<(usize, String)>]) -> Vec<(usize, usize, String)> {
+    for start in 0..waits.len() {
+        // Depth-first search, keeping the current path
+        let mut path: Vec<(usize, usize)> = vec![(start, 0)];
+        let mut visited = vec![false; waits.len()];
+        visited[start] = true;
+
+        while let Some((process, next_edge)) = path.last().cloned() {
+            if next_edge >= waits[process].len() {
+                path.pop();
+                continue;
+            }
+
+            path.last_mut().unwrap().1 += 1;
+            let (target, _) = &waits[process][next_edge];
+
+            if *target == start {
+                return path
+                    .iter()
+                    .map(|(from, edge)| {
+                        let (to, label) = &waits[*from][*edge - 1];
+                        (*from, *to, label.clone())
+                    })
+                    .collect();
+            }
+
+            if !visited[*target] {
+                visited[*target] = true;
+                path.push((*target, 0));
+            }
+        }
+    }
+
+    Vec::new()
+}
@@ -0,0 +1,319 @@
+//! This module contains the functions for exporting
+//! the CFSMs of each role and the global graph of a protocol
+//! as DOT, GraphML, JSON or as the communicating automata
+//! read by the [`KMC`] tool.
+//!
+//! # JSON schema
+//!
+//! The JSON export is a single object:
+//!
+//! ```json
+//! {
+//!     "protocol": "name_file",
+//!     "roles": ["RoleA", "RoleB"],
+//!     "cfsms": {
+//!         "RoleA": {
+//!             "initial": 0,
+//!             "nodes": [{ "id": 0, "label": "0" }, { "id": 1, "label": "1" }],
+//!             "edges": [{ "source": 0, "target": 1, "label": "RoleA!RoleB: i32" }]
+//!         },
+//!         "RoleB": { "...": "..." }
+//!     },
+//!     "global": {
+//!         "initial": 0,
+//!         "nodes": [{ "id": 0, "label": "RoleA:0,RoleB:0" }],
+//!         "edges": [{ "source": 0, "target": 1, "label": "RoleA->RoleB: i32" }]
+//!     }
+//! }
+//! ```
+//!
+//! * `roles` is sorted alphabetically, as are the keys of `cfsms`.
+//! * `id` is the index of the node in the graph, and `initial` is always `0`.
+//! * The labels of the edges of the CFSMs are either `Subject!Receiver: Payload`,
+//!   `Subject?Sender: Payload`, `0` for the end of a branch or `µ` for a recursion.
+//! * The labels of the edges of the global graph are `Sender->Receiver: Payload`.
+//!
+//! [`KMC`]: https://github.com/julien-lange/kmc
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"checking"` feature.*
+
+use petgraph::dot::Dot;
+use petgraph::visit::EdgeRef;
+use petgraph::Graph;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::{create_dir_all, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::product::synchronous_product;
+
+type GraphOfStrStr = Graph<String, String>;
+
+/// The formats available for exporting the graphs of a protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub enum Format {
+    /// One `.dot` file per role, and one for the global graph.
+    Dot,
+    /// One `.graphml` file per role, and one for the global graph.
+    GraphML,
+    /// A single `.json` file, following the schema of this module.
+    Json,
+    /// A single `.txt` file containing the communicating automata read by the [`KMC`] tool.
+    ///
+    /// [`KMC`]: https://github.com/julien-lange/kmc
+    Cfsm,
+}
+
+impl Format {
+    /// The extension of the files written for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Dot => "dot",
+            Format::GraphML => "graphml",
+            Format::Json => "json",
+            Format::Cfsm => "txt",
+        }
+    }
+}
+
+// Escape a string for XML attributes and text.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// Escape a string for JSON strings.
+fn escape_json(s: &str) -> String {
+    let mut result = String::new();
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Serialise a graph as DOT.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn to_dot(graph: &GraphOfStrStr) -> String {
+    format!("{}", Dot::new(graph))
+}
+
+/// Serialise a graph as GraphML, the graph being identified by `name`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn to_graphml(name: &str, graph: &GraphOfStrStr) -> String {
+    let mut result = String::new();
+
+    result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    result.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    result.push_str("  <key id=\"label\" for=\"all\" attr.name=\"label\" attr.type=\"string\"/>\n");
+    result.push_str(&format!(
+        "  <graph id=\"{}\" edgedefault=\"directed\">\n",
+        escape_xml(name)
+    ));
+
+    for node in graph.node_indices() {
+        result.push_str(&format!(
+            "    <node id=\"n{}\"><data key=\"label\">{}</data></node>\n",
+            node.index(),
+            escape_xml(&graph[node])
+        ));
+    }
+
+    for edge in graph.edge_references() {
+        result.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"n{}\" target=\"n{}\"><data key=\"label\">{}</data></edge>\n",
+            edge.id().index(),
+            edge.source().index(),
+            edge.target().index(),
+            escape_xml(edge.weight())
+        ));
+    }
+
+    result.push_str("  </graph>\n");
+    result.push_str("</graphml>\n");
+
+    result
+}
+
+// Serialise a graph as a JSON object, as described in the schema of this module.
+fn graph_to_json(graph: &GraphOfStrStr, indent: &str) -> String {
+    let nodes = graph
+        .node_indices()
+        .map(|node| {
+            format!(
+                "{}    {{ \"id\": {}, \"label\": \"{}\" }}",
+                indent,
+                node.index(),
+                escape_json(&graph[node])
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    let edges = graph
+        .edge_references()
+        .map(|edge| {
+            format!(
+                "{}    {{ \"source\": {}, \"target\": {}, \"label\": \"{}\" }}",
+                indent,
+                edge.source().index(),
+                edge.target().index(),
+                escape_json(edge.weight())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        "{{\n{indent}  \"initial\": 0,\n{indent}  \"nodes\": [\n{}\n{indent}  ],\n{indent}  \"edges\": [\n{}\n{indent}  ]\n{indent}}}",
+        nodes,
+        edges,
+        indent = indent
+    )
+}
+
+/// Serialise the CFSMs of all the roles and the global graph
+/// of the protocol `name` as JSON, following the schema of this module.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn to_json(name: &str, graphs: &HashMap<String, GraphOfStrStr>) -> String {
+    let mut roles = graphs.keys().cloned().collect::<Vec<_>>();
+    roles.sort();
+
+    let cfsms = roles
+        .iter()
+        .map(|role| {
+            format!(
+                "    \"{}\": {}",
+                escape_json(role),
+                graph_to_json(&graphs[role], "    ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        "{{\n  \"protocol\": \"{}\",\n  \"roles\": [{}],\n  \"cfsms\": {{\n{}\n  }},\n  \"global\": {}\n}}\n",
+        escape_json(name),
+        roles
+            .iter()
+            .map(|role| format!("\"{}\"", escape_json(role)))
+            .collect::<Vec<_>>()
+            .join(", "),
+        cfsms,
+        graph_to_json(&synchronous_product(graphs), "  ")
+    )
+}
+
+// Write `content` into `output_dir/file_name` and return the path of the file.
+fn write_file(
+    output_dir: &Path,
+    file_name: &str,
+    content: &str,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let path = output_dir.join(file_name);
+    let mut file = File::create(&path)?;
+    write!(file, "{}", content)?;
+    Ok(path)
+}
+
+/// Write the CFSMs of all the roles and the global graph of the protocol `name`
+/// into `output_dir`, for each of the provided formats.
+/// The folder is created if missing.
+///
+/// Returns the paths of the written files.
+///
+/// The files are named `name_RoleX.dot` and `name_global.dot` for [`Format::Dot`],
+/// `name_RoleX.graphml` and `name_global.graphml` for [`Format::GraphML`],
+/// and `name.json` for [`Format::Json`].
+/// The communicating automata of [`Format::Cfsm`] are only written by the checker,
+/// which is the only one to know them: use [`checker_concat!`] for this format.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::checking::export::{export, Format};
+///
+/// let (graphs, _) = mpstthree::checker_concat!(EndpointA, EndpointB, EndpointC).unwrap();
+///
+/// export("protocol", "graphs", &graphs, &[Format::Dot, Format::Json]).unwrap();
+/// ```
+///
+/// [`checker_concat!`]: crate::checker_concat
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn export<P: AsRef<Path>>(
+    name: &str,
+    output_dir: P,
+    graphs: &HashMap<String, GraphOfStrStr>,
+    formats: &[Format],
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let output_dir = output_dir.as_ref();
+    create_dir_all(output_dir)?;
+
+    let mut roles = graphs.keys().cloned().collect::<Vec<_>>();
+    roles.sort();
+
+    let mut result = Vec::new();
+
+    for format in formats {
+        match format {
+            Format::Dot | Format::GraphML => {
+                let serialise = |graph_name: &str, graph: &GraphOfStrStr| match format {
+                    Format::Dot => to_dot(graph),
+                    _ => to_graphml(graph_name, graph),
+                };
+
+                for role in roles.iter() {
+                    let graph_name = format!("{}_{}", name, role);
+                    result.push(write_file(
+                        output_dir,
+                        &format!("{}.{}", graph_name, format.extension()),
+                        &serialise(&graph_name, &graphs[role]),
+                    )?);
+                }
+
+                let graph_name = format!("{}_global", name);
+                result.push(write_file(
+                    output_dir,
+                    &format!("{}.{}", graph_name, format.extension()),
+                    &serialise(&graph_name, &synchronous_product(graphs)),
+                )?);
+            }
+            Format::Json => {
+                result.push(write_file(
+                    output_dir,
+                    &format!("{}.{}", name, format.extension()),
+                    &to_json(name, graphs),
+                )?);
+            }
+            Format::Cfsm => {}
+        }
+    }
+
+    Ok(result)
+}
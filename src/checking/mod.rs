@@ -7,6 +7,7 @@
 //! *This module is available only if MultiCrusty is built with
 //! the `"checking"` feature.*
 
+use petgraph::graph::EdgeIndex;
 use petgraph::Graph;
 
 use std::collections::hash_map::RandomState;
@@ -14,15 +15,33 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fs::{create_dir_all, remove_file, File};
 use std::io::Write;
+use std::path::Path;
 use std::process::Command;
 use std::str;
 
 #[doc(hidden)]
 mod aux_checker;
 
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub mod cancel;
+
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub mod deadlock;
+
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub mod export;
+
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub mod product;
+
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub mod temporal;
+
 use aux_checker::*;
+use export::Format;
 
 type HashGraph = HashMap<String, Graph<String, String>>;
+type HashBranches = HashMap<String, Vec<(EdgeIndex<u32>, String, bool)>>;
 
 /// The macro that allows to create digraphs from each endpoint,
 /// along with `enum` if needed. You can also provide the name of
@@ -41,6 +60,9 @@ type HashGraph = HashMap<String, Graph<String, String>>;
 /// # Arguments
 ///
 /// * \[Optional\] The name of the new file after running the [`KMC`] tool
+/// * \[Optional\] The folder where the files are written, followed by the formats to export, among
+///   `Dot`, `GraphML`, `Json` and `Cfsm`, between brackets. Only the `Cfsm` format runs the [`KMC`]
+///   tool. See the [`export`] module for the names of the files and the JSON schema.
 /// * Each starting endpoint, separated by a comma
 /// * \[Optional\] Each new `MeshedChannels` adopted by each sender of each choice, along with all
 ///   the different branches sent.
@@ -73,29 +95,46 @@ type HashGraph = HashMap<String, Graph<String, String>>;
 /// )
 /// ```
 ///
+/// The same call can also write the CFSMs and the global graph into the `graphs` folder,
+/// as DOT and JSON, without running the [`KMC`] tool:
+///
+/// ```ignore
+/// mpstthree::checker_concat!(
+///     "video_stream",
+///     "graphs",
+///     [Dot, Json],
+///     EndpointAFull,
+///     EndpointCFull,
+///     EndpointBFull
+///     =>
+///     [
+///         EndpointAVideo,
+///         Branches0BtoA, Video,
+///         Branches0CtoA, Video
+///     ],
+///     [
+///         EndpointAEnd,
+///         Branches0BtoA, End,
+///         Branches0CtoA, End
+///     ]
+/// )
+/// ```
+///
 /// [`KMC`]: https://github.com/julien-lange/kmc
+/// [`export`]: crate::checking::export
 ///
 /// *This macro is available only if MultiCrusty is built with
 /// the `"checking"` feature.*
 #[macro_export]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
 macro_rules! checker_concat {
+    // Internal arms, building the graphs with the function `$checker`,
+    // which has the same arguments as `mpstthree::checking::checker`
     (
-        $(
-            $sessiontype: ty
-        ),+ $(,)?
-    ) => {
-        {
-            mpstthree::checker_concat!(
-                "",
-                $(
-                    $sessiontype,
-                )+
-            )
-        }
-    };
-    (
+        @with $checker: path,
         $name_file: expr,
+        $output_dir: expr,
+        [ $( $format: ident ),* $(,)? ],
         $(
             $sessiontype: ty
         ),+ $(,)?
@@ -119,8 +158,10 @@ macro_rules! checker_concat {
             let branches_receivers: std::collections::HashMap<String, std::collections::HashMap<String, String>> =
                 std::collections::HashMap::with_hasher(state_branches);
 
-            mpstthree::checking::checker(
+            $checker(
                 $name_file,
+                $output_dir,
+                &[ $( mpstthree::checking::export::Format::$format , )* ],
                 sessions,
                 branches_receivers,
                 branching_sessions,
@@ -129,39 +170,10 @@ macro_rules! checker_concat {
         }
     };
     (
-        $(
-            $sessiontype: ty
-        ),+ $(,)?
-        =>
-        $(
-            [
-                $branch_stack: ty,
-                $(
-                    $choice: ty, $branch: ident
-                ),+ $(,)?
-            ]
-        ),+ $(,)?
-    ) => {
-        {
-            mpstthree::checker_concat!(
-                "",
-                $(
-                    $sessiontype,
-                )+
-                =>
-                $(
-                    [
-                        $branch_stack,
-                        $(
-                            $choice, $branch,
-                        )+
-                    ],
-                )+
-            )
-        }
-    };
-    (
+        @with $checker: path,
         $name_file: expr,
+        $output_dir: expr,
+        [ $( $format: ident ),* $(,)? ],
         $(
             $sessiontype: ty
         ),+ $(,)?
@@ -234,8 +246,60 @@ macro_rules! checker_concat {
             );
 
             // Create the graphs with the previous inputs
+            $checker(
+                $name_file,
+                $output_dir,
+                &[ $( mpstthree::checking::export::Format::$format , )* ],
+                sessions,
+                branches_receivers,
+                branching_sessions,
+                group_branches
+            )
+        }
+    };
+    (
+        $(
+            $sessiontype: ty
+        ),+ $(,)?
+    ) => {
+        {
+            mpstthree::checker_concat!(
+                "",
+                $(
+                    $sessiontype,
+                )+
+            )
+        }
+    };
+    (
+        $name_file: expr,
+        $(
+            $sessiontype: ty
+        ),+ $(,)?
+    ) => {
+        {
+            let mut sessions = Vec::new();
+
+            $(
+                sessions.push(String::from(std::any::type_name::<$sessiontype>()));
+            )+
+
+            let state_branching_sessions = std::collections::hash_map::RandomState::new();
+            let branching_sessions: std::collections::HashMap<String, String> =
+                std::collections::HashMap::with_hasher(state_branching_sessions);
+
+            let state_group_branches = std::collections::hash_map::RandomState::new();
+            let group_branches: std::collections::HashMap<String, i32> =
+                std::collections::HashMap::with_hasher(state_group_branches);
+
+            let state_branches = std::collections::hash_map::RandomState::new();
+            let branches_receivers: std::collections::HashMap<String, std::collections::HashMap<String, String>> =
+                std::collections::HashMap::with_hasher(state_branches);
+
             mpstthree::checking::checker(
                 $name_file,
+                "",
+                &[],
                 sessions,
                 branches_receivers,
                 branching_sessions,
@@ -243,35 +307,150 @@ macro_rules! checker_concat {
             )
         }
     };
+    (
+        $(
+            $sessiontype: ty
+        ),+ $(,)?
+        =>
+        $(
+            [
+                $branch_stack: ty,
+                $(
+                    $choice: ty, $branch: ident
+                ),+ $(,)?
+            ]
+        ),+ $(,)?
+    ) => {
+        {
+            mpstthree::checker_concat!(
+                "",
+                $(
+                    $sessiontype,
+                )+
+                =>
+                $(
+                    [
+                        $branch_stack,
+                        $(
+                            $choice, $branch,
+                        )+
+                    ],
+                )+
+            )
+        }
+    };
+    (
+        $name_file: expr,
+        $(
+            $sessiontype: ty
+        ),+ $(,)?
+        =>
+        $(
+            [
+                $branch_stack: ty,
+                $(
+                    $choice: ty, $branch: ident
+                ),+ $(,)?
+            ]
+        ),+ $(,)?
+    ) => {
+        {
+            mpstthree::checker_concat!(
+                $name_file,
+                "",
+                [],
+                $(
+                    $sessiontype,
+                )+
+                =>
+                $(
+                    [
+                        $branch_stack,
+                        $(
+                            $choice, $branch,
+                        )+
+                    ],
+                )+
+            )
+        }
+    };
+    (
+        $name_file: expr,
+        $output_dir: expr,
+        [ $( $format: ident ),* $(,)? ],
+        $(
+            $sessiontype: ty
+        ),+ $(,)?
+    ) => {
+        {
+            mpstthree::checker_concat!(
+                @with mpstthree::checking::checker,
+                $name_file,
+                $output_dir,
+                [ $( $format , )* ],
+                $(
+                    $sessiontype,
+                )+
+            )
+        }
+    };
+    (
+        $name_file: expr,
+        $output_dir: expr,
+        [ $( $format: ident ),* $(,)? ],
+        $(
+            $sessiontype: ty
+        ),+ $(,)?
+        =>
+        $(
+            [
+                $branch_stack: ty,
+                $(
+                    $choice: ty, $branch: ident
+                ),+ $(,)?
+            ]
+        ),+ $(,)?
+    ) => {
+        {
+            mpstthree::checker_concat!(
+                @with mpstthree::checking::checker,
+                $name_file,
+                $output_dir,
+                [ $( $format , )* ],
+                $(
+                    $sessiontype,
+                )+
+                =>
+                $(
+                    [
+                        $branch_stack,
+                        $(
+                            $choice, $branch,
+                        )+
+                    ],
+                )+
+            )
+        }
+    };
 }
 
-// Run the KMC command line
-pub(crate) fn kmc_cli(name_file: &str, kmc_number: i32) -> Result<(bool, String), Box<dyn Error>> {
+// Run the KMC command line on the file `cfsm_dir/name_file.txt`,
+// the outputs of the tool being written in `outputs_dir`
+pub(crate) fn kmc_cli(
+    name_file: &str,
+    kmc_number: i32,
+    cfsm_dir: &Path,
+    outputs_dir: &Path,
+) -> Result<(bool, String), Box<dyn Error>> {
     // Delete previous files
-    remove_file(format!(
-        "../mpst_rust_github/outputs/{}_{}_kmc.txt",
-        name_file, kmc_number,
-    ))
-    .unwrap_or(());
-    remove_file(format!(
-        "../mpst_rust_github/outputs/{}-sync-0norm-system.dot",
-        name_file
-    ))
-    .unwrap_or(());
-    remove_file(format!(
-        "../mpst_rust_github/outputs/{}-sync-0norm-system.png",
-        name_file
-    ))
-    .unwrap_or(());
-    remove_file(format!(
-        "../mpst_rust_github/outputs/{}-ts-{}.fsm",
-        name_file, kmc_number,
-    ))
-    .unwrap_or(());
+    remove_file(outputs_dir.join(format!("{}_{}_kmc.txt", name_file, kmc_number))).unwrap_or(());
+    remove_file(outputs_dir.join(format!("{}-sync-0norm-system.dot", name_file))).unwrap_or(());
+    remove_file(outputs_dir.join(format!("{}-sync-0norm-system.png", name_file))).unwrap_or(());
+    remove_file(outputs_dir.join(format!("{}-ts-{}.fsm", name_file, kmc_number))).unwrap_or(());
 
     // Run KMC tool, the outputs files of the tool are in the "outputs" folder
     let kmc = Command::new("KMC")
-        .arg(format!("cfsm/{}.txt", name_file))
+        .arg(cfsm_dir.join(format!("{}.txt", name_file)))
         .arg(format!("{:?}", kmc_number))
         .arg("--fsm")
         .output()?;
@@ -283,21 +462,102 @@ pub(crate) fn kmc_cli(name_file: &str, kmc_number: i32) -> Result<(bool, String)
     } else {
         // Write down the stdout of the previous command into
         // a corresponding file in the "outputs" folder
-        let mut kmc_file = File::create(format!("outputs/{}_{}_kmc.txt", name_file, kmc_number))?;
+        create_dir_all(outputs_dir)?;
+        let mut kmc_file =
+            File::create(outputs_dir.join(format!("{}_{}_kmc.txt", name_file, kmc_number)))?;
         writeln!(kmc_file, "{}", stdout)?;
         Ok((true, stdout))
     }
 }
 
-// The starting function for extracting the graphs
+// Write the cfsm of each role into `cfsm_dir/name_file.txt`
+// and run the KMC tool on it until finding the minimal `k`.
+fn run_kmc(
+    name_file: &str,
+    cfsm_sort: &[Vec<String>],
+    cfsm_dir: &Path,
+    outputs_dir: &Path,
+) -> Result<Option<i32>, Box<dyn Error>> {
+    // Create cfsm folder if missing
+    create_dir_all(cfsm_dir)?;
+
+    // Create the file
+    let mut cfsm_file = File::create(cfsm_dir.join(format!("{}.txt", name_file)))?;
+
+    // Write the cfsm into the file
+    for elt_cfsm in cfsm_sort.iter() {
+        for elt in elt_cfsm.iter() {
+            writeln!(cfsm_file, "{}", elt)?;
+        }
+
+        // Add a blank line
+        writeln!(cfsm_file)?;
+    }
+
+    let mut kmc_number = 1;
+    let mut kmc_result = kmc_cli(name_file, kmc_number, cfsm_dir, outputs_dir)?;
+
+    while !kmc_result.0 && kmc_number < 50 {
+        kmc_number += 1;
+        kmc_result = kmc_cli(name_file, kmc_number, cfsm_dir, outputs_dir)?;
+    }
+
+    if kmc_number == 50 {
+        println!(
+            "The protocol does not seem correct. Here is the last output: {:?}",
+            kmc_result.1
+        );
+        Ok(None)
+    } else {
+        Ok(Some(kmc_number))
+    }
+}
+
+// The starting function for extracting the graphs.
+//
+// If `output_dir` is empty, the cfsm are written into the `cfsm` folder
+// and the [`KMC`] tool is run if `name_file` is not empty, its outputs being in the `outputs` folder.
+// Otherwise, the graphs are exported in `output_dir` for each format of `formats`,
+// and the [`KMC`] tool is run only for [`Format::Cfsm`].
 #[doc(hidden)]
+#[allow(clippy::too_many_arguments)]
 pub fn checker(
     name_file: &str,
+    output_dir: &str,
+    formats: &[Format],
     sessions: Vec<String>,
     branches_receivers: HashMap<String, HashMap<String, String>>,
     branching_sessions: HashMap<String, String>,
     group_branches: HashMap<String, i32>,
 ) -> Result<(HashGraph, Option<i32>), Box<dyn Error>> {
+    let (result, _, kmc) = checker_with_branches(
+        name_file,
+        output_dir,
+        formats,
+        sessions,
+        branches_receivers,
+        branching_sessions,
+        group_branches,
+    )?;
+
+    Ok((result, kmc))
+}
+
+// Same as `checker`, but also returns the branches of each role:
+// the edges of its graph starting each branch of a choice,
+// along with the name of the variant of the branch and
+// whether the branch is chosen by the role or offered to it.
+#[doc(hidden)]
+#[allow(clippy::too_many_arguments)]
+pub fn checker_with_branches(
+    name_file: &str,
+    output_dir: &str,
+    formats: &[Format],
+    sessions: Vec<String>,
+    branches_receivers: HashMap<String, HashMap<String, String>>,
+    branching_sessions: HashMap<String, String>,
+    group_branches: HashMap<String, i32>,
+) -> Result<(HashGraph, HashBranches, Option<i32>), Box<dyn Error>> {
     // Clean the input sessions and extract the roles
     let (clean_sessions, roles) = clean_sessions(sessions.to_vec())?;
 
@@ -334,80 +594,61 @@ pub fn checker(
     let state_result = RandomState::new();
     let mut result: HashGraph = HashMap::with_hasher(state_result);
 
-    if !name_file.is_empty() {
-        // If a name file has been provided
+    // The branches chosen by each role
+    let state_result_branches = RandomState::new();
+    let mut result_branches: HashBranches = HashMap::with_hasher(state_result_branches);
 
-        // Create cfsm folder if missing
-        create_dir_all("cfsm")?;
+    let mut cfsm_sort = vec![vec!["".to_string()]; roles.len()];
 
-        // Create the file
-        let mut cfsm_file = File::create(format!("cfsm/{}.txt", name_file))?;
+    // Get all the graphs and add them to the result Hashmap
+    for (role, full_session) in clean_sessions {
+        // Get the graph and the cfsm for the current role
+        let (graph, cfsm, branches) = get_graph_session(
+            &role,
+            full_session,
+            &roles,
+            update_branches_receivers.clone(),
+            update_branching_sessions.clone(),
+            group_branches.clone(),
+        )?;
 
-        let mut cfsm_sort = vec![vec!["".to_string()]; roles.len()];
+        // Insert the graph to the returned result
+        result.insert(role.to_string(), graph);
+        result_branches.insert(role.to_string(), branches);
 
-        // Get all the graphs and add them to the result Hashmap
-        for (role, full_session) in clean_sessions {
-            // Get the graph and the cfsm for the current role
-            let (graph, cfsm) = get_graph_session(
-                &role,
-                full_session,
-                &roles,
-                update_branches_receivers.clone(),
-                update_branching_sessions.clone(),
-                group_branches.clone(),
-            )?;
-
-            // Insert the graph to the returned result
-            result.insert(role.to_string(), graph);
-
-            let index_role = roles.iter().position(|r| r == &role).unwrap();
-
-            cfsm_sort[index_role] = cfsm;
-        }
-
-        // Write the cfsm into the file
-        for elt_cfsm in cfsm_sort.iter() {
-            for elt in elt_cfsm.iter() {
-                writeln!(cfsm_file, "{}", elt)?;
-            }
-
-            // Add a blank line
-            writeln!(cfsm_file)?;
-        }
-
-        let mut kmc_number = 1;
-        let mut kmc_result = kmc_cli(name_file, kmc_number)?;
+        let index_role = roles.iter().position(|r| r == &role).unwrap();
 
-        while !kmc_result.0 && kmc_number < 50 {
-            kmc_number += 1;
-            kmc_result = kmc_cli(name_file, kmc_number)?;
-        }
+        cfsm_sort[index_role] = cfsm;
+    }
 
-        if kmc_number == 50 {
-            println!(
-                "The protocol does not seem correct. Here is the last output: {:?}",
-                kmc_result.1
-            );
-            Ok((result, None))
+    if output_dir.is_empty() {
+        if name_file.is_empty() {
+            Ok((result, result_branches, None))
         } else {
-            Ok((result, Some(kmc_number)))
+            // If a name file has been provided
+            let kmc = run_kmc(
+                name_file,
+                &cfsm_sort,
+                Path::new("cfsm"),
+                Path::new("outputs"),
+            )?;
+            Ok((result, result_branches, kmc))
         }
     } else {
-        // Get all the graphs and add them to the result Hashmap
-        for (role, full_session) in clean_sessions {
-            // Get the graph and the cfsm for the current role
-            let (graph, _) = get_graph_session(
-                &role,
-                full_session,
-                &roles,
-                update_branches_receivers.clone(),
-                update_branching_sessions.clone(),
-                group_branches.clone(),
-            )?;
+        let name_export = if name_file.is_empty() {
+            "protocol"
+        } else {
+            name_file
+        };
 
-            // Insert the graph to the returned result
-            result.insert(role.to_string(), graph);
+        export::export(name_export, output_dir, &result, formats)?;
+
+        if formats.contains(&Format::Cfsm) {
+            let output_dir = Path::new(output_dir);
+            let kmc = run_kmc(name_export, &cfsm_sort, output_dir, output_dir)?;
+            Ok((result, result_branches, kmc))
+        } else {
+            Ok((result, result_branches, None))
         }
-        Ok((result, None))
     }
 }
@@ -0,0 +1,192 @@
+//! This module contains the functions for building the
+//! synchronous product of the CFSMs of all the roles of a protocol,
+//! also called the global graph of the protocol.
+//!
+//! Each node of the global graph is a tuple of local nodes, one per role,
+//! and each edge is a synchronous interaction between two roles,
+//! labelled as `RoleA->RoleB: Payload`.
+//! The edges labelled `0` (end of a branch) and `µ` (recursion) in the CFSMs
+//! are silent and are never part of the global graph.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"checking"` feature.*
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Graph;
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+
+type GraphOfStrStr = Graph<String, String>;
+
+/// A local action, read from the label of an edge of a CFSM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Action {
+    /// `subject!peer: payload`
+    Send(String, String, String),
+    /// `subject?peer: payload`
+    Recv(String, String, String),
+    /// `0` or `µ`
+    Silent,
+}
+
+// Read the label of an edge of a CFSM.
+//
+// From "RoleA!RoleB: i32" to Action::Send("RoleA", "RoleB", "i32").
+pub(crate) fn parse_label(label: &str) -> Action {
+    if let Some((interaction, payload)) = label.split_once(": ") {
+        if let Some((subject, peer)) = interaction.split_once('!') {
+            return Action::Send(subject.to_string(), peer.to_string(), payload.to_string());
+        } else if let Some((subject, peer)) = interaction.split_once('?') {
+            return Action::Recv(subject.to_string(), peer.to_string(), payload.to_string());
+        }
+    }
+    Action::Silent
+}
+
+// All the nodes reachable from `node` through silent edges only, `node` included.
+pub(crate) fn silent_closure(graph: &GraphOfStrStr, node: NodeIndex<u32>) -> Vec<NodeIndex<u32>> {
+    let mut result = vec![node];
+    let mut index = 0;
+
+    while index < result.len() {
+        for edge in graph.edges(result[index]) {
+            if parse_label(edge.weight()) == Action::Silent && !result.contains(&edge.target()) {
+                result.push(edge.target());
+            }
+        }
+        index += 1;
+    }
+
+    result
+}
+
+// Whether a role may stop at `node`, i.e. whether a node without
+// outgoing edges is reachable through silent edges.
+pub(crate) fn is_local_terminal(graph: &GraphOfStrStr, node: NodeIndex<u32>) -> bool {
+    silent_closure(graph, node)
+        .iter()
+        .any(|n| graph.edges(*n).next().is_none())
+}
+
+// All the visible actions a role can fire from `node`,
+// along with the node reached after each of them.
+pub(crate) fn local_actions(
+    graph: &GraphOfStrStr,
+    node: NodeIndex<u32>,
+) -> Vec<(Action, NodeIndex<u32>)> {
+    let mut result = Vec::new();
+
+    for n in silent_closure(graph, node) {
+        for edge in graph.edges(n) {
+            let action = parse_label(edge.weight());
+            if action != Action::Silent {
+                result.push((action, edge.target()));
+            }
+        }
+    }
+
+    result
+}
+
+// Label of a global node, such as "RoleA:1,RoleB:2,RoleC:0".
+fn state_label(roles: &[String], state: &[NodeIndex<u32>]) -> String {
+    roles
+        .iter()
+        .zip(state.iter())
+        .map(|(role, node)| format!("{}:{}", role, node.index()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Read the label of a global node.
+//
+// From "RoleA:1,RoleB:0" to [("RoleA", 1), ("RoleB", 0)].
+#[cfg(any(feature = "diagram", feature = "property_testing"))]
+pub(crate) fn parse_state(label: &str) -> Vec<(String, NodeIndex<u32>)> {
+    label
+        .split(',')
+        .filter_map(|local| {
+            let (role, node) = local.rsplit_once(':')?;
+            Some((role.to_string(), NodeIndex::new(node.parse().ok()?)))
+        })
+        .collect()
+}
+
+/// Build the synchronous product of the CFSMs returned by
+/// [`checker_concat!`], that is the global graph of the protocol.
+///
+/// The node `0` of the returned graph is the starting state.
+/// Each node is labelled with the index of the local node
+/// of each role, such as `RoleA:1,RoleB:2,RoleC:0`,
+/// and each edge with the interaction, such as `RoleA->RoleB: i32`.
+///
+/// [`checker_concat!`]: crate::checker_concat
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn synchronous_product(graphs: &HashMap<String, GraphOfStrStr>) -> GraphOfStrStr {
+    let mut roles = graphs.keys().cloned().collect::<Vec<_>>();
+    roles.sort();
+
+    let state_seen = RandomState::new();
+    let mut seen: HashMap<Vec<NodeIndex<u32>>, NodeIndex<u32>> = HashMap::with_hasher(state_seen);
+
+    let mut graph = Graph::<String, String>::new();
+
+    let start = vec![NodeIndex::new(0); roles.len()];
+    let start_node = graph.add_node(state_label(&roles, &start));
+    seen.insert(start.clone(), start_node);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(state) = queue.pop_front() {
+        let current = seen[&state];
+
+        for (index_sender, sender) in roles.iter().enumerate() {
+            for (action, next_sender) in local_actions(&graphs[sender], state[index_sender]) {
+                if let Action::Send(_, receiver, payload) = action {
+                    let index_receiver = match roles.iter().position(|r| r == &receiver) {
+                        Some(index) => index,
+                        None => continue,
+                    };
+
+                    for (dual, next_receiver) in
+                        local_actions(&graphs[&receiver], state[index_receiver])
+                    {
+                        if dual != Action::Recv(receiver.clone(), sender.clone(), payload.clone()) {
+                            continue;
+                        }
+
+                        let mut next = state.clone();
+                        next[index_sender] = next_sender;
+                        next[index_receiver] = next_receiver;
+
+                        let target = if let Some(target) = seen.get(&next) {
+                            *target
+                        } else {
+                            let target = graph.add_node(state_label(&roles, &next));
+                            seen.insert(next.clone(), target);
+                            queue.push_back(next);
+                            target
+                        };
+
+                        let label = format!("{}->{}: {}", sender, receiver, payload);
+
+                        if !graph
+                            .edges_connecting(current, target)
+                            .any(|e| e.weight() == &label)
+                        {
+                            graph.add_edge(current, target, label);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    graph
+}
@@ -0,0 +1,703 @@
+//! This module contains the functions for checking temporal properties,
+//! written in a CTL-style language, over the global graph of a protocol.
+//!
+//! # Language
+//!
+//! ```text
+//! formula := formula -> formula              implication, right-associative
+//!          | formula | formula               disjunction
+//!          | formula & formula               conjunction
+//!          | !formula                        negation
+//!          | AX formula | EX formula         on all / some next states
+//!          | AF formula | EF formula         eventually, on all / some paths
+//!          | AG formula | EG formula         always, on all / some paths
+//!          | A[formula U formula]            until, on all paths
+//!          | E[formula U formula]            until, on some path
+//!          | (formula)
+//!          | true | false
+//!          | end                             the protocol is over
+//!          | Action | "Action"                the last interaction matches `Action`
+//! ```
+//!
+//! An action matches the label of an interaction of the global graph, such as
+//! `RoleA->RoleB: Login`, if it is equal either to the full label,
+//! to the payload (`Login`), or to the roles involved (`RoleA->RoleB`).
+//! Actions which are not plain identifiers must be quoted.
+//!
+//! The states of the global graph are paired with the last interaction
+//! which led to them, and the final states of the protocol are considered
+//! to loop on themselves without any interaction, so that every path is infinite.
+//!
+//! # Example
+//!
+//! Every `Login` is eventually followed by `Success` or `Fail`:
+//!
+//! ```text
+//! AG(Login -> AF(Success | Fail))
+//! ```
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"checking"` feature.*
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Graph;
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::product::synchronous_product;
+
+type GraphOfStrStr = Graph<String, String>;
+
+/// A temporal formula, which can be parsed from a string.
+///
+/// *This enum is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub enum Formula {
+    /// Always holds
+    True,
+    /// Never holds
+    False,
+    /// Holds if the last interaction matches the action
+    Action(String),
+    /// Holds if the protocol is over
+    End,
+    /// Negation
+    Not(Box<Formula>),
+    /// Conjunction
+    And(Box<Formula>, Box<Formula>),
+    /// Disjunction
+    Or(Box<Formula>, Box<Formula>),
+    /// Implication
+    Implies(Box<Formula>, Box<Formula>),
+    /// Holds on some next state
+    EX(Box<Formula>),
+    /// Holds on all next states
+    AX(Box<Formula>),
+    /// Eventually holds on some path
+    EF(Box<Formula>),
+    /// Eventually holds on all paths
+    AF(Box<Formula>),
+    /// Always holds on some path
+    EG(Box<Formula>),
+    /// Always holds on all paths
+    AG(Box<Formula>),
+    /// The first formula holds until the second one does, on some path
+    EU(Box<Formula>, Box<Formula>),
+    /// The first formula holds until the second one does, on all paths
+    AU(Box<Formula>, Box<Formula>),
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Formula::True => write!(f, "true"),
+            Formula::False => write!(f, "false"),
+            Formula::Action(action) => {
+                if is_identifier(action) && keyword(action).is_none() {
+                    write!(f, "{}", action)
+                } else {
+                    write!(f, "\"{}\"", action)
+                }
+            }
+            Formula::End => write!(f, "end"),
+            Formula::Not(a) => write!(f, "!{}", a),
+            Formula::And(a, b) => write!(f, "({} & {})", a, b),
+            Formula::Or(a, b) => write!(f, "({} | {})", a, b),
+            Formula::Implies(a, b) => write!(f, "({} -> {})", a, b),
+            Formula::EX(a) => write!(f, "EX {}", a),
+            Formula::AX(a) => write!(f, "AX {}", a),
+            Formula::EF(a) => write!(f, "EF {}", a),
+            Formula::AF(a) => write!(f, "AF {}", a),
+            Formula::EG(a) => write!(f, "EG {}", a),
+            Formula::AG(a) => write!(f, "AG {}", a),
+            Formula::EU(a, b) => write!(f, "E[{} U {}]", a, b),
+            Formula::AU(a, b) => write!(f, "A[{} U {}]", a, b),
+        }
+    }
+}
+
+/// The result of [`check_property`].
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub struct Verdict {
+    /// Whether the formula holds in the starting state
+    pub holds: bool,
+    /// The interactions, from the starting state, of a path showing why the
+    /// formula holds (a witness) or does not (a counterexample).
+    /// Empty if the formula does not need a path, such as `AG f` when it holds.
+    pub path: Vec<String>,
+    /// The interactions repeated forever after `path`, for infinite paths
+    /// such as the counterexamples of `AF f`.
+    /// Empty if the path ends in a final state.
+    pub cycle: Vec<String>,
+}
+
+// Parsing
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    LeftParenthesis,
+    RightParenthesis,
+    LeftBracket,
+    RightBracket,
+    Not,
+    And,
+    Or,
+    Implies,
+    Word(String),
+    Quoted(String),
+}
+
+fn is_identifier(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+// The operator or constant a word stands for, if any.
+fn keyword(word: &str) -> Option<&'static str> {
+    [
+        "true", "false", "end", "EX", "AX", "EF", "AF", "EG", "AG", "E", "A", "U",
+    ]
+    .iter()
+    .find(|k| **k == word)
+    .copied()
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, Box<dyn Error>> {
+    let mut result = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => result.push(Token::LeftParenthesis),
+            ')' => result.push(Token::RightParenthesis),
+            '[' => result.push(Token::LeftBracket),
+            ']' => result.push(Token::RightBracket),
+            '!' => result.push(Token::Not),
+            '&' => result.push(Token::And),
+            '|' => result.push(Token::Or),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                result.push(Token::Implies);
+            }
+            '"' => {
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => quoted.push(c),
+                        None => return Err("Unterminated quoted action".into()),
+                    }
+                }
+                result.push(Token::Quoted(quoted));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.peek() {
+                    if c.is_alphanumeric() || *c == '_' {
+                        word.push(*c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                result.push(Token::Word(word));
+            }
+            c => return Err(format!("Unexpected character {:?}", c).into()),
+        }
+    }
+
+    Ok(result)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), Box<dyn Error>> {
+        match self.tokens.get(self.position) {
+            Some(t) if *t == token => {
+                self.position += 1;
+                Ok(())
+            }
+            t => Err(format!("Expected {:?}, found {:?}", token, t).into()),
+        }
+    }
+
+    fn implies(&mut self) -> Result<Formula, Box<dyn Error>> {
+        let left = self.or()?;
+        if self.peek() == Some(&Token::Implies) {
+            self.position += 1;
+            Ok(Formula::Implies(Box::new(left), Box::new(self.implies()?)))
+        } else {
+            Ok(left)
+        }
+    }
+
+    fn or(&mut self) -> Result<Formula, Box<dyn Error>> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            left = Formula::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Formula, Box<dyn Error>> {
+        let mut left = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            left = Formula::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn until(&mut self) -> Result<(Box<Formula>, Box<Formula>), Box<dyn Error>> {
+        self.expect(Token::LeftBracket)?;
+        let left = self.implies()?;
+        self.expect(Token::Word("U".to_string()))?;
+        let right = self.implies()?;
+        self.expect(Token::RightBracket)?;
+        Ok((Box::new(left), Box::new(right)))
+    }
+
+    fn unary(&mut self) -> Result<Formula, Box<dyn Error>> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or("Unexpected end of formula")?;
+        self.position += 1;
+
+        match token {
+            Token::Not => Ok(Formula::Not(Box::new(self.unary()?))),
+            Token::LeftParenthesis => {
+                let result = self.implies()?;
+                self.expect(Token::RightParenthesis)?;
+                Ok(result)
+            }
+            Token::Quoted(action) => Ok(Formula::Action(action)),
+            Token::Word(word) => match keyword(&word) {
+                Some("true") => Ok(Formula::True),
+                Some("false") => Ok(Formula::False),
+                Some("end") => Ok(Formula::End),
+                Some("EX") => Ok(Formula::EX(Box::new(self.unary()?))),
+                Some("AX") => Ok(Formula::AX(Box::new(self.unary()?))),
+                Some("EF") => Ok(Formula::EF(Box::new(self.unary()?))),
+                Some("AF") => Ok(Formula::AF(Box::new(self.unary()?))),
+                Some("EG") => Ok(Formula::EG(Box::new(self.unary()?))),
+                Some("AG") => Ok(Formula::AG(Box::new(self.unary()?))),
+                Some("E") => {
+                    let (left, right) = self.until()?;
+                    Ok(Formula::EU(left, right))
+                }
+                Some("A") => {
+                    let (left, right) = self.until()?;
+                    Ok(Formula::AU(left, right))
+                }
+                Some(k) => Err(format!("Unexpected keyword {}", k).into()),
+                None => Ok(Formula::Action(word)),
+            },
+            t => Err(format!("Unexpected token {:?}", t).into()),
+        }
+    }
+}
+
+impl FromStr for Formula {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            position: 0,
+        };
+
+        let result = parser.implies()?;
+
+        if parser.position < parser.tokens.len() {
+            return Err(format!("Unexpected token {:?}", parser.tokens[parser.position]).into());
+        }
+
+        Ok(result)
+    }
+}
+
+// Model checking
+
+// The global graph, where each state is paired with the interaction leading to it.
+struct Model {
+    // The interaction leading to each state, if any, and whether the state is final.
+    states: Vec<(Option<String>, bool)>,
+    successors: Vec<Vec<usize>>,
+}
+
+impl Model {
+    fn new(global: &GraphOfStrStr) -> Model {
+        let state_index = RandomState::new();
+        let mut index: HashMap<(NodeIndex<u32>, Option<String>), usize> =
+            HashMap::with_hasher(state_index);
+
+        let mut states = Vec::new();
+        let mut nodes = Vec::new();
+        let mut successors: Vec<Vec<usize>> = Vec::new();
+
+        let start = (NodeIndex::new(0), None);
+        index.insert(start.clone(), 0);
+        nodes.push(start);
+        states.push((None, global.edges(NodeIndex::new(0)).next().is_none()));
+        successors.push(Vec::new());
+
+        let mut queue = VecDeque::new();
+        queue.push_back(0);
+
+        while let Some(current) = queue.pop_front() {
+            let node = nodes[current].0;
+
+            // The final states loop on themselves
+            let next = if states[current].1 {
+                vec![(node, None)]
+            } else {
+                global
+                    .edges(node)
+                    .map(|edge| (edge.target(), Some(edge.weight().to_string())))
+                    .collect::<Vec<_>>()
+            };
+
+            for state in next {
+                let target = if let Some(target) = index.get(&state) {
+                    *target
+                } else {
+                    let target = states.len();
+                    index.insert(state.clone(), target);
+                    states.push((state.1.clone(), global.edges(state.0).next().is_none()));
+                    nodes.push(state);
+                    successors.push(Vec::new());
+                    queue.push_back(target);
+                    target
+                };
+
+                if !successors[current].contains(&target) {
+                    successors[current].push(target);
+                }
+            }
+        }
+
+        Model { states, successors }
+    }
+
+    // Whether `action` matches the interaction leading to `state`.
+    fn matches(&self, state: usize, action: &str) -> bool {
+        match &self.states[state].0 {
+            Some(label) => match label.split_once(": ") {
+                Some((roles, payload)) => label == action || roles == action || payload == action,
+                None => label == action,
+            },
+            None => false,
+        }
+    }
+
+    // The states satisfying `formula`.
+    fn sat(&self, formula: &Formula) -> Vec<bool> {
+        let size = self.states.len();
+
+        match formula {
+            Formula::True => vec![true; size],
+            Formula::False => vec![false; size],
+            Formula::Action(action) => (0..size).map(|s| self.matches(s, action)).collect(),
+            Formula::End => self.states.iter().map(|(_, end)| *end).collect(),
+            Formula::Not(a) => self.sat(a).into_iter().map(|a| !a).collect(),
+            Formula::And(a, b) => zip(self.sat(a), self.sat(b), |a, b| a && b),
+            Formula::Or(a, b) => zip(self.sat(a), self.sat(b), |a, b| a || b),
+            Formula::Implies(a, b) => zip(self.sat(a), self.sat(b), |a, b| !a || b),
+            Formula::EX(a) => {
+                let a = self.sat(a);
+                (0..size)
+                    .map(|s| self.successors[s].iter().any(|n| a[*n]))
+                    .collect()
+            }
+            Formula::AX(a) => {
+                let a = self.sat(a);
+                (0..size)
+                    .map(|s| self.successors[s].iter().all(|n| a[*n]))
+                    .collect()
+            }
+            Formula::EF(a) => self.until(&vec![true; size], &self.sat(a), false),
+            Formula::AF(a) => self.until(&vec![true; size], &self.sat(a), true),
+            Formula::EU(a, b) => self.until(&self.sat(a), &self.sat(b), false),
+            Formula::AU(a, b) => self.until(&self.sat(a), &self.sat(b), true),
+            Formula::EG(a) => self.globally(&self.sat(a), false),
+            Formula::AG(a) => self.globally(&self.sat(a), true),
+        }
+    }
+
+    // Least fixpoint for `E[a U b]`, or `A[a U b]` if `all`.
+    fn until(&self, a: &[bool], b: &[bool], all: bool) -> Vec<bool> {
+        let mut result = b.to_vec();
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+            for s in 0..self.states.len() {
+                if !result[s] && a[s] {
+                    let next = &self.successors[s];
+                    if (all && next.iter().all(|n| result[*n]))
+                        || (!all && next.iter().any(|n| result[*n]))
+                    {
+                        result[s] = true;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    // Greatest fixpoint for `EG a`, or `AG a` if `all`.
+    fn globally(&self, a: &[bool], all: bool) -> Vec<bool> {
+        let mut result = a.to_vec();
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+            for s in 0..self.states.len() {
+                if result[s] {
+                    let next = &self.successors[s];
+                    if (all && !next.iter().all(|n| result[*n]))
+                        || (!all && !next.iter().any(|n| result[*n]))
+                    {
+                        result[s] = false;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    // The shortest path from `start`, going through states in `through`,
+    // to a state in `target`. The path does not contain `start`.
+    fn path_to(&self, start: usize, through: &[bool], target: &[bool]) -> Option<Vec<usize>> {
+        if target[start] {
+            return Some(Vec::new());
+        }
+
+        let mut parents = vec![None; self.states.len()];
+        let mut queue = VecDeque::new();
+        parents[start] = Some(start);
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            if !through[current] {
+                continue;
+            }
+
+            for next in self.successors[current].iter() {
+                if parents[*next].is_none() {
+                    parents[*next] = Some(current);
+
+                    if target[*next] {
+                        let mut path = vec![*next];
+                        let mut state = current;
+                        while state != start {
+                            path.push(state);
+                            state = parents[state].unwrap();
+                        }
+                        path.reverse();
+                        return Some(path);
+                    }
+
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        None
+    }
+
+    // An infinite path from `start` staying in `set`, as a prefix and a cycle.
+    // `set` must be closed, i.e. each of its states has a successor in it.
+    fn lasso(&self, start: usize, set: &[bool]) -> (Vec<usize>, Vec<usize>) {
+        let mut walk = vec![start];
+
+        loop {
+            let current = walk[walk.len() - 1];
+            let next = *self.successors[current]
+                .iter()
+                .find(|n| set[**n])
+                .unwrap_or(&current);
+
+            if let Some(position) = walk.iter().position(|s| *s == next) {
+                let mut cycle = walk[(position + 1)..].to_vec();
+                cycle.push(next);
+                return (walk[1..=position].to_vec(), cycle);
+            }
+
+            walk.push(next);
+        }
+    }
+
+    // A path from `state` explaining why `formula` evaluates to `expected`,
+    // as a prefix and a cycle.
+    fn explain(&self, state: usize, formula: &Formula, expected: bool) -> (Vec<usize>, Vec<usize>) {
+        // Append the explanation of `formula` at the end of `path`
+        let then = |path: Vec<usize>, formula: &Formula, expected: bool| {
+            let last = *path.last().unwrap_or(&state);
+            let (mut rest, cycle) = self.explain(last, formula, expected);
+            let mut path = path;
+            path.append(&mut rest);
+            (path, cycle)
+        };
+
+        match (formula, expected) {
+            (Formula::Not(a), _) => self.explain(state, a, !expected),
+            (Formula::And(a, b), true) | (Formula::Or(a, b), false) => {
+                let result = self.explain(state, a, expected);
+                if result.0.is_empty() && result.1.is_empty() {
+                    self.explain(state, b, expected)
+                } else {
+                    result
+                }
+            }
+            (Formula::And(a, b), false) | (Formula::Or(a, b), true) => {
+                if self.sat(a)[state] == expected {
+                    self.explain(state, a, expected)
+                } else {
+                    self.explain(state, b, expected)
+                }
+            }
+            (Formula::Implies(a, b), _) => self.explain(
+                state,
+                &Formula::Or(Box::new(Formula::Not(a.clone())), b.clone()),
+                expected,
+            ),
+            (Formula::EX(a), true) | (Formula::AX(a), false) => {
+                let a_sat = self.sat(a);
+                match self.successors[state]
+                    .iter()
+                    .find(|n| a_sat[**n] == expected)
+                {
+                    Some(next) => then(vec![*next], a, expected),
+                    None => (Vec::new(), Vec::new()),
+                }
+            }
+            (Formula::EF(a), true) => {
+                let target = self.sat(a);
+                match self.path_to(state, &vec![true; target.len()], &target) {
+                    Some(path) => then(path, a, true),
+                    None => (Vec::new(), Vec::new()),
+                }
+            }
+            (Formula::EU(a, b), true) => {
+                let target = self.sat(b);
+                match self.path_to(state, &self.sat(a), &target) {
+                    Some(path) => then(path, b, true),
+                    None => (Vec::new(), Vec::new()),
+                }
+            }
+            (Formula::AG(a), false) => {
+                let target = self.sat(a).into_iter().map(|a| !a).collect::<Vec<_>>();
+                match self.path_to(state, &vec![true; target.len()], &target) {
+                    Some(path) => then(path, a, false),
+                    None => (Vec::new(), Vec::new()),
+                }
+            }
+            (Formula::AU(a, b), false) => {
+                let not_b = self.sat(b).into_iter().map(|b| !b).collect::<Vec<_>>();
+                let target = zip(self.sat(a), not_b.clone(), |a, not_b| !a && not_b);
+                match self.path_to(state, &not_b, &target) {
+                    Some(path) => then(path, a, false),
+                    None => self.lasso(state, &self.globally(&not_b, false)),
+                }
+            }
+            (Formula::AF(a), false) => {
+                let not_a = self.sat(a).into_iter().map(|a| !a).collect::<Vec<_>>();
+                self.lasso(state, &self.globally(&not_a, false))
+            }
+            (Formula::EG(a), true) => self.lasso(state, &self.sat(&Formula::EG(a.clone()))),
+            _ => (Vec::new(), Vec::new()),
+        }
+    }
+
+    // The interactions leading to each state of `path`.
+    fn labels(&self, path: &[usize]) -> Vec<String> {
+        path.iter()
+            .filter_map(|s| self.states[*s].0.clone())
+            .collect()
+    }
+}
+
+fn zip<F: Fn(bool, bool) -> bool>(a: Vec<bool>, b: Vec<bool>, f: F) -> Vec<bool> {
+    a.into_iter().zip(b).map(|(a, b)| f(a, b)).collect()
+}
+
+/// Check a temporal formula on a global graph, such as the ones built by
+/// [`synchronous_product`] or [`cancellation_product`], from its node `0`.
+///
+/// [`synchronous_product`]: crate::checking::product::synchronous_product
+/// [`cancellation_product`]: crate::checking::cancel::cancellation_product
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn check_global(global: &GraphOfStrStr, formula: &Formula) -> Verdict {
+    let model = Model::new(global);
+    let holds = model.sat(formula)[0];
+    let (path, cycle) = model.explain(0, formula, holds);
+
+    Verdict {
+        holds,
+        path: model.labels(&path),
+        cycle: model.labels(&cycle),
+    }
+}
+
+/// Check a temporal formula, written in the language of this module,
+/// on the global graph of the protocol made of the CFSMs
+/// returned by [`checker_concat!`].
+///
+/// Returns an error if the formula cannot be parsed.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::checking::temporal::check_property;
+///
+/// let (graphs, _) = mpstthree::checker_concat!(EndpointA, EndpointC, EndpointS).unwrap();
+///
+/// let verdict = check_property(&graphs, "AG(Login -> AF(Success | Fail))").unwrap();
+///
+/// assert!(verdict.holds);
+/// ```
+///
+/// [`checker_concat!`]: crate::checker_concat
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"checking"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+pub fn check_property(
+    graphs: &HashMap<String, GraphOfStrStr>,
+    formula: &str,
+) -> Result<Verdict, Box<dyn Error>> {
+    Ok(check_global(
+        &synchronous_product(graphs),
+        &formula.parse::<Formula>()?,
+    ))
+}
@@ -0,0 +1,596 @@
+#![cfg(feature = "diagram")]
+
+//! This module contains the sequence diagrams of the protocols,
+//! written either for [Mermaid](https://mermaid.js.org)
+//! or for [PlantUML](https://plantuml.com).
+//!
+//! A [`Diagram`] is built either statically, from the CFSMs returned
+//! by [`checker_concat!`] with [`Diagram::from_graphs`],
+//! or dynamically, from a run recorded with [`record`]
+//! with [`Diagram::from_recording`].
+//!
+//! * The choices are drawn as `alt` blocks, with one branch per label,
+//!   written `RoleA: Label` after the role which chooses,
+//!   and a branch `End` when the protocol may also end there.
+//! * The recursions are drawn as `loop` blocks.
+//! * The cancellations are drawn as `break` blocks in the recorded runs,
+//!   and, with [`Diagram::from_graphs_with_cancellation`], as one option of a
+//!   `critical` block per role which may cancel its session.
+//!
+//! In the static diagrams, the global graph is unfolded from its
+//! starting state, and only one order is drawn for the interactions
+//! which can happen in any order.
+//! In the dynamic diagrams, each choice starts a new `alt` block,
+//! and the blocks of consecutive choices of the same role are
+//! gathered in a `loop` block.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mpstthree::diagram::Diagram;
+//!
+//! let (graphs, _) = mpstthree::checker_concat!(EndpointA, EndpointB, EndpointC => ...).unwrap();
+//!
+//! println!("{}", Diagram::from_graphs(&graphs).to_mermaid());
+//! ```
+//!
+//! [`checker_concat!`]: crate::checker_concat
+//! [`record`]: crate::testing::recording::record
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"diagram"` feature.*
+
+#[cfg(feature = "checking")]
+use petgraph::graph::NodeIndex;
+#[cfg(feature = "checking")]
+use petgraph::visit::EdgeRef;
+#[cfg(feature = "checking")]
+use petgraph::Graph;
+
+#[cfg(feature = "checking")]
+use std::collections::HashMap;
+use std::fmt::Write;
+
+#[cfg(feature = "checking")]
+use crate::checking::cancel::cancellation_product;
+#[cfg(feature = "checking")]
+use crate::checking::product::{
+    is_local_terminal, local_actions, parse_label, parse_state, silent_closure,
+    synchronous_product, Action,
+};
+
+#[cfg(feature = "recording")]
+use crate::testing::recording::Recording;
+
+#[cfg(feature = "checking")]
+type GraphOfStrStr = Graph<String, String>;
+
+/// An element of a sequence diagram.
+///
+/// *This enum is available only if MultiCrusty is built with
+/// the `"diagram"` feature.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "diagram")))]
+pub enum Item {
+    /// A message from a role to another one.
+    Message {
+        /// The sender.
+        from: String,
+        /// The receiver.
+        to: String,
+        /// The text of the arrow, such as the name of the payload.
+        label: String,
+    },
+    /// The cancellation of a role reaching another one.
+    Cancel {
+        /// The role which cancels its session.
+        from: String,
+        /// The role which is cancelled.
+        to: String,
+    },
+    /// A note over a role.
+    Note {
+        /// The role.
+        role: String,
+        /// The text of the note.
+        text: String,
+    },
+    /// A choice, with the label and the body of each branch.
+    Alt(Vec<(String, Vec<Item>)>),
+    /// A block repeated several times.
+    Loop(String, Vec<Item>),
+    /// A block ending the protocol.
+    Break(String, Vec<Item>),
+    /// A block along with the blocks which may interrupt it.
+    Critical(String, Vec<Item>, Vec<(String, Vec<Item>)>),
+}
+
+/// A sequence diagram, made of participants and items.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"diagram"` feature.*
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "diagram")))]
+pub struct Diagram {
+    /// The participants, in the order they are drawn.
+    pub participants: Vec<String>,
+    /// The items, from top to bottom.
+    pub items: Vec<Item>,
+}
+
+// Mermaid ends a statement with `;` and reads `#` as the start of an entity.
+fn mermaid_text(text: &str) -> String {
+    text.replace('#', "#35;").replace(';', "#59;")
+}
+
+fn write_mermaid(result: &mut String, items: &[Item], depth: usize) {
+    let indent = "    ".repeat(depth);
+
+    for item in items {
+        match item {
+            Item::Message { from, to, label } => {
+                let _ = writeln!(
+                    result,
+                    "{}{}->>{}: {}",
+                    indent,
+                    from,
+                    to,
+                    mermaid_text(label)
+                );
+            }
+            Item::Cancel { from, to } => {
+                let _ = writeln!(result, "{}{}-x{}: cancel", indent, from, to);
+            }
+            Item::Note { role, text } => {
+                let _ = writeln!(
+                    result,
+                    "{}Note over {}: {}",
+                    indent,
+                    role,
+                    mermaid_text(text)
+                );
+            }
+            Item::Alt(branches) => {
+                for (index, (label, body)) in branches.iter().enumerate() {
+                    let keyword = if index == 0 { "alt" } else { "else" };
+                    let _ = writeln!(result, "{}{} {}", indent, keyword, mermaid_text(label));
+                    write_mermaid(result, body, depth + 1);
+                }
+                let _ = writeln!(result, "{}end", indent);
+            }
+            Item::Loop(label, body) | Item::Break(label, body) => {
+                let keyword = if let Item::Loop(_, _) = item {
+                    "loop"
+                } else {
+                    "break"
+                };
+                let _ = writeln!(result, "{}{} {}", indent, keyword, mermaid_text(label));
+                write_mermaid(result, body, depth + 1);
+                let _ = writeln!(result, "{}end", indent);
+            }
+            Item::Critical(label, body, options) => {
+                let _ = writeln!(result, "{}critical {}", indent, mermaid_text(label));
+                write_mermaid(result, body, depth + 1);
+                for (label, body) in options {
+                    let _ = writeln!(result, "{}option {}", indent, mermaid_text(label));
+                    write_mermaid(result, body, depth + 1);
+                }
+                let _ = writeln!(result, "{}end", indent);
+            }
+        }
+    }
+}
+
+fn write_plantuml(result: &mut String, items: &[Item], depth: usize) {
+    let indent = "    ".repeat(depth);
+
+    for item in items {
+        match item {
+            Item::Message { from, to, label } => {
+                let _ = writeln!(result, "{}{} -> {} : {}", indent, from, to, label);
+            }
+            Item::Cancel { from, to } => {
+                let _ = writeln!(result, "{}{} ->x {} : cancel", indent, from, to);
+            }
+            Item::Note { role, text } => {
+                let _ = writeln!(result, "{}note over {} : {}", indent, role, text);
+            }
+            Item::Alt(branches) => {
+                for (index, (label, body)) in branches.iter().enumerate() {
+                    let keyword = if index == 0 { "alt" } else { "else" };
+                    let _ = writeln!(result, "{}{} {}", indent, keyword, label);
+                    write_plantuml(result, body, depth + 1);
+                }
+                let _ = writeln!(result, "{}end", indent);
+            }
+            Item::Loop(label, body) | Item::Break(label, body) => {
+                let keyword = if let Item::Loop(_, _) = item {
+                    "loop"
+                } else {
+                    "break"
+                };
+                let _ = writeln!(result, "{}{} {}", indent, keyword, label);
+                write_plantuml(result, body, depth + 1);
+                let _ = writeln!(result, "{}end", indent);
+            }
+            Item::Critical(label, body, options) => {
+                // PlantUML has no options in its critical blocks
+                write_plantuml(
+                    result,
+                    &[Item::Alt(
+                        std::iter::once((label.clone(), body.clone()))
+                            .chain(options.iter().cloned())
+                            .collect(),
+                    )],
+                    depth,
+                );
+            }
+        }
+    }
+}
+
+impl Diagram {
+    /// The diagram written for [Mermaid](https://mermaid.js.org).
+    pub fn to_mermaid(&self) -> String {
+        let mut result = String::from("sequenceDiagram\n");
+        for participant in self.participants.iter() {
+            let _ = writeln!(result, "    participant {}", participant);
+        }
+        write_mermaid(&mut result, &self.items, 1);
+        result
+    }
+
+    /// The diagram written for [PlantUML](https://plantuml.com).
+    pub fn to_plantuml(&self) -> String {
+        let mut result = String::from("@startuml\n");
+        for participant in self.participants.iter() {
+            let _ = writeln!(result, "participant {}", participant);
+        }
+        write_plantuml(&mut result, &self.items, 0);
+        result.push_str("@enduml\n");
+        result
+    }
+}
+
+// The unfolding of the global graph of a protocol.
+#[cfg(feature = "checking")]
+struct Unfolding<'a> {
+    graphs: &'a HashMap<String, GraphOfStrStr>,
+    global: GraphOfStrStr,
+}
+
+#[cfg(feature = "checking")]
+impl<'a> Unfolding<'a> {
+    // The role choosing a branch at `node`, if any: a role whose
+    // next actions are sending at least two different messages.
+    fn chooser(&self, node: NodeIndex<u32>) -> Option<String> {
+        parse_state(&self.global[node])
+            .into_iter()
+            .find(|(role, local)| {
+                let mut actions: Vec<Action> = Vec::new();
+                for (action, _) in local_actions(&self.graphs[role], *local) {
+                    if !actions.contains(&action) {
+                        actions.push(action);
+                    }
+                }
+
+                actions.len() > 1
+                    && actions
+                        .iter()
+                        .all(|action| matches!(action, Action::Send(_, _, _)))
+            })
+            .map(|(role, _)| role)
+    }
+
+    // The state of each role at `node`, where the local nodes
+    // only separated by silent edges, such as `µ`, are the same.
+    fn key(&self, node: NodeIndex<u32>) -> Vec<(String, NodeIndex<u32>)> {
+        parse_state(&self.global[node])
+            .into_iter()
+            .map(|(role, local)| {
+                let graph = &self.graphs[&role];
+                let same = silent_closure(graph, local)
+                    .into_iter()
+                    .filter(|n| {
+                        graph
+                            .edges(*n)
+                            .any(|edge| parse_label(edge.weight()) != Action::Silent)
+                    })
+                    .min()
+                    .unwrap_or(local);
+                (role, same)
+            })
+            .collect()
+    }
+
+    // The items from `node`, stopping at the states of `path`,
+    // which are the heads of the loops.
+    fn items(
+        &self,
+        node: NodeIndex<u32>,
+        path: &mut Vec<Vec<(String, NodeIndex<u32>)>>,
+        heads: &mut Vec<Vec<(String, NodeIndex<u32>)>>,
+    ) -> Vec<Item> {
+        let key = self.key(node);
+
+        if path.contains(&key) {
+            if !heads.contains(&key) {
+                heads.push(key);
+            }
+            return Vec::new();
+        }
+
+        path.push(key.clone());
+
+        let mut edges = self
+            .global
+            .edges(node)
+            .map(|edge| (edge.id(), edge.target(), edge.weight().to_string()))
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|(id, _, _)| *id);
+
+        let message = |label: &str| -> Option<(String, Item)> {
+            let (interaction, payload) = label.split_once(": ")?;
+            let (from, to) = interaction.split_once("->")?;
+            Some((
+                payload.to_string(),
+                Item::Message {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    label: payload.to_string(),
+                },
+            ))
+        };
+
+        let chooser = self.chooser(node);
+        let branches = edges
+            .iter()
+            .filter(|(_, _, label)| {
+                chooser
+                    .as_ref()
+                    .map(|role| label.starts_with(&format!("{}->", role)))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+
+        let mut result = Vec::new();
+
+        if let (Some(role), true) = (&chooser, branches.len() > 1) {
+            let mut alternatives = Vec::new();
+            for (_, target, label) in branches {
+                if let Some((payload, item)) = message(label) {
+                    let mut body = vec![item];
+                    body.append(&mut self.items(*target, path, heads));
+                    alternatives.push((format!("{}: {}", role, payload), body));
+                }
+            }
+            result.push(Item::Alt(alternatives));
+        } else if let Some((_, target, label)) = edges.first() {
+            if let Some((_, item)) = message(label) {
+                result.push(item);
+            }
+            result.append(&mut self.items(*target, path, heads));
+        }
+
+        path.pop();
+
+        // The protocol may also end here, as in a branch without any message
+        let may_end = parse_state(&self.global[node])
+            .iter()
+            .all(|(role, local)| is_local_terminal(&self.graphs[role], *local));
+
+        if may_end && !edges.is_empty() {
+            if let [Item::Alt(alternatives)] = &mut result[..] {
+                alternatives.push((String::from("End"), Vec::new()));
+            } else if let Some((_, _, label)) = edges.first() {
+                let role = label.split("->").next().unwrap_or_default();
+                let payload = label.split(": ").nth(1).unwrap_or_default();
+                result = vec![Item::Alt(vec![
+                    (format!("{}: {}", role, payload), result),
+                    (String::from("End"), Vec::new()),
+                ])];
+            }
+        }
+
+        if let Some(index) = heads.iter().position(|head| *head == key) {
+            heads.remove(index);
+            vec![Item::Loop(String::from("Recursion"), result)]
+        } else {
+            result
+        }
+    }
+}
+
+impl Diagram {
+    /// The diagram of the protocol made of the CFSMs
+    /// returned by [`checker_concat!`].
+    ///
+    /// [`checker_concat!`]: crate::checker_concat
+    ///
+    /// *This function is available only if MultiCrusty is built with
+    /// the `"diagram"` and `"checking"` features.*
+    #[cfg(feature = "checking")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+    pub fn from_graphs(graphs: &HashMap<String, GraphOfStrStr>) -> Self {
+        let mut participants = graphs.keys().cloned().collect::<Vec<_>>();
+        participants.sort();
+
+        let unfolding = Unfolding {
+            graphs,
+            global: synchronous_product(graphs),
+        };
+
+        let items = if unfolding.global.node_count() == 0 {
+            Vec::new()
+        } else {
+            unfolding.items(NodeIndex::new(0), &mut Vec::new(), &mut Vec::new())
+        };
+
+        Diagram {
+            participants,
+            items,
+        }
+    }
+
+    /// The diagram of the protocol made of the CFSMs
+    /// returned by [`checker_concat!`], where the roles may cancel
+    /// their sessions, as in the protocols built with [`bundle_impl_with_enum_and_cancel!`].
+    ///
+    /// The protocol is drawn in a `critical` block, with one option per role
+    /// which may cancel, showing the roles its cancellation may reach.
+    ///
+    /// [`checker_concat!`]: crate::checker_concat
+    /// [`bundle_impl_with_enum_and_cancel!`]: crate::bundle_impl_with_enum_and_cancel
+    ///
+    /// *This function is available only if MultiCrusty is built with
+    /// the `"diagram"` and `"checking"` features.*
+    #[cfg(feature = "checking")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "checking")))]
+    pub fn from_graphs_with_cancellation(graphs: &HashMap<String, GraphOfStrStr>) -> Self {
+        let diagram = Self::from_graphs(graphs);
+        let product = cancellation_product(graphs);
+
+        let labels = product
+            .edge_references()
+            .map(|edge| edge.weight().to_string())
+            .collect::<Vec<_>>();
+
+        let options = diagram
+            .participants
+            .iter()
+            .filter(|role| labels.contains(&format!("{}: cancel", role)))
+            .map(|role| {
+                let cancelled = diagram
+                    .participants
+                    .iter()
+                    .filter(|other| labels.contains(&format!("{}: cancelled by {}", other, role)))
+                    .map(|other| Item::Cancel {
+                        from: role.clone(),
+                        to: other.clone(),
+                    })
+                    .collect();
+                (format!("{} cancels", role), cancelled)
+            })
+            .collect();
+
+        Diagram {
+            participants: diagram.participants,
+            items: vec![Item::Critical(
+                String::from("Protocol"),
+                diagram.items,
+                options,
+            )],
+        }
+    }
+
+    /// The diagram of a run recorded with [`record`].
+    ///
+    /// [`record`]: crate::testing::recording::record
+    ///
+    /// *This function is available only if MultiCrusty is built with
+    /// the `"diagram"` and `"recording"` features.*
+    #[cfg(feature = "recording")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "recording")))]
+    pub fn from_recording(recording: &Recording) -> Self {
+        let mut participants: Vec<String> = Vec::new();
+        let mut add = |role: &str| {
+            if !participants.iter().any(|p| p == role) {
+                participants.push(role.to_string());
+            }
+        };
+
+        // The items before the first choice, then the chooser,
+        // the branch and the items of each choice
+        let mut prefix = Vec::new();
+        let mut choices: Vec<(String, String, Vec<Item>)> = Vec::new();
+
+        for entry in recording.entries() {
+            let item = if let Some((interaction, label)) = entry.step.split_once(": ") {
+                if let Some((from, to)) = interaction.split_once('!') {
+                    add(from);
+                    add(to);
+                    Some(Item::Message {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        label: match &entry.payload {
+                            Some(payload) => format!("{} = {}", label, payload),
+                            None => label.to_string(),
+                        },
+                    })
+                } else if let Some(branch) = label.strip_prefix("choose ") {
+                    add(interaction);
+                    choices.push((interaction.to_string(), branch.to_string(), Vec::new()));
+                    None
+                } else if label == "cancel" {
+                    add(interaction);
+                    Some(Item::Break(format!("{} cancels", interaction), Vec::new()))
+                } else {
+                    add(interaction);
+                    Some(Item::Note {
+                        role: interaction.to_string(),
+                        text: label.to_string(),
+                    })
+                }
+            } else {
+                None
+            };
+
+            if let Some(item) = item {
+                match choices.last_mut() {
+                    Some((_, _, items)) => items.push(item),
+                    None => prefix.push(item),
+                }
+            }
+        }
+
+        participants.sort();
+
+        // The cancellations reach all the other roles
+        let fill = |item: Item| match item {
+            Item::Break(label, _) => {
+                let from = label.trim_end_matches(" cancels").to_string();
+                let body = participants
+                    .iter()
+                    .filter(|to| **to != from)
+                    .map(|to| Item::Cancel {
+                        from: from.clone(),
+                        to: to.clone(),
+                    })
+                    .collect();
+                Item::Break(label, body)
+            }
+            item => item,
+        };
+
+        let mut items = prefix.into_iter().map(fill).collect::<Vec<_>>();
+        let mut choices = choices.into_iter().peekable();
+
+        while let Some((role, branch, body)) = choices.next() {
+            let mut run = vec![Item::Alt(vec![(
+                format!("{}: {}", role, branch),
+                body.into_iter().map(fill).collect(),
+            )])];
+
+            while let Some((_, branch, body)) = choices.next_if(|(next, _, _)| *next == role) {
+                run.push(Item::Alt(vec![(
+                    format!("{}: {}", role, branch),
+                    body.into_iter().map(fill).collect(),
+                )]));
+            }
+
+            if run.len() > 1 {
+                items.push(Item::Loop(
+                    format!("{} chooses {} times", role, run.len()),
+                    run,
+                ));
+            } else {
+                items.append(&mut run);
+            }
+        }
+
+        Diagram {
+            participants,
+            items,
+        }
+    }
+}
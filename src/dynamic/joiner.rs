@@ -0,0 +1,88 @@
+//! This module contains the [`Joiner`] through which the dynamic
+//! participants join a [`Pool`].
+//!
+//! [`Pool`]: crate::dynamic::pool::Pool
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"dynamic"` feature.*
+
+use crate::binary::struct_trait::session::Session;
+use crate::dynamic::pool::MemberId;
+use crossbeam_channel::Sender;
+use std::boxed::Box;
+use std::error::Error;
+use std::marker;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{Builder, JoinHandle};
+
+/// The handle through which the dynamic participants join a [`Pool`].
+/// It can be cloned, and sent to other threads.
+///
+/// [`Pool`]: crate::dynamic::pool::Pool
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"dynamic"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "dynamic")))]
+#[derive(Debug)]
+pub struct Joiner<S: Session> {
+    sender: Sender<(MemberId, S)>,
+    next: Arc<AtomicUsize>,
+}
+
+impl<S: Session> Clone for Joiner<S> {
+    fn clone(&self) -> Self {
+        Joiner {
+            sender: self.sender.clone(),
+            next: self.next.clone(),
+        }
+    }
+}
+
+impl<S: Session> Joiner<S> {
+    pub(crate) fn new(sender: Sender<(MemberId, S)>, next: Arc<AtomicUsize>) -> Self {
+        Joiner { sender, next }
+    }
+
+    /// Joins the pool, and returns the identifier of the new member
+    /// and its session with the host. The session can be used at once,
+    /// and its first exchange waits for the host to admit the member
+    /// with [`Pool::accept`].
+    ///
+    /// Fails if the pool is dropped.
+    ///
+    /// [`Pool::accept`]: crate::dynamic::pool::Pool::accept
+    pub fn join(&self) -> Result<(MemberId, S::Dual), Box<dyn Error>> {
+        let id = MemberId(self.next.fetch_add(1, Ordering::SeqCst));
+        let (here, there) = S::new();
+        self.sender
+            .send((id, here))
+            .map_err(|_| "The pool is closed, no participant can join it")?;
+        Ok((id, there))
+    }
+
+    /// Joins the pool as [`Joiner::join`], and runs the new member
+    /// `p` in a new thread.
+    pub fn fork<P>(&self, p: P) -> Result<(MemberId, JoinHandle<()>), Box<dyn Error>>
+    where
+        S: 'static,
+        S::Dual: 'static,
+        P: FnOnce(S::Dual) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+    {
+        let (id, there) = self.join()?;
+        let thread = Builder::new()
+            .name(id.to_string())
+            .stack_size(64 * 1024 * 1024)
+            .spawn(move || {
+                panic::set_hook(Box::new(|_info| {
+                    // do nothing
+                }));
+                match p(there) {
+                    Ok(()) => (),
+                    Err(e) => panic!("{}", e.to_string()),
+                }
+            })?;
+        Ok((id, thread))
+    }
+}
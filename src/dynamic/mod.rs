@@ -0,0 +1,86 @@
+#![cfg(feature = "dynamic")]
+
+//! This module contains the structures for the dynamic participants
+//! of a session: an unbounded set of participants of a role class,
+//! which join and leave while the session is running.
+//!
+//! The fixed roles of the session are bundled as usual, and their
+//! types are checked at compile time. One of them hosts the role class
+//! in a [`Pool`], and the dynamic participants join it with the
+//! [`Joiner`] of the pool. Each dynamic participant follows a binary
+//! session `S::Dual` with the host, whose type is checked at compile time,
+//! while the pool tracks which participants are members at runtime.
+//!
+//! The participants join with an explicit step on both sides: the
+//! participant calls [`Joiner::join`], and the host admits it with
+//! [`Pool::accept`]. A member rests in the pool at its session `S`,
+//! the well-defined point where it can leave: each [`Pool::step`] runs
+//! the session of a member until it either comes back to `S` with
+//! [`Step::Stay`], or ends with [`Step::Leave`].
+//!
+//! # Example
+//!
+//! ```
+//! use mpstthree::binary::recv::recv;
+//! use mpstthree::binary::send::send;
+//! use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+//! use mpstthree::dynamic::pool::{Pool, Step};
+//! use mpstthree::{choose, offer};
+//!
+//! // The session of a worker, from its side
+//! enum Command {
+//!     Work(Recv<u32, Send<u32, Worker>>),
+//!     Leave(End),
+//! }
+//! type Worker = Recv<Command, End>;
+//!
+//! // The session of the host with each worker
+//! type Host = Send<Command, End>;
+//!
+//! fn worker(s: Worker) -> Result<(), Box<dyn std::error::Error>> {
+//!     offer!(s, {
+//!         Command::Work(s) => {
+//!             let (task, s) = recv(s)?;
+//!             let s = send(task * 2, s);
+//!             worker(s)
+//!         },
+//!         Command::Leave(s) => {
+//!             mpstthree::binary::close::close(s)
+//!         },
+//!     })
+//! }
+//!
+//! let (mut pool, joiner) = Pool::<Host>::new();
+//!
+//! let (_, thread) = joiner.fork(worker).unwrap();
+//! let member = pool.accept().unwrap();
+//!
+//! pool.step(member, |s| {
+//!     let s = choose!(Command::Work, s);
+//!     let s = send(21, s);
+//!     let (result, s) = recv(s)?;
+//!     assert_eq!(result, 42);
+//!     Ok(Step::Stay(s))
+//! })
+//! .unwrap();
+//!
+//! pool.step(member, |s| Ok(Step::Leave(choose!(Command::Leave, s))))
+//!     .unwrap();
+//!
+//! assert!(pool.is_empty());
+//! assert!(thread.join().is_ok());
+//! ```
+//!
+//! [`Pool`]: crate::dynamic::pool::Pool
+//! [`Joiner`]: crate::dynamic::joiner::Joiner
+//! [`Joiner::join`]: crate::dynamic::joiner::Joiner::join
+//! [`Pool::accept`]: crate::dynamic::pool::Pool::accept
+//! [`Pool::step`]: crate::dynamic::pool::Pool::step
+//! [`Step::Stay`]: crate::dynamic::pool::Step::Stay
+//! [`Step::Leave`]: crate::dynamic::pool::Step::Leave
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"dynamic"` feature.*
+
+pub mod joiner;
+pub mod pool;
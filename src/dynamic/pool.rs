@@ -0,0 +1,210 @@
+//! This module contains the [`Pool`] hosting the dynamic participants
+//! of a role class, and tracking which ones are members.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"dynamic"` feature.*
+
+use crate::binary::close::close;
+use crate::binary::struct_trait::{end::End, session::Session};
+use crate::dynamic::joiner::Joiner;
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, TryRecvError};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The identifier of a dynamic participant, unique in its [`Pool`].
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"dynamic"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "dynamic")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemberId(pub(crate) usize);
+
+impl MemberId {
+    /// The index of the participant, in the order they joined the pool.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for MemberId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "member {}", self.0)
+    }
+}
+
+/// The outcome of a [`Pool::step`]: the member either comes back
+/// to the pool, at its session `S`, or leaves it.
+///
+/// *This enum is available only if MultiCrusty is built with
+/// the `"dynamic"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "dynamic")))]
+#[derive(Debug)]
+pub enum Step<S: Session> {
+    /// The member stays in the pool.
+    Stay(S),
+    /// The member leaves the pool, and its session is closed.
+    Leave(End),
+}
+
+/// The dynamic participants of a role class, hosted by a fixed role.
+///
+/// The host holds the session `S` with each member, and
+/// each member holds the dual session `S::Dual`.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"dynamic"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "dynamic")))]
+#[derive(Debug)]
+pub struct Pool<S: Session> {
+    joining: Receiver<(MemberId, S)>,
+    members: BTreeMap<MemberId, S>,
+    left: usize,
+}
+
+impl<S: Session> Pool<S> {
+    /// Creates an empty pool, and the [`Joiner`] through which
+    /// the participants join it.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> (Self, Joiner<S>) {
+        let (sender, joining) = unbounded();
+        let pool = Pool {
+            joining,
+            members: BTreeMap::new(),
+            left: 0,
+        };
+        (pool, Joiner::new(sender, Arc::new(AtomicUsize::new(0))))
+    }
+
+    fn admit(&mut self, id: MemberId, s: S) -> MemberId {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(member = id.0, "join");
+
+        self.members.insert(id, s);
+        id
+    }
+
+    /// Admits the next participant which joins the pool,
+    /// waiting for it if needed.
+    ///
+    /// Fails if no participant can join anymore,
+    /// once all the [`Joiner`] are dropped.
+    pub fn accept(&mut self) -> Result<MemberId, Box<dyn Error>> {
+        let (id, s) = self
+            .joining
+            .recv()
+            .map_err(|_| "No participant can join the pool anymore")?;
+        Ok(self.admit(id, s))
+    }
+
+    /// Admits the next participant which joins the pool,
+    /// waiting for it at most `timeout`.
+    pub fn accept_timeout(&mut self, timeout: Duration) -> Result<MemberId, Box<dyn Error>> {
+        match self.joining.recv_timeout(timeout) {
+            Ok((id, s)) => Ok(self.admit(id, s)),
+            Err(RecvTimeoutError::Timeout) => Err("No participant joined the pool in time".into()),
+            Err(RecvTimeoutError::Disconnected) => {
+                Err("No participant can join the pool anymore".into())
+            }
+        }
+    }
+
+    /// Admits all the participants which already joined the pool,
+    /// without waiting.
+    pub fn accept_pending(&mut self) -> Vec<MemberId> {
+        let mut admitted = Vec::new();
+        loop {
+            match self.joining.try_recv() {
+                Ok((id, s)) => admitted.push(self.admit(id, s)),
+                Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => return admitted,
+            }
+        }
+    }
+
+    /// The current members, in the order they joined the pool.
+    pub fn members(&self) -> Vec<MemberId> {
+        self.members.keys().copied().collect()
+    }
+
+    /// Whether `id` is a current member.
+    pub fn contains(&self, id: MemberId) -> bool {
+        self.members.contains_key(&id)
+    }
+
+    /// The number of current members.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Whether the pool has no current member.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// The number of members which left the pool,
+    /// including the ones which failed.
+    pub fn left(&self) -> usize {
+        self.left
+    }
+
+    /// Runs the session of the member `id` with `f`, until it either
+    /// comes back to the pool or leaves it.
+    ///
+    /// If `f` fails, for instance because the participant crashed,
+    /// the member is removed from the pool and the error is returned.
+    pub fn step<F>(&mut self, id: MemberId, f: F) -> Result<(), Box<dyn Error>>
+    where
+        F: FnOnce(S) -> Result<Step<S>, Box<dyn Error>>,
+    {
+        let s = self
+            .members
+            .remove(&id)
+            .ok_or_else(|| format!("The {} is not in the pool", id))?;
+
+        match f(s) {
+            Ok(Step::Stay(s)) => {
+                self.members.insert(id, s);
+                Ok(())
+            }
+            Ok(Step::Leave(s)) => {
+                #[cfg(feature = "tracing")]
+                tracing::trace!(member = id.0, "leave");
+
+                self.left += 1;
+                close(s)
+            }
+            Err(e) => {
+                #[cfg(feature = "tracing")]
+                tracing::trace!(member = id.0, error = %e, "leave");
+
+                self.left += 1;
+                Err(format!("The {} failed: {}", id, e).into())
+            }
+        }
+    }
+
+    /// Runs [`Pool::step`] for each current member, in the order
+    /// they joined the pool. Stops at the first failure.
+    pub fn step_all<F>(&mut self, mut f: F) -> Result<(), Box<dyn Error>>
+    where
+        F: FnMut(MemberId, S) -> Result<Step<S>, Box<dyn Error>>,
+    {
+        for id in self.members() {
+            self.step(id, |s| f(id, s))?;
+        }
+        Ok(())
+    }
+
+    /// Closes the pool, which must not have any member left.
+    /// The participants which did not join yet can not join anymore.
+    pub fn close(self) -> Result<(), Box<dyn Error>> {
+        if self.members.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("The pool still has {} members", self.members.len()).into())
+        }
+    }
+}
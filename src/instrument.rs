@@ -0,0 +1,178 @@
+//! This module contains the hooks called by the baked bundles
+//! and the macros for tracing the progress of the protocols.
+//!
+//! When MultiCrusty is built with the `"tracing"` feature,
+//! each operation of the baked bundles opens a span named after the operation
+//! (`send`, `recv`, `offer`, `choose`, `close` or `cancel`), with the fields
+//! `bundle` (the name of the *MeshedChannels*), `role` (the local role),
+//! and, when relevant, `peer` (the other role), `payload` (the name of the type
+//! of the payload) and `branch` (the label of the chosen branch, as given by [`branch_label`]),
+//! and emits an event at the `DEBUG` level inside this span.
+//! The functions of the [`binary`] module emit their own events at the `TRACE` level,
+//! which are nested into the spans of the bundles.
+//! All spans and events have targets starting with `mpstthree`.
+//!
+//! When MultiCrusty is built with the `"recording"` feature, the same hooks
+//! also log the steps of the roles run with [`record`].
+//!
+//! Without these features, all the hooks are no-ops.
+//!
+//! [`binary`]: crate::binary
+//! [`record`]: crate::testing::recording::record
+
+use crate::role::Role;
+
+use std::any::type_name;
+
+/// The guard of the span of an operation, which is exited when dropped.
+#[derive(Debug)]
+#[must_use = "the span is exited as soon as the guard is dropped"]
+pub struct Span {
+    #[cfg(feature = "tracing")]
+    _entered: tracing::span::EnteredSpan,
+}
+
+/// The label of a branch, from a path or a pattern such as `Branching::Success(s)`.
+pub fn branch_label(pattern: &str) -> &str {
+    let pattern = pattern.split('(').next().unwrap_or(pattern).trim();
+    pattern.rsplit("::").next().unwrap_or(pattern).trim()
+}
+
+/// The name of the type `T`, without the paths of the modules nor the generic parameters,
+/// such as `MeshedChannelsThree`.
+pub fn short_type_name<T: ?Sized>() -> &'static str {
+    let full_name = type_name::<T>();
+    let full_name = full_name.split('<').next().unwrap_or(full_name);
+    full_name.rsplit("::").next().unwrap_or(full_name)
+}
+
+/// The name of the type of `_value`, as given by [`short_type_name`].
+pub fn short_type_name_of<T: ?Sized>(_value: &T) -> &'static str {
+    short_type_name::<T>()
+}
+
+/// Open the span of a `send` of `payload` from `role` to `peer`.
+///
+/// With the `"recording"` feature, the step is recorded without the value of `payload`:
+/// the bundles baked with `recorded` call [`send_recorded`] instead.
+pub fn send<T>(bundle: &str, role: &str, peer: &str, _payload: &T) -> Span {
+    #[cfg(feature = "recording")]
+    crate::testing::recording::sent::<T>(role, peer, None);
+
+    send_span(bundle, role, peer, type_name::<T>())
+}
+
+/// Open the span of a `send` of `payload` from `role` to `peer`,
+/// as [`send`], but also give `payload` to the recording hook,
+/// so its value is recorded with the `"recording"` feature.
+///
+/// This is the hook of the bundles baked with `recorded`,
+/// such as `bundle_impl!(recorded MeshedChannelsThree, A, B, C)`,
+/// which is why their payloads must be `'static`.
+#[allow(unused_variables)]
+pub fn send_recorded<T: 'static>(bundle: &str, role: &str, peer: &str, payload: &T) -> Span {
+    #[cfg(feature = "recording")]
+    crate::testing::recording::sent::<T>(role, peer, Some(payload));
+
+    send_span(bundle, role, peer, type_name::<T>())
+}
+
+#[allow(unused_variables)]
+fn send_span(bundle: &str, role: &str, peer: &str, payload: &str) -> Span {
+    Span {
+        #[cfg(feature = "tracing")]
+        _entered: {
+            let span = tracing::debug_span!("send", bundle, role, peer, payload).entered();
+            tracing::debug!("{} sends {} to {}", role, payload, peer);
+            span
+        },
+    }
+}
+
+/// Open the span of a `recv` by `role` from `peer`.
+#[allow(unused_variables)]
+pub fn recv(bundle: &str, role: &str, peer: &str, payload: &str) -> Span {
+    Span {
+        #[cfg(feature = "tracing")]
+        _entered: {
+            let span = tracing::debug_span!("recv", bundle, role, peer, payload).entered();
+            tracing::debug!("{} waits for {} from {}", role, payload, peer);
+            span
+        },
+    }
+}
+
+/// Open the span of `role` receiving the `branch` chosen by another role.
+#[allow(unused_variables)]
+pub fn offer(bundle: &str, role: &str, branch: &str) -> Span {
+    Span {
+        #[cfg(feature = "tracing")]
+        _entered: {
+            let branch = branch_label(branch);
+            let span = tracing::debug_span!("offer", bundle, role, branch).entered();
+            tracing::debug!("{} follows {}", role, branch);
+            span
+        },
+    }
+}
+
+/// Open the span of the role named `name` in `session` receiving
+/// the branch matched by `pattern`, such as `Branching::Success(s)`.
+/// The names of the bundle and of the role are only computed
+/// when MultiCrusty is built with the `"tracing"` feature.
+#[allow(unused_variables)]
+pub fn offer_pattern<S, R: Role>(session: &S, name: &R, pattern: &str) -> Span {
+    Span {
+        #[cfg(feature = "tracing")]
+        _entered: offer(short_type_name::<S>(), &name.self_head_str(), pattern)._entered,
+    }
+}
+
+/// Open the span of `role` choosing `branch`.
+#[allow(unused_variables)]
+pub fn choose(bundle: &str, role: &str, branch: &str) -> Span {
+    #[cfg(feature = "recording")]
+    crate::testing::recording::step(format!("{}: choose {}", role, branch_label(branch)));
+
+    Span {
+        #[cfg(feature = "tracing")]
+        _entered: {
+            let branch = branch_label(branch);
+            let span = tracing::debug_span!("choose", bundle, role, branch).entered();
+            tracing::debug!("{} chooses {}", role, branch);
+            span
+        },
+    }
+}
+
+/// Open the span of `role` closing its session.
+#[allow(unused_variables)]
+pub fn close(bundle: &str, role: &str) -> Span {
+    #[cfg(feature = "recording")]
+    crate::testing::recording::step(format!("{}: close", role));
+
+    Span {
+        #[cfg(feature = "tracing")]
+        _entered: {
+            let span = tracing::debug_span!("close", bundle, role).entered();
+            tracing::debug!("{} closes", role);
+            span
+        },
+    }
+}
+
+/// Open the span of `role` cancelling its session.
+#[allow(unused_variables)]
+pub fn cancel(bundle: &str, role: &str) -> Span {
+    #[cfg(feature = "recording")]
+    crate::testing::recording::step(format!("{}: cancel", role));
+
+    Span {
+        #[cfg(feature = "tracing")]
+        _entered: {
+            let span = tracing::debug_span!("cancel", bundle, role).entered();
+            tracing::debug!("{} cancels", role);
+            span
+        },
+    }
+}
@@ -271,13 +271,24 @@
 //! 1. `default`: default features, for implementing the basic example above.
 //! 2. `macros_simple`: feature for implementing protocols with three participants, whatever are their name.
 //! 3. `macros_multiple`: feature for implementing protocols with any number of participants. Contains `macros_simple`.
-//! 4. `baking`: feature for implementing protocols with any number of participants and using associated functions instead of functions. Contains `macros_multiple`.
-//! 5. `transport_tcp`: feature containing primitives for communicating with TCP. **Requires `openssl`, `pkg-config` and `libssl-dev` installed on your machine**.
-//! 6. `transport_udp`: feature containing primitives for communicating with UDP. **Requires `openssl`, `pkg-config` and `libssl-dev` installed on your machine**.
-//! 7. `transport_http`: feature containing primitives for communicating with HTTP/HTTPS. **Requires `openssl`, `pkg-config` and `libssl-dev` installed on your machine**.
-//! 8. `transport`: feature containing `transport_tcp`, `transport_udp` and `transport_http`.
-//! 9. `checking`: feature for the top-down approach. Needs the [`KMC`] tool.
-//! 10. `full`: feature containing `checking`, `baking` and `transport`.
+//! 4. `baking`: feature for implementing protocols with any number of participants and using associated functions instead of functions, with families of indexed roles and ring, star and mesh templates instantiated for any number of roles, and sending one payload to all the other roles at once. Contains `macros_multiple`.
+//! 5. `transport_tcp`: feature containing primitives for communicating with TCP, and a rendezvous coordinator wiring the TCP connections of the roles, also run with `mpst rendezvous <address>`, heartbeats cancelling the session when a connection is lost, and resumable connections surviving transient failures. **Requires `openssl`, `pkg-config` and `libssl-dev` installed on your machine**.
+//! 6. `transport_tls`: feature containing primitives for communicating with TCP wrapped in TLS, with mutual authentication of the roles by [`rustls`](https://docs.rs/rustls). Contains `transport_tcp`.
+//! 7. `transport_udp`: feature containing primitives for communicating with UDP. **Requires `openssl`, `pkg-config` and `libssl-dev` installed on your machine**.
+//! 8. `transport_unix`: feature containing primitives for communicating with Unix domain sockets, for the roles running on the same host. Only available on Unix.
+//! 9. `transport_shm`: feature containing primitives for communicating through ring buffers in shared memory, signalled with futexes, for the latency-critical roles running on the same host. Only available on Linux.
+//! 10. `transport_websocket`: feature containing primitives for communicating with WebSockets, for instance with a participant running in a browser.
+//! 11. `transport_http`: feature containing primitives for communicating with HTTP/HTTPS. **Requires `openssl`, `pkg-config` and `libssl-dev` installed on your machine**.
+//! 12. `transport`: feature containing `transport_tcp`, `transport_tls`, `transport_udp`, `transport_unix`, `transport_shm`, `transport_websocket` and `transport_http`. The network transports, except HTTP, share a language-neutral wire format, specified in the `transport::wire` module.
+//! 13. `checking`: feature for the top-down approach. Needs the [`KMC`] tool.
+//! 14. `testing`: feature for unit-testing a single role against stubs of the other roles. Contains `baking`.
+//! 15. `property_testing`: feature for testing a role across the traces of its protocol with [`proptest`](https://docs.rs/proptest). Contains `testing` and `checking`.
+//! 16. `recording`: feature for recording the runs of the roles and replaying one role against the recorded peers. Contains `testing`.
+//! 17. `diagram`: feature for drawing the protocols as Mermaid or PlantUML sequence diagrams, from their types with `checking` or from their recorded runs with `recording`.
+//! 18. `tracing`: feature for emitting [`tracing`](https://docs.rs/tracing) spans and events from each operation on the sessions.
+//! 19. `scribble`: feature for reading Scribble protocols, from the `mpst` command-line tool or from build scripts. Contains `checking`.
+//! 20. `dynamic`: feature for the dynamic participants of a role class, which join and leave a running session.
+//! 21. `full`: feature containing `checking`, `baking`, `testing`, `property_testing`, `recording`, `diagram`, `tracing`, `scribble`, `dynamic` and `transport`.
 //!
 //! [`KMC`]: https://github.com/julien-lange/kmc
 
@@ -313,6 +324,24 @@ pub mod baking;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "interleaved")))]
 pub mod interleaved;
 
+#[cfg(feature = "testing")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+pub mod testing;
+
+pub mod instrument;
+
+#[cfg(feature = "diagram")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "diagram")))]
+pub mod diagram;
+
+#[cfg(feature = "scribble")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "scribble")))]
+pub mod scribble;
+
+#[cfg(feature = "dynamic")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "dynamic")))]
+pub mod dynamic;
+
 pub mod transport;
 
 pub mod attempt;
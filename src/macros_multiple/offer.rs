@@ -169,10 +169,17 @@ macro_rules! offer_mpst {
     ($session: expr, { $( $pat: pat => $result: expr, )+ }) => {
         (move || -> Result<_, _> {
             let (l, s) = $session.recv()?;
-            mpstthree::binary::cancel::cancel(s);
             match l {
                 $(
-                    $pat => $result,
+                    $pat => {
+                        let _span = mpstthree::instrument::offer_pattern(
+                            &s,
+                            &s.name,
+                            stringify!($pat),
+                        );
+                        mpstthree::binary::cancel::cancel(s);
+                        $result
+                    },
                 )+
                 _ => panic!("Unexpected payload") ,
             }
@@ -242,8 +249,6 @@ macro_rules! offer_cancel_mpst {
 macro_rules! offer_http_mpst {
     ($session: expr, $recv_mpst: ident, { $( $pat: pat => $result: expr, )+ }) => {
         (move || -> Result<_, _> {
-            let https = hyper_tls::HttpsConnector::new();
-            let client = hyper::Client::builder().build::<_, hyper::Body>(https);
             let (l, s, req) = $recv_mpst($session, false, Vec::new())?;
             mpstthree::binary::cancel::cancel(s);
             mpstthree::binary::cancel::cancel(req);
@@ -0,0 +1,60 @@
+//! This module contains the traits for the families of
+//! indexed roles, such as `Worker[4]` for the roles
+//! `RoleWorker0` to `RoleWorker3`, created by
+//! [`bundle_family!`] and its variants.
+//!
+//! A family is represented by an uninhabited marker, named after the
+//! family, which gives the role at index `I` with
+//! const generics, as `<Worker as Indexed<2>>::Name`.
+//!
+//! [`bundle_family!`]: crate::bundle_family
+
+use crate::role::Role;
+
+/// The marker of a family of indexed roles.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::bundle_family;
+/// use mpstthree::role::family::Family;
+///
+/// bundle_family!(MeshedChannelsFour, Worker[4]);
+///
+/// assert_eq!(Worker::NAME, "Worker");
+/// assert_eq!(Worker::SIZE, 4);
+/// ```
+pub trait Family {
+    /// The name of the family.
+    const NAME: &'static str;
+
+    /// The number of roles of the family.
+    const SIZE: usize;
+}
+
+/// The role at index `I` of a [`Family`], starting at 0.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::bundle_family;
+/// use mpstthree::role::end::RoleEnd;
+/// use mpstthree::role::family::{Indexed, Member};
+///
+/// bundle_family!(MeshedChannelsFour, Worker[4]);
+///
+/// // The stack of RoleWorker0, which receives from RoleWorker3
+/// type StackWorker0 = Member<Worker, 3, RoleEnd>;
+/// type NameWorker2 = <Worker as Indexed<2>>::Name;
+/// ```
+pub trait Indexed<const I: usize>: Family {
+    /// The role at index `I`, followed by the stack `R`.
+    type Role<R: Role>: Role;
+
+    /// The name of the role at index `I`.
+    type Name: Role;
+}
+
+/// The role at index `I` of the family `F`,
+/// followed by the stack `R`.
+pub type Member<F, const I: usize, R> = <F as Indexed<I>>::Role<R>;
@@ -30,6 +30,8 @@ pub mod c;
 pub mod c_dual;
 pub mod c_to_all;
 pub mod end;
+pub mod family;
+pub mod to_all;
 use std::marker;
 
 /// Trait for session types. Provides duality.
@@ -0,0 +1,85 @@
+//! This module contains the required definitions and
+//! functions for a role to send a payload to all the other roles.
+//! Its dual is it self.
+
+use crate::role::Role;
+use crossbeam_channel::{bounded, Sender};
+
+/// Gives the order to the
+/// [`MeshedChannels`] of a role which sends the same payload
+/// to all the other roles at once, with `send_to_all`.
+///
+/// All the binary sessions of the [`MeshedChannels`] must start with
+/// sending the payload. The payload is sent to each other role,
+/// in the order of the roles.
+///
+/// [`MeshedChannels`]: crate::meshedchannels::MeshedChannels
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::role::end::RoleEnd;
+/// use mpstthree::role::to_all::RoleToAll;
+/// use mpstthree::role::Role; // Only used for example
+///
+/// type StackToAll = RoleToAll<RoleEnd>;
+///
+/// let _ = StackToAll::new(); // Only used for example
+/// ```
+#[derive(Debug)]
+pub struct RoleToAll<R>
+where
+    R: Role,
+    R::Dual: Role,
+{
+    #[doc(hidden)]
+    pub sender: Sender<R::Dual>,
+}
+
+impl<R: Role> Role for RoleToAll<R> {
+    type Dual = RoleToAll<R::Dual>;
+
+    #[doc(hidden)]
+    fn new() -> (Self, Self::Dual) {
+        let (sender_normal, _) = bounded::<R>(1);
+        let (sender_dual, _) = bounded::<R::Dual>(1);
+
+        (
+            RoleToAll {
+                sender: sender_dual,
+            },
+            RoleToAll {
+                sender: sender_normal,
+            },
+        )
+    }
+
+    #[doc(hidden)]
+    fn head_str() -> String {
+        "RoleToAll".to_string()
+    }
+
+    #[doc(hidden)]
+    fn tail_str() -> String {
+        format!("{}<{}>", R::head_str(), R::tail_str())
+    }
+
+    #[doc(hidden)]
+    fn self_head_str(&self) -> String {
+        "RoleToAll".to_string()
+    }
+
+    #[doc(hidden)]
+    fn self_tail_str(&self) -> String {
+        format!("{}<{}>", R::head_str(), R::tail_str())
+    }
+}
+
+impl<R: Role> RoleToAll<R> {
+    /// Return the continuation for RoleToAll
+    pub fn continuation(&self) -> R {
+        let (here, there) = R::new();
+        self.sender.send(there).unwrap_or(());
+        here
+    }
+}
@@ -0,0 +1,184 @@
+//! This module contains the functions for generating,
+//! from the `build.rs` script of a crate, the Rust types of its
+//! Scribble protocols, replacing the `scripts/top_down.sh` flow.
+//!
+//! Each `name.scr` file of the `protocols` folder is turned into
+//! `name.rs` in the `OUT_DIR` of the crate, with the same types as
+//! [`Protocol::generate`], and Cargo is told to run the build script
+//! again whenever a protocol changes.
+//! The generated code is then included with [`include_protocol!`]
+//! in the module implementing the roles,
+//! which should have neither the same imports nor another protocol.
+//!
+//! # Example
+//!
+//! In `Cargo.toml`:
+//!
+//! ```toml
+//! [build-dependencies]
+//! mpstthree = { version = "0.1", features = ["scribble"] }
+//! ```
+//!
+//! In `build.rs`:
+//!
+//! ```no_run
+//! fn main() {
+//!     mpstthree::scribble::build::Builder::new()
+//!         .compile()
+//!         .unwrap();
+//! }
+//! ```
+//!
+//! In `src/fib.rs`, for `protocols/fib.scr`:
+//!
+//! ```ignore
+//! #![allow(clippy::type_complexity, dead_code)]
+//!
+//! mpstthree::include_protocol!("fib");
+//!
+//! fn endpoint_b(s: EndpointB) -> Result<(), Box<dyn std::error::Error>> {
+//!     offer_mpst!(s, {
+//!         Branching0fromAToB::Stop(s) => {
+//!             let (_, s) = s.recv()?;
+//!             s.close()
+//!         },
+//!         Branching0fromAToB::Fibonacci(s) => {
+//!             let (_, s) = s.recv()?;
+//!             let s = s.send(Fibonacci(1))?;
+//!             endpoint_b(s)
+//!         },
+//!     })
+//! }
+//! ```
+//!
+//! [`Protocol::generate`]: crate::scribble::Protocol::generate
+//! [`include_protocol!`]: crate::include_protocol
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"scribble"` feature.*
+
+use std::env;
+use std::error::Error;
+use std::fs::{create_dir_all, read_dir, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use super::Protocol;
+
+/// Generate the Rust types of all the Scribble protocols of a folder.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"scribble"` feature.*
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "scribble")))]
+pub struct Builder {
+    protocols: PathBuf,
+    out_dir: Option<PathBuf>,
+    rerun: bool,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Builder {
+    /// Read the protocols of the `protocols` folder, and write
+    /// the generated code in the `OUT_DIR` given by Cargo.
+    pub fn new() -> Self {
+        Builder {
+            protocols: PathBuf::from("protocols"),
+            out_dir: None,
+            rerun: true,
+        }
+    }
+
+    /// Read the protocols of `protocols` instead.
+    pub fn protocols<P: AsRef<Path>>(mut self, protocols: P) -> Self {
+        self.protocols = protocols.as_ref().to_path_buf();
+        self
+    }
+
+    /// Write the generated code in `out_dir` instead.
+    pub fn out_dir<P: AsRef<Path>>(mut self, out_dir: P) -> Self {
+        self.out_dir = Some(out_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Whether to print the `cargo:rerun-if-changed` instructions,
+    /// which is the default.
+    pub fn rerun_if_changed(mut self, rerun: bool) -> Self {
+        self.rerun = rerun;
+        self
+    }
+
+    /// Generate the code of each protocol, in the alphabetical order
+    /// of their files, and return the paths of the written files.
+    ///
+    /// A file is rewritten only if its content changes,
+    /// so that the crate is not needlessly recompiled.
+    /// The first protocol which cannot be parsed stops the generation,
+    /// with an error giving its file and its line.
+    pub fn compile(&self) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        let out_dir = match &self.out_dir {
+            Some(out_dir) => out_dir.clone(),
+            None => PathBuf::from(
+                env::var_os("OUT_DIR").ok_or("OUT_DIR is not set, is this a build script?")?,
+            ),
+        };
+        create_dir_all(&out_dir)?;
+
+        if self.rerun {
+            println!("cargo:rerun-if-changed={}", self.protocols.display());
+        }
+
+        let mut files = read_dir(&self.protocols)
+            .map_err(|e| format!("Cannot read {}: {}", self.protocols.display(), e))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        files.retain(|file| file.extension().map(|e| e == "scr").unwrap_or(false));
+        files.sort();
+
+        let mut result = Vec::new();
+
+        for file in files.iter() {
+            if self.rerun {
+                println!("cargo:rerun-if-changed={}", file.display());
+            }
+
+            let protocol = Protocol::load(file)?;
+            let code = format!(
+                "// Generated from {} by mpstthree::scribble::build\n\n{}",
+                file.display(),
+                protocol.generate_items()?
+            );
+
+            let output = out_dir.join(file.with_extension("rs").file_name().unwrap());
+            if read_to_string(&output).ok().as_ref() != Some(&code) {
+                write(&output, code)?;
+            }
+            result.push(output);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Include the code generated by [`Builder::compile`]
+/// for the protocol `name`, from the `OUT_DIR` of the crate.
+///
+/// # Example
+///
+/// ```ignore
+/// mpstthree::include_protocol!("fib");
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"scribble"` feature.*
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "scribble")))]
+macro_rules! include_protocol {
+    ($name: literal) => {
+        include!(concat!(env!("OUT_DIR"), "/", $name, ".rs"));
+    };
+}
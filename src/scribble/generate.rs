@@ -0,0 +1,429 @@
+//! The generation of the Rust types of the global protocols.
+
+use std::error::Error;
+use std::fmt::Write;
+
+use super::{branch_names, payload_name, Global, Protocol};
+
+// A statement where the payloads are replaced by the name of their type,
+// and each choice is numbered.
+enum Node {
+    Message {
+        from: String,
+        to: String,
+        payload: String,
+    },
+    Choice {
+        id: usize,
+        at: String,
+        branches: Vec<(String, Vec<Node>)>,
+    },
+    Rec {
+        name: String,
+        body: Vec<Node>,
+    },
+    Continue(String),
+}
+
+// The recursions in scope, along with their bodies.
+type Recs<'a> = Vec<(&'a str, &'a [Node])>;
+
+fn to_nodes(body: &[Global], count: &mut usize) -> Vec<Node> {
+    body.iter()
+        .map(|statement| match statement {
+            Global::Message {
+                label, from, to, ..
+            } => Node::Message {
+                from: from.clone(),
+                to: to.clone(),
+                payload: payload_name(label),
+            },
+            Global::Choice { at, branches } => {
+                let id = *count;
+                *count += 1;
+                Node::Choice {
+                    id,
+                    at: at.clone(),
+                    branches: branch_names(at, branches)
+                        .into_iter()
+                        .zip(branches.iter())
+                        .map(|(name, branch)| (name, to_nodes(branch, count)))
+                        .collect(),
+                }
+            }
+            Global::Rec { name, body } => Node::Rec {
+                name: name.clone(),
+                body: to_nodes(body, count),
+            },
+            Global::Continue(name) => Node::Continue(name.clone()),
+        })
+        .collect()
+}
+
+// The Rust type of the payloads written in Scribble.
+fn primitive(payload: &str) -> Option<&'static str> {
+    match payload {
+        "int" | "Int" | "Integer" | "i32" => Some("i32"),
+        "long" | "Long" | "i64" => Some("i64"),
+        "double" | "Double" | "f64" => Some("f64"),
+        "float" | "Float" | "f32" => Some("f32"),
+        "bool" | "Bool" | "boolean" | "Boolean" => Some("bool"),
+        "char" | "Char" | "Character" => Some("char"),
+        "string" | "String" | "str" => Some("String"),
+        _ => None,
+    }
+}
+
+// The name of the MeshedChannels for `number` roles.
+fn meshedchannels_name(number: usize) -> String {
+    const NUMBERS: [&str; 19] = [
+        "Two",
+        "Three",
+        "Four",
+        "Five",
+        "Six",
+        "Seven",
+        "Eight",
+        "Nine",
+        "Ten",
+        "Eleven",
+        "Twelve",
+        "Thirteen",
+        "Fourteen",
+        "Fifteen",
+        "Sixteen",
+        "Seventeen",
+        "Eighteen",
+        "Nineteen",
+        "Twenty",
+    ];
+
+    match NUMBERS.get(number - 2) {
+        Some(word) => format!("MeshedChannels{}", word),
+        None => format!("MeshedChannels{}", number),
+    }
+}
+
+struct Generator<'a> {
+    roles: &'a [String],
+    meshedchannels: String,
+}
+
+impl<'a> Generator<'a> {
+    // The other roles, in the order of the sessions of the MeshedChannels of `role`.
+    fn peers(&self, role: &str) -> Vec<&'a str> {
+        self.roles
+            .iter()
+            .filter(|r| *r != role)
+            .map(|r| r.as_str())
+            .collect()
+    }
+
+    // The binary sessions and the stack of `role` for `body`.
+    fn sessions<'b>(&self, role: &str, body: &'b [Node], recs: &Recs<'b>) -> (Vec<String>, String) {
+        let peers = self.peers(role);
+
+        let (statement, rest) = match body.split_first() {
+            Some(split) => split,
+            None => return (vec!["End".to_string(); peers.len()], "RoleEnd".to_string()),
+        };
+
+        match statement {
+            Node::Message { from, to, payload } => {
+                let (mut sessions, stack) = self.sessions(role, rest, recs);
+                let (kind, peer) = if from == role {
+                    ("Send", to)
+                } else if to == role {
+                    ("Recv", from)
+                } else {
+                    return (sessions, stack);
+                };
+                let index = peers.iter().position(|p| p == peer).unwrap();
+                sessions[index] = format!("{}<{}, {}>", kind, payload, sessions[index]);
+                (sessions, format!("Role{}<{}>", peer, stack))
+            }
+            Node::Choice { id, at, .. } => {
+                if at == role {
+                    (
+                        peers
+                            .iter()
+                            .map(|peer| format!("Choose{}from{}To{}", id, at, peer))
+                            .collect(),
+                        "RoleBroadcast".to_string(),
+                    )
+                } else {
+                    (
+                        peers
+                            .iter()
+                            .map(|peer| {
+                                if peer == at {
+                                    format!("Offer{}from{}To{}", id, at, role)
+                                } else {
+                                    "End".to_string()
+                                }
+                            })
+                            .collect(),
+                        format!("Role{}<RoleEnd>", at),
+                    )
+                }
+            }
+            Node::Rec { name, body } => {
+                let mut recs = recs.clone();
+                recs.push((name, body));
+                self.sessions(role, body, &recs)
+            }
+            Node::Continue(name) => {
+                let index = recs.iter().rposition(|(rec, _)| rec == name).unwrap();
+                self.sessions(role, recs[index].1, &recs[..=index].to_vec())
+            }
+        }
+    }
+
+    // The type of the MeshedChannels of `role` for `body`.
+    fn meshedchannels<'b>(&self, role: &str, body: &'b [Node], recs: &Recs<'b>) -> String {
+        let (sessions, stack) = self.sessions(role, body, recs);
+        format!(
+            "{}<{}, {}, Name{}>",
+            self.meshedchannels,
+            sessions.join(", "),
+            stack,
+            role
+        )
+    }
+}
+
+// All the choices of `body`, along with the recursions in their scope.
+fn choices<'a>(body: &'a [Node], recs: &Recs<'a>, result: &mut Vec<(&'a Node, Recs<'a>)>) {
+    for statement in body.iter() {
+        match statement {
+            Node::Choice { branches, .. } => {
+                result.push((statement, recs.clone()));
+                for (_, branch) in branches.iter() {
+                    choices(branch, recs, result);
+                }
+            }
+            Node::Rec { name, body } => {
+                let mut recs = recs.clone();
+                recs.push((name, body));
+                choices(body, &recs, result);
+            }
+            _ => {}
+        }
+    }
+}
+
+// The labels of `body`, along with their payloads, in their order of appearance.
+fn labels(body: &[Global], result: &mut Vec<(String, Vec<String>)>) -> Result<(), Box<dyn Error>> {
+    for statement in body.iter() {
+        match statement {
+            Global::Message {
+                label, payloads, ..
+            } => {
+                let name = payload_name(label);
+                match result.iter().find(|(other, _)| *other == name) {
+                    Some((_, others)) if others != payloads => {
+                        return Err(format!(
+                            "The label {} is used with the payloads ({}) and ({})",
+                            name,
+                            others.join(", "),
+                            payloads.join(", ")
+                        )
+                        .into())
+                    }
+                    Some(_) => {}
+                    None => result.push((name, payloads.clone())),
+                }
+            }
+            Global::Choice { branches, .. } => {
+                for branch in branches.iter() {
+                    labels(branch, result)?;
+                }
+            }
+            Global::Rec { body, .. } => labels(body, result)?,
+            Global::Continue(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+impl Protocol {
+    /// Generate the Rust types implementing the protocol with a bundle
+    /// created by [`bundle_impl_with_enum_and_cancel!`], as written
+    /// by hand in the examples of the library.
+    ///
+    /// Each label becomes a payload type, and the payloads of the
+    /// messages become its fields.
+    /// The payloads named as usual primitive types, such as `int` or `String`,
+    /// are translated to their Rust counterparts, and the other ones are
+    /// declared as empty structs.
+    ///
+    /// For each role `X`, the returned code contains the type `EndpointX`
+    /// of its MeshedChannels at the start of the protocol.
+    /// For each choice `n` at `X`, it contains the enum
+    /// `BranchingnfromXToY` sent to each other role `Y`,
+    /// and the type `EndpointXnBranch` of the MeshedChannels of `X`
+    /// after choosing `Branch`.
+    ///
+    /// [`bundle_impl_with_enum_and_cancel!`]: crate::bundle_impl_with_enum_and_cancel
+    pub fn generate(&self) -> Result<String, Box<dyn Error>> {
+        Ok(format!(
+            "// Generated from the Scribble protocol {} by `mpst generate`\n\n\
+            #![allow(clippy::type_complexity, dead_code)]\n\n{}",
+            self.name,
+            self.generate_items()?
+        ))
+    }
+
+    // The imports and the items of the generated code,
+    // which can be included in any module.
+    pub(crate) fn generate_items(&self) -> Result<String, Box<dyn Error>> {
+        let generator = Generator {
+            roles: &self.roles,
+            meshedchannels: meshedchannels_name(self.roles.len()),
+        };
+
+        let nodes = to_nodes(&self.body, &mut 0);
+
+        let mut all_choices = Vec::new();
+        choices(&nodes, &Vec::new(), &mut all_choices);
+
+        // Payload types
+        let mut all_labels = Vec::new();
+        labels(&self.body, &mut all_labels)?;
+
+        let mut payloads = String::new();
+        let mut others = Vec::new();
+
+        for (label, types) in all_labels.iter() {
+            let mut fields = Vec::new();
+
+            for payload in types.iter() {
+                if let Some(primitive) = primitive(payload) {
+                    fields.push(primitive.to_string());
+                } else if payload.contains('.') {
+                    return Err(format!("The payload {} is not a Rust type", payload).into());
+                } else if all_labels.iter().any(|(other, _)| other == payload) {
+                    return Err(format!("The payload {} is also a label", payload).into());
+                } else {
+                    if !others.contains(payload) {
+                        others.push(payload.to_string());
+                    }
+                    fields.push(payload.to_string());
+                }
+            }
+
+            if fields.is_empty() {
+                writeln!(payloads, "struct {};", label)?;
+            } else {
+                writeln!(payloads, "struct {}({});", label, fields.join(", "))?;
+            }
+        }
+
+        for other in others.iter() {
+            writeln!(payloads, "struct {};", other)?;
+        }
+
+        // Names
+        let mut names = String::new();
+
+        for role in self.roles.iter() {
+            writeln!(names, "type Name{} = Role{}<RoleEnd>;", role, role)?;
+        }
+
+        // Types and MP sessions
+        let mut types = String::new();
+        let mut endpoints = String::new();
+
+        for role in self.roles.iter() {
+            writeln!(types, "// {}", role.to_uppercase())?;
+            writeln!(endpoints, "// {}", role.to_uppercase())?;
+
+            writeln!(
+                endpoints,
+                "type Endpoint{} = {};",
+                role,
+                generator.meshedchannels(role, &nodes, &Vec::new())
+            )?;
+
+            for (choice, recs) in all_choices.iter() {
+                if let Node::Choice { id, at, branches } = choice {
+                    if at == role {
+                        for peer in generator.peers(role) {
+                            writeln!(
+                                types,
+                                "type Choose{}from{}To{} = Send<Branching{}from{}To{}, End>;",
+                                id, at, peer, id, at, peer
+                            )?;
+                        }
+
+                        for (name, branch) in branches.iter() {
+                            writeln!(
+                                endpoints,
+                                "type Endpoint{}{}{} = {};",
+                                role,
+                                id,
+                                name,
+                                generator.meshedchannels(role, branch, recs)
+                            )?;
+                        }
+                    } else {
+                        writeln!(types, "enum Branching{}from{}To{} {{", id, at, role)?;
+                        for (name, branch) in branches.iter() {
+                            writeln!(
+                                types,
+                                "    {}({}),",
+                                name,
+                                generator.meshedchannels(role, branch, recs)
+                            )?;
+                        }
+                        writeln!(types, "}}")?;
+                        writeln!(
+                            types,
+                            "type Offer{}from{}To{} = Recv<Branching{}from{}To{}, End>;",
+                            id, at, role, id, at, role
+                        )?;
+                    }
+                }
+            }
+        }
+
+        // Imports
+        let all_types = format!("{}{}", types, endpoints);
+        let sessions = ["End", "Recv", "Send"]
+            .iter()
+            .filter(|session| all_types.contains(&format!("{}<", session)) || **session == "End")
+            .map(|session| format!("{}::{}", session.to_lowercase(), session))
+            .collect::<Vec<_>>();
+
+        let mut result = String::new();
+
+        writeln!(
+            result,
+            "use mpstthree::binary::struct_trait::{{{}}};",
+            sessions.join(", ")
+        )?;
+        writeln!(result, "use mpstthree::bundle_impl_with_enum_and_cancel;")?;
+        if all_types.contains("RoleBroadcast") {
+            writeln!(result, "use mpstthree::role::broadcast::RoleBroadcast;")?;
+        }
+        writeln!(result, "use mpstthree::role::end::RoleEnd;\n")?;
+        writeln!(
+            result,
+            "// Create the new MeshedChannels for {} participants and the close and fork functions",
+            self.roles.len()
+        )?;
+        writeln!(
+            result,
+            "bundle_impl_with_enum_and_cancel!({}, {});\n",
+            generator.meshedchannels,
+            self.roles.join(", ")
+        )?;
+        writeln!(result, "// Payload types\n{}", payloads)?;
+        writeln!(result, "// Names\n{}", names)?;
+        writeln!(result, "// Types\n{}", types)?;
+        write!(result, "// Creating the MP sessions\n{}", endpoints)?;
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,88 @@
+//! The CFSMs of the global protocols.
+
+use petgraph::graph::NodeIndex;
+use petgraph::Graph;
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+
+use super::{payload_name, Global, Protocol};
+
+type GraphOfStrStr = Graph<String, String>;
+
+impl Protocol {
+    /// Build the CFSM of each role, labelled as the ones returned by
+    /// [`checker_concat!`] for the types given by [`Protocol::generate`].
+    ///
+    /// The keys are the names of the roles, such as `RoleA`,
+    /// and the node `0` of each graph is its starting state.
+    /// The result can be given to the functions of the [`checking`] module.
+    ///
+    /// [`checker_concat!`]: crate::checker_concat
+    /// [`checking`]: crate::checking
+    pub fn graphs(&self) -> HashMap<String, GraphOfStrStr> {
+        let state = RandomState::new();
+        let mut result = HashMap::with_hasher(state);
+
+        for role in self.roles.iter() {
+            let mut graph = Graph::new();
+            let start = graph.add_node("0".to_string());
+            build(&mut graph, role, &self.body, start, &mut Vec::new());
+            result.insert(format!("Role{}", role), graph);
+        }
+
+        result
+    }
+}
+
+// Add the local actions of `role` in `body`, starting from `node`.
+fn build(
+    graph: &mut GraphOfStrStr,
+    role: &str,
+    body: &[Global],
+    mut node: NodeIndex<u32>,
+    recs: &mut Vec<(String, NodeIndex<u32>)>,
+) {
+    for statement in body.iter() {
+        match statement {
+            Global::Message {
+                label, from, to, ..
+            } => {
+                let label = if from == role {
+                    format!("Role{}!Role{}: {}", from, to, payload_name(label))
+                } else if to == role {
+                    format!("Role{}?Role{}: {}", to, from, payload_name(label))
+                } else {
+                    continue;
+                };
+
+                let new_node = graph.add_node(graph.node_count().to_string());
+                graph.add_edge(node, new_node, label);
+                node = new_node;
+            }
+            Global::Choice { branches, .. } => {
+                for branch in branches.iter() {
+                    build(graph, role, branch, node, recs);
+                }
+                return;
+            }
+            Global::Rec { name, body } => {
+                recs.push((name.clone(), node));
+                build(graph, role, body, node, recs);
+                recs.pop();
+                return;
+            }
+            Global::Continue(name) => {
+                if let Some((_, target)) = recs.iter().rev().find(|(rec, _)| rec == name) {
+                    if *target != node && !graph.contains_edge(node, *target) {
+                        graph.add_edge(node, *target, "µ".to_string());
+                    }
+                }
+                return;
+            }
+        }
+    }
+
+    let new_node = graph.add_node(graph.node_count().to_string());
+    graph.add_edge(node, new_node, "0".to_string());
+}
@@ -0,0 +1,319 @@
+#![cfg(feature = "scribble")]
+
+//! This module contains the structures and the functions
+//! for reading global protocols written in [Scribble](https://www.scribble.org/),
+//! such as the ones in the `scribble_protocols` folder,
+//! without any external tool.
+//!
+//! A [`Protocol`] can be projected on each of its roles,
+//! turned into the Rust types of a bundle created with
+//! [`bundle_impl_with_enum_and_cancel!`], or into the CFSMs used by the
+//! functions of the [`checking`] module.
+//!
+//! The [`build`] module does the same from the `build.rs` script of a crate.
+//!
+//! Only a subset of Scribble is supported: a single `global protocol`
+//! made of messages, `choice at`, `rec` and `continue`,
+//! and recursive calls to itself with `do`.
+//! As in the rest of the library, each choice is broadcast by the choosing
+//! role to all the other roles.
+//!
+//! # Example
+//!
+//! ```
+//! use mpstthree::scribble::Protocol;
+//!
+//! let protocol = Protocol::parse(
+//!     "global protocol Fibonacci(role A, role B) {
+//!         rec Fib {
+//!             choice at A {
+//!                 fibonacci(Long) from A to B;
+//!                 fibonacci(Long) from B to A;
+//!                 continue Fib;
+//!             } or {
+//!                 stop() from A to B;
+//!             }
+//!         }
+//!     }",
+//! )
+//! .unwrap();
+//!
+//! assert_eq!(protocol.roles(), &["A", "B"]);
+//! assert!(protocol.project("B").unwrap().contains("fibonacci(Long) from A;"));
+//! ```
+//!
+//! [`bundle_impl_with_enum_and_cancel!`]: crate::bundle_impl_with_enum_and_cancel
+//! [`checking`]: crate::checking
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"scribble"` feature.*
+
+use std::error::Error;
+use std::fs::read_to_string;
+use std::path::Path;
+
+pub mod build;
+
+mod generate;
+mod graph;
+mod parse;
+mod project;
+
+/// A statement of a global protocol.
+///
+/// *This enum is available only if MultiCrusty is built with
+/// the `"scribble"` feature.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "scribble")))]
+pub enum Global {
+    /// `label(payloads) from from to to;`
+    Message {
+        /// The label of the message
+        label: String,
+        /// The types of the payloads of the message
+        payloads: Vec<String>,
+        /// The sending role
+        from: String,
+        /// The receiving role
+        to: String,
+    },
+    /// `choice at at { .. } or { .. }`
+    Choice {
+        /// The choosing role
+        at: String,
+        /// The statements of each branch
+        branches: Vec<Vec<Global>>,
+    },
+    /// `rec name { .. }`
+    Rec {
+        /// The name of the recursion
+        name: String,
+        /// The statements of the recursion
+        body: Vec<Global>,
+    },
+    /// `continue name;`
+    Continue(String),
+}
+
+/// A global protocol read from a Scribble file.
+///
+/// The statements are normalised: a choice or a recursion is always
+/// the last statement of its block, the statements following it
+/// in the Scribble file being copied at the end of each of its branches.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"scribble"` feature.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "scribble")))]
+pub struct Protocol {
+    name: String,
+    roles: Vec<String>,
+    body: Vec<Global>,
+}
+
+impl Protocol {
+    /// Parse and check a global protocol.
+    ///
+    /// The returned errors give the line of the faulty statement,
+    /// such as `line 3: unknown role Api`.
+    pub fn parse(source: &str) -> Result<Self, Box<dyn Error>> {
+        let (name, roles, body) = parse::parse(source)?;
+
+        let body = normalise(&body, &[]);
+
+        let protocol = Protocol { name, roles, body };
+        protocol.check_recursions(&protocol.body, &[])?;
+
+        Ok(protocol)
+    }
+
+    /// Read, parse and check the global protocol of a Scribble file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let source =
+            read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        Self::parse(&source).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    /// The name of the protocol.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The roles of the protocol, in their order of declaration.
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    /// The normalised statements of the protocol.
+    pub fn body(&self) -> &[Global] {
+        &self.body
+    }
+
+    // Check that each `continue` is reached only after a choice,
+    // otherwise the local types would be infinite without any branching.
+    fn check_recursions(
+        &self,
+        body: &[Global],
+        recs: &[(&str, &[Global])],
+    ) -> Result<(), Box<dyn Error>> {
+        for statement in body.iter() {
+            match statement {
+                Global::Choice { branches, .. } => {
+                    for branch in branches.iter() {
+                        self.check_recursions(branch, recs)?;
+                    }
+                }
+                Global::Rec { name, body } => {
+                    let mut recs = recs.to_vec();
+                    recs.push((name, body));
+                    reaches_choice(body, &recs, &mut Vec::new())?;
+                    self.check_recursions(body, &recs)?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Walk `body` until a choice, failing on a `continue` reached before.
+fn reaches_choice<'a>(
+    body: &'a [Global],
+    recs: &[(&'a str, &'a [Global])],
+    seen: &mut Vec<&'a str>,
+) -> Result<(), Box<dyn Error>> {
+    for statement in body.iter() {
+        match statement {
+            Global::Message { .. } => {}
+            Global::Choice { .. } => return Ok(()),
+            Global::Rec { name, body } => {
+                let mut recs = recs.to_vec();
+                recs.push((name, body));
+                return reaches_choice(body, &recs, seen);
+            }
+            Global::Continue(name) => {
+                if seen.contains(&name.as_str()) {
+                    return Err(format!(
+                        "rec {}: `continue {}` is reached without any choice",
+                        name, name
+                    )
+                    .into());
+                }
+                seen.push(name);
+                let (_, body) = recs.iter().find(|(rec, _)| rec == name).unwrap();
+                return reaches_choice(body, recs, seen);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Copy the statements following a choice or a recursion into each of its branches.
+fn normalise(body: &[Global], after: &[Global]) -> Vec<Global> {
+    let mut result = Vec::new();
+
+    for (index, statement) in body.iter().enumerate() {
+        let rest = || {
+            let mut rest = body[(index + 1)..].to_vec();
+            rest.extend_from_slice(after);
+            rest
+        };
+
+        match statement {
+            Global::Message { .. } => result.push(statement.clone()),
+            Global::Continue(_) => {
+                result.push(statement.clone());
+                return result;
+            }
+            Global::Choice { at, branches } => {
+                let rest = rest();
+                result.push(Global::Choice {
+                    at: at.clone(),
+                    branches: branches
+                        .iter()
+                        .map(|branch| normalise(branch, &rest))
+                        .collect(),
+                });
+                return result;
+            }
+            Global::Rec { name, body } => {
+                result.push(Global::Rec {
+                    name: name.clone(),
+                    body: normalise(body, &rest()),
+                });
+                return result;
+            }
+        }
+    }
+
+    if !after.is_empty() {
+        result.extend(normalise(after, &[]));
+    }
+
+    result
+}
+
+// The names of the branches of a choice, which are the variants of the related enums:
+// the capitalised label of the first message sent by the choosing role, if any.
+pub(crate) fn branch_names(at: &str, branches: &[Vec<Global>]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+
+    for (index, branch) in branches.iter().enumerate() {
+        let first = branch.iter().find_map(|statement| match statement {
+            Global::Message { label, from, .. } if from == at => Some(capitalise(label)),
+            _ => None,
+        });
+
+        let name = match first {
+            Some(name) if !result.contains(&name) => name,
+            Some(name) => format!("{}{}", name, index),
+            None => format!("Branch{}", index),
+        };
+
+        result.push(name);
+    }
+
+    result
+}
+
+// The name of the payload type of a label, such as `Fibonacci` for `fibonacci`,
+// with a suffix for the names already used by the generated code, such as `OkMessage` for `ok`.
+pub(crate) fn payload_name(label: &str) -> String {
+    const RESERVED: [&str; 16] = [
+        "Box",
+        "End",
+        "Err",
+        "Error",
+        "None",
+        "Ok",
+        "Option",
+        "Recv",
+        "Result",
+        "RoleBroadcast",
+        "RoleEnd",
+        "Self",
+        "Send",
+        "Some",
+        "String",
+        "Vec",
+    ];
+
+    let name = capitalise(label);
+    if RESERVED.contains(&name.as_str()) {
+        format!("{}Message", name)
+    } else {
+        name
+    }
+}
+
+// "fibonacci" to "Fibonacci".
+pub(crate) fn capitalise(label: &str) -> String {
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
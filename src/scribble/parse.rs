@@ -0,0 +1,393 @@
+//! The parser of the Scribble global protocols.
+
+use std::error::Error;
+
+use super::Global;
+
+// The name, the roles and the statements of a protocol.
+type Parsed = (String, Vec<String>, Vec<Global>);
+
+// A token, along with its line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    text: String,
+    line: usize,
+}
+
+// Split the source into identifiers and punctuation, dropping the comments.
+fn tokenise(source: &str) -> Result<Vec<Token>, Box<dyn Error>> {
+    let mut result = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let start = line;
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                        None => return Err(format!("line {}: unclosed comment", start).into()),
+                    }
+                }
+            }
+            '(' | ')' | '{' | '}' | ',' | ';' | ':' => result.push(Token {
+                text: c.to_string(),
+                line,
+            }),
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut text = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '.' {
+                        text.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                result.push(Token { text, line });
+            }
+            c => return Err(format!("line {}: unexpected character `{}`", line, c).into()),
+        }
+    }
+
+    Ok(result)
+}
+
+// The state of the parser.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    name: String,
+    roles: Vec<String>,
+}
+
+impl Parser {
+    // The line of the current token, or of the last one at the end of the file.
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or_else(|| self.tokens.last())
+            .map(|token| token.line)
+            .unwrap_or(1)
+    }
+
+    fn error<T>(&self, message: String) -> Result<T, Box<dyn Error>> {
+        Err(format!("line {}: {}", self.line(), message).into())
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens
+            .get(self.position)
+            .map(|token| token.text.as_str())
+    }
+
+    fn found(&self) -> String {
+        match self.peek() {
+            Some(text) => format!("`{}`", text),
+            None => "the end of the file".to_string(),
+        }
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|token| token.text.clone());
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &str, context: &str) -> Result<(), Box<dyn Error>> {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            self.error(format!(
+                "expected `{}` {}, found {}",
+                expected,
+                context,
+                self.found()
+            ))
+        }
+    }
+
+    fn identifier(&mut self, context: &str) -> Result<String, Box<dyn Error>> {
+        match self.peek() {
+            Some(text)
+                if text
+                    .chars()
+                    .next()
+                    .map(|c| c.is_alphabetic() || c == '_')
+                    .unwrap_or(false) =>
+            {
+                Ok(self.next().unwrap())
+            }
+            _ => self.error(format!("expected {}, found {}", context, self.found())),
+        }
+    }
+
+    fn role(&mut self, context: &str) -> Result<String, Box<dyn Error>> {
+        let line = self.line();
+        let role = self.identifier(context)?;
+        if self.roles.contains(&role) {
+            Ok(role)
+        } else {
+            Err(format!("line {}: unknown role {}", line, role).into())
+        }
+    }
+
+    // `module a.b;` and `import a.b;`
+    fn header(&mut self) -> Result<(), Box<dyn Error>> {
+        while matches!(self.peek(), Some("module") | Some("import")) {
+            self.next();
+            self.identifier("a module name")?;
+            self.expect(";", "after the module name")?;
+        }
+        Ok(())
+    }
+
+    // `(explicit) global protocol Name(role A, role B)`
+    fn signature(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.peek() == Some("explicit") {
+            self.next();
+        }
+        self.expect("global", "at the start of the protocol")?;
+        self.expect("protocol", "after `global`")?;
+        self.name = self.identifier("the name of the protocol")?;
+        self.expect("(", "before the roles")?;
+
+        loop {
+            self.expect("role", "before each role")?;
+            let line = self.line();
+            let role = self.identifier("the name of a role")?;
+            if self.roles.contains(&role) {
+                return Err(format!("line {}: role {} is declared twice", line, role).into());
+            }
+            self.roles.push(role);
+
+            match self.next().as_deref() {
+                Some(",") => {}
+                Some(")") => break,
+                _ => {
+                    self.position -= 1;
+                    return self.error(format!(
+                        "expected `,` or `)` after a role, found {}",
+                        self.found()
+                    ));
+                }
+            }
+        }
+
+        if self.roles.len() < 2 {
+            return self.error("a protocol needs at least two roles".to_string());
+        }
+
+        Ok(())
+    }
+
+    // `{ statements }`
+    fn block(&mut self, recs: &mut Vec<String>) -> Result<Vec<Global>, Box<dyn Error>> {
+        self.expect("{", "at the start of a block")?;
+
+        let mut result = Vec::new();
+
+        loop {
+            match self.peek() {
+                Some("}") => {
+                    self.next();
+                    return Ok(result);
+                }
+                None => return self.error("expected `}` at the end of a block".to_string()),
+                _ => {}
+            }
+
+            if matches!(result.last(), Some(Global::Continue(_))) {
+                return self.error(format!(
+                    "expected `}}` after `continue`, found {}",
+                    self.found()
+                ));
+            }
+
+            result.push(self.statement(recs)?);
+        }
+    }
+
+    fn statement(&mut self, recs: &mut Vec<String>) -> Result<Global, Box<dyn Error>> {
+        match self.peek() {
+            Some("choice") => {
+                self.next();
+                self.expect("at", "after `choice`")?;
+                let at = self.role("the choosing role")?;
+                let mut branches = vec![self.block(recs)?];
+                while self.peek() == Some("or") {
+                    self.next();
+                    branches.push(self.block(recs)?);
+                }
+                Ok(Global::Choice { at, branches })
+            }
+            Some("rec") => {
+                self.next();
+                let line = self.line();
+                let name = self.identifier("the name of the recursion")?;
+                if recs.contains(&name) {
+                    return Err(format!("line {}: rec {} is already defined", line, name).into());
+                }
+                recs.push(name.clone());
+                let body = self.block(recs)?;
+                recs.pop();
+                Ok(Global::Rec { name, body })
+            }
+            Some("continue") => {
+                self.next();
+                let line = self.line();
+                let name = self.identifier("the name of the recursion")?;
+                if !recs.contains(&name) {
+                    return Err(format!("line {}: unknown rec {}", line, name).into());
+                }
+                self.expect(";", "after `continue`")?;
+                Ok(Global::Continue(name))
+            }
+            Some("do") => {
+                self.next();
+                let line = self.line();
+                let name = self.identifier("the name of a protocol")?;
+                self.expect("(", "before the roles")?;
+                let mut roles = Vec::new();
+                while self.peek() != Some(")") {
+                    roles.push(self.role("a role")?);
+                    if self.peek() == Some(",") {
+                        self.next();
+                    }
+                }
+                self.next();
+                self.expect(";", "after `do`")?;
+                if name != self.name || roles != self.roles {
+                    return Err(format!(
+                        "line {}: only `do {}({})` is supported",
+                        line,
+                        self.name,
+                        self.roles.join(", ")
+                    )
+                    .into());
+                }
+                Ok(Global::Continue(name))
+            }
+            _ => self.message(),
+        }
+    }
+
+    // `label(payloads) from A to B;`
+    fn message(&mut self) -> Result<Global, Box<dyn Error>> {
+        let label = self.identifier("a statement")?;
+        self.expect("(", "after the label of the message")?;
+
+        let mut payloads = Vec::new();
+        while self.peek() != Some(")") {
+            let mut payload = self.identifier("the type of a payload")?;
+            // Named payloads, such as `x: int`
+            if self.peek() == Some(":") {
+                self.next();
+                payload = self.identifier("the type of a payload")?;
+            }
+            payloads.push(payload);
+
+            match self.peek() {
+                Some(",") => {
+                    self.next();
+                }
+                Some(")") => {}
+                _ => {
+                    return self.error(format!(
+                        "expected `,` or `)` after a payload, found {}",
+                        self.found()
+                    ))
+                }
+            }
+        }
+        self.next();
+
+        self.expect("from", "after the payloads of the message")?;
+        let from = self.role("the sending role")?;
+        self.expect("to", "after the sending role")?;
+        let line = self.line();
+        let to = self.role("the receiving role")?;
+        if from == to {
+            return Err(format!("line {}: role {} sends a message to itself", line, from).into());
+        }
+        self.expect(";", "after the message")?;
+
+        Ok(Global::Message {
+            label,
+            payloads,
+            from,
+            to,
+        })
+    }
+}
+
+// Parse a global protocol, returning its name, its roles and its statements.
+//
+// A recursive call with `do` to the protocol itself is
+// turned into a `continue` to a recursion wrapping the whole protocol.
+pub(crate) fn parse(source: &str) -> Result<Parsed, Box<dyn Error>> {
+    let mut parser = Parser {
+        tokens: tokenise(source)?,
+        position: 0,
+        name: String::new(),
+        roles: Vec::new(),
+    };
+
+    parser.header()?;
+    parser.signature()?;
+
+    let name = parser.name.clone();
+    let mut recs = vec![name.clone()];
+    let body = parser.block(&mut recs)?;
+
+    if parser.peek().is_some() {
+        return parser.error(format!(
+            "expected the end of the file, found {}",
+            parser.found()
+        ));
+    }
+
+    let body = if calls_itself(&body, &name) {
+        vec![Global::Rec {
+            name: name.clone(),
+            body,
+        }]
+    } else {
+        body
+    };
+
+    Ok((name, parser.roles, body))
+}
+
+// Whether `continue name` is used in `body`.
+fn calls_itself(body: &[Global], name: &str) -> bool {
+    body.iter().any(|statement| match statement {
+        Global::Continue(rec) => rec == name,
+        Global::Choice { branches, .. } => branches.iter().any(|branch| calls_itself(branch, name)),
+        Global::Rec { body, .. } => calls_itself(body, name),
+        Global::Message { .. } => false,
+    })
+}
@@ -0,0 +1,99 @@
+//! The projection of the global protocols on each of their roles.
+
+use std::error::Error;
+use std::fmt::Write;
+
+use super::{Global, Protocol};
+
+impl Protocol {
+    /// Project the protocol on `role` and print its local type,
+    /// in the syntax of the local protocols of Scribble.
+    ///
+    /// As each choice is broadcast to all the roles,
+    /// each role keeps all the choices of the global protocol.
+    pub fn project(&self, role: &str) -> Result<String, Box<dyn Error>> {
+        if !self.roles.contains(&role.to_string()) {
+            return Err(format!("Unknown role {} in protocol {}", role, self.name).into());
+        }
+
+        let mut result = format!(
+            "local protocol {}_{}({}) {{\n",
+            self.name,
+            role,
+            self.roles
+                .iter()
+                .map(|r| if r == role {
+                    format!("self {}", r)
+                } else {
+                    format!("role {}", r)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        write_local(&mut result, role, &self.body, 1)?;
+        result.push_str("}\n");
+
+        Ok(result)
+    }
+}
+
+// Write the projection of `body` on `role`.
+fn write_local(
+    result: &mut String,
+    role: &str,
+    body: &[Global],
+    depth: usize,
+) -> Result<(), Box<dyn Error>> {
+    let indent = "    ".repeat(depth);
+
+    for statement in body.iter() {
+        match statement {
+            Global::Message {
+                label,
+                payloads,
+                from,
+                to,
+            } => {
+                if from == role {
+                    writeln!(
+                        result,
+                        "{}{}({}) to {};",
+                        indent,
+                        label,
+                        payloads.join(", "),
+                        to
+                    )?;
+                } else if to == role {
+                    writeln!(
+                        result,
+                        "{}{}({}) from {};",
+                        indent,
+                        label,
+                        payloads.join(", "),
+                        from
+                    )?;
+                }
+            }
+            Global::Choice { at, branches } => {
+                write!(result, "{}choice at {} ", indent, at)?;
+                for (index, branch) in branches.iter().enumerate() {
+                    if index > 0 {
+                        result.push_str(" or ");
+                    }
+                    result.push_str("{\n");
+                    write_local(result, role, branch, depth + 1)?;
+                    write!(result, "{}}}", indent)?;
+                }
+                result.push('\n');
+            }
+            Global::Rec { name, body } => {
+                writeln!(result, "{}rec {} {{", indent, name)?;
+                write_local(result, role, body, depth + 1)?;
+                writeln!(result, "{}}}", indent)?;
+            }
+            Global::Continue(name) => writeln!(result, "{}continue {};", indent, name)?,
+        }
+    }
+
+    Ok(())
+}
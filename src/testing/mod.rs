@@ -0,0 +1,624 @@
+#![cfg(feature = "testing")]
+
+//! This module contains the traits, the structures and the macros
+//! for unit-testing a single role of a protocol built with the
+//! baking macros, such as [`bundle_impl_with_enum_and_cancel!`].
+//!
+//! The other roles are replaced by stubs which follow their local types
+//! automatically: the payloads they send are built either by the closures
+//! of their [`Script`] or with [`Default`], and the branches they choose
+//! are read from their [`Script`].
+//! Each stub records the steps it goes through, which can be read
+//! after the end of the protocol with [`Trace::steps`].
+//!
+//! # Example
+//!
+//! Testing the role A of the `o_auth` example, without implementing C nor S.
+//!
+//! ```ignore
+//! use mpstthree::testing::{stub, Script};
+//! use mpstthree::{bundle_impl_with_enum_and_cancel, stub_bundle, stub_offer, stub_payloads};
+//!
+//! bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, C, S);
+//! stub_bundle!(MeshedChannelsThree, A, C, S);
+//!
+//! stub_payloads!(default: Start, Redirect, Login, Auth, Password);
+//! stub_payloads!(default: Success, Token, Fail, Received);
+//! stub_offer!(Branching0fromAtoC { Success, Fail });
+//! stub_offer!(Branching0fromAtoS { Success, Fail });
+//!
+//! let script_c = Script::new();
+//! let trace_c = script_c.trace();
+//!
+//! let (thread_a, thread_c, thread_s) = fork_mpst(
+//!     endpoint_a,
+//!     stub::<EndpointC>(script_c),
+//!     stub::<EndpointS>(Script::new()),
+//! );
+//!
+//! assert!(thread_a.join().is_ok());
+//! assert!(thread_c.join().is_ok());
+//! assert!(thread_s.join().is_ok());
+//!
+//! assert_eq!(trace_c.steps()[0], "RoleC!RoleS: Start");
+//! ```
+//!
+//! [`bundle_impl_with_enum_and_cancel!`]: crate::bundle_impl_with_enum_and_cancel
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"testing"` feature.*
+
+use std::any::{Any, TypeId};
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::marker;
+use std::sync::{Arc, Mutex};
+
+#[cfg_attr(doc_cfg, doc(cfg(feature = "property_testing")))]
+pub mod property;
+
+#[cfg_attr(doc_cfg, doc(cfg(feature = "recording")))]
+pub mod recording;
+
+type PayloadFn = Box<dyn FnMut() -> Option<Box<dyn Any + marker::Send>> + marker::Send>;
+type InspectorFn = Box<dyn FnMut(&dyn Any) -> Result<(), Box<dyn Error>> + marker::Send>;
+
+/// A participant which plays its local type on its own,
+/// following a [`Script`].
+///
+/// This trait is implemented for all the sending, receiving and
+/// closing *MeshedChannels* by [`stub_bundle!`],
+/// and for the *MeshedChannels* which choose a branch by [`stub_choose!`].
+///
+/// [`stub_bundle!`]: crate::stub_bundle
+/// [`stub_choose!`]: crate::stub_choose
+///
+/// *This trait is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+pub trait Stub: marker::Sized {
+    /// Play the rest of the protocol, following `script`.
+    fn stub(self, script: &mut Script) -> Result<(), Box<dyn Error>>;
+}
+
+/// A payload which can be sent by a stub.
+///
+/// The payload is built by the closure provided with [`Script::with_payload`]
+/// or taken from the values provided with [`Script::with_values`]
+/// if any, else by [`Payload::default_payload`].
+///
+/// *This trait is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+pub trait Payload: marker::Sized + marker::Send + 'static {
+    /// The payload sent when the script does not provide any.
+    /// By default, there is none and the stub fails.
+    fn default_payload() -> Option<Self> {
+        None
+    }
+}
+
+/// A payload which can be received by a stub, along with
+/// the continuation `C` of the stub.
+///
+/// Plain payloads, implemented with [`stub_payloads!`], are checked
+/// by the inspectors of the [`Script`] before the stub goes on with `C`.
+/// Branching enums, implemented with [`stub_offer!`], drop `C`
+/// and go on with the *MeshedChannels* of the chosen branch.
+///
+/// [`stub_payloads!`]: crate::stub_payloads
+/// [`stub_offer!`]: crate::stub_offer
+///
+/// *This trait is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+pub trait Message<C>: marker::Sized + marker::Send {
+    /// The label of the payload in the [`Trace`].
+    fn label(&self) -> String {
+        type_label::<Self>()
+    }
+
+    /// Go on with the protocol once the payload is received.
+    fn received(self, continuation: C, script: &mut Script) -> Result<(), Box<dyn Error>>;
+}
+
+/// The name of the type `T`, without the paths of the modules,
+/// such as `Vec<i32>` instead of `alloc::vec::Vec<i32>`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+pub fn type_label<T: ?Sized>() -> String {
+    let full_name = std::any::type_name::<T>();
+
+    let mut result = String::new();
+    let mut segment = String::new();
+
+    for c in full_name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            result.push_str(segment.rsplit("::").next().unwrap_or(""));
+            segment.clear();
+            result.push(c);
+        }
+    }
+    result.push_str(segment.rsplit("::").next().unwrap_or(""));
+
+    result
+}
+
+/// The steps recorded by a stub, shared between the stub
+/// and the test.
+///
+/// The steps are labelled `RoleA!RoleB: Payload` for a sent payload,
+/// `RoleA?RoleB: Payload` for a received one, `RoleA: choose Branch`
+/// for a choice and `RoleA: close` for the end of the protocol.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+pub struct Trace {
+    steps: Arc<Mutex<Vec<String>>>,
+}
+
+impl Trace {
+    /// All the steps recorded so far.
+    pub fn steps(&self) -> Vec<String> {
+        match self.steps.lock() {
+            Ok(steps) => steps.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn push(&self, step: String) {
+        match self.steps.lock() {
+            Ok(mut steps) => steps.push(step),
+            Err(poisoned) => poisoned.into_inner().push(step),
+        }
+    }
+}
+
+/// The script followed by a stub: how to build the payloads it sends,
+/// how to check the payloads it receives and which branches it chooses.
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::testing::Script;
+///
+/// let script = Script::new()
+///     .with_payload(|| 42_i32)
+///     .with_inspector(|x: &i32| {
+///         if *x > 0 {
+///             Ok(())
+///         } else {
+///             Err("Expected a positive number".into())
+///         }
+///     })
+///     .with_branches(vec!["Continue", "Continue", "Stop"]);
+///
+/// assert_eq!(script.remaining_branches(), 3);
+/// ```
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+pub struct Script {
+    payloads: HashMap<TypeId, PayloadFn>,
+    inspectors: HashMap<TypeId, InspectorFn>,
+    branches: VecDeque<String>,
+    trace: Trace,
+}
+
+impl fmt::Debug for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Script")
+            .field("payloads", &self.payloads.len())
+            .field("inspectors", &self.inspectors.len())
+            .field("branches", &self.branches)
+            .field("trace", &self.trace)
+            .finish()
+    }
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Script {
+    /// An empty script: payloads are built with [`Payload::default_payload`],
+    /// received payloads are not checked and no branch can be chosen.
+    pub fn new() -> Self {
+        let state_payloads = RandomState::new();
+        let state_inspectors = RandomState::new();
+
+        Script {
+            payloads: HashMap::with_hasher(state_payloads),
+            inspectors: HashMap::with_hasher(state_inspectors),
+            branches: VecDeque::new(),
+            trace: Trace::default(),
+        }
+    }
+
+    /// Build the payloads of type `T` with `f`.
+    pub fn with_payload<T, F>(mut self, mut f: F) -> Self
+    where
+        T: Payload,
+        F: FnMut() -> T + marker::Send + 'static,
+    {
+        self.payloads.insert(
+            TypeId::of::<T>(),
+            Box::new(move || Some(Box::new(f()) as Box<dyn Any + marker::Send>)),
+        );
+        self
+    }
+
+    /// Send the payloads of type `T` from `values`, in order.
+    /// Once `values` is exhausted, the payloads are built with [`Payload::default_payload`].
+    pub fn with_values<T, I>(mut self, values: I) -> Self
+    where
+        T: Payload,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: marker::Send + 'static,
+    {
+        let mut values = values.into_iter();
+        self.payloads.insert(
+            TypeId::of::<T>(),
+            Box::new(move || {
+                values
+                    .next()
+                    .map(|value| Box::new(value) as Box<dyn Any + marker::Send>)
+            }),
+        );
+        self
+    }
+
+    /// Check the received payloads of type `T` with `f`.
+    /// The stub fails as soon as `f` returns an error.
+    pub fn with_inspector<T, F>(mut self, mut f: F) -> Self
+    where
+        T: 'static,
+        F: FnMut(&T) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+    {
+        self.inspectors.insert(
+            TypeId::of::<T>(),
+            Box::new(move |value: &dyn Any| match value.downcast_ref::<T>() {
+                Some(value) => f(value),
+                None => Err("Wrong type given to the inspector".into()),
+            }),
+        );
+        self
+    }
+
+    /// Add the names of the branches chosen by the stub, in order.
+    pub fn with_branches<I, B>(mut self, branches: I) -> Self
+    where
+        I: IntoIterator<Item = B>,
+        B: ToString,
+    {
+        self.branches
+            .extend(branches.into_iter().map(|branch| branch.to_string()));
+        self
+    }
+
+    /// The number of branches left to be chosen.
+    pub fn remaining_branches(&self) -> usize {
+        self.branches.len()
+    }
+
+    /// The trace of the stub following this script.
+    pub fn trace(&self) -> Trace {
+        self.trace.clone()
+    }
+
+    /// Build the next payload of type `T`.
+    pub fn payload<T: Payload>(&mut self) -> Result<T, Box<dyn Error>> {
+        if let Some(payload) = self.payloads.get_mut(&TypeId::of::<T>()).and_then(|f| f()) {
+            match payload.downcast::<T>() {
+                Ok(payload) => Ok(*payload),
+                Err(_) => Err(format!("Wrong payload built for {}", type_label::<T>()).into()),
+            }
+        } else {
+            T::default_payload()
+                .ok_or_else(|| format!("No payload provided for {}", type_label::<T>()).into())
+        }
+    }
+
+    /// Check a received payload with the inspector of its type, if any.
+    pub fn inspect<T: 'static>(&mut self, value: &T) -> Result<(), Box<dyn Error>> {
+        match self.inspectors.get_mut(&TypeId::of::<T>()) {
+            Some(f) => f(value),
+            None => Ok(()),
+        }
+    }
+
+    /// The next branch to be chosen.
+    pub fn branch(&mut self) -> Result<String, Box<dyn Error>> {
+        self.branches
+            .pop_front()
+            .ok_or_else(|| "No branch left in the script".into())
+    }
+
+    /// Record a step in the trace.
+    pub fn record(&mut self, step: String) {
+        self.trace.push(step);
+    }
+}
+
+/// Turn a script into a function which can be given to `fork_mpst`
+/// in place of the implementation of a role.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+pub fn stub<S: Stub>(
+    mut script: Script,
+) -> impl FnOnce(S) -> Result<(), Box<dyn Error>> + marker::Send + 'static {
+    move |s: S| s.stub(&mut script)
+}
+
+#[doc(hidden)]
+pub fn role_label<R: crate::role::Role>(_name: &R) -> String {
+    R::head_str()
+}
+
+macro_rules! impl_default_payloads {
+    ( $( $payload: ty ),+ $(,)? ) => {
+        $(
+            impl Payload for $payload {
+                fn default_payload() -> Option<Self> {
+                    Some(Default::default())
+                }
+            }
+
+            impl<C: Stub> Message<C> for $payload {
+                fn received(self, continuation: C, script: &mut Script) -> Result<(), Box<dyn Error>> {
+                    script.inspect(&self)?;
+                    continuation.stub(script)
+                }
+            }
+        )+
+    };
+}
+
+impl_default_payloads!(
+    (),
+    bool,
+    char,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    String,
+);
+
+/// Implement the stubs of all the roles of a protocol
+/// created with one of the baking macros, such as [`bundle_impl_with_enum_and_cancel!`].
+///
+/// The arguments are the same as the ones given to the baking macro.
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::{bundle_impl_with_enum_and_cancel, stub_bundle};
+///
+/// bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, B, C);
+/// stub_bundle!(MeshedChannelsThree, A, B, C);
+/// ```
+///
+/// [`bundle_impl_with_enum_and_cancel!`]: crate::bundle_impl_with_enum_and_cancel
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+macro_rules! stub_bundle {
+    (
+        $meshedchannels_name: ident,
+        $( $all_roles: ident),+ $(,)?
+    ) => {
+        mpst_seq::stub_bundle!(
+            $meshedchannels_name,
+            ( $( $all_roles , )+ )
+        );
+    };
+}
+
+/// Implement [`Payload`] and [`Message`] for the provided types,
+/// so that stubs can send and receive them.
+///
+/// With the `default:` prefix, the types must implement [`Default`],
+/// which is used when the script does not provide any payload.
+/// Otherwise, the payloads must be provided by the script.
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::stub_payloads;
+/// use mpstthree::testing::Script;
+///
+/// #[derive(Default)]
+/// struct Login;
+/// struct Password(String);
+///
+/// stub_payloads!(default: Login);
+/// stub_payloads!(Password);
+///
+/// let mut script = Script::new().with_payload(|| Password(String::from("secret")));
+///
+/// assert!(script.payload::<Login>().is_ok());
+/// assert_eq!(script.payload::<Password>().unwrap().0, "secret");
+/// ```
+///
+/// [`Payload`]: crate::testing::Payload
+/// [`Message`]: crate::testing::Message
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+macro_rules! stub_payloads {
+    (default: $( $payload: ty ),+ $(,)? ) => {
+        $(
+            impl mpstthree::testing::Payload for $payload {
+                fn default_payload() -> Option<Self> {
+                    Some(Default::default())
+                }
+            }
+
+            mpstthree::stub_payloads!(@message $payload);
+        )+
+    };
+    (@message $payload: ty) => {
+        impl<C: mpstthree::testing::Stub> mpstthree::testing::Message<C> for $payload {
+            fn received(
+                self,
+                continuation: C,
+                script: &mut mpstthree::testing::Script
+            ) -> Result<(), Box<dyn std::error::Error>> {
+                script.inspect(&self)?;
+                continuation.stub(script)
+            }
+        }
+    };
+    ( $( $payload: ty ),+ $(,)? ) => {
+        $(
+            impl mpstthree::testing::Payload for $payload {}
+
+            mpstthree::stub_payloads!(@message $payload);
+        )+
+    };
+}
+
+/// Implement [`Message`] for an enum sent when choosing a branch,
+/// so that stubs can receive it and go on with the chosen branch.
+///
+/// Each variant of the enum must hold the *MeshedChannels* of its branch.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::stub_offer;
+///
+/// enum Branching0fromAtoC {
+///     Success(EndpointCSuccess),
+///     Fail(EndpointCFail),
+/// }
+///
+/// stub_offer!(Branching0fromAtoC { Success, Fail });
+/// ```
+///
+/// [`Message`]: crate::testing::Message
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+macro_rules! stub_offer {
+    (
+        $branching: ident { $( $branch: ident ),+ $(,)? }
+    ) => {
+        impl<C> mpstthree::testing::Message<C> for $branching {
+            fn label(&self) -> String {
+                match self {
+                    $(
+                        $branching::$branch(_) => format!(
+                            "{}::{}",
+                            stringify!($branching),
+                            stringify!($branch)
+                        ),
+                    )+
+                }
+            }
+
+            fn received(
+                self,
+                continuation: C,
+                script: &mut mpstthree::testing::Script
+            ) -> Result<(), Box<dyn std::error::Error>> {
+                mpstthree::binary::cancel::cancel(continuation);
+                match self {
+                    $(
+                        $branching::$branch(s) => mpstthree::testing::Stub::stub(s, script),
+                    )+
+                }
+            }
+        }
+    };
+}
+
+/// Implement [`Stub`] for a *MeshedChannels* which chooses a branch,
+/// the branch being read from the script.
+///
+/// # Arguments
+///
+/// * The *MeshedChannels* which chooses
+/// * The name of the macro used for choosing, created by the baking macro
+/// * For each branch, its name in the script and the *MeshedChannels* of the branch,
+///   followed by the enum variants sent to each passive role
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::stub_choose;
+///
+/// stub_choose!(
+///     MeshedChannelsThree<Choose0fromAtoC, Choose0fromAtoS, RoleBroadcast, NameA>,
+///     choose_mpst_a_to_all,
+///     {
+///         Success: EndpointASuccess => Branching0fromAtoC::Success, Branching0fromAtoS::Success;
+///         Fail: EndpointAFail => Branching0fromAtoC::Fail, Branching0fromAtoS::Fail;
+///     }
+/// );
+/// ```
+///
+/// [`Stub`]: crate::testing::Stub
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"testing"` feature.*
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "testing")))]
+macro_rules! stub_choose {
+    (
+        $session: ty,
+        $choose: ident,
+        {
+            $( $branch: ident : $next: ty => $( $label: path ),+ $(,)? );+ $(;)?
+        }
+    ) => {
+        impl mpstthree::testing::Stub for $session {
+            fn stub(
+                self,
+                script: &mut mpstthree::testing::Script
+            ) -> Result<(), Box<dyn std::error::Error>> {
+                let role = mpstthree::testing::role_label(&self.name);
+                let branch = script.branch()?;
+                $(
+                    if branch == stringify!($branch) {
+                        script.record(format!("{}: choose {}", role, branch));
+                        let s = self;
+                        let s: $next = $choose!(s, $( $label ),+);
+                        return mpstthree::testing::Stub::stub(s, script);
+                    }
+                )+
+                Err(format!("Unknown branch {} for {}", branch, role).into())
+            }
+        }
+    };
+}
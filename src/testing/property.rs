@@ -0,0 +1,602 @@
+#![cfg(feature = "property_testing")]
+
+//! This module contains the [`proptest`] strategies for testing
+//! the implementation of a role across the traces of its protocol.
+//!
+//! The traces are the paths of the global graph built from the CFSMs
+//! of the protocol, from the starting state to a state
+//! where all the roles have terminated.
+//! The [`protocol_concat!`] macro takes the same arguments as
+//! [`checker_concat!`] and returns the corresponding [`Protocol`].
+//! For each trace, [`Protocol::script`] gives the [`Script`] of a stub,
+//! which chooses the same branches as in the trace,
+//! so the role under test can be run against stubs with `fork_mpst`
+//! on many branch combinations.
+//!
+//! The traces are built from a list of decisions, one per step,
+//! and are completed by the shortest path to a final state once
+//! the decisions are exhausted.
+//! Hence, when a test fails, [`proptest`] shrinks the failing trace
+//! towards the shortest trace following the same branches.
+//!
+//! A branch is named after the variant of the branching enums
+//! sent by the role which chooses it, such as `Success` and `Fail`
+//! in `o_auth`, even when several branches start with the same message
+//! or when the role does not send anything in a branch.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mpstthree::testing::property::Protocol;
+//! use mpstthree::testing::stub;
+//! use proptest::prelude::*;
+//!
+//! let protocol: Protocol =
+//!     mpstthree::protocol_concat!(EndpointA, EndpointC, EndpointS => ...).unwrap();
+//!
+//! proptest!(|(trace in protocol.traces(20), passwords in any::<Vec<String>>())| {
+//!     let script_c = protocol.script(&trace, "RoleC").unwrap().with_values(passwords);
+//!
+//!     let (thread_a, thread_c, thread_s) = fork_mpst(
+//!         endpoint_a,
+//!         stub::<EndpointC>(script_c),
+//!         stub::<EndpointS>(protocol.script(&trace, "RoleS").unwrap()),
+//!     );
+//!
+//!     prop_assert!(thread_a.join().is_ok());
+//!     prop_assert!(thread_c.join().is_ok());
+//!     prop_assert!(thread_s.join().is_ok());
+//! });
+//! ```
+//!
+//! [`proptest`]: https://docs.rs/proptest
+//! [`checker_concat!`]: crate::checker_concat
+//! [`protocol_concat!`]: crate::protocol_concat
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"property_testing"` feature.*
+
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Graph;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::error::Error;
+
+use super::Script;
+use crate::checking::product::{
+    is_local_terminal, parse_label, parse_state, silent_closure, synchronous_product, Action,
+};
+
+type GraphOfStrStr = Graph<String, String>;
+type HashBranches = HashMap<String, Vec<(EdgeIndex<u32>, String, bool)>>;
+
+// The branches taken along a path of the global graph.
+// Each choice is sent to all the other roles, so all the roles
+// take the same branches, in the same order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct Taken {
+    // The names of the branches taken by at least one role, in order
+    names: Vec<String>,
+    // The branches taken by each role, and whether the role chose them
+    roles: BTreeMap<String, Vec<(String, bool)>>,
+}
+
+impl Taken {
+    // Take the branches started by `edges` for `role`.
+    // Returns false if they are not the ones taken by the other roles.
+    fn take(&mut self, role: &str, edges: &[EdgeIndex<u32>], branches: &HashBranches) -> bool {
+        let starts = match branches.get(role) {
+            Some(starts) => starts,
+            None => return true,
+        };
+
+        for edge in edges {
+            for (_, name, chosen) in starts.iter().filter(|(start, _, _)| start == edge) {
+                let taken = self.roles.entry(role.to_string()).or_default();
+
+                match self.names.get(taken.len()) {
+                    Some(expected) if expected != name => return false,
+                    Some(_) => {}
+                    None => self.names.push(name.clone()),
+                }
+
+                taken.push((name.clone(), *chosen));
+            }
+        }
+
+        true
+    }
+}
+
+/// The global graph of a protocol, along with the CFSMs of its roles,
+/// from which the traces of the protocol are generated.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"property_testing"` feature.*
+#[derive(Debug, Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "property_testing")))]
+pub struct Protocol {
+    graphs: HashMap<String, GraphOfStrStr>,
+    // The edges of the CFSM of each role starting the branches it chooses
+    branches: HashBranches,
+    global: GraphOfStrStr,
+    // The local node of each role, for each global node
+    locals: Vec<Vec<(String, NodeIndex<u32>)>>,
+    // The number of steps from each global node to the closest final node
+    distances: Vec<Option<usize>>,
+}
+
+impl Protocol {
+    /// Build the global graph of the protocol from the CFSMs
+    /// of its roles, along with the edges starting each branch
+    /// chosen by each role.
+    /// Both are usually given by [`protocol_concat!`].
+    ///
+    /// [`protocol_concat!`]: crate::protocol_concat
+    pub fn new(graphs: &HashMap<String, GraphOfStrStr>, branches: &HashBranches) -> Self {
+        let global = synchronous_product(graphs);
+
+        let locals = global
+            .node_indices()
+            .map(|node| parse_state(&global[node]))
+            .collect::<Vec<_>>();
+
+        let mut distances = vec![None; global.node_count()];
+        let mut queue = VecDeque::new();
+
+        for node in global.node_indices() {
+            let done = locals[node.index()]
+                .iter()
+                .all(|(role, local)| is_local_terminal(&graphs[role], *local));
+
+            if done && global.edges(node).next().is_none() {
+                distances[node.index()] = Some(0);
+                queue.push_back(node);
+            }
+        }
+
+        while let Some(node) = queue.pop_front() {
+            let distance = distances[node.index()].unwrap_or(0);
+
+            for edge in global.edges_directed(node, petgraph::Direction::Incoming) {
+                if distances[edge.source().index()].is_none() {
+                    distances[edge.source().index()] = Some(distance + 1);
+                    queue.push_back(edge.source());
+                }
+            }
+        }
+
+        Protocol {
+            graphs: graphs.clone(),
+            branches: branches.clone(),
+            global,
+            locals,
+            distances,
+        }
+    }
+
+    /// The global graph of the protocol.
+    pub fn global(&self) -> &GraphOfStrStr {
+        &self.global
+    }
+
+    // The outgoing edges of a global node, in the order they were added.
+    fn sorted_edges(&self, node: NodeIndex<u32>) -> Vec<(NodeIndex<u32>, &String)> {
+        let mut edges = self
+            .global
+            .edges(node)
+            .map(|edge| (edge.id(), edge.target(), edge.weight()))
+            .collect::<Vec<_>>();
+        edges.sort_by_key(|(id, _, _)| *id);
+        edges
+            .into_iter()
+            .map(|(_, target, label)| (target, label))
+            .collect()
+    }
+
+    // The local node of `role` in the global node `node`.
+    fn local(&self, node: NodeIndex<u32>, role: &str) -> Option<NodeIndex<u32>> {
+        self.locals[node.index()]
+            .iter()
+            .find(|(r, _)| r == role)
+            .map(|(_, local)| *local)
+    }
+
+    // The silent edges of `graph` followed from `from` to `to`, if any.
+    fn silent_path(
+        graph: &GraphOfStrStr,
+        from: NodeIndex<u32>,
+        to: NodeIndex<u32>,
+    ) -> Option<Vec<EdgeIndex<u32>>> {
+        let mut parents: HashMap<NodeIndex<u32>, Option<EdgeIndex<u32>>> = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        parents.insert(from, None);
+        queue.push_back(from);
+
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut result = Vec::new();
+                let mut current = node;
+
+                while let Some(Some(edge)) = parents.get(&current) {
+                    result.insert(0, *edge);
+                    current = graph.edge_endpoints(*edge)?.0;
+                }
+
+                return Some(result);
+            }
+
+            for edge in graph.edges(node) {
+                if parse_label(edge.weight()) == Action::Silent
+                    && !parents.contains_key(&edge.target())
+                {
+                    parents.insert(edge.target(), Some(edge.id()));
+                    queue.push_back(edge.target());
+                }
+            }
+        }
+
+        None
+    }
+
+    // Take the step `label` from the global node `node` to `target`:
+    // the sender and the receiver follow their silent edges, then the edge
+    // of the interaction, and take the branches started by those edges.
+    // Returns false if they take other branches than the other roles.
+    fn take_step(
+        &self,
+        label: &str,
+        node: NodeIndex<u32>,
+        target: NodeIndex<u32>,
+        taken: &mut Taken,
+    ) -> bool {
+        let (interaction, payload) = match label.split_once(": ") {
+            Some(result) => result,
+            None => return true,
+        };
+        let (sender, receiver) = match interaction.split_once("->") {
+            Some(result) => result,
+            None => return true,
+        };
+
+        let actions = vec![
+            (
+                sender,
+                Action::Send(
+                    sender.to_string(),
+                    receiver.to_string(),
+                    payload.to_string(),
+                ),
+            ),
+            (
+                receiver,
+                Action::Recv(
+                    receiver.to_string(),
+                    sender.to_string(),
+                    payload.to_string(),
+                ),
+            ),
+        ];
+
+        for (role, action) in actions {
+            let (graph, from, to) = match (
+                self.graphs.get(role),
+                self.local(node, role),
+                self.local(target, role),
+            ) {
+                (Some(graph), Some(from), Some(to)) => (graph, from, to),
+                _ => return false,
+            };
+
+            let edges = silent_closure(graph, from).into_iter().find_map(|local| {
+                let edge = graph
+                    .edges(local)
+                    .find(|edge| edge.target() == to && parse_label(edge.weight()) == action)?;
+                let mut result = Self::silent_path(graph, from, local)?;
+                result.push(edge.id());
+                Some(result)
+            });
+
+            match edges {
+                Some(edges) if taken.take(role, &edges, &self.branches) => {}
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    // The roles which may still terminate through silent edges
+    // at the global node `node` take the branches started by those edges.
+    fn take_end(&self, node: NodeIndex<u32>, mut taken: Taken) -> Option<Taken> {
+        let mut roles = self.graphs.keys().collect::<Vec<_>>();
+        roles.sort();
+
+        for role in roles {
+            let graph = &self.graphs[role];
+            let local = self.local(node, role)?;
+
+            let ends = silent_closure(graph, local)
+                .into_iter()
+                .filter(|end| *end != local && graph.edges(*end).next().is_none())
+                .collect::<Vec<_>>();
+
+            if ends.is_empty() || graph.edges(local).next().is_none() {
+                continue;
+            }
+
+            taken = ends.iter().find_map(|end| {
+                let edges = Self::silent_path(graph, local, *end)?;
+                let mut next = taken.clone();
+                if next.take(role, &edges, &self.branches) {
+                    Some(next)
+                } else {
+                    None
+                }
+            })?;
+        }
+
+        Some(taken)
+    }
+
+    /// Build the trace following `decisions`: the `i`-th step is the edge
+    /// selected by `decisions[i]` among the outgoing edges of the current state.
+    /// Once `decisions` is exhausted, the trace follows the shortest path
+    /// to a final state, if any.
+    /// Only the edges where all the roles take the same branches are followed.
+    ///
+    /// Each step is labelled as in the global graph, such as `RoleA->RoleB: i32`.
+    pub fn follow(&self, decisions: &[Index]) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut node = NodeIndex::new(0);
+        let mut taken = Taken::default();
+
+        if self.global.node_count() == 0 {
+            return result;
+        }
+
+        for index in 0.. {
+            let mut edges = self
+                .sorted_edges(node)
+                .into_iter()
+                .filter_map(|(target, label)| {
+                    let mut next = taken.clone();
+                    if self.take_step(label, node, target, &mut next) {
+                        Some((target, label, next))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            if edges.is_empty() {
+                break;
+            }
+
+            let position = if let Some(decision) = decisions.get(index) {
+                decision.index(edges.len())
+            } else if self.distances[node.index()].is_some() {
+                match edges
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (target, _, _))| self.distances[target.index()].is_some())
+                    .min_by_key(|(_, (target, _, _))| self.distances[target.index()])
+                {
+                    Some((position, _)) => position,
+                    None => break,
+                }
+            } else {
+                break;
+            };
+
+            let (target, label, next) = edges.swap_remove(position);
+
+            result.push(label.clone());
+            node = target;
+            taken = next;
+        }
+
+        result
+    }
+
+    /// A strategy generating the traces of the protocol, following
+    /// at most `max_decisions` random decisions before going
+    /// to the closest final state.
+    pub fn traces(&self, max_decisions: usize) -> impl Strategy<Value = Vec<String>> {
+        let protocol = self.clone();
+
+        vec(any::<Index>(), 0..=max_decisions)
+            .prop_map(move |decisions| protocol.follow(&decisions))
+    }
+
+    // The branches taken along `trace`, if it is a trace of the protocol.
+    // Several edges may share the label of a step, so the path is searched
+    // with backtracking until the whole trace is followed
+    // with all the roles taking the same branches.
+    fn taken(&self, trace: &[String]) -> Option<Taken> {
+        if self.global.node_count() == 0 {
+            return if trace.is_empty() {
+                Some(Taken::default())
+            } else {
+                None
+            };
+        }
+
+        let mut failed = HashSet::new();
+
+        self.search(trace, 0, NodeIndex::new(0), Taken::default(), &mut failed)
+    }
+
+    fn search(
+        &self,
+        trace: &[String],
+        index: usize,
+        node: NodeIndex<u32>,
+        taken: Taken,
+        failed: &mut HashSet<(usize, NodeIndex<u32>, Taken)>,
+    ) -> Option<Taken> {
+        if index == trace.len() {
+            return self.take_end(node, taken);
+        }
+
+        let key = (index, node, taken);
+
+        if failed.contains(&key) {
+            return None;
+        }
+
+        for (target, label) in self.sorted_edges(node) {
+            if *label != trace[index] {
+                continue;
+            }
+
+            let mut next = key.2.clone();
+
+            if self.take_step(label, node, target, &mut next) {
+                if let Some(result) = self.search(trace, index + 1, target, next, failed) {
+                    return Some(result);
+                }
+            }
+        }
+
+        failed.insert(key);
+        None
+    }
+
+    /// The branches chosen by `role` along `trace`, in order.
+    ///
+    /// Each branch is named after the variant of the branching enums
+    /// sent by `role` for the choice.
+    ///
+    /// Returns an error if `trace` is not a trace of the protocol.
+    pub fn choices(&self, trace: &[String], role: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        if !self.graphs.contains_key(role) {
+            return Err(format!("Unknown role {}", role).into());
+        }
+
+        let taken = self
+            .taken(trace)
+            .ok_or_else(|| format!("{:?} is not a trace of the protocol", trace))?;
+
+        Ok(taken
+            .roles
+            .get(role)
+            .map(|branches| {
+                branches
+                    .iter()
+                    .filter(|(_, chosen)| *chosen)
+                    .map(|(name, _)| name.clone())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// The script of the stub of `role`, choosing
+    /// the same branches as in `trace`.
+    ///
+    /// Returns an error if `trace` is not a trace of the protocol.
+    pub fn script(&self, trace: &[String], role: &str) -> Result<Script, Box<dyn Error>> {
+        Ok(Script::new().with_branches(self.choices(trace, role)?))
+    }
+}
+
+/// Build the [`Protocol`] of the provided endpoints, which are given
+/// as for [`checker_concat!`], without the name of the file.
+/// Returns an error if the CFSMs of the endpoints cannot be built.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::testing::property::Protocol;
+///
+/// let protocol: Protocol = mpstthree::protocol_concat!(
+///     EndpointA,
+///     EndpointC,
+///     EndpointS
+///     =>
+///     [
+///         EndpointASuccess,
+///         Branching0fromAtoC, Success,
+///         Branching0fromAtoS, Success,
+///     ],
+///     [
+///         EndpointAFail,
+///         Branching0fromAtoC, Fail,
+///         Branching0fromAtoS, Fail,
+///     ]
+/// )
+/// .unwrap();
+/// ```
+///
+/// [`checker_concat!`]: crate::checker_concat
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"property_testing"` feature.*
+#[macro_export]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "property_testing")))]
+macro_rules! protocol_concat {
+    (
+        $(
+            $sessiontype: ty
+        ),+ $(,)?
+    ) => {
+        match mpstthree::checker_concat!(
+            @with mpstthree::checking::checker_with_branches,
+            "",
+            "",
+            [],
+            $(
+                $sessiontype,
+            )+
+        ) {
+            Ok((graphs, branches, _)) => Ok(
+                mpstthree::testing::property::Protocol::new(&graphs, &branches)
+            ),
+            Err(e) => Err(e),
+        }
+    };
+    (
+        $(
+            $sessiontype: ty
+        ),+ $(,)?
+        =>
+        $(
+            [
+                $branch_stack: ty,
+                $(
+                    $choice: ty, $branch: ident
+                ),+ $(,)?
+            ]
+        ),+ $(,)?
+    ) => {
+        match mpstthree::checker_concat!(
+            @with mpstthree::checking::checker_with_branches,
+            "",
+            "",
+            [],
+            $(
+                $sessiontype,
+            )+
+            =>
+            $(
+                [
+                    $branch_stack,
+                    $(
+                        $choice, $branch,
+                    )+
+                ],
+            )+
+        ) {
+            Ok((graphs, branches, _)) => Ok(
+                mpstthree::testing::property::Protocol::new(&graphs, &branches)
+            ),
+            Err(e) => Err(e),
+        }
+    };
+}
@@ -0,0 +1,458 @@
+#![cfg(feature = "recording")]
+
+//! This module contains the recording of the runs of the roles
+//! of a protocol built with the baking macros,
+//! and the replay of one role against the recorded peers.
+//!
+//! Each role given to `fork_mpst` through [`record`] logs its steps
+//! into a [`Recorder`]: the payloads it sends, with their sender,
+//! receiver and label, the branches it chooses and the end of its session.
+//! The payloads are written with the serializers provided
+//! with [`Recorder::with_serializer`]. Only the bundles baked with `recorded`,
+//! such as `bundle_impl!(recorded MeshedChannelsThree, A, B, C)`,
+//! give the values of their payloads to the recorder, so their payloads must
+//! be `'static`; the other bundles record their payloads without their values.
+//! Each step is stamped with a logical clock shared by all the recorded
+//! roles, which is incremented before the step happens, so a message
+//! is always stamped before anything its receiver does after receiving it.
+//!
+//! A [`Recording`], loaded from the file of the [`Recorder`],
+//! gives the [`Script`] of each role, from which its stub sends
+//! the same payloads and chooses the same branches.
+//! Running the role under test against these stubs reproduces
+//! the path of the recorded run.
+//!
+//! The steps are labelled as in [`Trace`]: `RoleA!RoleB: i32` for a payload,
+//! `RoleA: choose Branch` for a choice, `RoleA: close` for the end of a session
+//! and `RoleA: cancel` for a cancellation.
+//! In the file, each step is written on its own line, after its clock,
+//! and followed by ` = ` and its serialized payload, if any:
+//!
+//! ```text
+//! 1 RoleC: choose More
+//! 2 RoleC!RoleA: i32 = 7
+//! 3 RoleA!RoleB: i32 = 7
+//! ```
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mpstthree::testing::recording::{record, Recorder, Recording};
+//! use mpstthree::testing::stub;
+//!
+//! // The production run
+//! let recorder = Recorder::create("run.log")?.with_serializer(|x: &i32| x.to_string());
+//!
+//! let (thread_a, thread_b, thread_c) = fork_mpst(
+//!     record(&recorder, endpoint_a),
+//!     record(&recorder, endpoint_b),
+//!     record(&recorder, endpoint_c),
+//! );
+//!
+//! // The replay of A
+//! let recording = Recording::load("run.log")?.with_deserializer(|x: &str| x.parse::<i32>());
+//!
+//! let (thread_a, thread_b, thread_c) = fork_mpst(
+//!     endpoint_a,
+//!     stub::<EndpointB>(recording.script("RoleB")?),
+//!     stub::<EndpointC>(recording.script("RoleC")?),
+//! );
+//! ```
+//!
+//! [`Trace`]: crate::testing::Trace
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"recording"` feature.*
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::marker;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::{type_label, Payload, Script};
+
+type SerializerFn = Box<dyn Fn(&dyn Any) -> Option<String> + marker::Send>;
+type DeserializerFn =
+    Box<dyn Fn(Script, Vec<String>) -> Result<Script, Box<dyn Error>> + marker::Send + Sync>;
+
+thread_local! {
+    // The recorder of the role running on this thread, if any
+    static CURRENT: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+/// A step of a recorded run.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"recording"` feature.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "recording")))]
+pub struct Entry {
+    /// The logical clock of the step.
+    pub clock: u64,
+    /// The label of the step, such as `RoleA!RoleB: i32`.
+    pub step: String,
+    /// The serialized payload, if the step sends a payload
+    /// whose type has a serializer.
+    pub payload: Option<String>,
+}
+
+// Escape the line breaks of a payload, so each entry stays on one line.
+fn escape(payload: &str) -> String {
+    payload
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(payload: &str) -> String {
+    let mut result = String::new();
+    let mut chars = payload.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.clock, self.step)?;
+        if let Some(payload) = &self.payload {
+            write!(f, " = {}", escape(payload))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Entry {
+    type Err = Box<dyn Error>;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (clock, rest) = line
+            .split_once(' ')
+            .ok_or_else(|| format!("Missing step in {:?}", line))?;
+
+        let clock = clock
+            .parse()
+            .map_err(|_| format!("Wrong clock in {:?}", line))?;
+
+        let (step, payload) = match rest.split_once(" = ") {
+            Some((step, payload)) => (step, Some(unescape(payload))),
+            None => (rest, None),
+        };
+
+        Ok(Entry {
+            clock,
+            step: step.to_string(),
+            payload,
+        })
+    }
+}
+
+impl Entry {
+    // The role doing the step
+    fn role(&self) -> &str {
+        self.step.split(['!', ':']).next().unwrap_or("")
+    }
+}
+
+struct State {
+    serializers: HashMap<TypeId, SerializerFn>,
+    entries: Vec<Entry>,
+    clock: u64,
+    file: Option<File>,
+}
+
+/// The log shared by the recorded roles of a run.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"recording"` feature.*
+#[derive(Clone)]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "recording")))]
+pub struct Recorder {
+    state: Arc<Mutex<State>>,
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("Recorder")
+            .field("serializers", &state.serializers.len())
+            .field("entries", &state.entries)
+            .field("clock", &state.clock)
+            .finish()
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    /// A recorder keeping the steps in memory.
+    pub fn new() -> Self {
+        let state = RandomState::new();
+
+        Recorder {
+            state: Arc::new(Mutex::new(State {
+                serializers: HashMap::with_hasher(state),
+                entries: Vec::new(),
+                clock: 0,
+                file: None,
+            })),
+        }
+    }
+
+    /// A recorder also writing each step to the file at `path`
+    /// as soon as it happens, so the steps are kept even if the process aborts.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let recorder = Self::new();
+        recorder.lock().file = Some(File::create(path)?);
+        Ok(recorder)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Write the payloads of type `T` with `f`.
+    /// The payloads without serializer are recorded without their value.
+    pub fn with_serializer<T, F>(self, f: F) -> Self
+    where
+        T: 'static,
+        F: Fn(&T) -> String + marker::Send + 'static,
+    {
+        self.lock().serializers.insert(
+            TypeId::of::<T>(),
+            Box::new(move |value: &dyn Any| value.downcast_ref::<T>().map(&f)),
+        );
+        self
+    }
+
+    fn push(&self, step: String, payload: Option<&dyn Any>) {
+        let mut state = self.lock();
+
+        state.clock += 1;
+
+        let payload = payload.and_then(|payload| {
+            state
+                .serializers
+                .get(&payload.type_id())
+                .and_then(|f| f(payload))
+        });
+
+        let entry = Entry {
+            clock: state.clock,
+            step,
+            payload,
+        };
+
+        if let Some(file) = state.file.as_mut() {
+            // A failing log must not change the behaviour of the roles
+            let _ = writeln!(file, "{}", entry).and_then(|_| file.flush());
+        }
+
+        state.entries.push(entry);
+    }
+
+    /// The steps recorded so far.
+    pub fn recording(&self) -> Recording {
+        Recording::from_entries(self.lock().entries.clone())
+    }
+
+    /// Write the steps recorded so far to the file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(path)?;
+        for entry in self.lock().entries.iter() {
+            writeln!(file, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `f` with its steps recorded into `recorder`.
+/// The result can be given to `fork_mpst` in place of `f`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"recording"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "recording")))]
+pub fn record<S, F>(
+    recorder: &Recorder,
+    f: F,
+) -> impl FnOnce(S) -> Result<(), Box<dyn Error>> + marker::Send + 'static
+where
+    F: FnOnce(S) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+{
+    let recorder = recorder.clone();
+
+    move |s: S| {
+        let previous = CURRENT.with(|current| current.replace(Some(recorder)));
+        let result = f(s);
+        CURRENT.with(|current| current.replace(previous));
+        result
+    }
+}
+
+// Record a step of the role running on this thread, if it is recorded.
+pub(crate) fn step(step: String) {
+    CURRENT.with(|current| {
+        if let Some(recorder) = current.borrow().as_ref() {
+            recorder.push(step, None);
+        }
+    })
+}
+
+// Record a payload of type `T` sent by the role running on this thread,
+// if it is recorded, with its value if it is given.
+pub(crate) fn sent<T>(role: &str, peer: &str, payload: Option<&dyn Any>) {
+    CURRENT.with(|current| {
+        if let Some(recorder) = current.borrow().as_ref() {
+            recorder.push(format!("{}!{}: {}", role, peer, type_label::<T>()), payload);
+        }
+    })
+}
+
+/// The steps of a recorded run, from which the roles can be replayed.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"recording"` feature.*
+#[cfg_attr(doc_cfg, doc(cfg(feature = "recording")))]
+pub struct Recording {
+    entries: Vec<Entry>,
+    deserializers: Vec<(String, DeserializerFn)>,
+}
+
+impl fmt::Debug for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recording")
+            .field("entries", &self.entries)
+            .field("deserializers", &self.deserializers.len())
+            .finish()
+    }
+}
+
+impl FromStr for Recording {
+    type Err = Box<dyn Error>;
+
+    fn from_str(log: &str) -> Result<Self, Self::Err> {
+        let entries = log
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(Entry::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::from_entries(entries))
+    }
+}
+
+impl Recording {
+    fn from_entries(mut entries: Vec<Entry>) -> Self {
+        entries.sort_by_key(|entry| entry.clock);
+
+        Recording {
+            entries,
+            deserializers: Vec::new(),
+        }
+    }
+
+    /// Load the steps written by a [`Recorder`] to the file at `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Read the recorded payloads of type `T` with `f`.
+    pub fn with_deserializer<T, E, F>(mut self, f: F) -> Self
+    where
+        T: Payload,
+        E: Into<Box<dyn Error>>,
+        F: Fn(&str) -> Result<T, E> + marker::Send + Sync + 'static,
+    {
+        self.deserializers.push((
+            type_label::<T>(),
+            Box::new(move |script: Script, payloads: Vec<String>| {
+                let values = payloads
+                    .iter()
+                    .map(|payload| f(payload).map_err(Into::into))
+                    .collect::<Result<Vec<T>, _>>()?;
+                Ok(script.with_values(values))
+            }),
+        ));
+        self
+    }
+
+    /// All the steps, in the order of their clocks.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The steps of `role`, in order, labelled as in the file.
+    pub fn steps(&self, role: &str) -> Vec<String> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.role() == role)
+            .map(|entry| match &entry.payload {
+                Some(payload) => format!("{} = {}", entry.step, payload),
+                None => entry.step.clone(),
+            })
+            .collect()
+    }
+
+    /// The branches chosen by `role`, in order.
+    pub fn branches(&self, role: &str) -> Vec<String> {
+        let prefix = format!("{}: choose ", role);
+
+        self.entries
+            .iter()
+            .filter_map(|entry| entry.step.strip_prefix(&prefix))
+            .map(String::from)
+            .collect()
+    }
+
+    /// The script of the stub of `role`, sending the recorded payloads
+    /// and choosing the recorded branches.
+    ///
+    /// Returns an error if a recorded payload cannot be deserialized.
+    pub fn script(&self, role: &str) -> Result<Script, Box<dyn Error>> {
+        let mut script = Script::new().with_branches(self.branches(role));
+
+        let prefix = format!("{}!", role);
+
+        for (label, f) in self.deserializers.iter() {
+            let suffix = format!(": {}", label);
+
+            let payloads = self
+                .entries
+                .iter()
+                .filter(|entry| entry.step.starts_with(&prefix) && entry.step.ends_with(&suffix))
+                .filter_map(|entry| entry.payload.clone())
+                .collect();
+
+            script = f(script, payloads)?;
+        }
+
+        Ok(script)
+    }
+}
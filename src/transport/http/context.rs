@@ -0,0 +1,284 @@
+//! This module contains the client and the runtime
+//! shared by the HTTP sessions.
+//!
+//! The *send* and *recv* functions take either a `bool`,
+//! as before, or an [`HttpContext`].
+//! `true` uses a context shared by the whole process,
+//! created on its first use, and `false` exchanges
+//! the payloads without any HTTP request.
+//! A context keeps its connections alive between the messages
+//! of a session, and can be cloned to share its pool among roles.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_http"` feature.*
+
+use hyper::client::{HttpConnector, ResponseFuture};
+use hyper::{Body, Client, Request, Response};
+use hyper_tls::HttpsConnector;
+use std::boxed::Box;
+use std::error::Error;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+
+/// The `native-tls` crate, for configuring the TLS
+/// of an [`HttpContext`].
+pub use tokio_native_tls::native_tls;
+
+type HttpsClient = Client<HttpsConnector<HttpConnector>, Body>;
+
+static SHARED: OnceLock<HttpContext> = OnceLock::new();
+
+/// A client and the runtime driving its connections,
+/// shared by all the messages of a session.
+///
+/// Cloning a context shares its pool and its runtime.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub struct HttpContext {
+    client: HttpsClient,
+    runtime: Arc<Runtime>,
+}
+
+impl HttpContext {
+    /// A context with the default settings:
+    /// connections are kept alive, and both HTTP and HTTPS are allowed.
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        HttpContextBuilder::new().build()
+    }
+
+    /// Configure a new context.
+    pub fn builder() -> HttpContextBuilder {
+        HttpContextBuilder::new()
+    }
+
+    /// The context used when `true` is given
+    /// to the *send* and *recv* functions.
+    pub fn shared() -> Result<&'static Self, Box<dyn Error>> {
+        if let Some(context) = SHARED.get() {
+            return Ok(context);
+        }
+        let context = HttpContext::new()?;
+        Ok(SHARED.get_or_init(|| context))
+    }
+
+    /// The client of the context.
+    pub fn client(&self) -> &HttpsClient {
+        &self.client
+    }
+
+    /// Send `req` with the client of the context.
+    ///
+    /// As any future, the request is only sent once the response is awaited.
+    pub fn request(&self, req: Request<Body>) -> ResponseFuture {
+        self.client.request(req)
+    }
+
+    /// Run `future` to completion on the runtime of the context.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+/// Configure an [`HttpContext`].
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[derive(Debug)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub struct HttpContextBuilder {
+    keep_alive: bool,
+    pool_idle_timeout: Option<Duration>,
+    pool_max_idle_per_host: usize,
+    https_only: bool,
+    tls: Option<native_tls::TlsConnector>,
+    worker_threads: Option<usize>,
+}
+
+impl Default for HttpContextBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpContextBuilder {
+    /// The default settings.
+    pub fn new() -> Self {
+        HttpContextBuilder {
+            keep_alive: true,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            pool_max_idle_per_host: usize::MAX,
+            https_only: false,
+            tls: None,
+            worker_threads: None,
+        }
+    }
+
+    /// Whether to keep the connections alive between the messages,
+    /// which is the default.
+    pub fn keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// How long an idle connection is kept, `None` for ever.
+    /// The default is 90 seconds.
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = timeout;
+        self
+    }
+
+    /// The maximum number of idle connections kept for each host.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = max;
+        self
+    }
+
+    /// Whether to refuse the `http` URIs.
+    pub fn https_only(mut self, https_only: bool) -> Self {
+        self.https_only = https_only;
+        self
+    }
+
+    /// The TLS connector used for the `https` URIs,
+    /// for instance with other root certificates.
+    pub fn tls(mut self, tls: native_tls::TlsConnector) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// The number of threads of the runtime,
+    /// the number of cores by default.
+    pub fn worker_threads(mut self, worker_threads: usize) -> Self {
+        self.worker_threads = Some(worker_threads);
+        self
+    }
+
+    /// Create the client and start the runtime.
+    pub fn build(self) -> Result<HttpContext, Box<dyn Error>> {
+        let mut runtime = Builder::new_multi_thread();
+        runtime.enable_all();
+        if let Some(worker_threads) = self.worker_threads {
+            runtime.worker_threads(worker_threads);
+        }
+        let runtime = runtime.build()?;
+
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+
+        let mut https = match self.tls {
+            Some(tls) => HttpsConnector::from((http, tls.into())),
+            None => HttpsConnector::new_with_connector(http),
+        };
+        https.https_only(self.https_only);
+
+        let client = Client::builder()
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(match self.keep_alive {
+                true => self.pool_max_idle_per_host,
+                false => 0,
+            })
+            .build(https);
+
+        Ok(HttpContext {
+            client,
+            runtime: Arc::new(runtime),
+        })
+    }
+}
+
+/// How the *send* and *recv* functions use HTTP:
+/// `false` for no HTTP at all, `true` for the shared [`HttpContext`],
+/// or a given [`HttpContext`].
+///
+/// *This trait is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub trait HttpMode {
+    /// The context to use, `None` if there are no HTTP requests.
+    fn context(&self) -> Result<Option<&HttpContext>, Box<dyn Error>>;
+
+    /// Whether HTTP requests are sent.
+    fn is_http(&self) -> bool;
+
+    /// Send `req`, or build a request which is never sent if
+    /// there are no HTTP requests.
+    fn send_request(&self, req: Request<Body>) -> Result<ResponseFuture, Box<dyn Error>> {
+        match self.context()? {
+            Some(context) => Ok(context.request(req)),
+            None => Ok(Client::new().request(Request::default())),
+        }
+    }
+
+    /// Wait for the response of a request sent by [`HttpMode::send_request`],
+    /// or return the default response if there are no HTTP requests.
+    fn wait_response(&self, resp: ResponseFuture) -> Result<Response<Body>, Box<dyn Error>> {
+        match self.context()? {
+            Some(context) => Ok(context.block_on(resp)?),
+            None => Ok(Response::default()),
+        }
+    }
+
+    /// Wait for the response of a request sent by [`HttpMode::send_request`]
+    /// and read its whole body,
+    /// or return the default response if there are no HTTP requests.
+    fn wait_full_response(
+        &self,
+        resp: ResponseFuture,
+    ) -> Result<Response<Vec<u8>>, Box<dyn Error>> {
+        match self.context()? {
+            Some(context) => context.block_on(async move {
+                let (parts, body) = resp.await?.into_parts();
+                let body = hyper::body::to_bytes(body).await?;
+                Ok(Response::from_parts(parts, body.to_vec()))
+            }),
+            None => Ok(Response::default()),
+        }
+    }
+}
+
+impl HttpMode for bool {
+    fn context(&self) -> Result<Option<&HttpContext>, Box<dyn Error>> {
+        match self {
+            true => Ok(Some(HttpContext::shared()?)),
+            false => Ok(None),
+        }
+    }
+
+    fn is_http(&self) -> bool {
+        *self
+    }
+}
+
+impl HttpMode for HttpContext {
+    fn context(&self) -> Result<Option<&HttpContext>, Box<dyn Error>> {
+        Ok(Some(self))
+    }
+
+    fn is_http(&self) -> bool {
+        true
+    }
+}
+
+impl<H: HttpMode + ?Sized> HttpMode for &H {
+    fn context(&self) -> Result<Option<&HttpContext>, Box<dyn Error>> {
+        (**self).context()
+    }
+
+    fn is_http(&self) -> bool {
+        (**self).is_http()
+    }
+}
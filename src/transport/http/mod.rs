@@ -6,6 +6,18 @@
 //! *This module is available only if MultiCrusty is built with
 //! the `"transport"` feature or the `"transport_http"` feature.*
 
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub mod context;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub mod payload;
+
 #[cfg_attr(
     doc_cfg,
     doc(cfg(any(feature = "transport", feature = "transport_http")))
@@ -23,3 +35,9 @@ pub mod send;
     doc(cfg(any(feature = "transport", feature = "transport_http")))
 )]
 pub mod choose;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub mod server;
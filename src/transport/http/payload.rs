@@ -0,0 +1,763 @@
+//! This module contains the mapping of the payloads
+//! to HTTP requests and responses.
+//!
+//! A payload implementing [`HttpPayload`] is itself the body of
+//! the request or of the response exchanged over HTTP:
+//! the *send* functions of this module build the request from the payload,
+//! and the *recv* functions decode the payload from the response.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_http"` feature.*
+
+use crate::binary::struct_trait::{recv::Recv, send::Send, session::Session};
+use crate::transport::http::context::HttpMode;
+use crate::transport::http::server::{HttpServer, Responder};
+use hyper::client::ResponseFuture;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use std::boxed::Box;
+use std::error::Error;
+use std::marker;
+use std::panic;
+
+/// A payload which can be carried by an HTTP request or response.
+///
+/// Only [`HttpPayload::encode`] and [`HttpPayload::decode`] are required:
+/// by default, a payload is sent with a `POST` request on `/`,
+/// and answered with a `200 OK` response.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::transport::http::payload::HttpPayload;
+/// use std::error::Error;
+///
+/// struct Login(String);
+///
+/// impl HttpPayload for Login {
+///     fn path(&self) -> String {
+///         "/login".to_string()
+///     }
+///
+///     fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+///         Ok(self.0.as_bytes().to_vec())
+///     }
+///
+///     fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>> {
+///         Ok(Login(String::from_utf8(body.to_vec())?))
+///     }
+/// }
+/// ```
+///
+/// *This trait is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub trait HttpPayload: Sized {
+    /// The method of the requests carrying the payload.
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    /// The path of the requests carrying the payload,
+    /// appended to the URI of the receiver.
+    fn path(&self) -> String {
+        "/".to_string()
+    }
+
+    /// The headers of the requests and the responses carrying the payload.
+    fn headers(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// The status of the responses carrying the payload.
+    fn status(&self) -> StatusCode {
+        StatusCode::OK
+    }
+
+    /// Encode the payload as a body.
+    fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>>;
+
+    /// Decode a payload from a body.
+    fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>>;
+
+    /// Build the request carrying the payload to `uri`.
+    fn to_request(&self, uri: &str) -> Result<Request<Body>, Box<dyn Error>> {
+        let mut req = Request::builder().method(self.method()).uri(format!(
+            "{}{}",
+            uri.trim_end_matches('/'),
+            self.path()
+        ));
+
+        for (name, value) in self.headers() {
+            req = req.header(name, value);
+        }
+
+        Ok(req.body(Body::from(self.encode()?))?)
+    }
+
+    /// Build the response carrying the payload.
+    fn to_response(&self) -> Result<Response<Body>, Box<dyn Error>> {
+        let mut resp = Response::builder().status(self.status());
+
+        for (name, value) in self.headers() {
+            resp = resp.header(name, value);
+        }
+
+        Ok(resp.body(Body::from(self.encode()?))?)
+    }
+
+    /// Decode the payload carried by a request,
+    /// from its body by default.
+    fn from_request(req: &Request<Vec<u8>>) -> Result<Self, Box<dyn Error>> {
+        Self::decode(req.body())
+    }
+
+    /// Decode the payload carried by a response,
+    /// from its body by default.
+    fn from_response(resp: &Response<Vec<u8>>) -> Result<Self, Box<dyn Error>> {
+        Self::decode(resp.body())
+    }
+}
+
+// Primitive payloads are written as text
+macro_rules! impl_http_payload_text {
+    ($( $t: ty ),+) => {
+        $(
+            impl HttpPayload for $t {
+                fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+                    Ok(self.to_string().into_bytes())
+                }
+
+                fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>> {
+                    Ok(std::str::from_utf8(body)?.trim().parse::<$t>()?)
+                }
+            }
+        )+
+    };
+}
+
+impl_http_payload_text!(bool, i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64, String);
+
+impl HttpPayload for () {
+    fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+
+    fn decode(_body: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+/// Send a value of type `T` over http, in the request built by
+/// [`HttpPayload::to_request`] for `uri`. Returns the
+/// continuation of the session `S`. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub fn send_http_payload<T, S, H>(
+    x: T,
+    s: Send<T, S>,
+    http: H,
+    uri: &str,
+) -> Result<(S, ResponseFuture), Box<dyn Error>>
+where
+    T: marker::Send + HttpPayload,
+    S: Session,
+    H: HttpMode,
+{
+    let respfut = match http.is_http() {
+        true => http.send_request(x.to_request(uri)?)?,
+        false => http.send_request(Request::default())?,
+    };
+
+    ////////////////
+
+    let (here, there) = S::new();
+
+    match s.channel.send((x, there)) {
+        Ok(_) => Ok((here, respfut)),
+        Err(e) => panic!("{}", e.to_string()),
+    }
+}
+
+/// Receive a value of type `T` over http, decoded by
+/// [`HttpPayload::from_response`] from the response of `req`.
+/// Returns the continuation of the session `S`. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub fn recv_http_payload<T, S, H>(
+    s: Recv<T, S>,
+    http: H,
+    req: ResponseFuture,
+) -> Result<(T, S), Box<dyn Error>>
+where
+    T: marker::Send + HttpPayload,
+    S: Session,
+    H: HttpMode,
+{
+    // Await the response
+    let resp = match http.is_http() {
+        true => Some(http.wait_full_response(req)?),
+        false => None,
+    };
+
+    ////////////////
+
+    let (v, s) = s.channel.recv()?;
+
+    match resp {
+        Some(resp) => Ok((T::from_response(&resp)?, s)),
+        None => Ok((v, s)),
+    }
+}
+
+/// Receive a value of type `T`, decoded by [`HttpPayload::from_request`]
+/// from the next HTTP request of `server`. Returns the continuation of
+/// the session `S` and the [`Responder`] of the request. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub fn recv_http_payload_request<T, S>(
+    s: Recv<T, S>,
+    http: bool,
+    server: &HttpServer,
+) -> Result<(T, S, Responder), Box<dyn Error>>
+where
+    T: marker::Send + HttpPayload,
+    S: Session,
+{
+    // Wait for the request
+    let req = match http {
+        true => Some(server.next_request()?),
+        false => None,
+    };
+
+    ////////////////
+
+    let (v, s) = s.channel.recv()?;
+
+    match req {
+        Some((req, responder)) => Ok((T::from_request(&req)?, s, responder)),
+        None => Ok((v, s, Responder::default())),
+    }
+}
+
+/// Send a value of type `T`, answering the request of `responder`
+/// with the response built by [`HttpPayload::to_response`].
+/// Returns the continuation of the session `S`. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub fn send_http_payload_response<T, S>(
+    x: T,
+    s: Send<T, S>,
+    http: bool,
+    responder: Responder,
+) -> Result<S, Box<dyn Error>>
+where
+    T: marker::Send + HttpPayload,
+    S: Session,
+{
+    // Answer the request
+    if http {
+        responder.respond(x.to_response()?)?;
+    }
+
+    ////////////////
+
+    let (here, there) = S::new();
+
+    match s.channel.send((x, there)) {
+        Ok(_) => Ok(here),
+        Err(e) => panic!("{}", e.to_string()),
+    }
+}
+
+/// Creates a *send* function to send from a given binary session type of a MeshedChannels,
+/// in the request built by [`HttpPayload::to_request`].
+///
+/// # Arguments
+///
+/// * The name of the new *send* function
+/// * The name of the receiver
+/// * The name of the sender
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+/// * The index of the binary session type that will receive in the MeshedChannels for this specific
+///   role. Index starts at 1.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_send_http_payload_session, create_meshedchannels};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_send_http_payload_session!(send_http_d_to_a, RoleA, RoleD, MeshedChannels, 3, 1);
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_send_http_payload_session {
+    (
+        $func_name:ident,
+        $receiver:ident,
+        $sender:ident,
+        $meshedchannels_name:ident,
+        $n_sessions:literal,
+        $exclusion:literal
+    ) => {
+        mpst_seq::create_send_http_payload_session!(
+            $func_name,
+            $receiver,
+            $sender,
+            $meshedchannels_name,
+            $n_sessions,
+            $exclusion
+        );
+    };
+}
+
+/// Creates multiple *send* functions to send from a given binary session type of a MeshedChannels,
+/// in the requests built by [`HttpPayload::to_request`].
+///
+/// # Arguments
+///
+/// * The name of the new *send* functions
+/// * The name of the receivers
+/// * The index of the binary session types that will receive in the MeshedChannels for this
+///   specific role. Index starts at 1.
+/// * The name of the sender
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_meshedchannels, create_send_http_payload_session_bundle};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_send_http_payload_session_bundle!(
+///    send_http_d_to_a,
+///    RoleA,
+///    1 |
+///    send_http_d_to_b,
+///    RoleB,
+///    2 | =>
+///    RoleD,
+///    MeshedChannels,
+///    3
+/// );
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_send_http_payload_session_bundle {
+    ($( $func_name: ident, $receiver: ident, $exclusion: literal | )+ => $sender: ident, $meshedchannels_name: ident, $n_sessions: literal) => {
+       $(
+           mpstthree::create_send_http_payload_session!(
+               $func_name,
+               $receiver,
+               $sender,
+               $meshedchannels_name,
+               $n_sessions,
+               $exclusion
+            );
+        )+
+    }
+}
+
+/// Creates a *recv* function to receive from a simple role on a given binary session type
+/// of a MeshedChannels, decoded by [`HttpPayload::from_response`].
+///
+/// # Arguments
+///
+/// * The name of the new *recv* function
+/// * The name of the sender
+/// * The name of the receiver
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+/// * The index of the binary session type that will receive in the MeshedChannels for this specific
+///   role. Index starts at 1.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_recv_http_payload_session, create_meshedchannels};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_recv_http_payload_session!(recv_http_d_from_a, RoleA, RoleD, MeshedChannels, 3, 1);
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_recv_http_payload_session {
+    (
+        $func_name:ident,
+        $sender:ident,
+        $receiver:ident,
+        $meshedchannels_name:ident,
+        $n_sessions:literal,
+        $exclusion:literal
+    ) => {
+        mpst_seq::create_recv_http_payload_session!(
+            $func_name,
+            $sender,
+            $receiver,
+            $meshedchannels_name,
+            $n_sessions,
+            $exclusion
+        );
+    };
+}
+
+/// Creates multiple *recv* functions to receive from a simple role on a given binary session type
+/// of a MeshedChannels, decoded by [`HttpPayload::from_response`].
+///
+/// # Arguments
+///
+/// * The name of the new *recv* functions
+/// * The name of the senders
+/// * The index of the binary session types that will receive in the MeshedChannels for each
+///   specific role. Index starts at 1.
+/// * The name of the receiver
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_meshedchannels, create_recv_http_payload_session_bundle};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_recv_http_payload_session_bundle!(
+///    recv_http_d_from_a,
+///    RoleA,
+///    1 |
+///    recv_http_d_from_b,
+///    RoleB,
+///    2 | =>
+///    RoleD,
+///    MeshedChannels,
+///    3
+/// );
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_recv_http_payload_session_bundle {
+    ($( $func_name: ident, $sender: ident, $exclusion: literal | )+ => $receiver: ident, $meshedchannels_name: ident, $n_sessions: literal) => {
+       $(
+           mpstthree::create_recv_http_payload_session!(
+               $func_name,
+               $sender,
+               $receiver,
+               $meshedchannels_name,
+               $n_sessions,
+               $exclusion
+            );
+        )+
+    }
+}
+
+/// Creates a *recv* function to receive from a simple role on a given binary session type
+/// of a MeshedChannels, decoded by [`HttpPayload::from_request`]
+/// from the next HTTP request of an [`HttpServer`].
+///
+/// # Arguments
+///
+/// * The name of the new *recv* function
+/// * The name of the sender
+/// * The name of the receiver
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+/// * The index of the binary session type that will receive in the MeshedChannels for this specific
+///   role. Index starts at 1.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_recv_http_payload_request_session, create_meshedchannels};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_recv_http_payload_request_session!(recv_http_d_from_a, RoleA, RoleD, MeshedChannels, 3, 1);
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_recv_http_payload_request_session {
+    (
+        $func_name:ident,
+        $sender:ident,
+        $receiver:ident,
+        $meshedchannels_name:ident,
+        $n_sessions:literal,
+        $exclusion:literal
+    ) => {
+        mpst_seq::create_recv_http_payload_request_session!(
+            $func_name,
+            $sender,
+            $receiver,
+            $meshedchannels_name,
+            $n_sessions,
+            $exclusion
+        );
+    };
+}
+
+/// Creates multiple *recv* functions to receive from a simple role on a given binary session type
+/// of a MeshedChannels, decoded by [`HttpPayload::from_request`]
+/// from the next HTTP requests of an [`HttpServer`].
+///
+/// # Arguments
+///
+/// * The name of the new *recv* functions
+/// * The name of the senders
+/// * The index of the binary session types that will receive in the MeshedChannels for each
+///   specific role. Index starts at 1.
+/// * The name of the receiver
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_meshedchannels, create_recv_http_payload_request_session_bundle};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_recv_http_payload_request_session_bundle!(
+///    recv_http_d_from_a,
+///    RoleA,
+///    1 |
+///    recv_http_d_from_b,
+///    RoleB,
+///    2 | =>
+///    RoleD,
+///    MeshedChannels,
+///    3
+/// );
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_recv_http_payload_request_session_bundle {
+    ($( $func_name: ident, $sender: ident, $exclusion: literal | )+ => $receiver: ident, $meshedchannels_name: ident, $n_sessions: literal) => {
+       $(
+           mpstthree::create_recv_http_payload_request_session!(
+               $func_name,
+               $sender,
+               $receiver,
+               $meshedchannels_name,
+               $n_sessions,
+               $exclusion
+            );
+        )+
+    }
+}
+
+/// Creates a *send* function to send from a given binary session type of a MeshedChannels,
+/// answering an HTTP request with [`HttpPayload::to_response`].
+///
+/// # Arguments
+///
+/// * The name of the new *send* function
+/// * The name of the receiver
+/// * The name of the sender
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+/// * The index of the binary session type that will receive in the MeshedChannels for this specific
+///   role. Index starts at 1.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_send_http_payload_response_session, create_meshedchannels};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_send_http_payload_response_session!(send_http_d_to_a, RoleA, RoleD, MeshedChannels, 3, 1);
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_send_http_payload_response_session {
+    (
+        $func_name:ident,
+        $receiver:ident,
+        $sender:ident,
+        $meshedchannels_name:ident,
+        $n_sessions:literal,
+        $exclusion:literal
+    ) => {
+        mpst_seq::create_send_http_payload_response_session!(
+            $func_name,
+            $receiver,
+            $sender,
+            $meshedchannels_name,
+            $n_sessions,
+            $exclusion
+        );
+    };
+}
+
+/// Creates multiple *send* functions to send from a given binary session type of a MeshedChannels,
+/// answering HTTP requests with [`HttpPayload::to_response`].
+///
+/// # Arguments
+///
+/// * The name of the new *send* functions
+/// * The name of the receivers
+/// * The index of the binary session types that will receive in the MeshedChannels for this
+///   specific role. Index starts at 1.
+/// * The name of the sender
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_meshedchannels, create_send_http_payload_response_session_bundle};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_send_http_payload_response_session_bundle!(
+///    send_http_d_to_a,
+///    RoleA,
+///    1 |
+///    send_http_d_to_b,
+///    RoleB,
+///    2 | =>
+///    RoleD,
+///    MeshedChannels,
+///    3
+/// );
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_send_http_payload_response_session_bundle {
+    ($( $func_name: ident, $receiver: ident, $exclusion: literal | )+ => $sender: ident, $meshedchannels_name: ident, $n_sessions: literal) => {
+       $(
+           mpstthree::create_send_http_payload_response_session!(
+               $func_name,
+               $receiver,
+               $sender,
+               $meshedchannels_name,
+               $n_sessions,
+               $exclusion
+            );
+        )+
+    }
+}
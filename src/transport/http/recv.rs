@@ -6,39 +6,38 @@
 //! the `"transport"` feature or the `"transport_http"` feature.*
 
 use crate::binary::struct_trait::{recv::Recv, session::Session};
+use crate::transport::http::context::HttpMode;
 use hyper::client::ResponseFuture;
 use hyper::{Body, Response};
 use std::boxed::Box;
 use std::error::Error;
 use std::marker;
-use tokio::runtime::Runtime;
 
-/// Send a value of type `T` over http. Returns the
+/// Receive a value of type `T` over http. Returns the
 /// continuation of the session `S`. May fail.
 ///
+/// `http` is either a `bool` or an
+/// [`HttpContext`](crate::transport::http::context::HttpContext),
+/// whose runtime drives the request.
+///
 /// *This function is available only if MultiCrusty is built with
 /// the `"transport"` feature or the `"transport_http"` feature.*
 #[cfg_attr(
     doc_cfg,
     doc(cfg(any(feature = "transport", feature = "transport_http")))
 )]
-pub fn recv_http<T, S>(
+pub fn recv_http<T, S, H>(
     s: Recv<T, S>,
-    http: bool,
+    http: H,
     req: ResponseFuture,
 ) -> Result<(T, S, Response<Body>), Box<dyn Error>>
 where
     T: marker::Send,
     S: Session,
+    H: HttpMode,
 {
     // Await the response
-    let resp = match http {
-        true => {
-            let rt = Runtime::new()?;
-            rt.block_on(async move { req.await })?
-        }
-        false => Response::default(),
-    };
+    let resp = http.wait_response(req)?;
 
     ////////////////
 
@@ -49,6 +48,10 @@ where
 /// Creates a *recv* function to receive from a simple role on a given binary session type of a
 /// MeshedChannels.
 ///
+/// As [`recv_http`], the new function takes either a `bool`
+/// or an [`HttpContext`](crate::transport::http::context::HttpContext)
+/// shared by the messages of the session.
+///
 /// # Arguments
 ///
 /// * The name of the new *recv* function
@@ -6,9 +6,9 @@
 //! the `"transport"` feature or the `"transport_http"` feature.*
 
 use crate::binary::struct_trait::{send::Send, session::Session};
+use crate::transport::http::context::HttpMode;
 use hyper::client::ResponseFuture;
-use hyper::{Body, Client, Method, Request};
-use hyper_tls::HttpsConnector;
+use hyper::{Body, Method, Request};
 use std::boxed::Box;
 use std::error::Error;
 use std::marker;
@@ -17,16 +17,20 @@ use std::panic;
 /// Send a value of type `T` over http. Returns the
 /// continuation of the session `S`. May fail.
 ///
+/// `http` is either a `bool` or an
+/// [`HttpContext`](crate::transport::http::context::HttpContext),
+/// whose client is reused by all the messages.
+///
 /// *This function is available only if MultiCrusty is built with
 /// the `"transport"` feature or the `"transport_http"` feature.*
 #[cfg_attr(
     doc_cfg,
     doc(cfg(any(feature = "transport", feature = "transport_http")))
 )]
-pub fn send_http<T, S>(
+pub fn send_http<T, S, H>(
     x: T,
     s: Send<T, S>,
-    http: bool,
+    http: H,
     method: Method,
     uri: &str,
     header: Vec<(&str, &str)>,
@@ -35,10 +39,11 @@ pub fn send_http<T, S>(
 where
     T: marker::Send,
     S: Session,
+    H: HttpMode,
 {
     let (here, there) = S::new();
 
-    let respfut = match http {
+    let respfut = match http.is_http() {
         true => {
             let mut temp = Request::builder().method(method).uri(uri);
 
@@ -48,17 +53,9 @@ where
 
             let req = temp.body(Body::from(body))?;
 
-            let https = HttpsConnector::new();
-            let client = Client::builder().build::<_, Body>(https);
-
-            client.request(req)
-        }
-        false => {
-            let https = HttpsConnector::new();
-            let client = Client::builder().build::<_, Body>(https);
-
-            client.request(Request::default())
+            http.send_request(req)?
         }
+        false => http.send_request(Request::default())?,
     };
 
     ////////////////
@@ -72,6 +69,10 @@ where
 /// Creates a *send* function to send from a given binary session type of a MeshedChannels with more
 /// than 3 participants.
 ///
+/// As [`send_http`], the new function takes either a `bool`
+/// or an [`HttpContext`](crate::transport::http::context::HttpContext)
+/// shared by the messages of the session.
+///
 /// # Arguments
 ///
 /// * The name of the new *send* function
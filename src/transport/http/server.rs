@@ -0,0 +1,500 @@
+//! This module contains the functions and macros for
+//! serving HTTP, for at least two participants.
+//!
+//! A role bound to an [`HttpServer`] receives each of its
+//! payloads along with the next incoming HTTP request,
+//! and the [`Responder`] of this request is used
+//! to answer it when the role sends its next payload.
+//! As the requests built by the *send* functions of the clients
+//! are only sent once their responses are awaited,
+//! a client should receive the answer of the server right after
+//! sending its request.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_http"` feature.*
+
+use crate::binary::struct_trait::{recv::Recv, send::Send, session::Session};
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use hyper::server::Server;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, StatusCode};
+use std::boxed::Box;
+use std::convert::Infallible;
+use std::error::Error;
+use std::marker;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::sync::oneshot;
+
+// A request along with its read body
+type FullRequest = Request<Vec<u8>>;
+
+type Incoming = (FullRequest, oneshot::Sender<Response<Body>>);
+
+/// An HTTP server, running in the background,
+/// whose requests are handed to a role one at a time.
+///
+/// The body of each request is read before the request is handed,
+/// and the client waits until the role answers with its [`Responder`].
+/// The server is stopped when it is dropped.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[derive(Debug)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub struct HttpServer {
+    addr: SocketAddr,
+    connections: Arc<AtomicUsize>,
+    requests: Receiver<Incoming>,
+    shutdown: Option<oneshot::Sender<()>>,
+    runtime: Option<Runtime>,
+}
+
+impl HttpServer {
+    /// Start serving on `addr`.
+    ///
+    /// Use the port `0` to let the system pick a free port,
+    /// which is then given by [`HttpServer::local_addr`].
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, Box<dyn Error>> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let runtime = Runtime::new()?;
+        let (sender, requests) = unbounded();
+        let (shutdown, stop) = oneshot::channel::<()>();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let count = connections.clone();
+
+        let make_service = make_service_fn(move |_| {
+            count.fetch_add(1, Ordering::SeqCst);
+            let sender = sender.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let sender = sender.clone();
+                    async move { Ok::<_, Infallible>(handle(req, sender).await) }
+                }))
+            }
+        });
+
+        let server = {
+            let _guard = runtime.enter();
+            Server::from_tcp(listener)?
+                .serve(make_service)
+                .with_graceful_shutdown(async {
+                    stop.await.ok();
+                })
+        };
+        runtime.spawn(server);
+
+        Ok(HttpServer {
+            addr,
+            connections,
+            requests,
+            shutdown: Some(shutdown),
+            runtime: Some(runtime),
+        })
+    }
+
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The number of connections accepted so far.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    /// Wait for the next request, in their order of arrival,
+    /// and return it along with the [`Responder`] answering it.
+    pub fn next_request(&self) -> Result<(FullRequest, Responder), Box<dyn Error>> {
+        let (req, sender) = self.requests.recv()?;
+        Ok((
+            req,
+            Responder {
+                sender: Some(sender),
+            },
+        ))
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_timeout(Duration::from_secs(1));
+        }
+    }
+}
+
+// Read the body of `req` and wait for the role to answer it.
+async fn handle(req: Request<Body>, sender: Sender<Incoming>) -> Response<Body> {
+    let (parts, body) = req.into_parts();
+
+    let body = match hyper::body::to_bytes(body).await {
+        Ok(body) => body.to_vec(),
+        Err(_) => return status(StatusCode::BAD_REQUEST),
+    };
+
+    let (answer, response) = oneshot::channel();
+
+    if sender
+        .send((Request::from_parts(parts, body), answer))
+        .is_err()
+    {
+        return status(StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    // The role dropped the responder without answering
+    response
+        .await
+        .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+fn status(code: StatusCode) -> Response<Body> {
+    let mut response = Response::default();
+    *response.status_mut() = code;
+    response
+}
+
+/// Answer a request received by an [`HttpServer`].
+///
+/// The default responder, returned when `http` is `false`,
+/// answers nothing.
+/// If a responder is dropped without answering,
+/// the client receives a `500 Internal Server Error`.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[derive(Debug, Default)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub struct Responder {
+    sender: Option<oneshot::Sender<Response<Body>>>,
+}
+
+impl Responder {
+    /// Send `response` to the client. Fails if the client is gone.
+    pub fn respond(self, response: Response<Body>) -> Result<(), Box<dyn Error>> {
+        match self.sender {
+            Some(sender) => sender
+                .send(response)
+                .map_err(|_| "The HTTP client closed the connection".into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Receive a value of type `T` along with the next HTTP request
+/// of `server`. Returns the continuation of the session `S`,
+/// the request and its [`Responder`]. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub fn recv_http_request<T, S>(
+    s: Recv<T, S>,
+    http: bool,
+    server: &HttpServer,
+) -> Result<(T, S, FullRequest, Responder), Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    // Wait for the request
+    let (req, responder) = match http {
+        true => server.next_request()?,
+        false => (Request::default(), Responder::default()),
+    };
+
+    ////////////////
+
+    let (v, s) = s.channel.recv()?;
+    Ok((v, s, req, responder))
+}
+
+/// Send a value of type `T` and answer the request of `responder`
+/// with `resp`. Returns the continuation of the session `S`. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+pub fn send_http_response<T, S>(
+    x: T,
+    s: Send<T, S>,
+    http: bool,
+    responder: Responder,
+    resp: Response<Body>,
+) -> Result<S, Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    // Answer the request
+    if http {
+        responder.respond(resp)?;
+    }
+
+    ////////////////
+
+    let (here, there) = S::new();
+
+    match s.channel.send((x, there)) {
+        Ok(_) => Ok(here),
+        Err(e) => panic!("{}", e.to_string()),
+    }
+}
+
+/// Creates a *recv* function to receive from a simple role
+/// on a given binary session type of a MeshedChannels,
+/// along with the next HTTP request of an [`HttpServer`].
+///
+/// # Arguments
+///
+/// * The name of the new *recv* function
+/// * The name of the sender
+/// * The name of the receiver
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+/// * The index of the binary session type that will receive in the MeshedChannels for this specific
+///   role. Index starts at 1.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_recv_http_server_session, create_meshedchannels};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_recv_http_server_session!(recv_http_d_from_a, RoleA, RoleD, MeshedChannels, 3, 1);
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_recv_http_server_session {
+    (
+        $func_name:ident,
+        $sender:ident,
+        $receiver:ident,
+        $meshedchannels_name:ident,
+        $n_sessions:literal,
+        $exclusion:literal
+    ) => {
+        mpst_seq::create_recv_http_server_session!(
+            $func_name,
+            $sender,
+            $receiver,
+            $meshedchannels_name,
+            $n_sessions,
+            $exclusion
+        );
+    };
+}
+
+/// Creates multiple *recv* functions to receive from a
+/// simple role on a given binary session type of a
+/// MeshedChannels, along with the next HTTP request of an [`HttpServer`].
+///
+/// # Arguments
+///
+/// * The name of the new *recv* functions
+/// * The name of the senders
+/// * The index of the binary session types that will receive in the MeshedChannels for each
+///   specific role. Index starts at 1.
+/// * The name of the receiver
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_meshedchannels, create_recv_http_server_session_bundle};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_recv_http_server_session_bundle!(
+///    recv_http_d_from_a,
+///    RoleA,
+///    1 |
+///    recv_http_d_from_b,
+///    RoleB,
+///    2 | =>
+///    RoleD,
+///    MeshedChannels,
+///    3
+/// );
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_recv_http_server_session_bundle {
+    ($( $func_name: ident, $sender: ident, $exclusion: literal | )+ => $receiver: ident, $meshedchannels_name: ident, $n_sessions: literal) => {
+       $(
+           mpstthree::create_recv_http_server_session!(
+               $func_name,
+               $sender,
+               $receiver,
+               $meshedchannels_name,
+               $n_sessions,
+               $exclusion
+            );
+        )+
+    }
+}
+
+/// Creates a *send* function to send from a given binary session type
+/// of a MeshedChannels, answering an HTTP request with its [`Responder`].
+///
+/// # Arguments
+///
+/// * The name of the new *send* function
+/// * The name of the receiver
+/// * The name of the sender
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+/// * The index of the binary session type that will receive in the MeshedChannels for this specific
+///   role. Index starts at 1.
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_send_http_server_session, create_meshedchannels};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_send_http_server_session!(send_http_d_to_a, RoleA, RoleD, MeshedChannels, 3, 1);
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_send_http_server_session {
+    (
+        $func_name:ident,
+        $receiver:ident,
+        $sender:ident,
+        $meshedchannels_name:ident,
+        $n_sessions:literal,
+        $exclusion:literal
+    ) => {
+        mpst_seq::create_send_http_server_session!(
+            $func_name,
+            $receiver,
+            $sender,
+            $meshedchannels_name,
+            $n_sessions,
+            $exclusion
+        );
+    };
+}
+
+/// Creates multiple *send* functions to send from a given binary session type
+/// of a MeshedChannels, answering HTTP requests with their [`Responder`].
+///
+/// # Arguments
+///
+/// * The name of the new *send* functions
+/// * The name of the receivers
+/// * The index of the binary session types that will receive in the MeshedChannels for this
+///   specific role. Index starts at 1.
+/// * The name of the sender
+/// * The name of the *MeshedChannels* type that will be used
+/// * The number of participants (all together)
+///
+/// # Example
+///
+/// ```ignore
+/// use mpstthree::{create_multiple_normal_role, create_meshedchannels, create_send_http_server_session_bundle};
+///
+/// create_multiple_normal_role!(
+///     RoleA, RoleADual |
+///     RoleB, RoleBDual |
+///     RoleD, RoleDDual |
+/// );
+///
+/// create_meshedchannels!(MeshedChannels, 3);
+///
+/// create_send_http_server_session_bundle!(
+///    send_http_d_to_a,
+///    RoleA,
+///    1 |
+///    send_http_d_to_b,
+///    RoleB,
+///    2 | =>
+///    RoleD,
+///    MeshedChannels,
+///    3
+/// );
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_http"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_http")))
+)]
+macro_rules! create_send_http_server_session_bundle {
+    ($( $func_name: ident, $receiver: ident, $exclusion: literal | )+ => $sender: ident, $meshedchannels_name: ident, $n_sessions: literal) => {
+       $(
+           mpstthree::create_send_http_server_session!(
+               $func_name,
+               $receiver,
+               $sender,
+               $meshedchannels_name,
+               $n_sessions,
+               $exclusion
+            );
+        )+
+    }
+}
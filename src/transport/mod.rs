@@ -4,8 +4,15 @@
 //! *This module is available only if MultiCrusty is built with
 //! either the `"transport"` feature, the
 //! `"transport_udp"` feature, the
-//! `"transport_tcp"` feature or the
+//! `"transport_tcp"` feature, the
+//! `"transport_tls"` feature, the
+//! `"transport_unix"` feature, the
+//! `"transport_shm"` feature, the
+//! `"transport_websocket"` feature or the
 //! `"transport_http"` feature.*
+//!
+//! The network transports, except HTTP, send their messages
+//! in the frames of the [`wire`] format.
 
 #[cfg(feature = "transport_http")]
 #[cfg_attr(
@@ -21,9 +28,58 @@ pub mod http;
 )]
 pub mod tcp;
 
+#[cfg(feature = "transport_tls")]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub mod tls;
+
 #[cfg(feature = "transport_udp")]
 #[cfg_attr(
     doc_cfg,
     doc(cfg(any(feature = "transport", feature = "transport_udp")))
 )]
 pub mod udp;
+
+#[cfg(all(feature = "transport_unix", unix))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(any(feature = "transport", feature = "transport_unix"), unix)))
+)]
+pub mod unix;
+
+#[cfg(all(feature = "transport_shm", target_os = "linux"))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(
+        any(feature = "transport", feature = "transport_shm"),
+        target_os = "linux"
+    )))
+)]
+pub mod shm;
+
+#[cfg(feature = "transport_websocket")]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod websocket;
+
+#[cfg(any(
+    feature = "transport_tcp",
+    feature = "transport_udp",
+    feature = "transport_unix",
+    feature = "transport_websocket"
+))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(
+        feature = "transport",
+        feature = "transport_tcp",
+        feature = "transport_udp",
+        feature = "transport_unix",
+        feature = "transport_websocket"
+    )))
+)]
+pub mod wire;
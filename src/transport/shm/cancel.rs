@@ -0,0 +1,43 @@
+//! This module contains the functions
+//! for cancelling binary sessions and
+//! shutdown the related ShmChannel.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_shm"` feature.*
+
+use crate::transport::shm::channel::ShmChannel;
+use std::mem;
+
+/// Cancels a session. Always succeeds. If the partner calls
+/// `send`, `recv` or `close` after cancellation,
+/// those calls fail. Used for shared memory channel transport.
+///
+/// Drops the session *s* and shutdowns the `ShmChannel` *channel*
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::binary::struct_trait::end::End;
+/// use mpstthree::binary::struct_trait::session::Session;
+/// use mpstthree::meshedchannels::MeshedChannels;
+/// use mpstthree::role::a::RoleA;
+/// use mpstthree::role::end::RoleEnd;
+/// use mpstthree::transport::shm::cancel::cancel_shm;
+/// use mpstthree::transport::shm::channel::ShmChannel;
+///
+/// let (s, _s_dual) = MeshedChannels::<End, End, RoleEnd, RoleA<RoleEnd>>::new();
+/// let (channel, _other) = ShmChannel::pair().unwrap();
+/// cancel_shm(s, channel);
+/// ```
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub fn cancel_shm<T>(s: T, channel: ShmChannel) {
+    mem::drop(s);
+    channel.shutdown();
+    mem::drop(channel);
+}
@@ -0,0 +1,530 @@
+//! This module contains the channel in shared memory
+//! linking two roles, either in two threads
+//! or in two processes of the same host.
+//!
+//! The shared memory holds two ring buffers, one for each direction,
+//! of [`SLOTS`] slots of 128 bytes, the size of the data of a session.
+//! A role blocked on a full or an empty ring buffer spins for a while,
+//! then sleeps on a futex, woken up by the other role.
+//!
+//! A role either creates a named channel with [`ShmChannel::create`],
+//! which another process opens with [`ShmChannel::open`],
+//! or creates both ends at once with [`ShmChannel::pair`].
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_shm"` feature.*
+
+use std::boxed::Box;
+use std::cell::UnsafeCell;
+use std::error::Error;
+use std::ffi::CString;
+use std::fs::{self, File};
+use std::hint;
+use std::io;
+use std::mem;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::ptr::{self, NonNull};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant};
+
+type ShmData = [u8; 128];
+
+/// The number of slots of each ring buffer.
+///
+/// *This constant is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub const SLOTS: usize = 64;
+
+/// The error returned once the channel is shutdown by either role.
+pub(crate) const CLOSED: &str = "The shared memory channel was closed, the session is cancelled";
+
+const LOST: &str = "The process of the peer exited, the session is cancelled";
+
+// "MPSH", written once the channel is initialised
+const MAGIC: u32 = 0x4d50_5348;
+
+// The number of checks before sleeping on the futex,
+// when the peer may run on another processor
+const SPINS: u32 = 1 << 10;
+
+// The time between two checks of the peer while sleeping
+const CHECK: Duration = Duration::from_millis(100);
+
+// The time given to the creator to initialise a channel
+const INIT: Duration = Duration::from_secs(1);
+
+#[repr(C, align(64))]
+struct Padded(AtomicU32);
+
+#[repr(C)]
+struct Slot {
+    length: AtomicU32,
+    data: UnsafeCell<ShmData>,
+}
+
+// A ring buffer, written by a single sender and read by a single receiver
+#[repr(C)]
+struct Ring {
+    // The number of slots written, by the sender
+    head: Padded,
+    // The number of slots read, by the receiver
+    tail: Padded,
+    // The futex, bumped on each change of the ring buffer
+    event: Padded,
+    waiters: AtomicU32,
+    closed: AtomicU32,
+    slots: [Slot; SLOTS],
+}
+
+#[repr(C)]
+struct Region {
+    magic: AtomicU32,
+    // The process of each side, once it is attached
+    pids: [AtomicU32; 2],
+    // The ring buffer sent by each side
+    rings: [Ring; 2],
+}
+
+// The mapping of the region, shared by the clones of the channels
+#[derive(Debug)]
+struct Mapping {
+    region: NonNull<Region>,
+    // The name of a created channel, removed with the mapping at the latest
+    name: Mutex<Option<CString>>,
+    // Whether the channel is established, and shutdown with the mapping
+    attached: bool,
+    // A single sender and a single receiver for each ring buffer
+    senders: [Mutex<()>; 2],
+    receivers: [Mutex<()>; 2],
+}
+
+// The region is only accessed through atomics,
+// and through the slots owned by the sender or the receiver
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn new(file: &File, name: Option<CString>) -> io::Result<Self> {
+        // SAFETY: a fresh shared mapping of the whole file, checked below
+        let address = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                mem::size_of::<Region>(),
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if address == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Mapping {
+            region: NonNull::new(address as *mut Region).ok_or_else(io::Error::last_os_error)?,
+            name: Mutex::new(name),
+            attached: false,
+            senders: [Mutex::new(()), Mutex::new(())],
+            receivers: [Mutex::new(()), Mutex::new(())],
+        })
+    }
+
+    fn region(&self) -> &Region {
+        // SAFETY: the region is mapped until the mapping is dropped,
+        // and zeroed memory is a valid region
+        unsafe { self.region.as_ref() }
+    }
+
+    fn unlink(&self) {
+        if let Some(name) = lock(&self.name).take() {
+            // SAFETY: a valid C string
+            unsafe { libc::shm_unlink(name.as_ptr()) };
+        }
+    }
+
+    fn shutdown(&self) {
+        for ring in &self.region().rings {
+            ring.closed.store(1, Ordering::SeqCst);
+            notify(ring);
+        }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.attached {
+            self.shutdown();
+        }
+        self.unlink();
+        // SAFETY: the region is not used any more
+        unsafe {
+            libc::munmap(
+                self.region.as_ptr() as *mut libc::c_void,
+                mem::size_of::<Region>(),
+            )
+        };
+    }
+}
+
+/// One end of a channel in shared memory.
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::transport::shm::channel::ShmChannel;
+///
+/// let (a, b) = ShmChannel::pair().unwrap();
+/// a.send(&[42_u8; 128]).unwrap();
+///
+/// let mut data = [0_u8; 128];
+/// assert_eq!(b.recv(&mut data).unwrap(), 128);
+/// assert_eq!(data, [42_u8; 128]);
+///
+/// a.shutdown();
+/// assert!(b.recv(&mut data).is_err());
+/// ```
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[derive(Debug)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub struct ShmChannel {
+    mapping: Arc<Mapping>,
+    side: usize,
+}
+
+impl ShmChannel {
+    /// Create both ends of a channel, for two threads
+    /// of the same process.
+    pub fn pair() -> Result<(Self, Self), Box<dyn Error>> {
+        let name = CString::new("mpst")?;
+        // SAFETY: a valid C string
+        let fd = unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        // SAFETY: a new file descriptor, owned by the file
+        let file = unsafe { File::from_raw_fd(fd) };
+        file.set_len(mem::size_of::<Region>() as u64)?;
+
+        let mut mapping = Mapping::new(&file, None)?;
+        mapping.attached = true;
+        let mapping = Arc::new(mapping);
+        let region = mapping.region();
+        let pid = std::process::id();
+        region.pids[0].store(pid, Ordering::SeqCst);
+        region.pids[1].store(pid, Ordering::SeqCst);
+        region.magic.store(MAGIC, Ordering::SeqCst);
+
+        Ok((
+            ShmChannel {
+                mapping: mapping.clone(),
+                side: 0,
+            },
+            ShmChannel { mapping, side: 1 },
+        ))
+    }
+
+    /// Create the channel `name`, such as `/mpst_a_b`,
+    /// which another process opens with [`ShmChannel::open`].
+    /// The name is removed once the channel is dropped.
+    /// Fails if the name is already used.
+    pub fn create(name: &str) -> Result<Self, Box<dyn Error>> {
+        let name = shm_name(name)?;
+        // SAFETY: a valid C string
+        let fd = unsafe {
+            libc::shm_open(
+                name.as_ptr(),
+                libc::O_CREAT | libc::O_EXCL | libc::O_RDWR | libc::O_CLOEXEC,
+                0o600,
+            )
+        };
+        if fd < 0 {
+            return Err(format!(
+                "The shared memory channel {:?} could not be created: {}",
+                name,
+                io::Error::last_os_error()
+            )
+            .into());
+        }
+        // SAFETY: a new file descriptor, owned by the file
+        let file = unsafe { File::from_raw_fd(fd) };
+
+        let created = file
+            .set_len(mem::size_of::<Region>() as u64)
+            .and_then(|()| Mapping::new(&file, Some(name.clone())));
+        let mapping = match created {
+            Ok(mut mapping) => {
+                mapping.attached = true;
+                Arc::new(mapping)
+            }
+            Err(e) => {
+                // SAFETY: a valid C string
+                unsafe { libc::shm_unlink(name.as_ptr()) };
+                return Err(e.into());
+            }
+        };
+
+        let region = mapping.region();
+        region.pids[0].store(std::process::id(), Ordering::SeqCst);
+        region.magic.store(MAGIC, Ordering::SeqCst);
+
+        Ok(ShmChannel { mapping, side: 0 })
+    }
+
+    /// Open the channel `name`, created by another process
+    /// with [`ShmChannel::create`].
+    /// Fails if the channel does not exist or is already opened.
+    pub fn open(name: &str) -> Result<Self, Box<dyn Error>> {
+        let name = shm_name(name)?;
+        // SAFETY: a valid C string
+        let fd = unsafe { libc::shm_open(name.as_ptr(), libc::O_RDWR | libc::O_CLOEXEC, 0) };
+        if fd < 0 {
+            return Err(format!(
+                "The shared memory channel {:?} could not be opened: {}",
+                name,
+                io::Error::last_os_error()
+            )
+            .into());
+        }
+        // SAFETY: a new file descriptor, owned by the file
+        let file = unsafe { File::from_raw_fd(fd) };
+
+        // The creator may still be initialising the channel
+        let start = Instant::now();
+        while file.metadata()?.len() < mem::size_of::<Region>() as u64 {
+            if start.elapsed() > INIT {
+                return Err(
+                    format!("The shared memory channel {:?} is not initialised", name).into(),
+                );
+            }
+            sleep(Duration::from_millis(1));
+        }
+        let mut mapping = Mapping::new(&file, None)?;
+        let region = mapping.region();
+        while region.magic.load(Ordering::SeqCst) != MAGIC {
+            if start.elapsed() > INIT {
+                return Err(
+                    format!("The shared memory channel {:?} is not initialised", name).into(),
+                );
+            }
+            sleep(Duration::from_millis(1));
+        }
+
+        region.pids[1]
+            .compare_exchange(0, std::process::id(), Ordering::SeqCst, Ordering::SeqCst)
+            .map_err(|_| format!("The shared memory channel {:?} is already opened", name))?;
+        mapping.attached = true;
+
+        Ok(ShmChannel {
+            mapping: Arc::new(mapping),
+            side: 1,
+        })
+    }
+
+    /// Whether the other end of the channel is opened.
+    pub fn is_connected(&self) -> bool {
+        self.mapping.region().pids[1 - self.side].load(Ordering::SeqCst) != 0
+    }
+
+    /// Remove the name of a created channel, which cannot be opened any more,
+    /// as once the channel is dropped.
+    pub fn unlink(&self) {
+        self.mapping.unlink();
+    }
+
+    /// Creates a new handle to the same end of the channel.
+    pub fn try_clone(&self) -> Result<Self, Box<dyn Error>> {
+        Ok(ShmChannel {
+            mapping: self.mapping.clone(),
+            side: self.side,
+        })
+    }
+
+    /// Send `data`, of at most 128 bytes.
+    ///
+    /// Blocks while the ring buffer is full.
+    /// Fails if the channel is shutdown, or if the process of the peer exited.
+    pub fn send(&self, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        if data.len() > mem::size_of::<ShmData>() {
+            return Err(format!("The data of {} bytes is too long", data.len()).into());
+        }
+        let _sender = lock(&self.mapping.senders[self.side]);
+        let ring = &self.mapping.region().rings[self.side];
+
+        let head = ring.head.0.load(Ordering::Relaxed);
+        self.wait(ring, || {
+            ring.closed.load(Ordering::SeqCst) != 0
+                || head.wrapping_sub(ring.tail.0.load(Ordering::Acquire)) < SLOTS as u32
+        })?;
+        if ring.closed.load(Ordering::SeqCst) != 0 {
+            return Err(CLOSED.into());
+        }
+
+        let slot = &ring.slots[head as usize % SLOTS];
+        // SAFETY: the slot is free, and only this sender writes it
+        let slot_data = unsafe { &mut *slot.data.get() };
+        slot_data[..data.len()].copy_from_slice(data);
+        slot.length.store(data.len() as u32, Ordering::Relaxed);
+        ring.head.0.store(head.wrapping_add(1), Ordering::Release);
+        notify(ring);
+        Ok(())
+    }
+
+    /// Receive into `data`, and return the number of bytes received.
+    ///
+    /// Blocks while the ring buffer is empty.
+    /// Fails once the ring buffer is empty and the channel is shutdown,
+    /// or if the process of the peer exited.
+    pub fn recv(&self, data: &mut ShmData) -> Result<usize, Box<dyn Error>> {
+        let _receiver = lock(&self.mapping.receivers[self.side]);
+        let ring = &self.mapping.region().rings[1 - self.side];
+
+        let tail = ring.tail.0.load(Ordering::Relaxed);
+        self.wait(ring, || {
+            ring.head.0.load(Ordering::Acquire) != tail || ring.closed.load(Ordering::SeqCst) != 0
+        })?;
+        if ring.head.0.load(Ordering::Acquire) == tail {
+            return Err(CLOSED.into());
+        }
+
+        let slot = &ring.slots[tail as usize % SLOTS];
+        let length = (slot.length.load(Ordering::Relaxed) as usize).min(data.len());
+        // SAFETY: the slot is written, and only this receiver reads it
+        let slot_data = unsafe { &*slot.data.get() };
+        data[..length].copy_from_slice(&slot_data[..length]);
+        ring.tail.0.store(tail.wrapping_add(1), Ordering::Release);
+        notify(ring);
+        Ok(length)
+    }
+
+    /// Shutdown both directions of the channel.
+    /// The pending data can still be received by the peer,
+    /// then both roles fail to send and to receive.
+    pub fn shutdown(&self) {
+        self.mapping.shutdown();
+    }
+
+    // Spin, then sleep until `ready`, checking the peer regularly
+    fn wait<F>(&self, ring: &Ring, ready: F) -> Result<(), Box<dyn Error>>
+    where
+        F: Fn() -> bool,
+    {
+        for _ in 0..spins() {
+            if ready() {
+                return Ok(());
+            }
+            hint::spin_loop();
+        }
+        loop {
+            let event = ring.event.0.load(Ordering::SeqCst);
+            if ready() {
+                return Ok(());
+            }
+            ring.waiters.fetch_add(1, Ordering::SeqCst);
+            if !ready() {
+                futex_wait(&ring.event.0, event, CHECK);
+            }
+            ring.waiters.fetch_sub(1, Ordering::SeqCst);
+            if ready() {
+                return Ok(());
+            }
+            if !self.peer_alive() {
+                self.shutdown();
+                return Err(LOST.into());
+            }
+        }
+    }
+
+    // Whether the process of the peer still runs,
+    // the threads of a process being notified by the shutdown
+    fn peer_alive(&self) -> bool {
+        let pid = self.mapping.region().pids[1 - self.side].load(Ordering::SeqCst);
+        if pid == 0 || pid == std::process::id() {
+            return true;
+        }
+        // An exited child is a zombie until it is waited for
+        match fs::read_to_string(format!("/proc/{}/stat", pid)) {
+            Ok(stat) => !matches!(
+                stat.rsplit(')').next().map(str::trim_start),
+                Some(state) if state.starts_with('Z') || state.starts_with('X')
+            ),
+            Err(_) => false,
+        }
+    }
+}
+
+// Spinning on a single processor only delays the peer
+fn spins() -> u32 {
+    static SPINS_AVAILABLE: OnceLock<u32> = OnceLock::new();
+    *SPINS_AVAILABLE.get_or_init(|| match thread::available_parallelism() {
+        Ok(processors) if processors.get() > 1 => SPINS,
+        _ => 0,
+    })
+}
+
+// The name of a POSIX shared memory object starts with a slash
+fn shm_name(name: &str) -> Result<CString, Box<dyn Error>> {
+    let name = match name.strip_prefix('/') {
+        Some(_) => name.to_string(),
+        None => format!("/{}", name),
+    };
+    if name[1..].is_empty() || name[1..].contains('/') {
+        return Err(format!("Invalid name of a shared memory channel: {}", name).into());
+    }
+    Ok(CString::new(name)?)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+// Wake up the roles waiting on `ring`
+fn notify(ring: &Ring) {
+    ring.event.0.fetch_add(1, Ordering::SeqCst);
+    if ring.waiters.load(Ordering::SeqCst) > 0 {
+        // SAFETY: the futex is in the mapped region
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                ring.event.0.as_ptr(),
+                libc::FUTEX_WAKE,
+                i32::MAX,
+                ptr::null::<libc::timespec>(),
+                ptr::null::<u32>(),
+                0,
+            )
+        };
+    }
+}
+
+// Sleep until `futex` is not `expected` any more, or `timeout`
+fn futex_wait(futex: &AtomicU32, expected: u32, timeout: Duration) {
+    let timeout = libc::timespec {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_nsec: timeout.subsec_nanos() as libc::c_long,
+    };
+    // SAFETY: the futex is in the mapped region
+    unsafe {
+        libc::syscall(
+            libc::SYS_futex,
+            futex.as_ptr(),
+            libc::FUTEX_WAIT,
+            expected,
+            &timeout as *const libc::timespec,
+            ptr::null::<u32>(),
+            0,
+        )
+    };
+}
@@ -0,0 +1,34 @@
+//! This module contains the functions for closing
+//! binary sessions and
+//! shutdown the related ShmChannel.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_shm"` feature.*
+
+use crate::binary::struct_trait::{end::End, end::Signal};
+use crate::transport::shm::channel::ShmChannel;
+use std::boxed::Box;
+use std::error::Error;
+use std::mem;
+
+/// Closes a shared memory channel session. Synchronises with the partner, and
+/// fails if the partner has crashed.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub fn close_shm(s: End, channel: ShmChannel, shm: bool) -> Result<(), Box<dyn Error>> {
+    s.sender.send(Signal::Stop)?;
+    s.receiver.recv()?;
+    match shm {
+        true => {
+            channel.shutdown(); // Stop any operation on channel
+            mem::drop(channel); // close channel
+            Ok(())
+        }
+        false => Ok(()),
+    }
+}
@@ -0,0 +1,141 @@
+//! This module contains the functions for
+//! forking binary sessions and
+//! the related ShmChannel.
+//!
+//! A role either opens a channel already created by another role,
+//! with [`fork_shm`], or spawns the process of the other role
+//! and creates the channel it opens, with [`fork_shm_spawn`].
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_shm"` feature.*
+
+use crate::binary::struct_trait::session::Session;
+use crate::transport::shm::channel::ShmChannel;
+use std::boxed::Box;
+use std::env;
+use std::error::Error;
+use std::io;
+use std::marker;
+use std::panic;
+use std::process::{Child, Command};
+use std::thread::{sleep, Builder, JoinHandle};
+use std::time::Duration;
+
+type ShmFork<T> = Result<(JoinHandle<()>, T, ShmChannel), Box<dyn Error>>;
+
+type ShmSpawn<T> = Result<(JoinHandle<()>, T, ShmChannel, Child), Box<dyn Error>>;
+
+/// The environment variable holding the name of the channel
+/// given to the processes spawned by [`fork_shm_spawn`].
+///
+/// *This constant is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub const SHM_NAME_ENV: &str = "MPST_SHM_CHANNEL";
+
+fn spawn_thread<S, P>(
+    p: P,
+    name: String,
+    there: S,
+    channel: ShmChannel,
+) -> io::Result<JoinHandle<()>>
+where
+    S: Session + 'static,
+    P: FnOnce(S, ShmChannel) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+{
+    Builder::new()
+        .name(name)
+        .stack_size(64 * 1024 * 1024)
+        .spawn(move || {
+            panic::set_hook(Box::new(|_info| {
+                // do nothing
+            }));
+            match p(there, channel) {
+                Ok(()) => (),
+                Err(e) => panic!("{}", e.to_string()),
+            }
+        })
+}
+
+/// Creates a child process, and a session with two dual
+/// endpoints of type `S` and `S::Dual`. The first endpoint
+/// is given to the child process. Returns the
+/// second endpoint.
+///
+/// The channel `name` is opened, once created by another role
+/// with [`ShmChannel::create`].
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub fn fork_shm<S, P>(p: P, name: &str) -> ShmFork<S::Dual>
+where
+    S: Session + 'static,
+    P: FnOnce(S, ShmChannel) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+{
+    let channel = ShmChannel::open(name)?;
+    let copy_channel = channel.try_clone()?;
+    let (there, here) = Session::new();
+    let other_thread = spawn_thread(p, name.to_string(), there, copy_channel)?;
+    Ok((other_thread, here, channel))
+}
+
+/// Creates a child process, and a session with two dual
+/// endpoints of type `S` and `S::Dual`, as [`fork_shm`].
+///
+/// Creates the channel `name`, then spawns `command`,
+/// which is given `name` in the [`SHM_NAME_ENV`] environment
+/// variable, and waits for it to open the channel.
+/// Also returns the spawned process, which should be waited for.
+/// Fails if the process exits before opening the channel.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub fn fork_shm_spawn<S, P>(p: P, name: &str, command: &mut Command) -> ShmSpawn<S::Dual>
+where
+    S: Session + 'static,
+    P: FnOnce(S, ShmChannel) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+{
+    let channel = ShmChannel::create(name)?;
+
+    let mut child = command.env(SHM_NAME_ENV, name).spawn()?;
+
+    while !channel.is_connected() {
+        if let Some(status) = child.try_wait()? {
+            return Err(format!("The role exited before connecting: {}", status).into());
+        }
+        sleep(Duration::from_millis(10));
+    }
+
+    // The channel is opened, no other role is expected
+    channel.unlink();
+
+    let copy_channel = channel.try_clone()?;
+    let (there, here) = Session::new();
+    let other_thread = spawn_thread(p, name.to_string(), there, copy_channel)?;
+    Ok((other_thread, here, channel, child))
+}
+
+/// Connects a process spawned by [`fork_shm_spawn`]
+/// to the role which spawned it.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub fn connect_spawner() -> Result<ShmChannel, Box<dyn Error>> {
+    let name = env::var(SHM_NAME_ENV).map_err(|_| format!("{} is not set", SHM_NAME_ENV))?;
+    ShmChannel::open(&name)
+}
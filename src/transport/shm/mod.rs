@@ -0,0 +1,60 @@
+#![cfg(all(feature = "transport_shm", target_os = "linux"))]
+
+//! This module contains the functions
+//! and macros for handling a channel in shared memory,
+//! for the latency-critical roles running in different
+//! threads or processes of the same host.
+//!
+//! The `ping_pong_shm` bench compares it with
+//! the Unix domain sockets and with the in-memory channels.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_shm"` feature.*
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub mod channel;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub mod choose;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub mod offer;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub mod recv;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub mod send;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub mod close;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub mod cancel;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub mod fork;
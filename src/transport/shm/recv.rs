@@ -0,0 +1,44 @@
+//! This module contains the functions for
+//! receiving a payload
+//! for a shared memory channel.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_shm"` feature.*
+
+use crate::binary::struct_trait::{recv::Recv, session::Session};
+use crate::transport::shm::channel::ShmChannel;
+use std::boxed::Box;
+use std::error::Error;
+use std::marker;
+
+type ShmData = [u8; 128];
+
+type TupleRecv<T, S> = (T, S, ShmData, usize, ShmChannel);
+
+/// Receive a value of type `T`. Can fail. Returns either a
+/// pair of the received value and the continuation of the
+/// session `S` or an error.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub fn recv_shm<T, S>(
+    s: Recv<(T, ShmData), S>,
+    channel: ShmChannel,
+    shm: bool,
+) -> Result<TupleRecv<T, S>, Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    let (v, s) = s.channel.recv()?;
+    let mut data = [0_u8; 128];
+    let r = match shm {
+        true => channel.recv(&mut data)?,
+        false => 0_usize,
+    };
+    Ok((v.0, s, data, r, channel))
+}
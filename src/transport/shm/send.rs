@@ -0,0 +1,49 @@
+//! This module contains the functions for
+//! sending a payload
+//! for a shared memory channel.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_shm"` feature.*
+
+use crate::binary::struct_trait::{send::Send, session::Session};
+use crate::transport::shm::channel::ShmChannel;
+use std::boxed::Box;
+use std::error::Error;
+use std::marker;
+use std::panic;
+
+type ShmData = [u8; 128];
+
+/// Send a value of type `T` over a shared memory channel. Returns the
+/// continuation of the session `S` and the continuation
+/// of the ShmChannel. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_shm"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_shm")))
+)]
+pub fn send_shm<T, S>(
+    x: T, // Need to force x and data to be of the same type every time but for choice/offer
+    data: &ShmData,
+    s: Send<(T, ShmData), S>,
+    channel: ShmChannel,
+    shm: bool,
+) -> Result<(S, ShmChannel), Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    let (here, there) = S::new();
+    match s.channel.send(((x, *data), there)) {
+        Ok(()) => match shm {
+            true => {
+                channel.send(data)?;
+                Ok((here, channel))
+            }
+            false => Ok((here, channel)),
+        },
+        Err(e) => panic!("{}", e.to_string()),
+    }
+}
@@ -1,12 +1,48 @@
-//! This module contains the macros for
+//! This module contains the macros and the functions for
 //! choosing a specific branch
 //! for a TCP connection.
 //!
 //! *This module is available only if MultiCrusty is built with
-//! the `"transport"` feature.*
+//! the `"transport"` feature or the `"transport_tcp"` feature.*
+
+use crate::transport::wire::frame::write_label;
+use std::boxed::Box;
+use std::error::Error;
+use std::net::TcpStream;
+
+/// Send `label`, the name of the chosen variant, as a label frame of the wire
+/// format over the TCP stream, if `tcp`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn send_tcp_label(
+    stream: &mut TcpStream,
+    label: &str,
+    tcp: bool,
+) -> Result<(), Box<dyn Error>> {
+    if tcp {
+        write_label(stream, label)?;
+    }
+    Ok(())
+}
 
 /// Choose between many different sessions wrapped in an
-/// `enum`
+/// `enum`.
+///
+/// With a TCP stream and whether it is used, the name of the chosen variant
+/// is also sent over it with [`send_tcp_label`], and the result is either
+/// the continuation of the session or the error of the TCP stream,
+/// in which case the session is cancelled.
+///
+/// # Example
+///
+/// ```ignore
+/// let s = choose_tcp!(BinaryA::More, s, [0_u8; 128], &mut stream, true)?;
+/// ```
 ///
 /// *This macro is available only if MultiCrusty is built with
 /// the `"transport"` feature or the `"transport_tcp"` feature.*
@@ -23,4 +59,18 @@ macro_rules! choose_tcp {
         mpstthree::binary::cancel::cancel($data);
         here
     }};
+    ($label:path, $session:expr, $data:expr, $stream:expr, $tcp:expr) => {{
+        let s = $session;
+        match mpstthree::transport::tcp::choose::send_tcp_label(
+            $stream,
+            mpstthree::instrument::branch_label(stringify!($label)),
+            $tcp,
+        ) {
+            Ok(()) => Ok(mpstthree::choose_tcp!($label, s, $data)),
+            Err(e) => {
+                mpstthree::binary::cancel::cancel(s);
+                Err(e)
+            }
+        }
+    }};
 }
@@ -0,0 +1,363 @@
+//! This module contains the heartbeats detecting
+//! the loss of the TcpStreams of a role,
+//! and translating it into a cancellation of the session.
+//!
+//! A [`Heartbeat`] enables the keep-alive probes of the streams,
+//! so that the connection to a dead peer, or a half-open connection,
+//! fails within [`Heartbeat::bound`] instead of blocking for ever.
+//! A [`Watchdog`] watches all the streams of a role:
+//! once one of them is lost, all the others are shutdown,
+//! as with [`cancel_tcp`](crate::transport::tcp::cancel::cancel_tcp),
+//! so that the other roles are notified in turn,
+//! and [`send_tcp_watched`] and [`recv_tcp_watched`] fail,
+//! which drops the session as when a role is cancelled.
+//!
+//! The streams of the other network transports are watched
+//! through their TcpStream, given by `TlsStream::tcp_stream`
+//! or by `WsStream::get_ref`.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tcp"` feature.*
+
+use crate::binary::struct_trait::{recv::Recv, send::Send, session::Session};
+use crate::transport::tcp::recv::recv_tcp;
+use crate::transport::tcp::send::send_tcp;
+use socket2::{SockRef, TcpKeepalive};
+use std::boxed::Box;
+use std::error::Error;
+use std::io;
+use std::marker;
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{sleep, spawn, JoinHandle};
+use std::time::{Duration, Instant};
+
+type TcpData = [u8; 128];
+
+type TupleRecv<T, S> = (T, S, TcpData, usize, TcpStream);
+
+/// The configuration of the heartbeats of the TcpStreams.
+///
+/// A probe is sent after `interval` without any traffic,
+/// then every `interval`, and the connection is lost
+/// if no probe is acknowledged for `timeout`.
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::transport::tcp::heartbeat::Heartbeat;
+/// use std::time::Duration;
+///
+/// let heartbeat = Heartbeat::new(Duration::from_secs(1), Duration::from_secs(5)).unwrap();
+/// assert_eq!(heartbeat.bound(), Duration::from_secs(7));
+/// ```
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub struct Heartbeat {
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Heartbeat {
+            interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Heartbeat {
+    /// Heartbeats every `interval`, losing the connection
+    /// after `timeout` without answer.
+    /// Fails if `interval` is shorter than a second,
+    /// the precision of the keep-alive probes, or longer than `timeout`.
+    pub fn new(interval: Duration, timeout: Duration) -> Result<Self, Box<dyn Error>> {
+        if interval < Duration::from_secs(1) || interval > timeout {
+            return Err(format!(
+                "The interval {:?} must be at least a second and at most the timeout {:?}",
+                interval, timeout
+            )
+            .into());
+        }
+        Ok(Heartbeat { interval, timeout })
+    }
+
+    /// The time between two heartbeats.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// The time without answer after which the connection is lost.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// The time within which a dead peer is detected:
+    /// the first probe, the timeout, and the check of the [`Watchdog`].
+    ///
+    /// The bound holds on Linux and Android, the other systems
+    /// using their own number of probes.
+    pub fn bound(&self) -> Duration {
+        self.timeout + 2 * self.interval
+    }
+
+    /// Enable the heartbeats on `stream`.
+    ///
+    /// On Linux and Android, the data sent but not acknowledged
+    /// for `timeout` also loses the connection.
+    pub fn enable(&self, stream: &TcpStream) -> io::Result<()> {
+        let socket = SockRef::from(stream);
+        let keepalive = TcpKeepalive::new()
+            .with_time(self.interval)
+            .with_interval(self.interval);
+
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let keepalive = keepalive.with_retries(self.retries());
+
+        socket.set_tcp_keepalive(&keepalive)?;
+
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        socket.set_tcp_user_timeout(Some(self.timeout))?;
+
+        Ok(())
+    }
+
+    /// Enable the heartbeats on all the `streams` of a role,
+    /// and watch them until the returned [`Watchdog`] is dropped.
+    pub fn watch(&self, streams: &[&TcpStream]) -> Result<Watchdog, Box<dyn Error>> {
+        let streams = streams
+            .iter()
+            .map(|stream| {
+                self.enable(stream)?;
+                Ok((describe(stream), stream.try_clone()?))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let watched = Arc::new(Watched {
+            streams,
+            lost: Mutex::new(None),
+        });
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let copy_watched = watched.clone();
+        let copy_stop = stop.clone();
+        let interval = self.interval;
+
+        let thread = spawn(move || {
+            let mut last = Instant::now();
+            while !copy_stop.load(Ordering::Relaxed) {
+                if last.elapsed() >= interval {
+                    last = Instant::now();
+                    copy_watched.check_streams();
+                }
+                sleep(Duration::from_millis(10));
+            }
+        });
+
+        Ok(Watchdog {
+            watched,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    // The number of probes sent during the timeout.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn retries(&self) -> u32 {
+        let retries = self.timeout.as_millis() / self.interval.as_millis();
+        retries.clamp(1, u128::from(u32::MAX)) as u32
+    }
+}
+
+#[derive(Debug)]
+struct Watched {
+    // The streams, with their peers, which are unknown once lost
+    streams: Vec<(String, TcpStream)>,
+    lost: Mutex<Option<String>>,
+}
+
+impl Watched {
+    // Look for an error on any stream, such as the timeout of the heartbeats.
+    fn check_streams(&self) {
+        for (peer, stream) in &self.streams {
+            match stream.take_error() {
+                Ok(None) => (),
+                Ok(Some(e)) | Err(e) => {
+                    self.lose(format!(
+                        "The connection to {} was lost, the session is cancelled: {}",
+                        peer, e
+                    ));
+                    return;
+                }
+            }
+        }
+    }
+
+    // Record the first loss, and shutdown all the streams,
+    // which unblocks the role and notifies the other roles.
+    fn lose(&self, error: String) {
+        let mut lost = match self.lost.lock() {
+            Ok(lost) => lost,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if lost.is_none() {
+            *lost = Some(error);
+            for (_peer, stream) in &self.streams {
+                stream.shutdown(Shutdown::Both).unwrap_or(());
+            }
+        }
+    }
+
+    fn lost(&self) -> Option<String> {
+        match self.lost.lock() {
+            Ok(lost) => lost.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+
+/// Watches the TcpStreams of a role, created by [`Heartbeat::watch`].
+///
+/// Stops watching when dropped.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[derive(Debug)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub struct Watchdog {
+    watched: Arc<Watched>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// The error of the first stream lost, if any.
+    pub fn lost(&self) -> Option<String> {
+        self.watched.lost()
+    }
+
+    /// Fails if a stream was lost.
+    pub fn check(&self) -> Result<(), Box<dyn Error>> {
+        match self.lost() {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Cancel all the streams of the role, as if one of them was lost.
+    pub fn cancel(&self) {
+        self.watched
+            .lose(String::from("The session was cancelled by the role"));
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().unwrap_or(());
+        }
+    }
+}
+
+// The peer of `stream`, for the errors.
+fn describe(stream: &TcpStream) -> String {
+    match stream.peer_addr() {
+        Ok(addr) => addr.to_string(),
+        Err(_) => String::from("a peer"),
+    }
+}
+
+// Lose the streams on the errors of the network,
+// but not on the errors of the session.
+fn network_error(error: Box<dyn Error>, stream: &str, watchdog: &Watchdog) -> Box<dyn Error> {
+    if error.downcast_ref::<io::Error>().is_some() {
+        watchdog.watched.lose(format!(
+            "The connection to {} was lost, the session is cancelled: {}",
+            stream, error
+        ));
+    }
+    match watchdog.lost() {
+        Some(lost) => lost.into(),
+        None => error,
+    }
+}
+
+/// Send a value of type `T` over tcp, as
+/// [`send_tcp`](crate::transport::tcp::send::send_tcp),
+/// unless a stream watched by `watchdog` was lost.
+/// If the stream is lost while sending, all the streams
+/// watched by `watchdog` are shutdown.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn send_tcp_watched<T, S>(
+    x: T,
+    data: &TcpData,
+    s: Send<(T, TcpData), S>,
+    stream: TcpStream,
+    tcp: bool,
+    watchdog: &Watchdog,
+) -> Result<(S, TcpStream), Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    watchdog.check()?;
+    let peer = describe(&stream);
+    send_tcp(x, data, s, stream, tcp).map_err(|e| network_error(e, &peer, watchdog))
+}
+
+/// Receive a value of type `T`, as
+/// [`recv_tcp`](crate::transport::tcp::recv::recv_tcp),
+/// unless a stream watched by `watchdog` was lost.
+///
+/// If the stream is lost, or closed by the peer, while receiving,
+/// all the streams watched by `watchdog` are shutdown,
+/// so that the cancellation reaches the other roles.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn recv_tcp_watched<T, S>(
+    s: Recv<(T, TcpData), S>,
+    stream: TcpStream,
+    tcp: bool,
+    watchdog: &Watchdog,
+) -> Result<TupleRecv<T, S>, Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    watchdog.check()?;
+    let peer = describe(&stream);
+    recv_tcp(s, stream, tcp).map_err(|e| match e.downcast_ref::<io::Error>() {
+        // A frame is expected, the peer cancelled the session
+        Some(io) if io.kind() == io::ErrorKind::UnexpectedEof => {
+            watchdog.watched.lose(format!(
+                "The connection to {} was closed, the session is cancelled",
+                peer
+            ));
+            watchdog.lost().unwrap_or_default().into()
+        }
+        _ => network_error(e, &peer, watchdog),
+    })
+}
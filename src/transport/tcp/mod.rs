@@ -6,6 +6,11 @@
 //! *This module is available only if MultiCrusty is built with
 //! the `"transport"` feature.*
 
+use std::boxed::Box;
+use std::error::Error;
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+
 #[cfg_attr(
     doc_cfg,
     doc(cfg(any(feature = "transport", feature = "transport_tcp")))
@@ -47,3 +52,32 @@ pub mod cancel;
     doc(cfg(any(feature = "transport", feature = "transport_tcp")))
 )]
 pub mod fork;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub mod rendezvous;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub mod heartbeat;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub mod resume;
+
+/// Read a line byte by byte, so that the frames
+/// following the line are not lost in a buffer.
+pub(crate) fn read_line(stream: &TcpStream) -> Result<String, Box<dyn Error>> {
+    let mut line = Vec::new();
+    BufReader::with_capacity(1, stream).read_until(b'\n', &mut line)?;
+    match line.pop() {
+        Some(b'\n') => Ok(String::from_utf8(line)?),
+        _ => Err("The connection was closed".into()),
+    }
+}
@@ -1,13 +1,48 @@
-//! This module contains the macros for
+//! This module contains the macros and the functions for
 //! receiving a choice
 //! for a TCP connection.
 //!
 //! *This module is available only if MultiCrusty is built with
 //! the `"transport"` feature or the `"transport_tcp"` feature.*
 
+use crate::transport::wire::frame::read_label;
+use std::boxed::Box;
+use std::error::Error;
+use std::net::TcpStream;
+
+/// Receive the label of a branch from a label frame of the wire format
+/// over the TCP stream, or `None` without `tcp`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn recv_tcp_label(stream: &mut TcpStream, tcp: bool) -> Result<Option<String>, Box<dyn Error>> {
+    if !tcp {
+        return Ok(None);
+    }
+
+    Ok(Some(read_label(stream)?))
+}
+
 /// Offer a choice between many different sessions wrapped
 /// in an `enum`.
 ///
+/// With a TCP stream and whether it is used, the label of the branch
+/// is first received from it with [`recv_tcp_label`], and must be the name
+/// of the variant received on the session.
+///
+/// # Example
+///
+/// ```ignore
+/// offer_tcp!(s, &mut stream, true, {
+///     BinaryA::Done(s) => close_tcp(s, stream, true),
+///     BinaryA::More(s) => { ... },
+/// })
+/// ```
+///
 /// *This macro is available only if MultiCrusty is built with
 /// the `"transport"` feature or the `"transport_tcp"` feature.*
 #[macro_export]
@@ -30,4 +65,23 @@ macro_rules! offer_tcp {
             }
         })()
     };
+    ($session: expr, $stream: expr, $tcp: expr, { $( $pat: pat => $result: expr , )+ }) => {{
+        let label = mpstthree::transport::tcp::offer::recv_tcp_label($stream, $tcp);
+        (move || -> Result<_, _> {
+            let label = label?;
+            let ((data, cont), s) = mpstthree::binary::recv::recv($session)?;
+            mpstthree::binary::cancel::cancel(s);
+            mpstthree::binary::cancel::cancel(data);
+
+            match cont {
+                $(
+                    $pat => {
+                        mpstthree::transport::wire::frame::check_label(&label, stringify!($pat))?;
+                        $result
+                    },
+                )+
+                _ => panic!("Unexpected payload") ,
+            }
+        })()
+    }};
 }
@@ -6,9 +6,9 @@
 //! the `"transport"` feature or the `"transport_tcp"` feature.*
 
 use crate::binary::struct_trait::{recv::Recv, session::Session};
+use crate::transport::wire::frame::read_data;
 use std::boxed::Box;
 use std::error::Error;
-use std::io::Read;
 use std::marker;
 use std::net::TcpStream;
 
@@ -20,6 +20,11 @@ type TupleRecv<T, S> = (T, S, TcpData, usize, TcpStream);
 /// pair of the received value and the continuation of the
 /// session `S` or an error.
 ///
+/// With `tcp`, the data is read from a payload frame of the wire format,
+/// and its length is returned. If the peer has closed the connection before
+/// sending a whole frame, the error is an [`std::io::Error`] of kind
+/// [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof).
+///
 /// *This function is available only if MultiCrusty is built with
 /// the `"transport"` feature or the `"transport_tcp"` feature.*
 #[cfg_attr(
@@ -40,7 +45,7 @@ where
     let r = match tcp {
         true => {
             // stream.shutdown(Shutdown::Write)?; // Force stream to be read only. Needed?
-            stream.read(&mut data)?
+            read_data(&mut stream, &mut data)?
         }
         false => 0_usize,
     };
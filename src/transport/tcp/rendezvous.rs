@@ -0,0 +1,448 @@
+//! This module contains a coordinator wiring the TCP connections
+//! of the roles of a session, so that a role only needs
+//! the address of the coordinator instead of the addresses
+//! of all the other roles.
+//!
+//! Each role [`join`]s a session, identified by a name shared by its roles,
+//! and registers with the coordinator under its role name,
+//! with the address on which it listens.
+//! Once all the roles of the session are registered, the coordinator
+//! sends each of them the addresses of the others, and each role
+//! connects to the roles listed before it and accepts the connections
+//! of the roles listed after it.
+//! Each role then holds a [`Mesh`] with a TcpStream for each other role,
+//! in the order of the sessions of its `MeshedChannels`,
+//! and [`fork_mesh`] runs its `MeshedChannels` endpoint with these streams.
+//! A role disconnecting before all the roles are registered
+//! is dropped from its session, and can join it again.
+//!
+//! The coordinator runs either in a thread, with [`Coordinator::spawn`],
+//! or with `mpst rendezvous <address>`.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tcp"` feature.*
+
+use crate::binary::struct_trait::session::Session;
+use crate::transport::tcp::read_line;
+use std::boxed::Box;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{self, Write};
+use std::marker;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{sleep, spawn, Builder, JoinHandle};
+use std::time::Duration;
+
+// How long the coordinator waits for the registration
+// of a connected role.
+const REGISTER_TIMEOUT: Duration = Duration::from_secs(5);
+
+type MeshFork<T> = Result<(JoinHandle<()>, T), Box<dyn Error>>;
+
+// A role waiting for the other roles of its session.
+#[derive(Debug)]
+struct Registered {
+    role: String,
+    addr: SocketAddr,
+    stream: TcpStream,
+}
+
+#[derive(Debug)]
+struct Pending {
+    roles: Vec<String>,
+    registered: Vec<Registered>,
+}
+
+/// A coordinator gathering the roles of the sessions
+/// and sending them the addresses of each other.
+///
+/// Many sessions can be wired at the same time,
+/// and each registration is read in its own thread,
+/// so a role slow to register does not delay the others.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[derive(Debug)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub struct Coordinator {
+    listener: TcpListener,
+    sessions: Arc<Mutex<HashMap<String, Pending>>>,
+}
+
+impl Coordinator {
+    /// Listen for the roles on `addr`.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, Box<dyn Error>> {
+        Ok(Coordinator {
+            listener: TcpListener::bind(addr)?,
+            sessions: Arc::new(Mutex::new(HashMap::with_hasher(RandomState::new()))),
+        })
+    }
+
+    /// The address on which the coordinator listens.
+    pub fn local_addr(&self) -> Result<SocketAddr, Box<dyn Error>> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Wire the sessions for ever.
+    pub fn serve(self) -> Result<(), Box<dyn Error>> {
+        loop {
+            let (stream, _addr) = self.listener.accept()?;
+            self.register(stream);
+        }
+    }
+
+    /// Wire the sessions in another thread,
+    /// until the returned handle is dropped.
+    pub fn spawn(self) -> Result<CoordinatorHandle, Box<dyn Error>> {
+        let addr = self.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let copy_stop = stop.clone();
+
+        self.listener.set_nonblocking(true)?;
+
+        let thread = spawn(move || {
+            while !copy_stop.load(Ordering::Relaxed) {
+                match self.listener.accept() {
+                    Ok((stream, _addr)) => {
+                        if stream.set_nonblocking(false).is_ok() {
+                            self.register(stream);
+                        }
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        sleep(Duration::from_millis(10))
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(CoordinatorHandle {
+            addr,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    // Register the role connected on `stream` in another thread,
+    // then wire its session if all the roles are registered.
+    // The errors are sent to the role, not to the coordinator.
+    fn register(&self, stream: TcpStream) {
+        let sessions = self.sessions.clone();
+        spawn(move || {
+            let mut copy_stream = match stream.try_clone() {
+                Ok(copy_stream) => copy_stream,
+                Err(_) => return,
+            };
+
+            if let Err(e) = try_register(&sessions, stream) {
+                writeln!(copy_stream, "ERROR {}", e).unwrap_or(());
+            }
+        });
+    }
+}
+
+// Read the registration of the role connected on `stream`,
+// then add it to its session in `sessions`.
+fn try_register(
+    sessions: &Mutex<HashMap<String, Pending>>,
+    stream: TcpStream,
+) -> Result<(), Box<dyn Error>> {
+    stream.set_read_timeout(Some(REGISTER_TIMEOUT))?;
+    let line = read_line(&stream)?;
+    stream.set_read_timeout(None)?;
+
+    let (session, role, roles, addr) = match line.split(' ').collect::<Vec<_>>()[..] {
+        ["REGISTER", session, role, roles, addr] => (session, role, roles, addr),
+        _ => return Err(format!("Unexpected registration: {}", line).into()),
+    };
+    let roles = roles.split(',').map(String::from).collect::<Vec<_>>();
+    let addr = addr.parse::<SocketAddr>()?;
+
+    if !roles.iter().any(|r| r == role) {
+        return Err(format!("{} is not a role of the session {}", role, session).into());
+    }
+
+    let mut sessions = sessions.lock().map_err(|e| e.to_string())?;
+
+    let pending = sessions
+        .entry(session.to_string())
+        .or_insert_with(|| Pending {
+            roles: roles.clone(),
+            registered: Vec::new(),
+        });
+
+    // Forget the roles which have disconnected while waiting
+    pending.registered.retain(|r| !is_closed(&r.stream));
+    if pending.registered.is_empty() {
+        pending.roles = roles.clone();
+    }
+
+    if pending.roles != roles {
+        return Err(format!(
+            "The roles of the session {} are {}",
+            session,
+            pending.roles.join(",")
+        )
+        .into());
+    }
+    if pending.registered.iter().any(|r| r.role == role) {
+        return Err(format!("{} is already registered in the session {}", role, session).into());
+    }
+
+    pending.registered.push(Registered {
+        role: role.to_string(),
+        addr,
+        stream,
+    });
+
+    if pending.registered.len() == pending.roles.len() {
+        if let Some(pending) = sessions.remove(session) {
+            let peers = pending
+                .registered
+                .iter()
+                .map(|r| format!("{}={}", r.role, r.addr))
+                .collect::<Vec<_>>()
+                .join(",");
+            for mut registered in pending.registered {
+                writeln!(registered.stream, "PEERS {}", peers).unwrap_or(());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Whether the role waiting on `stream` has closed its connection,
+// without blocking.
+fn is_closed(stream: &TcpStream) -> bool {
+    if stream.set_nonblocking(true).is_err() {
+        return true;
+    }
+    let closed = match stream.peek(&mut [0; 1]) {
+        Ok(size) => size == 0,
+        Err(e) => e.kind() != io::ErrorKind::WouldBlock,
+    };
+    stream.set_nonblocking(false).is_err() || closed
+}
+
+/// A coordinator running in another thread,
+/// which is stopped when the handle is dropped.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[derive(Debug)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub struct CoordinatorHandle {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CoordinatorHandle {
+    /// The address on which the coordinator listens.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for CoordinatorHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().unwrap_or(());
+        }
+    }
+}
+
+/// The TCP connections of a role with all the other roles of its session.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[derive(Debug)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub struct Mesh {
+    session: String,
+    role: String,
+    peers: Vec<(String, TcpStream)>,
+}
+
+impl Mesh {
+    /// The name of the session.
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// The name of the role.
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    /// The names of the other roles, in the order of the roles of the session.
+    pub fn peers(&self) -> Vec<&str> {
+        self.peers.iter().map(|(peer, _)| peer.as_str()).collect()
+    }
+
+    /// The stream connected to `peer`.
+    pub fn stream(&self, peer: &str) -> Option<&TcpStream> {
+        self.peers
+            .iter()
+            .find(|(name, _)| name == peer)
+            .map(|(_, stream)| stream)
+    }
+
+    /// The streams connected to the other roles, in the order of the
+    /// roles of the session, which is also the order of the sessions
+    /// of the `MeshedChannels` of the role.
+    pub fn into_streams(self) -> Vec<TcpStream> {
+        self.peers.into_iter().map(|(_, stream)| stream).collect()
+    }
+}
+
+/// Join the session `session` as `role`, one of `roles`,
+/// with the coordinator listening on `coordinator`.
+/// Blocks until all the roles have joined and are connected to each other.
+///
+/// All the roles must give the same `roles`, in the same order.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn join<A: ToSocketAddrs>(
+    coordinator: A,
+    session: &str,
+    role: &str,
+    roles: &[&str],
+) -> Result<Mesh, Box<dyn Error>> {
+    for name in roles.iter().chain([session, role].iter()) {
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ',' || c == '=') {
+            return Err(format!("Invalid name: {:?}", name).into());
+        }
+    }
+    let index = roles
+        .iter()
+        .position(|r| *r == role)
+        .ok_or_else(|| format!("{} is not one of the roles", role))?;
+
+    let mut stream = TcpStream::connect(coordinator)?;
+
+    // Listen on the interface reaching the coordinator
+    let listener = TcpListener::bind((stream.local_addr()?.ip(), 0))?;
+
+    writeln!(
+        stream,
+        "REGISTER {} {} {} {}",
+        session,
+        role,
+        roles.join(","),
+        listener.local_addr()?
+    )?;
+
+    let line = read_line(&stream)?;
+    let peers = match line.split_once(' ') {
+        Some(("PEERS", peers)) => peers,
+        Some(("ERROR", error)) => return Err(error.into()),
+        _ => return Err(format!("Unexpected answer of the coordinator: {}", line).into()),
+    };
+    let mut addrs = HashMap::with_hasher(RandomState::new());
+    for peer in peers.split(',') {
+        let (name, addr) = peer
+            .split_once('=')
+            .ok_or_else(|| format!("Unexpected peer: {}", peer))?;
+        addrs.insert(name.to_string(), addr.parse::<SocketAddr>()?);
+    }
+
+    let mut connected = HashMap::with_hasher(RandomState::new());
+
+    // Connect to the roles listed before
+    for peer in &roles[..index] {
+        let addr = addrs
+            .get(*peer)
+            .ok_or_else(|| format!("No address for {}", peer))?;
+        let mut stream = TcpStream::connect(addr)?;
+        writeln!(stream, "HELLO {} {}", session, role)?;
+        connected.insert(peer.to_string(), stream);
+    }
+
+    // Accept the roles listed after
+    while connected.len() < roles.len() - 1 {
+        let (stream, _addr) = listener.accept()?;
+        let line = read_line(&stream)?;
+        match line.split(' ').collect::<Vec<_>>()[..] {
+            ["HELLO", s, peer]
+                if s == session
+                    && roles[index + 1..].contains(&peer)
+                    && !connected.contains_key(peer) =>
+            {
+                connected.insert(peer.to_string(), stream);
+            }
+            _ => return Err(format!("Unexpected connection: {}", line).into()),
+        }
+    }
+
+    let peers = roles
+        .iter()
+        .filter(|r| **r != role)
+        .map(|r| {
+            let stream = connected.remove(*r).ok_or("Missing connection")?;
+            Ok((r.to_string(), stream))
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    Ok(Mesh {
+        session: session.to_string(),
+        role: role.to_string(),
+        peers,
+    })
+}
+
+/// Creates a child process, and a session with two dual
+/// endpoints of type `S` and `S::Dual`, as
+/// [`fork_tcp`](crate::transport::tcp::fork::fork_tcp).
+/// The first endpoint, such as the `MeshedChannels` of the role of `mesh`,
+/// is given to the child process with the streams of `mesh`,
+/// in the order of its sessions. Returns the second endpoint.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn fork_mesh<S, P>(p: P, mesh: Mesh) -> MeshFork<S::Dual>
+where
+    S: Session + 'static,
+    P: FnOnce(S, Vec<TcpStream>) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+{
+    let name = format!("{} {}", mesh.session, mesh.role);
+    let streams = mesh.into_streams();
+    let (there, here) = Session::new();
+    let other_thread = Builder::new()
+        .name(name)
+        .stack_size(64 * 1024 * 1024)
+        .spawn(move || {
+            panic::set_hook(Box::new(|_info| {
+                // do nothing
+            }));
+            match p(there, streams) {
+                Ok(()) => (),
+                Err(e) => panic!("{}", e.to_string()),
+            }
+        })?;
+    Ok((other_thread, here))
+}
@@ -0,0 +1,544 @@
+//! This module contains the resumable TCP connections,
+//! which survive the transient failures of the network.
+//!
+//! The payloads are sent in payload frames of the wire format,
+//! wrapped in envelopes carrying their sequence number,
+//! and the number of payloads received from the peer,
+//! which acknowledges them, as described in [`wire`].
+//! Each side keeps the payloads not acknowledged yet,
+//! up to a window after which it waits for an acknowledgement.
+//! When the connection fails, the role which connected reconnects,
+//! and the role which accepted accepts it again on the same listener.
+//! Both sides then tell each other how many payloads they received,
+//! and send again the payloads lost with the connection,
+//! so that each role resumes at the step of the protocol it reached.
+//! If the session cannot be resumed within the timeout, it is cancelled.
+//!
+//! [`wire`]: crate::transport::wire
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tcp"` feature.*
+
+use crate::binary::struct_trait::{
+    end::End, end::Signal, recv::Recv, send::Send, session::Session,
+};
+use crate::transport::tcp::heartbeat::Heartbeat;
+use crate::transport::tcp::read_line;
+use crate::transport::wire::frame::{encode, read_data, Frame};
+use std::boxed::Box;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::marker;
+use std::mem;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+type TcpData = [u8; 128];
+
+type TupleRecv<T, S> = (T, S, TcpData, usize, ResumableStream);
+
+const DATA: u8 = 0;
+const ACK: u8 = 1;
+const CLOSE: u8 = 2;
+// Asks the peer for an acknowledgement.
+const SYNC: u8 = 3;
+
+// The kind, the sequence number and the acknowledgement,
+// followed by a payload frame for the data.
+const HEADER: usize = 17;
+
+// How long to wait between two attempts to reconnect.
+const RETRY: Duration = Duration::from_millis(10);
+
+/// The default number of payloads sent
+/// and not acknowledged yet.
+///
+/// *This constant is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub const WINDOW: usize = 64;
+
+#[derive(Debug)]
+enum Side {
+    Connect(Vec<SocketAddr>),
+    Accept(TcpListener),
+}
+
+/// A TCP connection which is resumed after a failure.
+///
+/// Both sides must give the same name for the session,
+/// which identifies the connection when resuming it.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[derive(Debug)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub struct ResumableStream {
+    session: String,
+    side: Side,
+    stream: TcpStream,
+    timeout: Duration,
+    heartbeat: Option<Heartbeat>,
+    window: usize,
+    // The sequence number of the next payload sent
+    next: u64,
+    // The payloads sent and not acknowledged
+    sent: VecDeque<(u64, TcpData)>,
+    // The number of payloads received
+    received: u64,
+    inbox: VecDeque<TcpData>,
+    closing: bool,
+    peer_closed: bool,
+    resumptions: usize,
+}
+
+impl ResumableStream {
+    /// Connect to the role listening on `address` for the session `session`.
+    ///
+    /// Tries again until `timeout`, which is also
+    /// the time given to each resumption.
+    pub fn connect<A: ToSocketAddrs>(
+        address: A,
+        session: &str,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn Error>> {
+        let addrs = address.to_socket_addrs()?.collect::<Vec<_>>();
+        ResumableStream::open(Side::Connect(addrs), session, timeout)
+    }
+
+    /// Accept the role connecting to `listener` for the session `session`.
+    ///
+    /// The listener is kept for the resumptions,
+    /// and should not be used by other sessions meanwhile.
+    pub fn accept(
+        listener: &TcpListener,
+        session: &str,
+        timeout: Duration,
+    ) -> Result<Self, Box<dyn Error>> {
+        ResumableStream::open(Side::Accept(listener.try_clone()?), session, timeout)
+    }
+
+    fn open(side: Side, session: &str, timeout: Duration) -> Result<Self, Box<dyn Error>> {
+        if session.is_empty() || session.contains(char::is_whitespace) {
+            return Err(format!("Invalid session: {:?}", session).into());
+        }
+
+        let (stream, _received) = establish(
+            &side,
+            session,
+            0,
+            timeout,
+            "The connection could not be established".into(),
+        )?;
+
+        Ok(ResumableStream {
+            session: session.to_string(),
+            side,
+            stream,
+            timeout,
+            heartbeat: None,
+            window: WINDOW,
+            next: 0,
+            sent: VecDeque::new(),
+            received: 0,
+            inbox: VecDeque::new(),
+            closing: false,
+            peer_closed: false,
+            resumptions: 0,
+        })
+    }
+
+    /// The name of the session.
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// How many times the connection was resumed.
+    pub fn resumptions(&self) -> usize {
+        self.resumptions
+    }
+
+    /// Enable the heartbeats on the connection, and on the
+    /// connections resuming it, so that a dead peer is noticed.
+    pub fn set_heartbeat(&mut self, heartbeat: Heartbeat) -> io::Result<()> {
+        heartbeat.enable(&self.stream)?;
+        self.heartbeat = Some(heartbeat);
+        Ok(())
+    }
+
+    /// Set how many payloads can be sent without being acknowledged,
+    /// by default [`WINDOW`].
+    /// Beyond this number, the peer is asked for an acknowledgement,
+    /// and sending waits for it.
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window.max(1);
+    }
+
+    /// Another handle on the current TcpStream,
+    /// which is replaced when the connection is resumed.
+    pub fn tcp_stream(&self) -> io::Result<TcpStream> {
+        self.stream.try_clone()
+    }
+
+    /// Send a payload, resuming the connection if needed.
+    pub fn send(&mut self, data: &TcpData) -> Result<(), Box<dyn Error>> {
+        if self.sent.len() >= self.window {
+            self.write_frame(SYNC, self.next, None)?;
+            while self.sent.len() >= self.window {
+                self.read_frame()?;
+            }
+        }
+
+        // Otherwise, the payload would be lost silently
+        // if the connection was closed by the peer
+        if self.closed_by_peer() {
+            self.resume("The connection was closed by the peer".into())?;
+        }
+
+        let seq = self.next;
+        self.next += 1;
+        self.sent.push_back((seq, *data));
+
+        self.write_frame(DATA, seq, Some(data))
+    }
+
+    /// Receive a payload, resuming the connection if needed.
+    pub fn recv(&mut self) -> Result<TcpData, Box<dyn Error>> {
+        while self.inbox.is_empty() {
+            if self.peer_closed {
+                return Err(format!("The peer closed the session {}", self.session).into());
+            }
+            self.read_frame()?;
+        }
+
+        self.inbox
+            .pop_front()
+            .ok_or_else(|| "No payload received".into())
+    }
+
+    /// Wait until both sides have received all the payloads,
+    /// then shutdown the connection.
+    pub fn close(mut self) -> Result<(), Box<dyn Error>> {
+        self.closing = true;
+        self.write_frame(CLOSE, self.next, None)?;
+        while !self.peer_closed || !self.sent.is_empty() {
+            self.read_frame()?;
+        }
+        self.stream.shutdown(Shutdown::Both).unwrap_or(());
+        Ok(())
+    }
+
+    /// Drop the connection without resuming it.
+    pub fn cancel(self) {
+        self.stream.shutdown(Shutdown::Both).unwrap_or(());
+    }
+
+    // An envelope, acknowledging all the payloads received.
+    fn frame(&self, kind: u8, seq: u64, data: Option<&TcpData>) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut frame = Vec::with_capacity(HEADER + 128);
+        frame.push(kind);
+        frame.extend_from_slice(&seq.to_be_bytes());
+        frame.extend_from_slice(&self.received.to_be_bytes());
+        if let Some(data) = data {
+            frame.extend(encode(&Frame::payload("", "", data.to_vec()))?);
+        }
+        Ok(frame)
+    }
+
+    // Write a frame, resuming the connection if needed,
+    // which sends the frame again if it is kept.
+    fn write_frame(
+        &mut self,
+        kind: u8,
+        seq: u64,
+        data: Option<&TcpData>,
+    ) -> Result<(), Box<dyn Error>> {
+        let frame = self.frame(kind, seq, data)?;
+        match self.stream.write_all(&frame) {
+            Ok(()) => Ok(()),
+            Err(e) => self.resume(e.into()),
+        }
+    }
+
+    // Whether the peer closed or reset the connection,
+    // without waiting for any frame.
+    fn closed_by_peer(&self) -> bool {
+        let mut byte = [0_u8; 1];
+        if self.stream.set_nonblocking(true).is_err() {
+            return true;
+        }
+        let closed = match self.stream.peek(&mut byte) {
+            Ok(n) => n == 0,
+            Err(e) => e.kind() != io::ErrorKind::WouldBlock,
+        };
+        self.stream.set_nonblocking(false).is_err() || closed
+    }
+
+    // Read and handle the next frame, resuming the connection if needed.
+    fn read_frame(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut header = [0_u8; HEADER];
+        let mut data = [0_u8; 128];
+
+        if let Err(e) = self.stream.read_exact(&mut header) {
+            return self.resume(e.into());
+        }
+        if header[0] == DATA {
+            // Only a failure of the connection is resumed,
+            // not a payload frame which cannot be read
+            if let Err(e) = read_data(&mut self.stream, &mut data) {
+                return match e.downcast::<io::Error>() {
+                    Ok(e) => self.resume(e),
+                    Err(e) => Err(e),
+                };
+            }
+        }
+
+        let mut number = [0_u8; 8];
+        number.copy_from_slice(&header[1..9]);
+        let seq = u64::from_be_bytes(number);
+        number.copy_from_slice(&header[9..17]);
+        self.acknowledge(u64::from_be_bytes(number));
+
+        match header[0] {
+            // Otherwise, the payload was received before a resumption
+            DATA if seq == self.received => {
+                self.received += 1;
+                self.inbox.push_back(data);
+            }
+            DATA if seq > self.received => {
+                return Err(format!("The payload {} was skipped", self.received).into())
+            }
+            DATA | ACK => (),
+            SYNC => self.write_frame(ACK, self.next, None)?,
+            CLOSE => self.peer_closed = true,
+            kind => return Err(format!("Unexpected frame: {}", kind).into()),
+        }
+        Ok(())
+    }
+
+    // Forget the payloads received by the peer.
+    fn acknowledge(&mut self, received: u64) {
+        while matches!(self.sent.front(), Some((seq, _)) if *seq < received) {
+            self.sent.pop_front();
+        }
+    }
+
+    // Replace the failed connection, and send again the payloads
+    // lost with it, or cancel the session if it cannot be resumed
+    // within the timeout.
+    fn resume(&mut self, error: Box<dyn Error>) -> Result<(), Box<dyn Error>> {
+        self.stream.shutdown(Shutdown::Both).unwrap_or(());
+
+        let (stream, received) = establish(
+            &self.side,
+            &self.session,
+            self.received,
+            self.timeout,
+            error,
+        )?;
+        self.stream = stream;
+        self.resumptions += 1;
+
+        if let Some(heartbeat) = &self.heartbeat {
+            heartbeat.enable(&self.stream)?;
+        }
+
+        self.acknowledge(received);
+        let mut frames = Vec::new();
+        for (seq, data) in self.sent.clone() {
+            frames.extend(self.frame(DATA, seq, Some(&data))?);
+        }
+        if self.sent.len() >= self.window {
+            frames.extend(self.frame(SYNC, self.next, None)?);
+        }
+        if self.closing {
+            frames.extend(self.frame(CLOSE, self.next, None)?);
+        }
+        match self.stream.write_all(&frames) {
+            Ok(()) => Ok(()),
+            Err(e) => self.resume(e.into()),
+        }
+    }
+}
+
+// Connect, or accept, until the peer of `session` is reached,
+// and exchange the numbers of payloads received.
+// Returns the connection and the number of payloads received by the peer.
+fn establish(
+    side: &Side,
+    session: &str,
+    received: u64,
+    timeout: Duration,
+    error: Box<dyn Error>,
+) -> Result<(TcpStream, u64), Box<dyn Error>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Ok(Some(established)) = attempt(side, session, received, deadline) {
+            return Ok(established);
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "The session {} could not be resumed within {:?}, the session is cancelled: {}",
+                session, timeout, error
+            )
+            .into());
+        }
+        sleep(RETRY);
+    }
+}
+
+fn attempt(
+    side: &Side,
+    session: &str,
+    received: u64,
+    deadline: Instant,
+) -> Result<Option<(TcpStream, u64)>, Box<dyn Error>> {
+    let remaining = deadline
+        .saturating_duration_since(Instant::now())
+        .max(RETRY);
+
+    let stream = match side {
+        Side::Connect(addrs) => {
+            match addrs
+                .iter()
+                .find_map(|addr| TcpStream::connect_timeout(addr, remaining).ok())
+            {
+                Some(stream) => stream,
+                None => return Ok(None),
+            }
+        }
+        Side::Accept(listener) => {
+            listener.set_nonblocking(true)?;
+            let accepted = listener.accept();
+            listener.set_nonblocking(false)?;
+            match accepted {
+                Ok((stream, _addr)) => {
+                    stream.set_nonblocking(false)?;
+                    stream
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    };
+
+    stream.set_read_timeout(Some(remaining))?;
+    let hello = format!("RESUME {} {}\n", session, received);
+    if let Side::Connect(_) = side {
+        (&stream).write_all(hello.as_bytes())?;
+    }
+    let peer = read_line(&stream)?;
+    stream.set_read_timeout(None)?;
+
+    let received = match peer.split(' ').collect::<Vec<_>>()[..] {
+        ["RESUME", s, received] if s == session => received.parse()?,
+        _ => {
+            writeln!(&stream, "ERROR Unexpected resumption: {}", peer).unwrap_or(());
+            stream.shutdown(Shutdown::Both).unwrap_or(());
+            return Err(format!("Unexpected resumption: {}", peer).into());
+        }
+    };
+    if let Side::Accept(_) = side {
+        (&stream).write_all(hello.as_bytes())?;
+    }
+    Ok(Some((stream, received)))
+}
+
+/// Send a value of type `T` over a resumable connection.
+/// Returns the continuation of the session `S` and the
+/// ResumableStream. Fails if the connection is lost
+/// and cannot be resumed.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn send_resumable<T, S>(
+    x: T,
+    data: &TcpData,
+    s: Send<(T, TcpData), S>,
+    mut stream: ResumableStream,
+    tcp: bool,
+) -> Result<(S, ResumableStream), Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    let (here, there) = S::new();
+    s.channel
+        .send(((x, *data), there))
+        .map_err(|e| e.to_string())?;
+    if tcp {
+        stream.send(data)?;
+    }
+    Ok((here, stream))
+}
+
+/// Receive a value of type `T` over a resumable connection.
+/// Fails if the connection is lost and cannot be resumed.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn recv_resumable<T, S>(
+    s: Recv<(T, TcpData), S>,
+    mut stream: ResumableStream,
+    tcp: bool,
+) -> Result<TupleRecv<T, S>, Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    let (v, s) = s.channel.recv()?;
+    let (data, r) = match tcp {
+        true => (stream.recv()?, 128),
+        false => ([0_u8; 128], 0),
+    };
+    Ok((v.0, s, data, r, stream))
+}
+
+/// Closes a session over a resumable connection.
+/// Synchronises with the partner, and fails if the partner
+/// has crashed, or if the connection cannot be resumed.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn close_resumable(s: End, stream: ResumableStream, tcp: bool) -> Result<(), Box<dyn Error>> {
+    s.sender.send(Signal::Stop)?;
+    s.receiver.recv()?;
+    match tcp {
+        true => stream.close(),
+        false => Ok(()),
+    }
+}
+
+/// Cancels a session over a resumable connection,
+/// without resuming the connection.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tcp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tcp")))
+)]
+pub fn cancel_resumable<T>(s: T, stream: ResumableStream) {
+    mem::drop(s);
+    stream.cancel();
+}
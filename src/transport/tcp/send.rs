@@ -6,9 +6,9 @@
 //! the `"transport"` feature or the `"transport_tcp"` feature.*
 
 use crate::binary::struct_trait::{send::Send, session::Session};
+use crate::transport::wire::frame::write_data;
 use std::boxed::Box;
 use std::error::Error;
-use std::io::Write;
 use std::marker;
 use std::net::TcpStream;
 use std::panic;
@@ -43,7 +43,7 @@ where
                 true => {
                     // stream.shutdown(Shutdown::Read)?; // TODO: Force stream to be write only.
                     // Needed?
-                    stream.write_all(data)?;
+                    write_data(&mut stream, data)?;
                     Ok((here, stream))
                 }
                 false => Ok((here, stream)),
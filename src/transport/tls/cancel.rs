@@ -0,0 +1,27 @@
+//! This module contains the functions
+//! for cancelling binary sessions and
+//! shutdown the related TlsStream.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tls"` feature.*
+
+use crate::transport::tls::stream::TlsStream;
+use std::mem;
+
+/// Cancels a session. Always succeeds. If the partner calls
+/// `send`, `recv` or `close` after cancellation,
+/// those calls fail. Used for TLS transport.
+///
+/// Drops the session *s* and shutdowns the `TlsStream` *stream*
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tls"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub fn cancel_tls<T>(s: T, stream: TlsStream) {
+    mem::drop(s);
+    stream.shutdown().unwrap_or(());
+    mem::drop(stream);
+}
@@ -0,0 +1,34 @@
+//! This module contains the functions for closing
+//! binary sessions and
+//! shutdown the related TlsStream.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tls"` feature.*
+
+use crate::binary::struct_trait::{end::End, end::Signal};
+use crate::transport::tls::stream::TlsStream;
+use std::boxed::Box;
+use std::error::Error;
+use std::mem;
+
+/// Closes a TLS session. Synchronises with the partner, and
+/// fails if the partner has crashed.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tls"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub fn close_tls(s: End, stream: TlsStream, tls: bool) -> Result<(), Box<dyn Error>> {
+    s.sender.send(Signal::Stop)?;
+    s.receiver.recv()?;
+    match tls {
+        true => {
+            stream.shutdown().unwrap_or(()); // The peer may already have closed the connection.
+            mem::drop(stream);
+            Ok(())
+        }
+        false => Ok(()),
+    }
+}
@@ -0,0 +1,107 @@
+//! This module contains the certificates and the keys
+//! of a role, for the TLS connections.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tls"` feature.*
+
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::ring;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{ClientConfig, RootCertStore, ServerConfig};
+use std::boxed::Box;
+use std::error::Error;
+use std::sync::Arc;
+
+/// The `rustls` crate, for building a [`TlsConfig`]
+/// from custom configurations.
+pub use rustls;
+
+/// The configurations used by a role for connecting
+/// to the other roles, and for accepting their connections.
+///
+/// Cloning a configuration shares its certificates.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tls"` feature.*
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub struct TlsConfig {
+    client: Arc<ClientConfig>,
+    server: Arc<ServerConfig>,
+}
+
+impl TlsConfig {
+    /// A configuration from PEM encoded data:
+    /// the certificate chain of the role, its private key,
+    /// and the certificates of the authorities trusted
+    /// for signing the certificates of the other roles.
+    ///
+    /// The certificate of the role is presented on both sides
+    /// of a connection, and the certificates of the other roles
+    /// are always required.
+    pub fn new(
+        cert_pem: impl AsRef<[u8]>,
+        key_pem: impl AsRef<[u8]>,
+        ca_pem: impl AsRef<[u8]>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let certs =
+            CertificateDer::pem_slice_iter(cert_pem.as_ref()).collect::<Result<Vec<_>, _>>()?;
+        if certs.is_empty() {
+            return Err("No certificate found for the role".into());
+        }
+        let key = PrivateKeyDer::from_pem_slice(key_pem.as_ref())?;
+
+        let mut roots = RootCertStore::empty();
+        for ca in CertificateDer::pem_slice_iter(ca_pem.as_ref()) {
+            roots.add(ca?)?;
+        }
+        if roots.is_empty() {
+            return Err("No certificate authority found".into());
+        }
+        let roots = Arc::new(roots);
+
+        let provider = Arc::new(ring::default_provider());
+
+        let client = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()?
+            .with_webpki_verifier(
+                WebPkiServerVerifier::builder_with_provider(roots.clone(), provider.clone())
+                    .build()?,
+            )
+            .with_client_auth_cert(certs.clone(), key.clone_key())?;
+
+        let server = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()?
+            .with_client_cert_verifier(
+                WebPkiClientVerifier::builder_with_provider(roots, provider).build()?,
+            )
+            .with_single_cert(certs, key)?;
+
+        Ok(TlsConfig::from_configs(Arc::new(client), Arc::new(server)))
+    }
+
+    /// A configuration from the given `rustls` configurations,
+    /// for instance with another crypto provider.
+    ///
+    /// The server configuration should require the certificates
+    /// of the clients, otherwise the connections are refused
+    /// as their role cannot be checked.
+    pub fn from_configs(client: Arc<ClientConfig>, server: Arc<ServerConfig>) -> Self {
+        TlsConfig { client, server }
+    }
+
+    /// The configuration for connecting to the other roles.
+    pub fn client(&self) -> &Arc<ClientConfig> {
+        &self.client
+    }
+
+    /// The configuration for accepting the connections of the other roles.
+    pub fn server(&self) -> &Arc<ServerConfig> {
+        &self.server
+    }
+}
@@ -0,0 +1,54 @@
+//! This module contains the functions
+//! for forking binary sessions and
+//! the related TlsStream.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tls"` feature.*
+
+use crate::binary::struct_trait::session::Session;
+use crate::transport::tls::config::TlsConfig;
+use crate::transport::tls::stream::TlsStream;
+use std::boxed::Box;
+use std::error::Error;
+use std::marker;
+use std::panic;
+use std::thread::{Builder, JoinHandle};
+
+type TlsFork<T> = Result<(JoinHandle<()>, T, TlsStream), Box<dyn Error>>;
+
+/// Creates a child process, and a session with two dual
+/// endpoints of type `S` and `S::Dual`. The first endpoint
+/// is given to the child process. Returns the
+/// second endpoint.
+///
+/// The connection to `address` must present a certificate
+/// bound to `peer_role`, and presents the certificate of `config`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tls"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub fn fork_tls<S, P>(p: P, address: &str, config: &TlsConfig, peer_role: &str) -> TlsFork<S::Dual>
+where
+    S: Session + 'static,
+    P: FnOnce(S, TlsStream) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+{
+    let stream = TlsStream::connect(address, config, peer_role)?;
+    let copy_stream = stream.try_clone()?;
+    let (there, here) = Session::new();
+    let other_thread = Builder::new()
+        .name(String::from(address))
+        .stack_size(64 * 1024 * 1024)
+        .spawn(move || {
+            panic::set_hook(Box::new(|_info| {
+                // do nothing
+            }));
+            match p(there, copy_stream) {
+                Ok(()) => (),
+                Err(e) => panic!("{}", e.to_string()),
+            }
+        })?;
+    Ok((other_thread, here, stream))
+}
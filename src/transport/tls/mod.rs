@@ -0,0 +1,56 @@
+#![cfg(feature = "transport_tls")]
+
+//! This module contains the functions
+//! for handling a TCP connection wrapped in TLS,
+//! with [`rustls`](https://docs.rs/rustls).
+//!
+//! Each role holds a certificate whose subject alternative name
+//! is its own name, such as `RoleA`, signed by a certificate authority
+//! shared by all the roles.
+//! Both sides of a connection check the certificate of the other one,
+//! and its name against the role they expect.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tls"` feature.*
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub mod config;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub mod stream;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub mod recv;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub mod send;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub mod close;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub mod cancel;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub mod fork;
@@ -0,0 +1,50 @@
+//! This module contains the functions for
+//! sending a payload
+//! for a TLS connection.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tls"` feature.*
+
+use crate::binary::struct_trait::{send::Send, session::Session};
+use crate::transport::tls::stream::TlsStream;
+use crate::transport::wire::frame::write_data;
+use std::boxed::Box;
+use std::error::Error;
+use std::marker;
+use std::panic;
+
+type TlsData = [u8; 128];
+
+/// Send a value of type `T` over TLS. Returns the
+/// continuation of the session `S` and the continuation
+/// of the TlsStream. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tls"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub fn send_tls<T, S>(
+    x: T,
+    data: &TlsData,
+    s: Send<(T, TlsData), S>,
+    mut stream: TlsStream,
+    tls: bool,
+) -> Result<(S, TlsStream), Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    let (here, there) = S::new();
+    match s.channel.send(((x, *data), there)) {
+        Ok(()) => match tls {
+            true => {
+                write_data(&mut stream, data)?;
+                Ok((here, stream))
+            }
+            false => Ok((here, stream)),
+        },
+        Err(e) => panic!("{}", e.to_string()),
+    }
+}
@@ -0,0 +1,279 @@
+//! This module contains the TLS stream
+//! replacing the TcpStream of the TCP transport.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_tls"` feature.*
+
+use crate::transport::tls::config::TlsConfig;
+use rustls::client::verify_server_name;
+use rustls::pki_types::ServerName;
+use rustls::server::ParsedCertificate;
+use rustls::{ClientConnection, Connection, ServerConnection};
+use std::boxed::Box;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+// The size of the buffer of the raw bytes read from the socket
+const RECEIVED_SIZE: usize = 16 * 1024;
+
+/// A TCP connection wrapped in TLS, whose peer
+/// has shown a certificate bound to the expected role.
+///
+/// As [`TcpStream::try_clone`], cloning a stream shares the
+/// same connection, whose reads, on one side, and writes, on the other side,
+/// are then serialised.
+/// A read waits for the socket without blocking the writes,
+/// so one clone can read while another one writes.
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_tls"` feature.*
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_tls")))
+)]
+pub struct TlsStream {
+    connection: Arc<Mutex<Connection>>,
+    // The raw bytes read from the socket and not given to the connection yet
+    received: Arc<Mutex<Vec<u8>>>,
+    // Held while the records are written to the socket, to keep them in order
+    sending: Arc<Mutex<()>>,
+    socket: Arc<TcpStream>,
+    peer_role: String,
+}
+
+impl TlsStream {
+    /// Connect to `address`, which must present
+    /// a certificate bound to `peer_role`.
+    pub fn connect<A: ToSocketAddrs>(
+        address: A,
+        config: &TlsConfig,
+        peer_role: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        TlsStream::connect_stream(TcpStream::connect(address)?, config, peer_role)
+    }
+
+    /// Run the client side of the handshake on an open `stream`,
+    /// whose peer must present a certificate bound to `peer_role`.
+    pub fn connect_stream(
+        mut stream: TcpStream,
+        config: &TlsConfig,
+        peer_role: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        let name = ServerName::try_from(peer_role.to_string())?;
+        let mut connection = ClientConnection::new(config.client().clone(), name)?;
+
+        while connection.is_handshaking() {
+            if let Err(e) = connection.complete_io(&mut stream) {
+                stream.shutdown(Shutdown::Both).unwrap_or(());
+                return Err(e.into());
+            }
+        }
+
+        Ok(TlsStream::new(connection.into(), stream, peer_role))
+    }
+
+    /// Run the server side of the handshake on an accepted `stream`,
+    /// whose peer must present a certificate bound to `peer_role`.
+    pub fn accept(
+        mut stream: TcpStream,
+        config: &TlsConfig,
+        peer_role: &str,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut connection = ServerConnection::new(config.server().clone())?;
+
+        while connection.is_handshaking() {
+            if let Err(e) = connection.complete_io(&mut stream) {
+                stream.shutdown(Shutdown::Both).unwrap_or(());
+                return Err(e.into());
+            }
+        }
+
+        // The certificate is signed by a trusted authority,
+        // check that it is the certificate of the expected role
+        let bound = match connection
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+        {
+            Some(cert) => {
+                let name = ServerName::try_from(peer_role.to_string())?;
+                verify_server_name(&ParsedCertificate::try_from(cert)?, &name).is_ok()
+            }
+            None => false,
+        };
+
+        if !bound {
+            connection.send_close_notify();
+            connection.complete_io(&mut stream).unwrap_or((0, 0));
+            stream.shutdown(Shutdown::Both).unwrap_or(());
+            return Err(format!(
+                "The certificate of the peer is not bound to the role {}",
+                peer_role
+            )
+            .into());
+        }
+
+        Ok(TlsStream::new(connection.into(), stream, peer_role))
+    }
+
+    fn new(connection: Connection, socket: TcpStream, peer_role: &str) -> Self {
+        TlsStream {
+            connection: Arc::new(Mutex::new(connection)),
+            received: Arc::new(Mutex::new(Vec::new())),
+            sending: Arc::new(Mutex::new(())),
+            socket: Arc::new(socket),
+            peer_role: peer_role.to_string(),
+        }
+    }
+
+    /// The role of the peer, checked during the handshake.
+    pub fn peer_role(&self) -> &str {
+        &self.peer_role
+    }
+
+    /// The address of the peer.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.peer_addr()
+    }
+
+    /// Another handle on the underlying TcpStream,
+    /// for instance for watching its [heartbeats](crate::transport::tcp::heartbeat).
+    pub fn tcp_stream(&self) -> io::Result<TcpStream> {
+        self.socket.try_clone()
+    }
+
+    /// Another handle on the same connection.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(self.clone())
+    }
+
+    /// Tell the peer that the connection is closed,
+    /// then shutdown the underlying TcpStream.
+    pub fn shutdown(&self) -> io::Result<()> {
+        self.send(|connection| {
+            connection.send_close_notify();
+            Ok(())
+        })?;
+        match self.socket.shutdown(Shutdown::Both) {
+            // The peer has already closed the connection
+            Err(e) if e.kind() == io::ErrorKind::NotConnected => Ok(()),
+            result => result,
+        }
+    }
+
+    // Update the connection with `f`, then write the records
+    // it produced to the socket, without holding the connection
+    fn send<T, F>(&self, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut Connection) -> io::Result<T>,
+    {
+        let sending = lock(&self.sending)?;
+        let result = f(&mut *lock(&self.connection)?)?;
+        self.write_records(sending)?;
+        Ok(result)
+    }
+
+    // Write the records queued by the connection until there are none left.
+    // `sending` is released while holding the connection, so the records
+    // queued meanwhile by a reader are either written here or by the reader.
+    fn write_records(&self, sending: MutexGuard<'_, ()>) -> io::Result<()> {
+        loop {
+            let mut connection = lock(&self.connection)?;
+            if !connection.wants_write() {
+                drop(sending);
+                return Ok(());
+            }
+
+            let mut records = Vec::new();
+            while connection.wants_write() {
+                connection.write_tls(&mut records)?;
+            }
+            drop(connection);
+
+            (&*self.socket).write_all(&records)?;
+        }
+    }
+
+    // Process the bytes given to the connection, then write the records
+    // queued in answer, such as alerts or key updates,
+    // unless a writer is sending, which then writes them.
+    fn process(&self, mut connection: MutexGuard<'_, Connection>) -> io::Result<()> {
+        let result = connection
+            .process_new_packets()
+            .map(drop)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+
+        if connection.wants_write() {
+            if let Ok(sending) = self.sending.try_lock() {
+                drop(connection);
+                self.write_records(sending)?;
+            }
+        }
+
+        result
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> io::Result<MutexGuard<'_, T>> {
+    mutex.lock().map_err(|e| io::Error::other(e.to_string()))
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut received = lock(&self.received)?;
+
+        loop {
+            let mut connection = lock(&self.connection)?;
+
+            match connection.reader().read(buf) {
+                // Ok(0) once the peer has sent its close_notify
+                Ok(size) => return Ok(size),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+
+            if received.is_empty() {
+                // Wait for the socket without blocking the writers
+                drop(connection);
+                received.resize(RECEIVED_SIZE, 0);
+                let size = match (&*self.socket).read(&mut received) {
+                    Ok(size) => size,
+                    Err(e) => {
+                        received.clear();
+                        return Err(e);
+                    }
+                };
+                received.truncate(size);
+                connection = lock(&self.connection)?;
+
+                if size == 0 {
+                    // The end of the socket, with or without close_notify
+                    connection.read_tls(&mut io::empty())?;
+                    self.process(connection)?;
+                    continue;
+                }
+            }
+
+            // The connection may only take part of the bytes,
+            // until its plaintext has been read
+            let mut bytes: &[u8] = &received;
+            let size = connection.read_tls(&mut bytes)?;
+            received.drain(..size);
+            self.process(connection)?;
+        }
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.send(|connection| connection.writer().write(buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send(|connection| connection.writer().flush())?;
+        (&*self.socket).flush()
+    }
+}
@@ -1,12 +1,45 @@
-//! This module contains the macros for
+//! This module contains the macros and the functions for
 //! choosing a specific branch
 //! for a UDP connection.
 //!
 //! *This module is available only if MultiCrusty is built with
 //! the `"transport"` feature or the `"transport_udp"` feature.*
 
+use crate::transport::wire::frame::{encode, Frame};
+use std::boxed::Box;
+use std::error::Error;
+use std::net::UdpSocket;
+
+/// Send `label`, the name of the chosen variant, as a label frame of the wire
+/// format over the UDP socket, if `udp`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_udp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_udp")))
+)]
+pub fn send_udp_label(socket: &UdpSocket, label: &str, udp: bool) -> Result<(), Box<dyn Error>> {
+    if udp {
+        // A datagram holds a whole frame
+        socket.send(&encode(&Frame::label("", "", label))?)?;
+    }
+    Ok(())
+}
+
 /// Choose between many different sessions wrapped in an
-/// `enum`
+/// `enum`.
+///
+/// With a UDP socket and whether it is used, the name of the chosen variant
+/// is also sent over it with [`send_udp_label`], and the result is either
+/// the continuation of the session or the error of the UDP socket,
+/// in which case the session is cancelled.
+///
+/// # Example
+///
+/// ```ignore
+/// let s = choose_udp!(BinaryA::More, s, [0_u8; 128], &socket, true)?;
+/// ```
 ///
 /// *This macro is available only if MultiCrusty is built with
 /// the `"transport"` feature or the `"transport_udp"` feature.*
@@ -23,4 +56,18 @@ macro_rules! choose_udp {
         mpstthree::binary::cancel::cancel($data);
         here
     }};
+    ($label:path, $session:expr, $data:expr, $socket:expr, $udp:expr) => {{
+        let s = $session;
+        match mpstthree::transport::udp::choose::send_udp_label(
+            $socket,
+            mpstthree::instrument::branch_label(stringify!($label)),
+            $udp,
+        ) {
+            Ok(()) => Ok(mpstthree::choose_udp!($label, s, $data)),
+            Err(e) => {
+                mpstthree::binary::cancel::cancel(s);
+                Err(e)
+            }
+        }
+    }};
 }
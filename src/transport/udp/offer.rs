@@ -1,13 +1,52 @@
-//! This module contains the macros for
+//! This module contains the macros and the functions for
 //! receiving a choice
 //! for a UDP connection.
 //!
 //! *This module is available only if MultiCrusty is built with
 //! the `"transport"` feature or the `"transport_udp"` feature.*
 
+use crate::transport::wire::frame::decode;
+use std::boxed::Box;
+use std::error::Error;
+use std::net::UdpSocket;
+
+/// Receive the label of a branch from a label frame of the wire format
+/// over the UDP socket, or `None` without `udp`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_udp"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_udp")))
+)]
+pub fn recv_udp_label(socket: &UdpSocket, udp: bool) -> Result<Option<String>, Box<dyn Error>> {
+    if !udp {
+        return Ok(None);
+    }
+
+    // A datagram holds a whole frame
+    let mut datagram = [0_u8; 1024];
+    let size = socket.recv(&mut datagram)?;
+    let (frame, _size) = decode(&datagram[..size])?;
+    Ok(Some(frame.as_label()?.to_string()))
+}
+
 /// Offer a choice between many different sessions wrapped
 /// in an `enum`.
 ///
+/// With a UDP socket and whether it is used, the label of the branch
+/// is first received from it with [`recv_udp_label`], and must be the name
+/// of the variant received on the session.
+///
+/// # Example
+///
+/// ```ignore
+/// offer_udp!(s, &socket, true, {
+///     BinaryA::Done(s) => close_udp(s, socket, true),
+///     BinaryA::More(s) => { ... },
+/// })
+/// ```
+///
 /// *This macro is available only if MultiCrusty is built with
 /// the `"transport"` feature or the `"transport_udp"` feature.*
 #[macro_export]
@@ -30,4 +69,23 @@ macro_rules! offer_udp {
             }
         })()
     };
+    ($session: expr, $socket: expr, $udp: expr, { $( $pat: pat => $result: expr , )+ }) => {{
+        let label = mpstthree::transport::udp::offer::recv_udp_label($socket, $udp);
+        (move || -> Result<_, _> {
+            let label = label?;
+            let ((data, cont), s) = mpstthree::binary::recv::recv($session)?;
+            mpstthree::binary::cancel::cancel(s);
+            mpstthree::binary::cancel::cancel(data);
+
+            match cont {
+                $(
+                    $pat => {
+                        mpstthree::transport::wire::frame::check_label(&label, stringify!($pat))?;
+                        $result
+                    },
+                )+
+                _ => panic!("Unexpected payload") ,
+            }
+        })()
+    }};
 }
@@ -6,6 +6,7 @@
 //! the `"transport"` feature or the `"transport_udp"` feature.*
 
 use crate::binary::struct_trait::{recv::Recv, session::Session};
+use crate::transport::wire::frame::{data_from, decode};
 use std::boxed::Box;
 use std::error::Error;
 use std::marker;
@@ -37,7 +38,13 @@ where
     let (v, s) = s.channel.recv()?;
     let mut data = [0_u8; 128];
     let r = match udp {
-        true => socket.recv(&mut data)?,
+        true => {
+            // A datagram holds a whole frame
+            let mut datagram = [0_u8; 1024];
+            let size = socket.recv(&mut datagram)?;
+            let (frame, _size) = decode(&datagram[..size])?;
+            data_from(&frame, &mut data)?
+        }
         false => 0_usize,
     };
     Ok((v.0, s, data, r, socket))
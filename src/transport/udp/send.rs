@@ -6,6 +6,7 @@
 //! the `"transport"` feature or the `"transport_udp"` feature.*
 
 use crate::binary::struct_trait::{send::Send, session::Session};
+use crate::transport::wire::frame::{encode, Frame};
 use std::boxed::Box;
 use std::error::Error;
 use std::marker;
@@ -17,6 +18,11 @@ type UdpData = [u8; 128];
 /// Send a value of type `T` over UDP. Returns the
 /// continuation of the session `S` and the UdpSocket. May fail.
 ///
+/// With `udp`, `data` is sent in a payload frame of the wire format,
+/// in a single datagram, and the returned size is the length of `data`,
+/// not the number of bytes of the datagram, which also holds the header
+/// of the frame. Without `udp`, the returned size is `0`.
+///
 /// *This function is available only if MultiCrusty is built with
 /// the `"transport"` feature or the `"transport_udp"` feature.*
 #[cfg_attr(
@@ -38,8 +44,9 @@ where
     match s.channel.send(((x, *data), there)) {
         Ok(()) => match udp {
             true => {
-                let result = socket.send(data)?;
-                Ok((here, result, socket))
+                // A datagram holds a whole frame
+                socket.send(&encode(&Frame::payload("", "", data.to_vec()))?)?;
+                Ok((here, data.len(), socket))
             }
             false => Ok((here, 0, socket)),
         },
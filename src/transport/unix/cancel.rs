@@ -0,0 +1,45 @@
+//! This module contains the functions
+//! for cancelling binary sessions and
+//! shutdown the related UnixStream.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_unix"` feature.*
+
+use std::mem;
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+
+/// Cancels a session. Always succeeds. If the partner calls
+/// `send`, `recv` or `close` after cancellation,
+/// those calls fail. Used for Unix domain socket transport.
+///
+/// Drops the session *s* and shutdowns the `UnixStream` *stream*,
+/// even if the peer has already closed it.
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::binary::struct_trait::end::End;
+/// use mpstthree::binary::struct_trait::session::Session;
+/// use mpstthree::meshedchannels::MeshedChannels;
+/// use mpstthree::role::a::RoleA;
+/// use mpstthree::role::end::RoleEnd;
+/// use mpstthree::transport::unix::cancel::cancel_unix;
+/// use std::os::unix::net::UnixStream;
+///
+/// let (s, _s_dual) = MeshedChannels::<End, End, RoleEnd, RoleA<RoleEnd>>::new();
+/// let (stream, _other) = UnixStream::pair().unwrap();
+/// cancel_unix(s, stream);
+/// ```
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn cancel_unix<T>(s: T, stream: UnixStream) {
+    mem::drop(s);
+    stream.shutdown(Shutdown::Both).unwrap_or(());
+    mem::drop(stream);
+}
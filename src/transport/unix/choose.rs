@@ -0,0 +1,76 @@
+//! This module contains the macros and the functions for
+//! choosing a specific branch
+//! for a Unix domain socket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_unix"` feature.*
+
+use crate::transport::wire::frame::write_label;
+use std::boxed::Box;
+use std::error::Error;
+use std::os::unix::net::UnixStream;
+
+/// Send `label`, the name of the chosen variant, as a label frame of the wire
+/// format over the Unix stream, if `unix`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn send_unix_label(
+    stream: &mut UnixStream,
+    label: &str,
+    unix: bool,
+) -> Result<(), Box<dyn Error>> {
+    if unix {
+        write_label(stream, label)?;
+    }
+    Ok(())
+}
+
+/// Choose between many different sessions wrapped in an
+/// `enum`.
+///
+/// With a Unix stream and whether it is used, the name of the chosen variant
+/// is also sent over it with [`send_unix_label`], and the result is either
+/// the continuation of the session or the error of the Unix stream,
+/// in which case the session is cancelled.
+///
+/// # Example
+///
+/// ```ignore
+/// let s = choose_unix!(BinaryA::More, s, [0_u8; 128], &mut stream, true)?;
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+macro_rules! choose_unix {
+    ($label:path, $session:expr, $data:expr) => {{
+        let (here, there) = <_ as mpstthree::binary::struct_trait::session::Session>::new();
+        let s = mpstthree::binary::send::send(($data, $label(there)), $session);
+        mpstthree::binary::cancel::cancel(s);
+        mpstthree::binary::cancel::cancel($data);
+        here
+    }};
+    ($label:path, $session:expr, $data:expr, $stream:expr, $unix:expr) => {{
+        let s = $session;
+        match mpstthree::transport::unix::choose::send_unix_label(
+            $stream,
+            mpstthree::instrument::branch_label(stringify!($label)),
+            $unix,
+        ) {
+            Ok(()) => Ok(mpstthree::choose_unix!($label, s, $data)),
+            Err(e) => {
+                mpstthree::binary::cancel::cancel(s);
+                Err(e)
+            }
+        }
+    }};
+}
@@ -0,0 +1,35 @@
+//! This module contains the functions for closing
+//! binary sessions and
+//! shutdown the related UnixStream.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_unix"` feature.*
+
+use crate::binary::struct_trait::{end::End, end::Signal};
+use std::boxed::Box;
+use std::error::Error;
+use std::mem;
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+
+/// Closes a Unix domain socket session. Synchronises with the partner, and
+/// fails if the partner has crashed.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn close_unix(s: End, stream: UnixStream, unix: bool) -> Result<(), Box<dyn Error>> {
+    s.sender.send(Signal::Stop)?;
+    s.receiver.recv()?;
+    match unix {
+        true => {
+            stream.shutdown(Shutdown::Both).unwrap_or(()); // Stop any operation on stream. Cannot fail as stream may already been stopped.
+            mem::drop(stream); // close stream
+            Ok(())
+        }
+        false => Ok(()),
+    }
+}
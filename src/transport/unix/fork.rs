@@ -0,0 +1,187 @@
+//! This module contains the functions for
+//! forking binary sessions and
+//! the related UnixStream.
+//!
+//! A role either connects to another role already listening
+//! on a socket path, with [`fork_unix`], or spawns the process
+//! of the other role and waits for it on a socket path,
+//! with [`fork_unix_spawn`].
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_unix"` feature.*
+
+use crate::binary::struct_trait::session::Session;
+use std::boxed::Box;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::marker;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::panic;
+use std::path::Path;
+use std::process::{Child, Command};
+use std::thread::{sleep, Builder, JoinHandle};
+use std::time::{Duration, Instant};
+
+type UnixFork<T> = Result<(JoinHandle<()>, T, UnixStream), Box<dyn Error>>;
+
+type UnixSpawn<T> = Result<(JoinHandle<()>, T, UnixStream, Child), Box<dyn Error>>;
+
+/// The environment variable holding the socket path
+/// given to the processes spawned by [`fork_unix_spawn`].
+///
+/// *This constant is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub const SOCKET_PATH_ENV: &str = "MPST_UNIX_SOCKET";
+
+fn spawn_thread<S, P>(
+    p: P,
+    name: String,
+    there: S,
+    stream: UnixStream,
+) -> io::Result<JoinHandle<()>>
+where
+    S: Session + 'static,
+    P: FnOnce(S, UnixStream) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+{
+    Builder::new()
+        .name(name)
+        .stack_size(64 * 1024 * 1024)
+        .spawn(move || {
+            panic::set_hook(Box::new(|_info| {
+                // do nothing
+            }));
+            match p(there, stream) {
+                Ok(()) => (),
+                Err(e) => panic!("{}", e.to_string()),
+            }
+        })
+}
+
+/// Creates a child process, and a session with two dual
+/// endpoints of type `S` and `S::Dual`. The first endpoint
+/// is given to the child process. Returns the
+/// second endpoint.
+///
+/// The stream is connected to the role listening on `path`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn fork_unix<S, P, A>(p: P, path: A) -> UnixFork<S::Dual>
+where
+    S: Session + 'static,
+    P: FnOnce(S, UnixStream) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+    A: AsRef<Path>,
+{
+    let stream = UnixStream::connect(path.as_ref())?;
+    let copy_stream = stream.try_clone()?;
+    let (there, here) = Session::new();
+    let other_thread = spawn_thread(p, path.as_ref().display().to_string(), there, copy_stream)?;
+    Ok((other_thread, here, stream))
+}
+
+/// Creates a child process, and a session with two dual
+/// endpoints of type `S` and `S::Dual`, as [`fork_unix`].
+///
+/// Listens on `path`, replacing any socket left there, then spawns
+/// `command`, which is given `path` in the [`SOCKET_PATH_ENV`] environment
+/// variable, and waits for its connection.
+/// Also returns the spawned process, which should be waited for.
+/// Fails if `path` exists and is not a socket, if the process exits
+/// before connecting, or if it does not connect within `timeout`,
+/// in which case the process is killed.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn fork_unix_spawn<S, P, A>(
+    p: P,
+    path: A,
+    command: &mut Command,
+    timeout: Duration,
+) -> UnixSpawn<S::Dual>
+where
+    S: Session + 'static,
+    P: FnOnce(S, UnixStream) -> Result<(), Box<dyn Error>> + marker::Send + 'static,
+    A: AsRef<Path>,
+{
+    let path = path.as_ref();
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(path)?,
+        Ok(_) => return Err(format!("{} is not a socket", path.display()).into()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+        Err(e) => return Err(e.into()),
+    }
+
+    let listener = UnixListener::bind(path)?;
+    listener.set_nonblocking(true)?;
+
+    let mut child = command.env(SOCKET_PATH_ENV, path).spawn()?;
+    let start = Instant::now();
+
+    let stream = loop {
+        match listener.accept() {
+            Ok((stream, _addr)) => break stream,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                if let Some(status) = child.try_wait()? {
+                    fs::remove_file(path).unwrap_or(());
+                    return Err(format!("The role exited before connecting: {}", status).into());
+                }
+                if start.elapsed() >= timeout {
+                    child.kill().unwrap_or(());
+                    child.wait().unwrap_or_default();
+                    fs::remove_file(path).unwrap_or(());
+                    return Err(format!(
+                        "The role did not connect within {} ms",
+                        timeout.as_millis()
+                    )
+                    .into());
+                }
+                sleep(Duration::from_millis(10));
+            }
+            Err(e) => {
+                child.kill().unwrap_or(());
+                child.wait().unwrap_or_default();
+                fs::remove_file(path).unwrap_or(());
+                return Err(e.into());
+            }
+        }
+    };
+    stream.set_nonblocking(false)?;
+
+    // The connection is established, no other role is expected
+    fs::remove_file(path).unwrap_or(());
+
+    let copy_stream = stream.try_clone()?;
+    let (there, here) = Session::new();
+    let other_thread = spawn_thread(p, path.display().to_string(), there, copy_stream)?;
+    Ok((other_thread, here, stream, child))
+}
+
+/// Connects a process spawned by [`fork_unix_spawn`]
+/// to the role which spawned it.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn connect_spawner() -> Result<UnixStream, Box<dyn Error>> {
+    let path =
+        env::var_os(SOCKET_PATH_ENV).ok_or_else(|| format!("{} is not set", SOCKET_PATH_ENV))?;
+    Ok(UnixStream::connect(path)?)
+}
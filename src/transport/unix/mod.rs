@@ -0,0 +1,50 @@
+#![cfg(all(feature = "transport_unix", unix))]
+
+//! This module contains the functions
+//! and macros for handling a Unix domain socket,
+//! for the roles running in different processes of the same host.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_unix"` feature.*
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub mod choose;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub mod offer;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub mod recv;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub mod send;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub mod close;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub mod cancel;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub mod fork;
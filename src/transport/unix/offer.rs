@@ -0,0 +1,90 @@
+//! This module contains the macros and the functions for
+//! receiving a choice
+//! for a Unix domain socket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_unix"` feature.*
+
+use crate::transport::wire::frame::read_label;
+use std::boxed::Box;
+use std::error::Error;
+use std::os::unix::net::UnixStream;
+
+/// Receive the label of a branch from a label frame of the wire format
+/// over the Unix stream, or `None` without `unix`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn recv_unix_label(
+    stream: &mut UnixStream,
+    unix: bool,
+) -> Result<Option<String>, Box<dyn Error>> {
+    if !unix {
+        return Ok(None);
+    }
+
+    Ok(Some(read_label(stream)?))
+}
+
+/// Offer a choice between many different sessions wrapped
+/// in an `enum`.
+///
+/// With a Unix stream and whether it is used, the label of the branch
+/// is first received from it with [`recv_unix_label`], and must be the name
+/// of the variant received on the session.
+///
+/// # Example
+///
+/// ```ignore
+/// offer_unix!(s, &mut stream, true, {
+///     BinaryA::Done(s) => close_unix(s, stream, true),
+///     BinaryA::More(s) => { ... },
+/// })
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+macro_rules! offer_unix {
+    ($session: expr, { $( $pat: pat => $result: expr , )+ }) => {
+        (move || -> Result<_, _> {
+            let ((data, cont), s) = mpstthree::binary::recv::recv($session)?;
+            mpstthree::binary::cancel::cancel(s);
+            mpstthree::binary::cancel::cancel(data);
+
+            match cont {
+                $(
+                    $pat => $result,
+                )+
+                _ => panic!("Unexpected payload") ,
+            }
+        })()
+    };
+    ($session: expr, $stream: expr, $unix: expr, { $( $pat: pat => $result: expr , )+ }) => {{
+        let label = mpstthree::transport::unix::offer::recv_unix_label($stream, $unix);
+        (move || -> Result<_, _> {
+            let label = label?;
+            let ((data, cont), s) = mpstthree::binary::recv::recv($session)?;
+            mpstthree::binary::cancel::cancel(s);
+            mpstthree::binary::cancel::cancel(data);
+
+            match cont {
+                $(
+                    $pat => {
+                        mpstthree::transport::wire::frame::check_label(&label, stringify!($pat))?;
+                        $result
+                    },
+                )+
+                _ => panic!("Unexpected payload") ,
+            }
+        })()
+    }};
+}
@@ -0,0 +1,50 @@
+//! This module contains the functions for
+//! receiving a payload
+//! for a Unix domain socket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_unix"` feature.*
+
+use crate::binary::struct_trait::{recv::Recv, session::Session};
+use crate::transport::wire::frame::read_data;
+use std::boxed::Box;
+use std::error::Error;
+use std::marker;
+use std::os::unix::net::UnixStream;
+
+type UnixData = [u8; 128];
+
+type TupleRecv<T, S> = (T, S, UnixData, usize, UnixStream);
+
+/// Receive a value of type `T`. Can fail. Returns either a
+/// pair of the received value and the continuation of the
+/// session `S` or an error.
+///
+/// With `unix`, the data is read from a payload frame of the wire format,
+/// and its length is returned. If the peer has closed the connection before
+/// sending a whole frame, the error is an [`std::io::Error`] of kind
+/// [`UnexpectedEof`](std::io::ErrorKind::UnexpectedEof).
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn recv_unix<T, S>(
+    s: Recv<(T, UnixData), S>,
+    mut stream: UnixStream,
+    unix: bool,
+) -> Result<TupleRecv<T, S>, Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    let (v, s) = s.channel.recv()?;
+    let mut data = [0_u8; 128];
+    let r = match unix {
+        true => read_data(&mut stream, &mut data)?,
+        false => 0_usize,
+    };
+    Ok((v.0, s, data, r, stream))
+}
@@ -0,0 +1,50 @@
+//! This module contains the functions for
+//! sending a payload
+//! for a Unix domain socket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_unix"` feature.*
+
+use crate::binary::struct_trait::{send::Send, session::Session};
+use crate::transport::wire::frame::write_data;
+use std::boxed::Box;
+use std::error::Error;
+use std::marker;
+use std::os::unix::net::UnixStream;
+use std::panic;
+
+type UnixData = [u8; 128];
+
+/// Send a value of type `T` over a Unix domain socket. Returns the
+/// continuation of the session `S` and the continuation
+/// of the UnixStream. May fail.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_unix"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_unix")))
+)]
+pub fn send_unix<T, S>(
+    x: T, // Need to force x and data to be of the same type every time but for choice/offer
+    data: &UnixData,
+    s: Send<(T, UnixData), S>,
+    mut stream: UnixStream,
+    unix: bool,
+) -> Result<(S, UnixStream), Box<dyn Error>>
+where
+    T: marker::Send,
+    S: Session,
+{
+    let (here, there) = S::new();
+    match s.channel.send(((x, *data), there)) {
+        Ok(()) => match unix {
+            true => {
+                write_data(&mut stream, data)?;
+                Ok((here, stream))
+            }
+            false => Ok((here, stream)),
+        },
+        Err(e) => panic!("{}", e.to_string()),
+    }
+}
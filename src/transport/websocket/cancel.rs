@@ -0,0 +1,27 @@
+//! This module contains the functions
+//! for cancelling sessions and
+//! the related WebSocket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+use crate::transport::websocket::stream::{abort, WsStream};
+use std::mem;
+
+/// Cancels a session. Always succeeds. If the partner calls
+/// `send`, `recv` or `close` after cancellation,
+/// those calls fail. Used for WebSocket transport.
+///
+/// Drops the session *s* and the connection of *ws* without
+/// its close frames, which the peer sees as an abnormal closure.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn cancel_ws<T>(s: T, ws: WsStream) {
+    mem::drop(s);
+    abort(ws);
+}
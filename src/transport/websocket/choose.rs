@@ -0,0 +1,50 @@
+//! This module contains the functions for
+//! choosing a specific branch
+//! over a WebSocket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+use crate::transport::websocket::stream::{WsStream, ABNORMAL_CLOSURE};
+use crate::transport::wire::frame::{self as wire, encode};
+use std::boxed::Box;
+use std::error::Error;
+use tungstenite::Message;
+
+/// Send `label`, the name of the chosen variant, as a label frame of the wire
+/// format in a binary frame of `ws`,
+/// then choose with `choose` on the session `s`, such as
+/// a function created by `create_fn_choose_mpst_multi_to_all_bundle!`.
+/// Returns the continuation of the session.
+///
+/// If the frame cannot be sent, the session is cancelled.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn choose_ws<M, N, F>(
+    s: M,
+    choose: F,
+    label: &str,
+    ws: &mut WsStream,
+    websocket: bool,
+) -> Result<N, Box<dyn Error>>
+where
+    F: FnOnce(M) -> N,
+{
+    if websocket {
+        if let Err(e) = ws.send(Message::Binary(encode(&wire::Frame::label("", "", label))?)) {
+            drop(s);
+            return match e {
+                tungstenite::Error::Io(_)
+                | tungstenite::Error::ConnectionClosed
+                | tungstenite::Error::AlreadyClosed => Err(ABNORMAL_CLOSURE.into()),
+                e => Err(e.into()),
+            };
+        }
+    }
+    Ok(choose(s))
+}
@@ -0,0 +1,59 @@
+//! This module contains the functions for closing
+//! sessions and
+//! the related WebSocket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+use crate::transport::websocket::stream::{abort, WsStream, ABNORMAL_CLOSURE};
+use std::boxed::Box;
+use std::error::Error;
+use tungstenite::error::ProtocolError;
+
+/// Closes a session with `close`, such as `mpstthree::binary::close::close`
+/// or a function created by `close_mpst!`, then exchanges the
+/// close frames of `ws`.
+/// Synchronises with the partner, and fails if the partner has crashed
+/// or if the connection is lost before the close frames.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn close_ws<M, F>(
+    s: M,
+    close: F,
+    mut ws: WsStream,
+    websocket: bool,
+) -> Result<(), Box<dyn Error>>
+where
+    F: FnOnce(M) -> Result<(), Box<dyn Error>>,
+{
+    if let Err(e) = close(s) {
+        abort(ws);
+        return Err(e);
+    }
+
+    if !websocket {
+        return Ok(());
+    }
+
+    match ws.close(None) {
+        Ok(()) | Err(tungstenite::Error::ConnectionClosed) => (),
+        Err(_) => return Err(ABNORMAL_CLOSURE.into()),
+    }
+
+    // Wait for the close frame of the peer
+    loop {
+        match ws.read() {
+            Ok(_) => (),
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(()),
+            Err(tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake))
+            | Err(tungstenite::Error::Io(_))
+            | Err(tungstenite::Error::AlreadyClosed) => return Err(ABNORMAL_CLOSURE.into()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "transport_websocket")]
+
+//! This module contains the functions
+//! and macros for handling a WebSocket,
+//! for instance with a participant running in a browser.
+//!
+//! Each payload, encoded by [`payload::WsPayload`], and each branch label,
+//! the name of the chosen variant, is sent as a frame of the
+//! [wire format](crate::transport::wire) in a binary frame of the WebSocket;
+//! receiving a text frame is an error.
+//! Closing a session exchanges the close frames of the WebSocket,
+//! and a connection lost without them cancels the session.
+//!
+//! The *send*, *recv* and *close* functions wrap the ones of the
+//! in-memory sessions, binary or multiparty,
+//! such as `mpstthree::binary::send::send` or the functions
+//! created by `create_send_mpst_session!`.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod stream;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod payload;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod choose;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod offer;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod recv;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod send;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod close;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub mod cancel;
@@ -0,0 +1,102 @@
+//! This module contains the macros and the functions for
+//! receiving a choice
+//! over a WebSocket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+use crate::transport::websocket::stream::{read_frame, Frame, WsStream};
+use crate::transport::wire::frame::check_label;
+use std::boxed::Box;
+use std::error::Error;
+
+/// Receive the label of a branch from a binary frame of `ws`,
+/// holding a label frame of the wire format, or `None` without WebSocket.
+/// A text frame is rejected.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn recv_ws_label(ws: &mut WsStream, websocket: bool) -> Result<Option<String>, Box<dyn Error>> {
+    if !websocket {
+        return Ok(None);
+    }
+
+    match read_frame(ws)? {
+        Frame::Label(label) => Ok(Some(label)),
+        Frame::Payload(_) => Err("Expected a label, received a payload".into()),
+        Frame::Close => Err("The WebSocket was closed before the end of the session".into()),
+    }
+}
+
+/// Check that the received `label` is the one of the branch `pattern`,
+/// such as `Branching::Auth(s)`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn check_ws_label(label: &Option<String>, pattern: &str) -> Result<(), Box<dyn Error>> {
+    check_label(label, pattern)
+}
+
+/// Offer a choice between many different sessions wrapped
+/// in an `enum`, whose label is also received from a WebSocket.
+///
+/// # Arguments
+///
+/// * The session to be used
+/// * The *recv* function that will be used
+/// * The WebSocket
+/// * Whether the WebSocket is used
+/// * Each path, which are each variant of the enum which contains the new branches
+/// * The block of code to process each new session
+///
+/// # Example
+///
+/// ```ignore
+/// offer_ws_mpst!(s, recv_mpst_c_from_a, &mut ws, true, {
+///     Branching0fromAtoC::Auth(s) => {
+///         let (token, s) = recv_ws(s, recv_mpst_c_from_a, &mut ws, true)?;
+///         close_ws(s, close_mpst_multi, ws, true)
+///     },
+///     Branching0fromAtoC::Done(s) => {
+///         close_ws(s, close_mpst_multi, ws, true)
+///     },
+/// })
+/// ```
+///
+/// *This macro is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[macro_export]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+macro_rules! offer_ws_mpst {
+    ($session: expr, $recv_mpst: expr, $ws: expr, $websocket: expr, { $( $pat: pat => $result: expr, )+ }) => {{
+        let label = mpstthree::transport::websocket::offer::recv_ws_label($ws, $websocket);
+        (move || -> Result<_, _> {
+            let label = label?;
+            let (l, s) = $recv_mpst($session)?;
+            mpstthree::binary::cancel::cancel(s);
+            match l {
+                $(
+                    $pat => {
+                        mpstthree::transport::websocket::offer::check_ws_label(
+                            &label,
+                            stringify!($pat),
+                        )?;
+                        $result
+                    },
+                )+
+                _ => panic!("Unexpected payload") ,
+            }
+        })()
+    }};
+}
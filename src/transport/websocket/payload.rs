@@ -0,0 +1,16 @@
+//! This module contains the encoding of the payloads
+//! in the binary frames of a WebSocket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+/// A payload sent in the binary frames of a WebSocket,
+/// encoded as in the [wire format](crate::transport::wire).
+///
+/// *This trait is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub use crate::transport::wire::payload::Payload as WsPayload;
@@ -0,0 +1,60 @@
+//! This module contains the functions for
+//! receiving a payload
+//! over a WebSocket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+use crate::transport::websocket::payload::WsPayload;
+use crate::transport::websocket::stream::{read_frame, Frame, WsStream};
+use std::boxed::Box;
+use std::error::Error;
+
+/// Receive a value of type `T` from a binary frame of `ws`,
+/// and with `recv` from the session `s`, such as
+/// `mpstthree::binary::recv::recv` or a function created
+/// by `create_recv_mpst_session!`.
+/// Returns the decoded value and the continuation of the session.
+///
+/// If the connection is lost, or closed by the peer, before
+/// the payload is received, the session is cancelled.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn recv_ws<T, M, N, F>(
+    s: M,
+    recv: F,
+    ws: &mut WsStream,
+    websocket: bool,
+) -> Result<(T, N), Box<dyn Error>>
+where
+    T: WsPayload,
+    F: FnOnce(M) -> Result<(T, N), Box<dyn Error>>,
+{
+    if !websocket {
+        return recv(s);
+    }
+
+    match read_frame(ws) {
+        Ok(Frame::Payload(frame)) => {
+            let (_x, s) = recv(s)?;
+            Ok((T::decode(&frame)?, s))
+        }
+        Ok(Frame::Label(label)) => {
+            drop(s);
+            Err(format!("Expected a payload, received the label {}", label).into())
+        }
+        Ok(Frame::Close) => {
+            drop(s);
+            Err("The WebSocket was closed before the end of the session".into())
+        }
+        Err(e) => {
+            drop(s);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! This module contains the functions for
+//! sending a payload
+//! over a WebSocket.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+use crate::transport::websocket::payload::WsPayload;
+use crate::transport::websocket::stream::{WsStream, ABNORMAL_CLOSURE};
+use crate::transport::wire::frame::{self as wire, encode};
+use std::boxed::Box;
+use std::error::Error;
+use tungstenite::Message;
+
+/// Send a value of type `T` in a binary frame of `ws`,
+/// then with `send` on the session `s`, such as
+/// `mpstthree::binary::send::send` or a function created
+/// by `create_send_mpst_session!`.
+/// Returns the continuation of the session.
+///
+/// If the frame cannot be sent, the session is cancelled.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn send_ws<T, M, N, F>(
+    x: T,
+    s: M,
+    send: F,
+    ws: &mut WsStream,
+    websocket: bool,
+) -> Result<N, Box<dyn Error>>
+where
+    T: WsPayload,
+    F: FnOnce(T, M) -> N,
+{
+    if websocket {
+        if let Err(e) = ws.send(Message::Binary(encode(&wire::Frame::payload(
+            "",
+            "",
+            x.encode()?,
+        ))?)) {
+            drop(s);
+            return match e {
+                tungstenite::Error::Io(_)
+                | tungstenite::Error::ConnectionClosed
+                | tungstenite::Error::AlreadyClosed => Err(ABNORMAL_CLOSURE.into()),
+                e => Err(e.into()),
+            };
+        }
+    }
+    Ok(send(x, s))
+}
@@ -0,0 +1,99 @@
+//! This module contains the WebSocket
+//! carrying the payloads and the branch labels.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature or the `"transport_websocket"` feature.*
+
+use crate::transport::wire::frame::{self as wire, decode, Kind};
+use std::boxed::Box;
+use std::error::Error;
+use std::net::{Shutdown, TcpStream};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::error::ProtocolError;
+use tungstenite::{Message, WebSocket};
+
+/// The `tungstenite` crate, for configuring the WebSockets.
+pub use tungstenite;
+
+/// A WebSocket over a TCP connection,
+/// on the client side or on the server side.
+///
+/// *This type is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub type WsStream = WebSocket<TcpStream>;
+
+/// Open a WebSocket to `url`, such as `ws://localhost:3000/role`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn connect_ws(url: &str) -> Result<WsStream, Box<dyn Error>> {
+    let request = url.into_client_request()?;
+    let host = request.uri().host().ok_or("The URL has no host")?;
+    let port = request.uri().port_u16().unwrap_or(80);
+    let stream = TcpStream::connect((host, port))?;
+    let (ws, _response) = tungstenite::client(request, stream).map_err(|e| e.to_string())?;
+    Ok(ws)
+}
+
+/// Run the server side of the WebSocket handshake
+/// on an accepted `stream`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or the `"transport_websocket"` feature.*
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(feature = "transport", feature = "transport_websocket")))
+)]
+pub fn accept_ws(stream: TcpStream) -> Result<WsStream, Box<dyn Error>> {
+    Ok(tungstenite::accept(stream).map_err(|e| e.to_string())?)
+}
+
+/// What a WebSocket carries for a session.
+#[derive(Debug)]
+pub(crate) enum Frame {
+    Payload(Vec<u8>),
+    Label(String),
+    Close,
+}
+
+/// The error returned when the connection is lost
+/// without the close frames.
+pub(crate) const ABNORMAL_CLOSURE: &str =
+    "The WebSocket was closed abnormally, the session is cancelled";
+
+/// Read the next frame, skipping the pings and the pongs.
+pub(crate) fn read_frame(ws: &mut WsStream) -> Result<Frame, Box<dyn Error>> {
+    loop {
+        match ws.read() {
+            Ok(Message::Binary(bytes)) => return from_wire(decode(&bytes)?.0),
+            Ok(Message::Text(_)) => return Err("Expected a frame of the wire format".into()),
+            Ok(Message::Close(_)) => return Ok(Frame::Close),
+            Ok(_) => (),
+            Err(tungstenite::Error::ConnectionClosed) => return Ok(Frame::Close),
+            Err(tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake))
+            | Err(tungstenite::Error::Io(_))
+            | Err(tungstenite::Error::AlreadyClosed) => return Err(ABNORMAL_CLOSURE.into()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn from_wire(frame: wire::Frame) -> Result<Frame, Box<dyn Error>> {
+    match frame.kind {
+        Kind::Payload => Ok(Frame::Payload(frame.body)),
+        Kind::Label => Ok(Frame::Label(frame.as_label()?.to_string())),
+    }
+}
+
+/// Drop the connection without the close frames.
+pub(crate) fn abort(ws: WsStream) {
+    ws.get_ref().shutdown(Shutdown::Both).unwrap_or(());
+}
@@ -0,0 +1,298 @@
+//! This module contains the frames of the wire format,
+//! with their encoder and their decoder.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature, the `"transport_tcp"` feature,
+//! the `"transport_udp"` feature, the `"transport_unix"` feature
+//! or the `"transport_websocket"` feature.*
+
+use crate::instrument::branch_label;
+use crate::role::Role;
+use std::boxed::Box;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::io::{self, Read, Write};
+
+const MAGIC: [u8; 2] = *b"MP";
+
+const VERSION: u8 = 1;
+
+/// The largest body of a frame, 16 MiB.
+///
+/// *This constant is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+pub const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// What a frame carries.
+///
+/// *This enum is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A payload, encoded as a [`Payload`](crate::transport::wire::payload::Payload).
+    Payload,
+    /// The label of a chosen branch.
+    Label,
+}
+
+impl Kind {
+    fn byte(self) -> u8 {
+        match self {
+            Kind::Payload => 0,
+            Kind::Label => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for Kind {
+    type Error = Box<dyn Error>;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        match byte {
+            0 => Ok(Kind::Payload),
+            1 => Ok(Kind::Label),
+            byte => Err(format!("Unknown kind of frame: {}", byte).into()),
+        }
+    }
+}
+
+/// A frame of the wire format.
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::transport::wire::frame::{decode, encode, Frame};
+///
+/// let frame = Frame::label("A", "B", "More");
+/// let bytes = encode(&frame).unwrap();
+/// assert_eq!(&bytes[..4], b"MP\x01\x01");
+/// assert_eq!(decode(&bytes).unwrap(), (frame, bytes.len()));
+/// ```
+///
+/// *This struct is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// What the frame carries.
+    pub kind: Kind,
+    /// The role sending the frame, empty between two roles only.
+    pub from: String,
+    /// The role receiving the frame, empty between two roles only.
+    pub to: String,
+    /// The encoded payload, or the label.
+    pub body: Vec<u8>,
+}
+
+impl Frame {
+    /// A frame carrying an encoded payload.
+    pub fn payload(from: &str, to: &str, body: Vec<u8>) -> Self {
+        Frame {
+            kind: Kind::Payload,
+            from: from.to_string(),
+            to: to.to_string(),
+            body,
+        }
+    }
+
+    /// A frame carrying the label of a branch.
+    pub fn label(from: &str, to: &str, label: &str) -> Self {
+        Frame {
+            kind: Kind::Label,
+            from: from.to_string(),
+            to: to.to_string(),
+            body: label.as_bytes().to_vec(),
+        }
+    }
+
+    /// The label carried by the frame.
+    pub fn as_label(&self) -> Result<&str, Box<dyn Error>> {
+        match self.kind {
+            Kind::Label => Ok(std::str::from_utf8(&self.body)?),
+            Kind::Payload => Err("Expected a label, received a payload".into()),
+        }
+    }
+
+    /// The encoded payload carried by the frame.
+    pub fn as_payload(&self) -> Result<&[u8], Box<dyn Error>> {
+        match self.kind {
+            Kind::Payload => Ok(&self.body),
+            Kind::Label => Err(format!(
+                "Expected a payload, received the label {}",
+                String::from_utf8_lossy(&self.body)
+            )
+            .into()),
+        }
+    }
+}
+
+/// The identifier of the role `R` on the wire,
+/// which is its name without `Role`, such as `A` for `RoleA<RoleEnd>`.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+pub fn role_id<R: Role>() -> String {
+    let name = R::head_str();
+    match name.strip_prefix("Role") {
+        Some(id) if !id.is_empty() => id.to_string(),
+        _ => name,
+    }
+}
+
+/// Encode `frame`.
+/// Fails if a role is longer than 255 bytes,
+/// or if the body is longer than [`MAX_BODY`].
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+pub fn encode(frame: &Frame) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut bytes = Vec::with_capacity(10 + frame.from.len() + frame.to.len() + frame.body.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(VERSION);
+    bytes.push(frame.kind.byte());
+    for role in [&frame.from, &frame.to] {
+        let length = u8::try_from(role.len())
+            .map_err(|_| format!("The role {} is longer than 255 bytes", role))?;
+        bytes.push(length);
+        bytes.extend_from_slice(role.as_bytes());
+    }
+    if frame.body.len() > MAX_BODY {
+        return Err(format!("The body of {} bytes is too long", frame.body.len()).into());
+    }
+    bytes.extend_from_slice(&(frame.body.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(&frame.body);
+    Ok(bytes)
+}
+
+/// Decode the frame at the start of `bytes`.
+/// Returns the frame and the number of bytes it takes.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+pub fn decode(bytes: &[u8]) -> Result<(Frame, usize), Box<dyn Error>> {
+    let mut reader = bytes;
+    let frame = read_frame(&mut reader).map_err(|e| match e.downcast_ref::<io::Error>() {
+        Some(io) if io.kind() == io::ErrorKind::UnexpectedEof => "The frame is incomplete".into(),
+        _ => e,
+    })?;
+    Ok((frame, bytes.len() - reader.len()))
+}
+
+/// Read the next frame from `reader`.
+///
+/// Fails with an [`io::ErrorKind::UnexpectedEof`] error
+/// if the stream ends before the frame.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+pub fn read_frame<R: Read>(reader: &mut R) -> Result<Frame, Box<dyn Error>> {
+    let mut header = [0_u8; 4];
+    reader.read_exact(&mut header)?;
+    if header[..2] != MAGIC {
+        return Err("Not a frame of the wire format".into());
+    }
+    if header[2] != VERSION {
+        return Err(format!("Unsupported version of the wire format: {}", header[2]).into());
+    }
+    let kind = Kind::try_from(header[3])?;
+
+    let from = read_role(reader)?;
+    let to = read_role(reader)?;
+
+    let mut length = [0_u8; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_BODY {
+        return Err(format!("The body of {} bytes is too long", length).into());
+    }
+    let mut body = vec![0_u8; length];
+    reader.read_exact(&mut body)?;
+
+    let frame = Frame {
+        kind,
+        from,
+        to,
+        body,
+    };
+    if kind == Kind::Label {
+        frame.as_label()?;
+    }
+    Ok(frame)
+}
+
+fn read_role<R: Read>(reader: &mut R) -> Result<String, Box<dyn Error>> {
+    let mut length = [0_u8; 1];
+    reader.read_exact(&mut length)?;
+    let mut role = vec![0_u8; length[0] as usize];
+    reader.read_exact(&mut role)?;
+    Ok(String::from_utf8(role)?)
+}
+
+/// Write `frame` to `writer`, in a single write.
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+pub fn write_frame<W: Write>(writer: &mut W, frame: &Frame) -> Result<(), Box<dyn Error>> {
+    writer.write_all(&encode(frame)?)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Check that the received `label`, if any, is the one of the branch `pattern`,
+/// such as `Branching::More(s)`, as given by [`branch_label`].
+///
+/// *This function is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+pub fn check_label(label: &Option<String>, pattern: &str) -> Result<(), Box<dyn Error>> {
+    match label {
+        Some(label) if label != branch_label(pattern) => Err(format!(
+            "The label {} does not match the branch {}",
+            label,
+            branch_label(pattern)
+        )
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Write the label of a branch of a binary session, in a label frame.
+#[cfg(any(feature = "transport_tcp", feature = "transport_unix"))]
+pub(crate) fn write_label<W: Write>(writer: &mut W, label: &str) -> Result<(), Box<dyn Error>> {
+    write_frame(writer, &Frame::label("", "", label))
+}
+
+/// Read the label of a branch of a binary session, from a label frame.
+#[cfg(any(feature = "transport_tcp", feature = "transport_unix"))]
+pub(crate) fn read_label<R: Read>(reader: &mut R) -> Result<String, Box<dyn Error>> {
+    Ok(read_frame(reader)?.as_label()?.to_string())
+}
+
+/// Write the data of a binary session, in a payload frame.
+#[cfg(any(feature = "transport_tcp", feature = "transport_unix"))]
+pub(crate) fn write_data<W: Write>(writer: &mut W, data: &[u8]) -> Result<(), Box<dyn Error>> {
+    write_frame(writer, &Frame::payload("", "", data.to_vec()))
+}
+
+/// Read the data of a binary session, from a payload frame,
+/// and return its length.
+#[cfg(any(feature = "transport_tcp", feature = "transport_unix"))]
+pub(crate) fn read_data<R: Read>(
+    reader: &mut R,
+    data: &mut [u8; 128],
+) -> Result<usize, Box<dyn Error>> {
+    data_from(&read_frame(reader)?, data)
+}
+
+/// Copy the data of a payload frame, and return its length.
+#[cfg(any(
+    feature = "transport_tcp",
+    feature = "transport_udp",
+    feature = "transport_unix"
+))]
+pub(crate) fn data_from(frame: &Frame, data: &mut [u8; 128]) -> Result<usize, Box<dyn Error>> {
+    let body = frame.as_payload()?;
+    if body.len() > data.len() {
+        return Err(format!("The payload of {} bytes is too long", body.len()).into());
+    }
+    data[..body.len()].copy_from_slice(body);
+    Ok(body.len())
+}
@@ -0,0 +1,113 @@
+#![cfg(any(
+    feature = "transport_tcp",
+    feature = "transport_udp",
+    feature = "transport_unix",
+    feature = "transport_websocket"
+))]
+
+//! The wire format of the network transports, so that
+//! the processes of a protocol can be written in other languages.
+//!
+//! # Frames
+//!
+//! Each message is sent in a frame, all integers being big-endian:
+//!
+//! | Offset      | Size | Content                                       |
+//! |-------------|------|-----------------------------------------------|
+//! | 0           | 2    | The magic bytes `0x4D 0x50`, that is `"MP"`   |
+//! | 2           | 1    | The version of the format, `1`                |
+//! | 3           | 1    | The [`Kind`](frame::Kind): `0` for a payload, `1` for a label |
+//! | 4           | 1    | The length `n` of the sender                  |
+//! | 5           | n    | The sender, in UTF-8                          |
+//! | 5 + n       | 1    | The length `m` of the receiver                |
+//! | 6 + n       | m    | The receiver, in UTF-8                        |
+//! | 6 + n + m   | 4    | The length `l` of the body, at most [`MAX_BODY`](frame::MAX_BODY) |
+//! | 10 + n + m  | l    | The body                                      |
+//!
+//! Over a stream, such as TCP, TLS or a Unix domain socket, the frames follow each other.
+//! Over UDP, each datagram holds one frame,
+//! and over a WebSocket, each binary message holds one frame.
+//! The closing and the cancellation of a session are those of the
+//! connection: a TCP connection shutdown,
+//! or the close frames of a WebSocket.
+//!
+//! # Roles
+//!
+//! A role is identified by its name in the protocol,
+//! such as `A` for `RoleA`, as given by [`role_id`](frame::role_id).
+//! The sender and the receiver are empty when the connection
+//! links exactly two roles, as in the binary sessions.
+//!
+//! # Labels
+//!
+//! The body of a label is the name of the chosen variant
+//! of the `Branching*` enum, in UTF-8, such as `More` for
+//! `Branching0fromAtoB::More`, as given by
+//! [`branch_label`](crate::instrument::branch_label).
+//! It is sent by the choosing role, before the payloads of the branch,
+//! by `choose_tcp!`, `choose_udp!` and `choose_unix!` when given a connection,
+//! and by `choose_ws`.
+//!
+//! # Payloads
+//!
+//! The body of a payload is its [`Payload`](payload::Payload) encoding:
+//! the booleans and the numbers in decimal text, such as `true`, `-42` or `1.5`,
+//! the strings in UTF-8, `()` as an empty body,
+//! and the bytes as they are.
+//!
+//! # Resumable connections
+//!
+//! The resumable connections of `transport::tcp::resume` start,
+//! and start again after each failure, with the line `RESUME <session> <received>\n`
+//! of each side, the connecting side first, where `<received>` is the number
+//! of payloads received from the peer so far.
+//! A side receiving another line answers `ERROR <reason>\n` and closes the connection.
+//!
+//! The payload frames are then wrapped in envelopes, all integers being big-endian:
+//!
+//! | Offset | Size | Content                                                      |
+//! |--------|------|--------------------------------------------------------------|
+//! | 0      | 1    | The kind: `0` for a payload, `1` for an acknowledgement, `2` for the closing, `3` for a request of acknowledgement |
+//! | 1      | 8    | The sequence number of the payload, or of the next payload   |
+//! | 9      | 8    | The number of payloads received from the peer, which acknowledges them |
+//! | 17     |      | For a payload only, its payload frame                        |
+//!
+//! A request of acknowledgement is answered by an acknowledgement.
+//! After a resumption, each side sends again, in order, the payloads
+//! not acknowledged by the number received from the peer.
+//!
+//! # Conformance
+//!
+//! The file `tests/wire_mod/vectors.txt` holds encoded frames,
+//! the bytes written to a stream by the choice of a branch,
+//! invalid frames and encoded payloads, which an implementation
+//! in another language should encode and decode in the same way.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature, the `"transport_tcp"` feature,
+//! the `"transport_udp"` feature, the `"transport_unix"` feature
+//! or the `"transport_websocket"` feature.*
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(
+        feature = "transport",
+        feature = "transport_tcp",
+        feature = "transport_udp",
+        feature = "transport_unix",
+        feature = "transport_websocket"
+    )))
+)]
+pub mod frame;
+
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(any(
+        feature = "transport",
+        feature = "transport_tcp",
+        feature = "transport_udp",
+        feature = "transport_unix",
+        feature = "transport_websocket"
+    )))
+)]
+pub mod payload;
@@ -0,0 +1,105 @@
+//! This module contains the encoding of the payloads
+//! in the body of the frames.
+//!
+//! *This module is available only if MultiCrusty is built with
+//! the `"transport"` feature, the `"transport_tcp"` feature,
+//! the `"transport_udp"` feature, the `"transport_unix"` feature
+//! or the `"transport_websocket"` feature.*
+
+use std::boxed::Box;
+use std::error::Error;
+
+/// A payload encoded in the body of a frame.
+///
+/// The primitive types and `String` are encoded as text,
+/// which is also easy to decode in other languages,
+/// and the bytes are sent as they are.
+///
+/// # Example
+///
+/// ```
+/// use mpstthree::transport::wire::payload::Payload;
+/// use std::error::Error;
+///
+/// struct Point(i32, i32);
+///
+/// impl Payload for Point {
+///     fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+///         Ok(format!("{},{}", self.0, self.1).into_bytes())
+///     }
+///
+///     fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>> {
+///         let (x, y) = std::str::from_utf8(body)?
+///             .split_once(',')
+///             .ok_or("Not a point")?;
+///         Ok(Point(x.parse()?, y.parse()?))
+///     }
+/// }
+///
+/// let body = Point(1, 2).encode().unwrap();
+/// let point = Point::decode(&body).unwrap();
+/// assert_eq!((point.0, point.1), (1, 2));
+/// ```
+///
+/// *This trait is available only if MultiCrusty is built with
+/// the `"transport"` feature or one of the network transport features.*
+pub trait Payload: Sized {
+    /// The body of the frame.
+    fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>>;
+
+    /// The payload read from the body of a frame.
+    fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>>;
+}
+
+macro_rules! impl_payload_text {
+    ($( $t: ty ),+) => {
+        $(
+            impl Payload for $t {
+                fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+                    Ok(self.to_string().into_bytes())
+                }
+
+                fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>> {
+                    Ok(std::str::from_utf8(body)?.parse::<$t>()?)
+                }
+            }
+        )+
+    };
+}
+
+impl_payload_text!(bool, i8, i16, i32, i64, u8, u16, u32, u64, usize, f32, f64, String);
+
+impl Payload for () {
+    fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+
+    fn decode(_body: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+impl Payload for Vec<u8> {
+    fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(self.clone())
+    }
+
+    fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(body.to_vec())
+    }
+}
+
+impl Payload for [u8; 128] {
+    fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut data = [0_u8; 128];
+        if body.len() != data.len() {
+            return Err(format!("Expected 128 bytes, received {}", body.len()).into());
+        }
+        data.copy_from_slice(body);
+        Ok(data)
+    }
+}
@@ -1,4 +1,4 @@
-#![allow(clippy::type_complexity)]
+#![allow(clippy::type_complexity, clippy::redundant_closure_call)]
 
 mod baking_mod;
 
@@ -94,3 +94,25 @@ fn macro_baking() {
     // Macro multi choice with macro of macro directly in the baking_mod generation
     baking_mod::macros_baking::macro_multi_recursion_short::new_run_usecase_recursive();
 }
+
+// Tests for the checking of protocols with cancellation
+#[test]
+fn cancel_checking() {
+    baking_mod::cancel_checking::cancel_run();
+    baking_mod::cancel_checking::cancel_checker();
+    baking_mod::cancel_checking::paired::cancel_checker_paired();
+    baking_mod::cancel_checking::cancel_checker_blocked();
+}
+
+#[test]
+fn send_to_all() {
+    baking_mod::send_to_all::send_to_all();
+    baking_mod::send_to_all::send_to_all_checker();
+    baking_mod::send_to_all::basic::send_to_all_basic();
+}
+
+// The payloads are required to be 'static only by the bundles baked with `recorded`
+#[test]
+fn borrowed_payloads() {
+    baking_mod::borrowed_payloads::borrowed_payloads();
+}
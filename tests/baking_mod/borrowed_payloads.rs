@@ -0,0 +1,51 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::bundle_impl_with_enum_and_cancel;
+use mpstthree::role::end::RoleEnd;
+
+use std::error::Error;
+
+// Create new roles
+bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, B, C);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+
+// A sends a borrowed payload to B, which passes it on to C
+type EndpointA<'a> = MeshedChannelsThree<Send<&'a str, End>, End, RoleB<RoleEnd>, NameA>;
+type EndpointB<'a> =
+    MeshedChannelsThree<Recv<&'a str, End>, Send<&'a str, End>, RoleA<RoleC<RoleEnd>>, NameB>;
+type EndpointC<'a> = MeshedChannelsThree<End, Recv<&'a str, End>, RoleB<RoleEnd>, NameC>;
+
+// The payloads do not need to be 'static
+fn endpoint_a<'a>(s: EndpointA<'a>, payload: &'a str) -> Result<(), Box<dyn Error>> {
+    let s = s.send(payload)?;
+    s.close()
+}
+
+fn endpoint_b(s: EndpointB<'_>) -> Result<(), Box<dyn Error>> {
+    let (payload, s) = s.recv()?;
+    let s = s.send(payload)?;
+    s.close()
+}
+
+fn endpoint_c(s: EndpointC<'_>) -> Result<(), Box<dyn Error>> {
+    let (payload, s) = s.recv()?;
+    assert_eq!(payload, "Borrowed");
+    s.close()
+}
+
+/////////////////////////////////////////
+
+pub fn borrowed_payloads() {
+    let (thread_a, thread_b, thread_c) = fork_mpst(
+        |s: EndpointA<'static>| endpoint_a(s, "Borrowed"),
+        endpoint_b,
+        endpoint_c,
+    );
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_b.join().is_ok());
+    assert!(thread_c.join().is_ok());
+}
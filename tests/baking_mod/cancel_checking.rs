@@ -0,0 +1,262 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::bundle_impl_with_enum_and_cancel;
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+
+use mpstthree::checker_concat;
+use mpstthree::checking::cancel::{cancellation_product, check_cancellation};
+
+use std::error::Error;
+
+// Create new roles
+bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, B, C);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+
+// Types
+// A
+enum Branching0fromCtoA {
+    More(
+        MeshedChannelsThree<
+            End,
+            Recv<(), Send<(), RecursAtoC>>,
+            RoleC<RoleC<RoleC<RoleEnd>>>,
+            NameA,
+        >,
+    ),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameA>),
+}
+type RecursAtoC = Recv<Branching0fromCtoA, End>;
+// B
+enum Branching0fromCtoB {
+    More(MeshedChannelsThree<End, Recv<(), RecursBtoC>, RoleC<RoleC<RoleEnd>>, NameB>),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameB>),
+}
+type RecursBtoC = Recv<Branching0fromCtoB, End>;
+// C
+type Choose0fromCtoA = Send<Branching0fromCtoA, End>;
+type Choose0fromCtoB = Send<Branching0fromCtoB, End>;
+type EndpointDoneC = MeshedChannelsThree<End, End, RoleEnd, NameC>;
+type EndpointMoreC = MeshedChannelsThree<
+    Send<(), Recv<(), Choose0fromCtoA>>,
+    Send<(), Choose0fromCtoB>,
+    RoleA<RoleA<RoleB<RoleBroadcast>>>,
+    NameC,
+>;
+
+// Creating the MP sessions
+type EndpointA = MeshedChannelsThree<End, RecursAtoC, RoleC<RoleEnd>, NameA>;
+type EndpointB = MeshedChannelsThree<End, RecursBtoC, RoleC<RoleEnd>, NameB>;
+type EndpointC = MeshedChannelsThree<Choose0fromCtoA, Choose0fromCtoB, RoleBroadcast, NameC>;
+
+// The same protocol, where the choices are sent along with a session used for cancelling
+pub mod paired {
+    use super::{MeshedChannelsThree, NameA, NameB, NameC, RoleA, RoleB, RoleC};
+
+    use mpstthree::checker_concat;
+    use mpstthree::checking::cancel::check_cancellation;
+
+    use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+    use mpstthree::role::broadcast::RoleBroadcast;
+    use mpstthree::role::end::RoleEnd;
+
+    // A
+    enum Branching0fromCtoA {
+        More(
+            MeshedChannelsThree<
+                End,
+                Recv<(), Send<(), RecursAtoC>>,
+                RoleC<RoleC<RoleC<RoleEnd>>>,
+                NameA,
+            >,
+        ),
+        Done(MeshedChannelsThree<End, End, RoleEnd, NameA>),
+    }
+    type RecursAtoC = Recv<(End, Branching0fromCtoA), End>;
+    // B
+    enum Branching0fromCtoB {
+        More(MeshedChannelsThree<End, Recv<(), RecursBtoC>, RoleC<RoleC<RoleEnd>>, NameB>),
+        Done(MeshedChannelsThree<End, End, RoleEnd, NameB>),
+    }
+    type RecursBtoC = Recv<(End, Branching0fromCtoB), End>;
+    // C
+    type Choose0fromCtoA = Send<(End, Branching0fromCtoA), End>;
+    type Choose0fromCtoB = Send<(End, Branching0fromCtoB), End>;
+    type EndpointDoneC = MeshedChannelsThree<End, End, RoleEnd, NameC>;
+    type EndpointMoreC = MeshedChannelsThree<
+        Send<(), Recv<(), Choose0fromCtoA>>,
+        Send<(), Choose0fromCtoB>,
+        RoleA<RoleA<RoleB<RoleBroadcast>>>,
+        NameC,
+    >;
+
+    // Creating the MP sessions
+    type EndpointA = MeshedChannelsThree<End, RecursAtoC, RoleC<RoleEnd>, NameA>;
+    type EndpointB = MeshedChannelsThree<End, RecursBtoC, RoleC<RoleEnd>, NameB>;
+    type EndpointC = MeshedChannelsThree<Choose0fromCtoA, Choose0fromCtoB, RoleBroadcast, NameC>;
+
+    /////////////////////////////////////////
+
+    pub fn cancel_checker_paired() {
+        let (graphs, _) = checker_concat!(
+            EndpointA,
+            EndpointC,
+            EndpointB
+            =>
+            [
+                EndpointMoreC,
+                Branching0fromCtoA, More,
+                Branching0fromCtoB, More
+            ],
+            [
+                EndpointDoneC,
+                Branching0fromCtoA, Done,
+                Branching0fromCtoB, Done
+            ]
+        )
+        .unwrap();
+
+        let graph_a = &graphs["RoleA"];
+
+        let labels = graph_a
+            .edge_references()
+            .map(|edge| edge.weight().to_string())
+            .collect::<Vec<_>>();
+
+        // The choice is read through the pair
+        assert_eq!(labels, vec!["0", "RoleA?RoleC: ()", "RoleA!RoleC: ()", "µ"]);
+
+        assert_eq!(check_cancellation(&graphs), None);
+    }
+}
+
+// A protocol where A and B wait on each other
+type BlockedA = MeshedChannelsThree<Recv<(), End>, End, RoleB<RoleEnd>, NameA>;
+type BlockedB = MeshedChannelsThree<Recv<(), End>, End, RoleA<RoleEnd>, NameB>;
+type BlockedC = MeshedChannelsThree<End, End, RoleEnd, NameC>;
+
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromCtoA::Done(s) => {
+            s.close()
+        },
+        Branching0fromCtoA::More(s) => {
+            let (_, s) = s.recv()?;
+            let s = s.send(())?;
+            endpoint_a(s)
+        },
+    })
+}
+
+fn endpoint_b(s: EndpointB) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromCtoB::Done(s) => {
+            s.close()
+        },
+        Branching0fromCtoB::More(s) => {
+            let (_, s) = s.recv()?;
+            endpoint_b(s)
+        },
+    })
+}
+
+fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    let s: EndpointMoreC =
+        choose_mpst_c_to_all!(s, Branching0fromCtoA::More, Branching0fromCtoB::More);
+
+    let s = s.send(())?;
+    let (_, s) = s.recv()?;
+    let s = s.send(())?;
+
+    // Cancel instead of choosing the next branch
+    s.cancel();
+
+    Ok(())
+}
+
+/////////////////////////////////////////
+
+pub fn cancel_run() {
+    let (thread_a, thread_b, thread_c) = fork_mpst(endpoint_a, endpoint_b, endpoint_c);
+
+    // The cancellation of C reaches A and B
+    assert!(thread_a.join().is_err());
+    assert!(thread_b.join().is_err());
+    assert!(thread_c.join().is_ok());
+}
+
+pub fn cancel_checker() {
+    let (graphs, kmc) = checker_concat!(
+        EndpointA,
+        EndpointC,
+        EndpointB
+        =>
+        [
+            EndpointMoreC,
+            Branching0fromCtoA, More,
+            Branching0fromCtoB, More
+        ],
+        [
+            EndpointDoneC,
+            Branching0fromCtoA, Done,
+            Branching0fromCtoB, Done
+        ]
+    )
+    .unwrap();
+
+    assert_eq!(kmc, None);
+
+    // Every role either terminates or is cancelled
+    assert_eq!(check_cancellation(&graphs), None);
+
+    let product = cancellation_product(&graphs);
+
+    assert_eq!(
+        product[petgraph::graph::NodeIndex::new(0)],
+        "RoleA:0,RoleB:0,RoleC:0"
+    );
+
+    let labels = product
+        .edge_references()
+        .map(|edge| edge.weight().to_string())
+        .collect::<Vec<_>>();
+
+    for label in [
+        "RoleC->RoleA: ()",
+        "RoleA: cancel",
+        "RoleC: cancel",
+        "RoleA: cancelled by RoleC",
+        "RoleB: cancelled by RoleC",
+    ] {
+        assert!(labels.iter().any(|l| l == label), "Missing {}", label);
+    }
+
+    assert!(product
+        .node_indices()
+        .any(|node| product[node] == "RoleA:cancelled,RoleB:cancelled,RoleC:cancelled"));
+}
+
+pub fn cancel_checker_blocked() {
+    let (graphs, _) = checker_concat!(BlockedA, BlockedB, BlockedC).unwrap();
+
+    let deadlock = check_cancellation(&graphs).unwrap();
+
+    assert!(deadlock.trace.is_empty());
+    assert_eq!(
+        deadlock.blocked,
+        vec![
+            "RoleA waits for RoleA?RoleB: ()",
+            "RoleB waits for RoleB?RoleA: ()"
+        ]
+    );
+    assert_eq!(
+        deadlock.cycle,
+        vec![
+            "RoleA waits for RoleB on RoleA?RoleB: ()",
+            "RoleB waits for RoleA on RoleB?RoleA: ()"
+        ]
+    );
+}
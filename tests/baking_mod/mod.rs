@@ -1,3 +1,6 @@
+pub mod borrowed_payloads;
+pub mod cancel_checking;
 pub mod macros_baking;
+pub mod send_to_all;
 pub mod simple_baking;
 pub mod unit_meshedchannels;
@@ -0,0 +1,159 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::bundle_impl_with_enum_and_cancel;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::role::to_all::RoleToAll;
+
+use mpstthree::checker_concat;
+
+use std::error::Error;
+
+// Create new roles
+bundle_impl_with_enum_and_cancel!(MeshedChannelsFour, A, B, C, D);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+type NameD = RoleD<RoleEnd>;
+
+// A announces to all the other roles, which all reply
+type EndpointA = MeshedChannelsFour<
+    Send<String, Recv<usize, End>>,
+    Send<String, Recv<usize, End>>,
+    Send<String, Recv<usize, End>>,
+    RoleToAll<RoleB<RoleC<RoleD<RoleEnd>>>>,
+    NameA,
+>;
+type EndpointB =
+    MeshedChannelsFour<Recv<String, Send<usize, End>>, End, End, RoleA<RoleA<RoleEnd>>, NameB>;
+type EndpointC =
+    MeshedChannelsFour<Recv<String, Send<usize, End>>, End, End, RoleA<RoleA<RoleEnd>>, NameC>;
+type EndpointD =
+    MeshedChannelsFour<Recv<String, Send<usize, End>>, End, End, RoleA<RoleA<RoleEnd>>, NameD>;
+
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    let s = s.send_to_all(String::from("Announcement"))?;
+
+    let (b, s) = s.recv()?;
+    let (c, s) = s.recv()?;
+    let (d, s) = s.recv()?;
+
+    assert_eq!((b, c, d), (12, 12, 12));
+
+    s.close()
+}
+
+fn endpoint_b(s: EndpointB) -> Result<(), Box<dyn Error>> {
+    let (announcement, s) = s.recv()?;
+    let s = s.send(announcement.len())?;
+    s.close()
+}
+
+fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    let (announcement, s) = s.recv()?;
+    let s = s.send(announcement.len())?;
+    s.close()
+}
+
+fn endpoint_d(s: EndpointD) -> Result<(), Box<dyn Error>> {
+    let (announcement, s) = s.recv()?;
+    let s = s.send(announcement.len())?;
+    s.close()
+}
+
+// The same protocol, with `send` functions that can not fail
+pub mod basic {
+    use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+    use mpstthree::bundle_impl;
+    use mpstthree::role::end::RoleEnd;
+    use mpstthree::role::to_all::RoleToAll;
+
+    use std::error::Error;
+
+    // Create new roles
+    bundle_impl!(MeshedChannelsThree, A, B, C);
+
+    // B announces to A and C, then goes on with C
+    type EndpointA = MeshedChannelsThree<Recv<i32, End>, End, RoleB<RoleEnd>, RoleA<RoleEnd>>;
+    type EndpointB = MeshedChannelsThree<
+        Send<i32, End>,
+        Send<i32, Recv<i32, End>>,
+        RoleToAll<RoleC<RoleEnd>>,
+        RoleB<RoleEnd>,
+    >;
+    type EndpointC =
+        MeshedChannelsThree<End, Recv<i32, Send<i32, End>>, RoleB<RoleB<RoleEnd>>, RoleC<RoleEnd>>;
+
+    fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+        let (payload, s) = s.recv()?;
+        assert_eq!(payload, 7);
+        s.close()
+    }
+
+    fn endpoint_b(s: EndpointB) -> Result<(), Box<dyn Error>> {
+        let s = s.send_to_all(7);
+        let (payload, s) = s.recv()?;
+        assert_eq!(payload, 8);
+        s.close()
+    }
+
+    fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+        let (payload, s) = s.recv()?;
+        s.send(payload + 1).close()
+    }
+
+    pub fn send_to_all_basic() {
+        let (thread_a, thread_b, thread_c) = fork_mpst(endpoint_a, endpoint_b, endpoint_c);
+
+        assert!(thread_a.join().is_ok());
+        assert!(thread_b.join().is_ok());
+        assert!(thread_c.join().is_ok());
+    }
+}
+
+/////////////////////////////////////////
+
+pub fn send_to_all() {
+    let (thread_a, thread_b, thread_c, thread_d) =
+        fork_mpst(endpoint_a, endpoint_b, endpoint_c, endpoint_d);
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_b.join().is_ok());
+    assert!(thread_c.join().is_ok());
+    assert!(thread_d.join().is_ok());
+}
+
+pub fn send_to_all_checker() {
+    let (graphs, kmc) = checker_concat!(EndpointA, EndpointB, EndpointC, EndpointD).unwrap();
+
+    // The payload is sent to each other role, in order
+    let labels_a = graphs["RoleA"]
+        .edge_references()
+        .map(|edge| edge.weight().to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        labels_a,
+        vec![
+            "RoleA!RoleB: String",
+            "RoleA!RoleC: String",
+            "RoleA!RoleD: String",
+            "RoleA?RoleB: usize",
+            "RoleA?RoleC: usize",
+            "RoleA?RoleD: usize",
+            "0"
+        ]
+    );
+
+    let labels_c = graphs["RoleC"]
+        .edge_references()
+        .map(|edge| edge.weight().to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        labels_c,
+        vec!["RoleC?RoleA: String", "RoleC!RoleA: usize", "0"]
+    );
+
+    assert_eq!(kmc, None);
+}
@@ -1,4 +1,4 @@
-#![allow(clippy::type_complexity)]
+#![allow(clippy::type_complexity, clippy::redundant_closure_call)]
 
 mod binary_mod;
 
@@ -135,7 +135,7 @@ pub fn ping_works() {
 //
 // }()
 // .is_ok());
-/// ```
+// ```
 
 // Test a simple calculator server, implemented using binary
 // choice.
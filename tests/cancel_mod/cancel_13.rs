@@ -77,6 +77,8 @@ type R2B<R> = RoleB<RoleB<R>>;
 type R2C<R> = RoleC<RoleC<R>>;
 type R2D<R> = RoleD<RoleD<R>>;
 // B
+// Never offered, the offer of B is commented out below
+#[allow(dead_code)]
 enum Branching0fromDtoB {
     More(MeshedChannelsFour<End, SR<End>, RS<RecursBtoD>, R2D<R2C<RoleD<RoleEnd>>>, NameB>),
     Done(MeshedChannelsFour<End, End, End, RoleEnd, NameB>),
@@ -1,4 +1,4 @@
-#![allow(clippy::type_complexity)]
+#![allow(clippy::type_complexity, clippy::redundant_closure_call)]
 
 mod checking_mod;
 mod graph_mod;
@@ -6,6 +6,14 @@ mod graph_mod;
 #[test]
 fn graph_mod() {
     graph_mod::simple::simple_triple_endpoints();
+    graph_mod::export::global_graph();
+    graph_mod::export::formats();
+    graph_mod::export::files();
+    graph_mod::deadlock::cyclic_wait();
+    graph_mod::deadlock::shared_without_deadlock();
+    graph_mod::deadlock::buffered_sends();
+    graph_mod::temporal::properties();
+    graph_mod::temporal::formulas();
 }
 
 #[test]
@@ -0,0 +1,10 @@
+#![allow(clippy::type_complexity)]
+
+mod diagram_mod;
+
+#[test]
+fn diagrams() {
+    diagram_mod::accumulator::static_diagrams();
+    diagram_mod::accumulator::cancellation_diagrams();
+    diagram_mod::accumulator::recorded_diagrams();
+}
@@ -0,0 +1,239 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::bundle_impl_with_enum_and_cancel;
+use mpstthree::checker_concat;
+use mpstthree::diagram::Diagram;
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::testing::recording::{record, Recorder};
+
+use std::error::Error;
+
+// C sends numbers to A, which forwards their sum to B, until C cancels
+
+bundle_impl_with_enum_and_cancel!(recorded MeshedChannelsThree, A, B, C);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+
+// Types
+// A
+enum Branching0fromCtoA {
+    More(
+        MeshedChannelsThree<
+            Send<i32, End>,
+            Recv<i32, RecursAtoC>,
+            RoleC<RoleB<RoleC<RoleEnd>>>,
+            NameA,
+        >,
+    ),
+    Done(MeshedChannelsThree<End, Recv<(), End>, RoleC<RoleEnd>, NameA>),
+}
+type RecursAtoC = Recv<Branching0fromCtoA, End>;
+// B
+enum Branching0fromCtoB {
+    More(MeshedChannelsThree<Recv<i32, End>, RecursBtoC, RoleA<RoleC<RoleEnd>>, NameB>),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameB>),
+}
+type RecursBtoC = Recv<Branching0fromCtoB, End>;
+// C
+type Choose0fromCtoA = Send<Branching0fromCtoA, End>;
+type Choose0fromCtoB = Send<Branching0fromCtoB, End>;
+type EndpointDoneC = MeshedChannelsThree<Send<(), End>, End, RoleA<RoleEnd>, NameC>;
+type EndpointMoreC =
+    MeshedChannelsThree<Send<i32, Choose0fromCtoA>, Choose0fromCtoB, RoleA<RoleBroadcast>, NameC>;
+
+// Creating the MP sessions
+type EndpointA = MeshedChannelsThree<End, RecursAtoC, RoleC<RoleEnd>, NameA>;
+type EndpointB = MeshedChannelsThree<End, RecursBtoC, RoleC<RoleEnd>, NameB>;
+type EndpointC = MeshedChannelsThree<Choose0fromCtoA, Choose0fromCtoB, RoleBroadcast, NameC>;
+
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    recurs_a(s, 0)
+}
+
+fn recurs_a(s: EndpointA, sum: i32) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromCtoA::Done(s) => {
+            let (_, s) = s.recv()?;
+            s.close()
+        },
+        Branching0fromCtoA::More(s) => {
+            let (x, s) = s.recv()?;
+            let s = s.send(sum + x)?;
+            recurs_a(s, sum + x)
+        },
+    })
+}
+
+fn endpoint_b(s: EndpointB) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromCtoB::Done(s) => {
+            s.close()
+        },
+        Branching0fromCtoB::More(s) => {
+            let (_, s) = s.recv()?;
+            endpoint_b(s)
+        },
+    })
+}
+
+fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    let s: EndpointMoreC =
+        choose_mpst_c_to_all!(s, Branching0fromCtoA::More, Branching0fromCtoB::More);
+    let s = s.send(1)?;
+
+    let s: EndpointMoreC =
+        choose_mpst_c_to_all!(s, Branching0fromCtoA::More, Branching0fromCtoB::More);
+    let s = s.send(2)?;
+
+    s.cancel();
+
+    Ok(())
+}
+
+fn graphs() -> std::collections::HashMap<String, petgraph::Graph<String, String>> {
+    let (graphs, _) = checker_concat!(
+        EndpointA,
+        EndpointC,
+        EndpointB
+        =>
+        [
+            EndpointMoreC,
+            Branching0fromCtoA, More,
+            Branching0fromCtoB, More
+        ],
+        [
+            EndpointDoneC,
+            Branching0fromCtoA, Done,
+            Branching0fromCtoB, Done
+        ]
+    )
+    .unwrap();
+
+    graphs
+}
+
+/////////////////////////////////////////
+
+pub fn static_diagrams() {
+    let diagram = Diagram::from_graphs(&graphs());
+
+    assert_eq!(diagram.participants, vec!["RoleA", "RoleB", "RoleC"]);
+    assert_eq!(
+        diagram.to_mermaid(),
+        "sequenceDiagram
+    participant RoleA
+    participant RoleB
+    participant RoleC
+    loop Recursion
+        alt RoleC: i32
+            RoleC->>RoleA: i32
+            RoleA->>RoleB: i32
+        else RoleC: ()
+            RoleC->>RoleA: ()
+        end
+    end
+"
+    );
+    assert_eq!(
+        diagram.to_plantuml(),
+        "@startuml
+participant RoleA
+participant RoleB
+participant RoleC
+loop Recursion
+    alt RoleC: i32
+        RoleC -> RoleA : i32
+        RoleA -> RoleB : i32
+    else RoleC: ()
+        RoleC -> RoleA : ()
+    end
+end
+@enduml
+"
+    );
+}
+
+pub fn cancellation_diagrams() {
+    let diagram = Diagram::from_graphs_with_cancellation(&graphs());
+
+    assert_eq!(
+        diagram.to_mermaid(),
+        "sequenceDiagram
+    participant RoleA
+    participant RoleB
+    participant RoleC
+    critical Protocol
+        loop Recursion
+            alt RoleC: i32
+                RoleC->>RoleA: i32
+                RoleA->>RoleB: i32
+            else RoleC: ()
+                RoleC->>RoleA: ()
+            end
+        end
+    option RoleA cancels
+        RoleA-xRoleB: cancel
+        RoleA-xRoleC: cancel
+    option RoleB cancels
+        RoleB-xRoleA: cancel
+    option RoleC cancels
+        RoleC-xRoleA: cancel
+    end
+"
+    );
+
+    // PlantUML has no options in its critical blocks
+    let plantuml = diagram.to_plantuml();
+    assert!(plantuml.contains("alt Protocol\n    loop Recursion\n"));
+    assert!(plantuml.contains("else RoleC cancels\n    RoleC ->x RoleA : cancel\nend\n"));
+}
+
+pub fn recorded_diagrams() {
+    let recorder = Recorder::new().with_serializer(|x: &i32| x.to_string());
+
+    let (thread_a, thread_b, thread_c) = fork_mpst(
+        record(&recorder, endpoint_a),
+        record(&recorder, endpoint_b),
+        record(&recorder, endpoint_c),
+    );
+
+    assert!(thread_a.join().is_err());
+    assert!(thread_b.join().is_err());
+    assert!(thread_c.join().is_ok());
+
+    let diagram = Diagram::from_recording(&recorder.recording());
+
+    assert_eq!(diagram.participants, vec!["RoleA", "RoleB", "RoleC"]);
+
+    // Both choices of C are gathered in a loop, and the messages
+    // of A may be recorded before or after the cancellation of C
+    let mermaid = diagram.to_mermaid();
+    assert!(mermaid.starts_with(
+        "sequenceDiagram
+    participant RoleA
+    participant RoleB
+    participant RoleC
+    loop RoleC chooses 2 times
+        alt RoleC: More
+            RoleC->>RoleA: i32 = 1
+"
+    ));
+    assert_eq!(mermaid.matches("\n        alt RoleC: More\n").count(), 2);
+    assert!(mermaid.contains("\n            RoleC->>RoleA: i32 = 2\n"));
+    assert!(mermaid.contains(
+        "
+            break RoleC cancels
+                RoleC-xRoleA: cancel
+                RoleC-xRoleB: cancel
+            end
+"
+    ));
+
+    let plantuml = diagram.to_plantuml();
+    assert!(plantuml.contains("        RoleC -> RoleA : i32 = 2\n"));
+    assert!(plantuml.contains("        break RoleC cancels\n"));
+    assert!(plantuml.ends_with("    end\nend\n@enduml\n"));
+}
@@ -0,0 +1,18 @@
+mod dynamic_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn dynamic_pool() {
+    dynamic_mod::pool::join_and_leave();
+    dynamic_mod::pool::request_leave();
+    dynamic_mod::pool::failed_member();
+    dynamic_mod::pool::closed();
+}
+
+#[test]
+#[timeout(60000)]
+fn dynamic_session() {
+    dynamic_mod::session::workers();
+}
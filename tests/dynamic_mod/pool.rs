@@ -0,0 +1,189 @@
+use mpstthree::binary::close::close;
+use mpstthree::binary::recv::recv;
+use mpstthree::binary::send::send;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::dynamic::pool::{Pool, Step};
+use mpstthree::{choose, offer};
+
+use std::error::Error;
+use std::time::Duration;
+
+// The host dismisses the workers
+enum Command {
+    Work(Recv<u32, Send<u32, Worker>>),
+    Leave(End),
+}
+type Worker = Recv<Command, End>;
+type Host = Send<Command, End>;
+
+fn worker(s: Worker) -> Result<(), Box<dyn Error>> {
+    offer!(s, {
+        Command::Work(s) => {
+            let (task, s) = recv(s)?;
+            let s = send(task + 1, s);
+            worker(s)
+        },
+        Command::Leave(s) => {
+            close(s)
+        },
+    })
+}
+
+// The workers ask to leave
+enum Request {
+    Ready(Recv<u32, Send<u32, Server>>),
+    Leave(End),
+}
+type Client = Send<Request, End>;
+type Server = Recv<Request, End>;
+
+fn client(s: Client, tasks: u32) -> Result<(), Box<dyn Error>> {
+    if tasks == 0 {
+        let s = choose!(Request::Leave, s);
+        close(s)
+    } else {
+        let s = choose!(Request::Ready, s);
+        let s = send(tasks, s);
+        let (result, s) = recv(s)?;
+        assert_eq!(result, tasks * 2);
+        client(s, tasks - 1)
+    }
+}
+
+pub fn join_and_leave() {
+    let (mut pool, joiner) = Pool::<Host>::new();
+    assert!(pool.is_empty());
+
+    let threads: Vec<_> = (0..3).map(|_| joiner.fork(worker).unwrap().1).collect();
+
+    let members: Vec<_> = (0..3).map(|_| pool.accept().unwrap()).collect();
+    assert_eq!(pool.len(), 3);
+    assert_eq!(pool.members(), members);
+
+    // A participant joins while the others work
+    let (id, late) = joiner.join().unwrap();
+    let late = std::thread::spawn(move || worker(late).is_ok());
+
+    let mut results = Vec::new();
+    pool.step_all(|id, s| {
+        let s = choose!(Command::Work, s);
+        let s = send(id.index() as u32, s);
+        let (result, s) = recv(s)?;
+        results.push(result);
+        Ok(Step::Stay(s))
+    })
+    .unwrap();
+    assert_eq!(results, vec![1, 2, 3]);
+
+    assert!(!pool.contains(id));
+    assert_eq!(pool.accept_pending(), vec![id]);
+    assert!(pool.contains(id));
+    assert_eq!(id.to_string(), "member 3");
+
+    // The first member leaves, the others stay
+    pool.step(members[0], |s| Ok(Step::Leave(choose!(Command::Leave, s))))
+        .unwrap();
+    assert_eq!(pool.len(), 3);
+    assert_eq!(pool.left(), 1);
+    assert!(pool.step(members[0], |s| Ok(Step::Stay(s))).is_err());
+
+    pool.step_all(|_, s| Ok(Step::Leave(choose!(Command::Leave, s))))
+        .unwrap();
+    assert!(pool.is_empty());
+    assert_eq!(pool.left(), 4);
+    assert!(pool.close().is_ok());
+
+    for thread in threads {
+        assert!(thread.join().is_ok());
+    }
+    assert!(late.join().unwrap());
+}
+
+pub fn request_leave() {
+    let (mut pool, joiner) = Pool::<Server>::new();
+
+    let threads: Vec<_> = (1..=3)
+        .map(|tasks| joiner.fork(move |s| client(s, tasks)).unwrap().1)
+        .collect();
+    drop(joiner);
+
+    (0..3).for_each(|_| {
+        pool.accept().unwrap();
+    });
+
+    let mut results = Vec::new();
+    while !pool.is_empty() {
+        pool.step_all(|_, s| {
+            let results = &mut results;
+            offer!(s, {
+                Request::Ready(s) => {
+                    let (request, s) = recv(s)?;
+                    results.push(request);
+                    let s = send(request * 2, s);
+                    Ok(Step::Stay(s))
+                },
+                Request::Leave(s) => {
+                    Ok(Step::Leave(s))
+                },
+            })
+        })
+        .unwrap();
+    }
+
+    assert_eq!(results, vec![1, 2, 3, 1, 2, 1]);
+    assert_eq!(pool.left(), 3);
+
+    // All the joiners are dropped
+    assert!(pool.accept().is_err());
+
+    for thread in threads {
+        assert!(thread.join().is_ok());
+    }
+}
+
+pub fn failed_member() {
+    let (mut pool, joiner) = Pool::<Host>::new();
+
+    // The participant crashes right after joining
+    let (_, thread) = joiner
+        .fork(|s: Worker| {
+            drop(s);
+            Err("Crashed".into())
+        })
+        .unwrap();
+    let id = pool.accept().unwrap();
+    assert!(thread.join().is_err());
+
+    let error = pool
+        .step(id, |s| {
+            let s = choose!(Command::Work, s);
+            let s = send(0, s);
+            let (_, s) = recv(s)?;
+            Ok(Step::Stay(s))
+        })
+        .unwrap_err();
+    assert!(error.to_string().starts_with("The member 0 failed"));
+    assert!(pool.is_empty());
+    assert_eq!(pool.left(), 1);
+}
+
+pub fn closed() {
+    let (mut pool, joiner) = Pool::<Host>::new();
+
+    assert!(pool
+        .accept_timeout(Duration::from_millis(10))
+        .unwrap_err()
+        .to_string()
+        .contains("in time"));
+
+    let (_, s) = joiner.join().unwrap();
+    pool.accept().unwrap();
+
+    // A member is left
+    assert!(pool.close().is_err());
+    drop(s);
+
+    let (pool, joiner) = Pool::<Host>::new();
+    drop(pool);
+    assert!(joiner.join().is_err());
+}
@@ -0,0 +1,108 @@
+use mpstthree::binary::close::close;
+use mpstthree::binary::recv::recv;
+use mpstthree::binary::send::send;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::dynamic::joiner::Joiner;
+use mpstthree::dynamic::pool::{Pool, Step};
+use mpstthree::role::end::RoleEnd;
+use mpstthree::{bundle_impl_with_enum_and_cancel, choose, offer};
+
+use std::error::Error;
+use std::thread::spawn;
+
+// Create new roles
+bundle_impl_with_enum_and_cancel!(MeshedChannelsTwo, Client, Manager);
+
+// The fixed roles
+type EndpointClient = MeshedChannelsTwo<
+    Send<Vec<u32>, Recv<Vec<u32>, End>>,
+    RoleManager<RoleManager<RoleEnd>>,
+    RoleClient<RoleEnd>,
+>;
+type EndpointManager = MeshedChannelsTwo<
+    Recv<Vec<u32>, Send<Vec<u32>, End>>,
+    RoleClient<RoleClient<RoleEnd>>,
+    RoleManager<RoleEnd>,
+>;
+
+// The dynamic workers
+enum Command {
+    Work(Recv<u32, Send<u32, Worker>>),
+    Leave(End),
+}
+type Worker = Recv<Command, End>;
+type Host = Send<Command, End>;
+
+fn endpoint_client(s: EndpointClient) -> Result<(), Box<dyn Error>> {
+    let s = s.send((1..=10).collect())?;
+    let (results, s) = s.recv()?;
+    assert_eq!(
+        results,
+        (1..=10).map(|task| task * task).collect::<Vec<_>>()
+    );
+    s.close()
+}
+
+fn endpoint_manager(s: EndpointManager, mut pool: Pool<Host>) -> Result<(), Box<dyn Error>> {
+    let (tasks, s) = s.recv()?;
+
+    // At least one worker is needed
+    pool.accept()?;
+
+    let mut results = Vec::new();
+    for task in tasks {
+        // The workers which joined meanwhile take the next tasks
+        pool.accept_pending();
+
+        let members = pool.members();
+        let member = members[task as usize % members.len()];
+        pool.step(member, |s| {
+            let s = choose!(Command::Work, s);
+            let s = send(task, s);
+            let (result, s) = recv(s)?;
+            results.push(result);
+            Ok(Step::Stay(s))
+        })?;
+    }
+
+    pool.accept_pending();
+    pool.step_all(|_, s| Ok(Step::Leave(choose!(Command::Leave, s))))?;
+    pool.close()?;
+
+    let s = s.send(results)?;
+    s.close()
+}
+
+fn worker(s: Worker) -> Result<(), Box<dyn Error>> {
+    offer!(s, {
+        Command::Work(s) => {
+            let (task, s) = recv(s)?;
+            let s = send(task * task, s);
+            worker(s)
+        },
+        Command::Leave(s) => {
+            close(s)
+        },
+    })
+}
+
+pub fn workers() {
+    let (pool, joiner): (Pool<Host>, Joiner<Host>) = Pool::new();
+
+    let (thread_client, thread_manager) =
+        fork_mpst(endpoint_client, move |s| endpoint_manager(s, pool));
+
+    // The workers join while the session is running
+    let workers = spawn(move || {
+        (0..4)
+            .map(|_| joiner.fork(worker).unwrap().1)
+            .collect::<Vec<_>>()
+    });
+
+    assert!(thread_client.join().is_ok());
+    assert!(thread_manager.join().is_ok());
+
+    for thread in workers.join().unwrap() {
+        assert!(thread.join().is_ok());
+    }
+}
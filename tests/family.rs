@@ -0,0 +1,18 @@
+mod family_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn family_indexed() {
+    family_mod::indexed::family();
+    family_mod::indexed::members();
+}
+
+#[test]
+#[timeout(60000)]
+fn family_templates() {
+    family_mod::ring::ring();
+    family_mod::star::star();
+    family_mod::mesh::mesh();
+}
@@ -0,0 +1,38 @@
+use mpstthree::binary::struct_trait::end::End;
+use mpstthree::bundle_family;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::role::family::{Family, Indexed, Member};
+use mpstthree::role::Role;
+
+// Create new roles
+bundle_family!(MeshedChannelsSix, Leader, Worker[4], Monitor);
+
+type NameWorker0 = <Worker as Indexed<0>>::Name;
+type NameWorker3 = <Worker as Indexed<3>>::Name;
+
+// Receives from RoleLeader, then sends to RoleWorker1
+type StackWorker0 = RoleLeader<Member<Worker, 1, RoleEnd>>;
+
+type EndpointWorker0 = MeshedChannelsSix<End, End, End, End, End, RoleEnd, NameWorker0>;
+
+pub fn family() {
+    assert_eq!(Worker::NAME, "Worker");
+    assert_eq!(Worker::SIZE, 4);
+}
+
+pub fn members() {
+    assert_eq!(NameWorker0::head_str(), "RoleWorker0");
+    assert_eq!(NameWorker3::head_str(), "RoleWorker3");
+    assert_eq!(RoleWorker2::<RoleEnd>::head_str(), "RoleWorker2");
+
+    let stack: RoleLeader<RoleWorker1<RoleEnd>> = StackWorker0::new().0;
+    assert_eq!(stack.self_head_str(), "RoleLeader");
+    assert_eq!(stack.self_tail_str(), "RoleWorker1<RoleEnd<>>");
+
+    // The indexed roles are the roles of the bundle
+    let (s, _) = <EndpointWorker0 as mpstthree::binary::struct_trait::session::Session>::new();
+    assert_eq!(s.name.self_head_str(), "RoleWorker0");
+
+    // RoleMonitor is not part of the family
+    assert_eq!(RoleMonitor::<RoleEnd>::head_str(), "RoleMonitor");
+}
@@ -0,0 +1,77 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::role::end::RoleEnd;
+use mpstthree::{bundle_family, template_mesh};
+
+use std::error::Error;
+
+// Create new roles
+bundle_family!(MeshedChannelsFour, Worker[4]);
+
+// Each pair of roles exchanges a payload both ways
+template_mesh!(Mesh, MeshedChannelsFour, u8, Worker[4]);
+
+// The projection on RoleWorker2
+type ExpectedWorker2 = MeshedChannelsFour<
+    Recv<u8, Send<u8, End>>,
+    Recv<u8, Send<u8, End>>,
+    Send<u8, Recv<u8, End>>,
+    RoleWorker0<RoleWorker0<RoleWorker1<RoleWorker1<RoleWorker3<RoleWorker3<RoleEnd>>>>>>,
+    RoleWorker2<RoleEnd>,
+>;
+
+fn endpoint_worker_0(s: MeshWorker0) -> Result<(), Box<dyn Error>> {
+    let s = s.send(0);
+    let (_, s) = s.recv()?;
+    let s = s.send(0);
+    let (_, s) = s.recv()?;
+    let s = s.send(0);
+    let (_, s) = s.recv()?;
+    s.close()
+}
+
+fn endpoint_worker_1(s: MeshWorker1) -> Result<(), Box<dyn Error>> {
+    let (_, s) = s.recv()?;
+    let s = s.send(1);
+    let s = s.send(1);
+    let (_, s) = s.recv()?;
+    let s = s.send(1);
+    let (_, s) = s.recv()?;
+    s.close()
+}
+
+fn endpoint_worker_2(s: ExpectedWorker2) -> Result<(), Box<dyn Error>> {
+    let (_, s) = s.recv()?;
+    let s = s.send(2);
+    let (_, s) = s.recv()?;
+    let s = s.send(2);
+    let s = s.send(2);
+    let (_, s) = s.recv()?;
+    s.close()
+}
+
+fn endpoint_worker_3(s: MeshWorker3) -> Result<(), Box<dyn Error>> {
+    let (_, s) = s.recv()?;
+    let s = s.send(3);
+    let (_, s) = s.recv()?;
+    let s = s.send(3);
+    let (_, s) = s.recv()?;
+    let s = s.send(3);
+    s.close()
+}
+
+pub fn mesh() {
+    // The template and the projection are the same type
+    let endpoint_worker_2: fn(MeshWorker2) -> Result<(), Box<dyn Error>> = endpoint_worker_2;
+
+    let (thread_0, thread_1, thread_2, thread_3) = fork_mpst(
+        endpoint_worker_0,
+        endpoint_worker_1,
+        endpoint_worker_2,
+        endpoint_worker_3,
+    );
+
+    assert!(thread_0.join().is_ok());
+    assert!(thread_1.join().is_ok());
+    assert!(thread_2.join().is_ok());
+    assert!(thread_3.join().is_ok());
+}
@@ -0,0 +1,4 @@
+pub mod indexed;
+pub mod mesh;
+pub mod ring;
+pub mod star;
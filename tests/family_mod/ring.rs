@@ -0,0 +1,50 @@
+use mpstthree::{bundle_family_with_enum_and_cancel, template_ring};
+
+use std::error::Error;
+
+// Create new roles
+bundle_family_with_enum_and_cancel!(MeshedChannelsSix, Leader, Worker[5]);
+
+// Each role sends to the next one, RoleWorker4 back to RoleLeader
+template_ring!(Ring, MeshedChannelsSix, i64, Leader, Worker[5]);
+
+fn endpoint_leader(s: RingLeader) -> Result<(), Box<dyn Error>> {
+    let s = s.send(0)?;
+    let (payload, s) = s.recv()?;
+    assert_eq!(payload, 5);
+    s.close()
+}
+
+macro_rules! relay {
+    ($endpoint_name: ident, $endpoint_type: ty) => {
+        fn $endpoint_name(s: $endpoint_type) -> Result<(), Box<dyn Error>> {
+            let (payload, s) = s.recv()?;
+            let s = s.send(payload + 1)?;
+            s.close()
+        }
+    };
+}
+
+relay!(endpoint_worker_0, RingWorker0);
+relay!(endpoint_worker_1, RingWorker1);
+relay!(endpoint_worker_2, RingWorker2);
+relay!(endpoint_worker_3, RingWorker3);
+relay!(endpoint_worker_4, RingWorker4);
+
+pub fn ring() {
+    let (thread_leader, thread_0, thread_1, thread_2, thread_3, thread_4) = fork_mpst(
+        endpoint_leader,
+        endpoint_worker_0,
+        endpoint_worker_1,
+        endpoint_worker_2,
+        endpoint_worker_3,
+        endpoint_worker_4,
+    );
+
+    assert!(thread_leader.join().is_ok());
+    assert!(thread_0.join().is_ok());
+    assert!(thread_1.join().is_ok());
+    assert!(thread_2.join().is_ok());
+    assert!(thread_3.join().is_ok());
+    assert!(thread_4.join().is_ok());
+}
@@ -0,0 +1,59 @@
+use mpstthree::{bundle_family_with_cancel, template_star};
+
+use std::error::Error;
+
+// Create new roles
+bundle_family_with_cancel!(MeshedChannelsFive, Hub, Leaf[4]);
+
+// RoleHub sends to each leaf, then each leaf replies
+template_star!(Star, MeshedChannelsFive, String, Hub, Leaf[4]);
+
+fn endpoint_hub(s: StarHub) -> Result<(), Box<dyn Error>> {
+    let s = s.send(String::from("0"))?;
+    let s = s.send(String::from("1"))?;
+    let s = s.send(String::from("2"))?;
+    let s = s.send(String::from("3"))?;
+
+    let (reply_0, s) = s.recv()?;
+    let (reply_1, s) = s.recv()?;
+    let (reply_2, s) = s.recv()?;
+    let (reply_3, s) = s.recv()?;
+
+    assert_eq!(
+        vec![reply_0, reply_1, reply_2, reply_3],
+        vec!["Leaf0", "Leaf1", "Leaf2", "Leaf3"]
+    );
+
+    s.close()
+}
+
+macro_rules! leaf {
+    ($endpoint_name: ident, $endpoint_type: ty) => {
+        fn $endpoint_name(s: $endpoint_type) -> Result<(), Box<dyn Error>> {
+            let (payload, s) = s.recv()?;
+            let s = s.send(format!("Leaf{}", payload))?;
+            s.close()
+        }
+    };
+}
+
+leaf!(endpoint_leaf_0, StarLeaf0);
+leaf!(endpoint_leaf_1, StarLeaf1);
+leaf!(endpoint_leaf_2, StarLeaf2);
+leaf!(endpoint_leaf_3, StarLeaf3);
+
+pub fn star() {
+    let (thread_hub, thread_0, thread_1, thread_2, thread_3) = fork_mpst(
+        endpoint_hub,
+        endpoint_leaf_0,
+        endpoint_leaf_1,
+        endpoint_leaf_2,
+        endpoint_leaf_3,
+    );
+
+    assert!(thread_hub.join().is_ok());
+    assert!(thread_0.join().is_ok());
+    assert!(thread_1.join().is_ok());
+    assert!(thread_2.join().is_ok());
+    assert!(thread_3.join().is_ok());
+}
@@ -0,0 +1,112 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::meshedchannels::MeshedChannels;
+
+use mpstthree::checker_concat;
+use mpstthree::checking::deadlock::check_interleaved;
+
+use mpstthree::role::a::RoleA;
+use mpstthree::role::b::RoleB;
+use mpstthree::role::c::RoleC;
+use mpstthree::role::end::RoleEnd;
+
+// First bundle: B sends to A
+type FirstA = MeshedChannels<Recv<i32, End>, End, RoleB<RoleEnd>, RoleA<RoleEnd>>;
+type FirstB = MeshedChannels<Send<i32, End>, End, RoleA<RoleEnd>, RoleB<RoleEnd>>;
+type FirstC = MeshedChannels<End, End, RoleEnd, RoleC<RoleEnd>>;
+
+// Second bundle: A sends to B
+type SecondA = MeshedChannels<Send<i32, End>, End, RoleB<RoleEnd>, RoleA<RoleEnd>>;
+type SecondB = MeshedChannels<Recv<i32, End>, End, RoleA<RoleEnd>, RoleB<RoleEnd>>;
+type SecondC = MeshedChannels<End, End, RoleEnd, RoleC<RoleEnd>>;
+
+// Third bundle: A and B both send to each other before receiving
+type ThirdA = MeshedChannels<Send<i32, Recv<i32, End>>, End, RoleB<RoleB<RoleEnd>>, RoleA<RoleEnd>>;
+type ThirdB = MeshedChannels<Send<i32, Recv<i32, End>>, End, RoleA<RoleA<RoleEnd>>, RoleB<RoleEnd>>;
+type ThirdC = MeshedChannels<End, End, RoleEnd, RoleC<RoleEnd>>;
+
+/////////////////////////////////////////
+
+pub fn cyclic_wait() {
+    let (first, _) = checker_concat!(FirstA, FirstB, FirstC).unwrap();
+    let (second, _) = checker_concat!(SecondA, SecondB, SecondC).unwrap();
+
+    // Each bundle on its own is deadlock-free
+    assert_eq!(
+        check_interleaved(&[("first", &first)], &[], 1).unwrap(),
+        None
+    );
+    assert_eq!(
+        check_interleaved(&[("second", &second)], &[], 1).unwrap(),
+        None
+    );
+
+    // A and B are both shared: A may wait on the first bundle
+    // while B waits on the second one
+    let deadlock = check_interleaved(
+        &[("first", &first), ("second", &second)],
+        &[
+            &[("first", "RoleA"), ("second", "RoleA")],
+            &[("first", "RoleB"), ("second", "RoleB")],
+        ],
+        1,
+    )
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        deadlock.trace,
+        vec!["RoleA (first) is selected", "RoleB (second) is selected"]
+    );
+    assert_eq!(
+        deadlock.cycle,
+        vec![
+            "RoleA (first) / RoleA (second) waits for RoleB (first) / RoleB (second) on RoleA?RoleB: i32",
+            "RoleB (first) / RoleB (second) waits for RoleA (first) / RoleA (second) on RoleB?RoleA: i32",
+        ]
+    );
+    assert_eq!(deadlock.blocked.len(), 2);
+    assert!(format!("{}", deadlock).contains("Cyclic wait:"));
+}
+
+pub fn shared_without_deadlock() {
+    let (first, _) = checker_concat!(FirstA, FirstB, FirstC).unwrap();
+    let (second, _) = checker_concat!(SecondA, SecondB, SecondC).unwrap();
+
+    // Only A is shared: whatever the order chosen by A, B and C can progress
+    assert_eq!(
+        check_interleaved(
+            &[("first", &first), ("second", &second)],
+            &[&[("first", "RoleA"), ("second", "RoleA")]],
+            1,
+        )
+        .unwrap(),
+        None
+    );
+
+    assert!(check_interleaved(
+        &[("first", &first), ("second", &second)],
+        &[&[("first", "RoleA"), ("third", "RoleA")]],
+        1,
+    )
+    .is_err());
+
+    assert!(check_interleaved(
+        &[("first", &first), ("second", &second)],
+        &[&[("first", "RoleA"), ("first", "RoleB")]],
+        1,
+    )
+    .is_err());
+}
+
+pub fn buffered_sends() {
+    let (third, _) = checker_concat!(ThirdA, ThirdB, ThirdC).unwrap();
+
+    // With synchronous sends, A and B would wait for each other
+    // to receive, but each send is buffered until it is received
+    assert_eq!(
+        check_interleaved(&[("third", &third)], &[], 1).unwrap(),
+        None
+    );
+
+    assert!(check_interleaved(&[("third", &third)], &[], 0).is_err());
+}
@@ -0,0 +1,142 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::meshedchannels::MeshedChannels;
+
+use mpstthree::checker_concat;
+use mpstthree::checking::export::{export, to_graphml, to_json, Format};
+use mpstthree::checking::product::synchronous_product;
+
+use petgraph::dot::Dot;
+
+use mpstthree::role::a::RoleA;
+use mpstthree::role::b::RoleB;
+use mpstthree::role::c::RoleC;
+use mpstthree::role::end::RoleEnd;
+
+use std::env::temp_dir;
+use std::fs::{read_to_string, remove_dir_all};
+
+// Creating the binary sessions
+type AtoB<N> = Send<N, End>;
+type AtoC<N> = Recv<N, End>;
+
+type BtoA<N> = <AtoB<N> as Session>::Dual;
+type BtoC<N> = Send<N, End>;
+
+type CtoA<N> = <AtoC<N> as Session>::Dual;
+type CtoB<N> = <BtoC<N> as Session>::Dual;
+
+// Stacks
+type StackA = RoleB<RoleC<RoleEnd>>;
+type StackB = RoleA<RoleC<RoleEnd>>;
+type StackC = RoleA<RoleB<RoleEnd>>;
+
+// Creating the MP sessions
+type EndpointA<N> = MeshedChannels<AtoB<N>, AtoC<N>, StackA, RoleA<RoleEnd>>;
+type EndpointB<N> = MeshedChannels<BtoA<N>, BtoC<N>, StackB, RoleB<RoleEnd>>;
+type EndpointC<N> = MeshedChannels<CtoA<N>, CtoB<N>, StackC, RoleC<RoleEnd>>;
+
+/////////////////////////////////////////
+
+pub fn global_graph() {
+    let (graphs, kmc) = checker_concat!(EndpointA<i32>, EndpointC<i32>, EndpointB<i32>).unwrap();
+
+    assert_eq!(kmc, None);
+
+    assert_eq!(
+        format!("{:?}", Dot::new(&synchronous_product(&graphs))),
+        "digraph {\n    \
+            0 [ label = \"\\\"RoleA:0,RoleB:0,RoleC:0\\\"\" ]\n    \
+            1 [ label = \"\\\"RoleA:1,RoleB:1,RoleC:0\\\"\" ]\n    \
+            2 [ label = \"\\\"RoleA:2,RoleB:1,RoleC:1\\\"\" ]\n    \
+            3 [ label = \"\\\"RoleA:2,RoleB:2,RoleC:2\\\"\" ]\n    \
+            0 -> 1 [ label = \"\\\"RoleA->RoleB: i32\\\"\" ]\n    \
+            1 -> 2 [ label = \"\\\"RoleC->RoleA: i32\\\"\" ]\n    \
+            2 -> 3 [ label = \"\\\"RoleB->RoleC: i32\\\"\" ]\n\
+        }\n"
+    );
+}
+
+pub fn formats() {
+    let (graphs, _) = checker_concat!(EndpointA<i32>, EndpointC<i32>, EndpointB<i32>).unwrap();
+
+    assert_eq!(
+        to_graphml("simple_RoleA", &graphs["RoleA"]),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+            <key id=\"label\" for=\"all\" attr.name=\"label\" attr.type=\"string\"/>\n  \
+            <graph id=\"simple_RoleA\" edgedefault=\"directed\">\n    \
+                <node id=\"n0\"><data key=\"label\">0</data></node>\n    \
+                <node id=\"n1\"><data key=\"label\">1</data></node>\n    \
+                <node id=\"n2\"><data key=\"label\">2</data></node>\n    \
+                <node id=\"n3\"><data key=\"label\">3</data></node>\n    \
+                <edge id=\"e0\" source=\"n0\" target=\"n1\"><data key=\"label\">RoleA!RoleB: i32</data></edge>\n    \
+                <edge id=\"e1\" source=\"n1\" target=\"n2\"><data key=\"label\">RoleA?RoleC: i32</data></edge>\n    \
+                <edge id=\"e2\" source=\"n2\" target=\"n3\"><data key=\"label\">0</data></edge>\n  \
+            </graph>\n\
+        </graphml>\n"
+    );
+
+    let json = to_json("simple", &graphs);
+
+    assert!(json.starts_with(
+        "{\n  \"protocol\": \"simple\",\n  \"roles\": [\"RoleA\", \"RoleB\", \"RoleC\"],\n"
+    ));
+    assert!(json.contains("{ \"source\": 0, \"target\": 1, \"label\": \"RoleA!RoleB: i32\" }"));
+    assert!(json.contains("{ \"source\": 2, \"target\": 3, \"label\": \"RoleB->RoleC: i32\" }"));
+}
+
+pub fn files() {
+    let output_dir = temp_dir().join("mpstthree_graph_mod_export");
+    remove_dir_all(&output_dir).unwrap_or(());
+
+    let (graphs, kmc) = checker_concat!(
+        "simple",
+        output_dir.to_str().unwrap(),
+        [Dot, GraphML, Json],
+        EndpointA<i32>,
+        EndpointC<i32>,
+        EndpointB<i32>
+    )
+    .unwrap();
+
+    // The KMC tool is only run for the `Cfsm` format
+    assert_eq!(kmc, None);
+
+    for file in [
+        "simple_RoleA.dot",
+        "simple_RoleB.dot",
+        "simple_RoleC.dot",
+        "simple_global.dot",
+        "simple_RoleA.graphml",
+        "simple_global.graphml",
+        "simple.json",
+    ] {
+        assert!(output_dir.join(file).exists(), "Missing {}", file);
+    }
+
+    assert_eq!(
+        read_to_string(output_dir.join("simple.json")).unwrap(),
+        to_json("simple", &graphs)
+    );
+
+    // Export again, in another folder, with the returned graphs
+    let other_dir = output_dir.join("dot");
+
+    let paths = export("other", &other_dir, &graphs, &[Format::Dot]).unwrap();
+
+    assert_eq!(paths.len(), 4);
+    assert_eq!(
+        read_to_string(other_dir.join("other_global.dot")).unwrap(),
+        "digraph {\n    \
+            0 [ label = \"RoleA:0,RoleB:0,RoleC:0\" ]\n    \
+            1 [ label = \"RoleA:1,RoleB:1,RoleC:0\" ]\n    \
+            2 [ label = \"RoleA:2,RoleB:1,RoleC:1\" ]\n    \
+            3 [ label = \"RoleA:2,RoleB:2,RoleC:2\" ]\n    \
+            0 -> 1 [ label = \"RoleA->RoleB: i32\" ]\n    \
+            1 -> 2 [ label = \"RoleC->RoleA: i32\" ]\n    \
+            2 -> 3 [ label = \"RoleB->RoleC: i32\" ]\n\
+        }\n"
+    );
+
+    remove_dir_all(&output_dir).unwrap_or(());
+}
@@ -1 +1,4 @@
+pub mod deadlock;
+pub mod export;
 pub mod simple;
+pub mod temporal;
@@ -0,0 +1,157 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::meshedchannels::MeshedChannels;
+use mpstthree::role::broadcast::RoleBroadcast;
+
+use mpstthree::checker_concat;
+use mpstthree::checking::temporal::{check_property, Formula};
+
+use mpstthree::role::a::RoleA;
+use mpstthree::role::b::RoleB;
+use mpstthree::role::c::RoleC;
+use mpstthree::role::end::RoleEnd;
+
+// Client = A
+// Server = B
+// Logger = C
+
+// Payloads
+struct Login;
+struct Token;
+struct Success;
+struct Fail;
+
+// A
+enum Branches0AtoB {
+    Success(MeshedChannels<Recv<Token, End>, End, RoleB<RoleEnd>, RoleA<RoleEnd>>),
+    Fail(MeshedChannels<End, End, RoleEnd, RoleA<RoleEnd>>),
+}
+type EndpointA = MeshedChannels<
+    Send<Login, Recv<Branches0AtoB, End>>,
+    End,
+    RoleB<RoleB<RoleEnd>>,
+    RoleA<RoleEnd>,
+>;
+
+// C
+enum Branches0CtoB {
+    Success(MeshedChannels<End, Recv<Success, End>, RoleB<RoleEnd>, RoleC<RoleEnd>>),
+    Fail(MeshedChannels<End, Recv<Fail, End>, RoleB<RoleEnd>, RoleC<RoleEnd>>),
+}
+type EndpointC = MeshedChannels<End, Recv<Branches0CtoB, End>, RoleB<RoleEnd>, RoleC<RoleEnd>>;
+
+// B
+type EndpointBSuccess =
+    MeshedChannels<Send<Token, End>, Send<Success, End>, RoleC<RoleA<RoleEnd>>, RoleB<RoleEnd>>;
+type EndpointBFail = MeshedChannels<End, Send<Fail, End>, RoleC<RoleEnd>, RoleB<RoleEnd>>;
+type EndpointB = MeshedChannels<
+    Recv<Login, Send<Branches0AtoB, End>>,
+    Send<Branches0CtoB, End>,
+    RoleA<RoleBroadcast>,
+    RoleB<RoleEnd>,
+>;
+
+/////////////////////////////////////////
+
+pub fn properties() {
+    let (graphs, _) = checker_concat!(
+        EndpointA,
+        EndpointC,
+        EndpointB
+        =>
+        [
+            EndpointBSuccess,
+            Branches0AtoB, Success,
+            Branches0CtoB, Success
+        ],
+        [
+            EndpointBFail,
+            Branches0AtoB, Fail,
+            Branches0CtoB, Fail
+        ]
+    )
+    .unwrap();
+
+    // Every Login is eventually followed by Success or Fail
+    let verdict = check_property(&graphs, "AG(Login -> AF(Success | Fail))").unwrap();
+    assert!(verdict.holds);
+    assert!(verdict.path.is_empty());
+
+    // But not always by Success
+    let verdict = check_property(&graphs, "AG(Login -> AF Success)").unwrap();
+    assert!(!verdict.holds);
+    assert_eq!(
+        verdict.path,
+        vec!["RoleA->RoleB: Login", "RoleB->RoleC: Fail"]
+    );
+    assert!(verdict.cycle.is_empty());
+
+    // A Token may be sent, along with a witness
+    let verdict = check_property(&graphs, "EF \"RoleB->RoleA: Token\"").unwrap();
+    assert!(verdict.holds);
+    assert_eq!(
+        verdict.path,
+        vec![
+            "RoleA->RoleB: Login",
+            "RoleB->RoleC: Success",
+            "RoleB->RoleA: Token"
+        ]
+    );
+
+    // No Token before Success
+    let verdict = check_property(&graphs, "A[!Token U (Success | Fail)]").unwrap();
+    assert!(verdict.holds);
+
+    let verdict = check_property(&graphs, "E[!Token U Success]").unwrap();
+    assert!(verdict.holds);
+    assert_eq!(
+        verdict.path,
+        vec!["RoleA->RoleB: Login", "RoleB->RoleC: Success"]
+    );
+
+    // The protocol always ends
+    assert!(check_property(&graphs, "AF end").unwrap().holds);
+    assert!(!check_property(&graphs, "EG !end").unwrap().holds);
+    assert!(
+        check_property(&graphs, "AX \"RoleA->RoleB\"")
+            .unwrap()
+            .holds
+    );
+    assert!(
+        !check_property(&graphs, "AG !\"RoleB->RoleC\"")
+            .unwrap()
+            .holds
+    );
+}
+
+pub fn formulas() {
+    let formula = "AG(Login -> AF(Success | Fail))"
+        .parse::<Formula>()
+        .unwrap();
+
+    assert_eq!(
+        formula,
+        Formula::AG(Box::new(Formula::Implies(
+            Box::new(Formula::Action("Login".to_string())),
+            Box::new(Formula::AF(Box::new(Formula::Or(
+                Box::new(Formula::Action("Success".to_string())),
+                Box::new(Formula::Action("Fail".to_string()))
+            ))))
+        )))
+    );
+
+    assert_eq!(format!("{}", formula), "AG (Login -> AF (Success | Fail))");
+    assert_eq!(format!("{}", formula).parse::<Formula>().unwrap(), formula);
+
+    assert_eq!(
+        format!(
+            "{}",
+            "E[!\"RoleA->RoleB\" U end]".parse::<Formula>().unwrap()
+        ),
+        "E[!\"RoleA->RoleB\" U end]"
+    );
+
+    assert!("AG(".parse::<Formula>().is_err());
+    assert!("AG Login)".parse::<Formula>().is_err());
+    assert!("E[Login Success]".parse::<Formula>().is_err());
+    assert!("\"Login".parse::<Formula>().is_err());
+}
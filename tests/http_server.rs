@@ -0,0 +1,36 @@
+#![allow(clippy::type_complexity)]
+
+mod http_server_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn o_auth_server() {
+    http_server_mod::o_auth::accepted();
+    http_server_mod::o_auth::refused();
+    http_server_mod::o_auth::without_http();
+}
+
+#[test]
+#[timeout(60000)]
+fn server_binary() {
+    http_server_mod::binary::answer();
+    http_server_mod::binary::dropped_responder();
+}
+
+#[test]
+#[timeout(60000)]
+fn http_context() {
+    http_server_mod::context::given_context();
+    http_server_mod::context::without_keep_alive();
+    http_server_mod::context::shared_context();
+    http_server_mod::context::generated_functions();
+}
+
+#[test]
+#[timeout(60000)]
+fn http_payload() {
+    http_server_mod::payload::binary();
+    http_server_mod::payload::multiparty();
+}
@@ -0,0 +1,78 @@
+use hyper::{Body, Method, Response, StatusCode};
+use mpstthree::binary::close::close;
+use mpstthree::binary::fork::fork_with_thread_id;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::http::recv::recv_http;
+use mpstthree::transport::http::send::send_http;
+use mpstthree::transport::http::server::{recv_http_request, send_http_response, HttpServer};
+use std::error::Error;
+use tokio::runtime::Runtime;
+
+type ClientMPST<N> = Send<N, Recv<N, End>>;
+type ServerMPST<N> = <ClientMPST<N> as Session>::Dual;
+
+fn body(resp: Response<Body>) -> Result<String, Box<dyn Error>> {
+    let bytes = Runtime::new()?.block_on(hyper::body::to_bytes(resp.into_body()))?;
+    Ok(String::from_utf8(bytes.to_vec())?)
+}
+
+pub fn answer() {
+    let server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let uri = format!("http://{}/double", server.local_addr());
+
+    let (thread, s) = fork_with_thread_id(move |s: ClientMPST<i32>| {
+        let (s, req) = send_http(21, s, true, Method::POST, &uri, vec![], "21")?;
+        let (result, s, resp) = recv_http(s, true, req)?;
+
+        assert_eq!(result, 42);
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body(resp)?, "42");
+
+        close(s)
+    });
+
+    assert!(|s: ServerMPST<i32>| -> Result<(), Box<dyn Error>> {
+        let (x, s, req, responder) = recv_http_request(s, true, &server)?;
+
+        assert_eq!(req.method(), Method::POST);
+        assert_eq!(req.uri().path(), "/double");
+        assert_eq!(req.body(), b"21");
+
+        let s = send_http_response(
+            x * 2,
+            s,
+            true,
+            responder,
+            Response::new(Body::from((x * 2).to_string())),
+        )?;
+        close(s)
+    }(s)
+    .is_ok());
+
+    assert!(thread.join().is_ok());
+}
+
+pub fn dropped_responder() {
+    let server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let uri = format!("http://{}/", server.local_addr());
+
+    let (thread, s) = fork_with_thread_id(move |s: ClientMPST<i32>| {
+        let (s, req) = send_http(0, s, true, Method::GET, &uri, vec![], "")?;
+        let (_, s, resp) = recv_http(s, true, req)?;
+
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        close(s)
+    });
+
+    assert!(|s: ServerMPST<i32>| -> Result<(), Box<dyn Error>> {
+        let (x, s, _req, responder) = recv_http_request(s, true, &server)?;
+        drop(responder);
+
+        let s = send_http_response(x, s, false, Default::default(), Response::default())?;
+        close(s)
+    }(s)
+    .is_ok());
+
+    assert!(thread.join().is_ok());
+}
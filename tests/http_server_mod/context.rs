@@ -0,0 +1,161 @@
+use hyper::{Body, Method, Request, Response, StatusCode};
+use mpstthree::binary::close::close;
+use mpstthree::binary::fork::fork_with_thread_id;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::role::end::RoleEnd;
+use mpstthree::transport::http::context::HttpContext;
+use mpstthree::transport::http::recv::recv_http;
+use mpstthree::transport::http::send::send_http;
+use mpstthree::transport::http::server::{recv_http_request, send_http_response, HttpServer};
+use mpstthree::{
+    bundle_struct_fork_close_multi, create_multiple_normal_role_short,
+    create_recv_http_server_session, create_recv_http_session, create_send_http_server_session,
+    create_send_http_session,
+};
+use std::error::Error;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+type ClientMPST<N> = Send<N, Recv<N, Send<N, Recv<N, Send<N, Recv<N, End>>>>>>;
+type ServerMPST<N> = <ClientMPST<N> as Session>::Dual;
+
+// The client increments three times a counter held by the server,
+// with the same client for all the requests.
+fn client<H: mpstthree::transport::http::context::HttpMode + Copy>(
+    s: ClientMPST<i32>,
+    http: H,
+    addr: SocketAddr,
+) -> Result<(), Box<dyn Error>> {
+    let uri = format!("http://{}/count", addr);
+
+    let (s, req) = send_http(1, s, http, Method::POST, &uri, vec![], "")?;
+    let (one, s, resp) = recv_http(s, http, req)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let (s, req) = send_http(one, s, http, Method::POST, &uri, vec![], "")?;
+    let (two, s, resp) = recv_http(s, http, req)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let (s, req) = send_http(two, s, http, Method::POST, &uri, vec![], "")?;
+    let (three, s, resp) = recv_http(s, http, req)?;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(three, 3);
+
+    close(s)
+}
+
+fn server(s: ServerMPST<i32>, server: &HttpServer) -> Result<(), Box<dyn Error>> {
+    let (x, s, _req, responder) = recv_http_request(s, true, server)?;
+    let s = send_http_response(x, s, true, responder, Response::new(Body::empty()))?;
+    let (x, s, _req, responder) = recv_http_request(s, true, server)?;
+    let s = send_http_response(x + 1, s, true, responder, Response::new(Body::empty()))?;
+    let (x, s, _req, responder) = recv_http_request(s, true, server)?;
+    let s = send_http_response(x + 1, s, true, responder, Response::new(Body::empty()))?;
+    close(s)
+}
+
+pub fn given_context() {
+    let http_server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let addr = http_server.local_addr();
+
+    let context = HttpContext::builder()
+        .pool_idle_timeout(Some(Duration::from_secs(5)))
+        .worker_threads(1)
+        .build()
+        .unwrap();
+
+    let (thread, s) = fork_with_thread_id(move |s: ClientMPST<i32>| client(s, &context, addr));
+
+    assert!(server(s, &http_server).is_ok());
+    assert!(thread.join().is_ok());
+
+    // The connection is kept alive between the requests
+    assert_eq!(http_server.connections(), 1);
+}
+
+pub fn without_keep_alive() {
+    let http_server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let addr = http_server.local_addr();
+
+    let context = HttpContext::builder().keep_alive(false).build().unwrap();
+
+    let (thread, s) = fork_with_thread_id(move |s: ClientMPST<i32>| client(s, &context, addr));
+
+    assert!(server(s, &http_server).is_ok());
+    assert!(thread.join().is_ok());
+
+    assert_eq!(http_server.connections(), 3);
+}
+
+pub fn shared_context() {
+    let http_server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let addr = http_server.local_addr();
+
+    let (thread, s) = fork_with_thread_id(move |s: ClientMPST<i32>| client(s, true, addr));
+
+    assert!(server(s, &http_server).is_ok());
+    assert!(thread.join().is_ok());
+
+    // The same runtime is handed each time
+    assert!(std::ptr::eq(
+        HttpContext::shared().unwrap(),
+        HttpContext::shared().unwrap()
+    ));
+}
+
+// Two roles sharing a context, with the generated functions
+bundle_struct_fork_close_multi!(close_mpst_multi, fork_mpst, MeshedChannelsTwo, 2);
+
+create_multiple_normal_role_short!(A, B);
+
+create_send_http_session!(send_http_a_to_b, RoleB, RoleA, MeshedChannelsTwo, 2, 1);
+create_recv_http_session!(recv_http_a_from_b, RoleB, RoleA, MeshedChannelsTwo, 2, 1);
+create_recv_http_server_session!(recv_http_b_from_a, RoleA, RoleB, MeshedChannelsTwo, 2, 1);
+create_send_http_server_session!(send_http_b_to_a, RoleA, RoleB, MeshedChannelsTwo, 2, 1);
+
+type EndpointA = MeshedChannelsTwo<
+    Send<i32, Recv<i32, Send<i32, Recv<i32, End>>>>,
+    RoleB<RoleB<RoleB<RoleB<RoleEnd>>>>,
+    RoleA<RoleEnd>,
+>;
+type EndpointB = MeshedChannelsTwo<
+    Recv<i32, Send<i32, Recv<i32, Send<i32, End>>>>,
+    RoleA<RoleA<RoleA<RoleA<RoleEnd>>>>,
+    RoleB<RoleEnd>,
+>;
+
+fn endpoint_a(s: EndpointA, context: &HttpContext, addr: SocketAddr) -> Result<(), Box<dyn Error>> {
+    let uri = format!("http://{}/", addr);
+
+    let (s, resp) = send_http_a_to_b(1, s, context, Request::post(&uri).body(Body::empty())?)?;
+    let (x, s, _resp) = recv_http_a_from_b(s, context, vec![resp])?;
+    let (s, resp) = send_http_a_to_b(x, s, context, Request::post(&uri).body(Body::empty())?)?;
+    let (x, s, resp) = recv_http_a_from_b(s, context, vec![resp])?;
+
+    assert_eq!(x, 4);
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    close_mpst_multi(s)
+}
+
+fn endpoint_b(s: EndpointB, server: &HttpServer) -> Result<(), Box<dyn Error>> {
+    let (x, s, _req, responder) = recv_http_b_from_a(s, true, server)?;
+    let s = send_http_b_to_a(x * 2, s, true, responder, Response::new(Body::empty()))?;
+    let (x, s, _req, responder) = recv_http_b_from_a(s, true, server)?;
+    let s = send_http_b_to_a(x * 2, s, true, responder, Response::new(Body::empty()))?;
+    close_mpst_multi(s)
+}
+
+pub fn generated_functions() {
+    let http_server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let addr = http_server.local_addr();
+    let context = HttpContext::new().unwrap();
+
+    let (thread_a, thread_b) = fork_mpst(
+        move |s| endpoint_a(s, &context, addr),
+        move |s| endpoint_b(s, &http_server),
+    );
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_b.join().is_ok());
+}
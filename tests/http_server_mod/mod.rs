@@ -0,0 +1,4 @@
+pub mod binary;
+pub mod context;
+pub mod o_auth;
+pub mod payload;
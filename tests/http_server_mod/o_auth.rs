@@ -0,0 +1,227 @@
+// A localhost stand-in for the o_auth protocol of tests/http:
+// the authenticator A serves the login request of the client C over HTTP,
+// then tells everyone whether the client is logged in,
+// and the client then asks the server S for a picture.
+
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::transport::http::server::HttpServer;
+use mpstthree::{
+    bundle_struct_fork_close_multi, create_fn_choose_mpst_multi_to_all_bundle,
+    create_multiple_normal_role_short, create_recv_http_server_session,
+    create_recv_http_session_bundle, create_send_http_server_session, create_send_mpst_http_bundle,
+    offer_http_mpst,
+};
+
+use hyper::{Body, Method, Request, Response, StatusCode};
+use tokio::runtime::Runtime;
+
+use std::error::Error;
+use std::net::SocketAddr;
+
+static PASSWORD: i32 = 1234;
+static TOKEN: i32 = 42;
+
+// Create the new MeshedChannels for three participants and the close and fork functions
+bundle_struct_fork_close_multi!(close_mpst_multi, fork_mpst, MeshedChannelsThree, 3);
+
+// Create new roles
+// normal
+create_multiple_normal_role_short!(A, C, S);
+
+// Create new send functions
+// A
+create_send_http_server_session!(send_http_a_to_c, RoleC, RoleA, MeshedChannelsThree, 3, 1);
+// C
+create_send_mpst_http_bundle!(
+    send_http_c_to_a, RoleA, 1 |
+    send_http_c_to_s, RoleS, 2 | =>
+    RoleC, MeshedChannelsThree, 3
+);
+// S
+create_send_mpst_http_bundle!(
+    send_http_s_to_c, RoleC, 2 | =>
+    RoleS, MeshedChannelsThree, 3
+);
+
+// Create new recv functions and related types
+// A
+create_recv_http_server_session!(recv_http_a_from_c, RoleC, RoleA, MeshedChannelsThree, 3, 1);
+// C
+create_recv_http_session_bundle!(
+    recv_http_c_to_a, RoleA, 1 |
+    recv_http_c_to_s, RoleS, 2 | =>
+    RoleC, MeshedChannelsThree, 3
+);
+// S
+create_recv_http_session_bundle!(
+    recv_http_s_to_a, RoleA, 1 |
+    recv_http_s_to_c, RoleC, 2 | =>
+    RoleS, MeshedChannelsThree, 3
+);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+type NameS = RoleS<RoleEnd>;
+
+// Types
+// A
+type Choose0fromAtoC = Send<Branching0fromAtoC, End>;
+type Choose0fromAtoS = Send<Branching0fromAtoS, End>;
+
+type EndpointAAuth = MeshedChannelsThree<End, End, RoleEnd, NameA>;
+type EndpointADone = MeshedChannelsThree<End, End, RoleEnd, NameA>;
+
+// C
+enum Branching0fromAtoC {
+    Auth(MeshedChannelsThree<End, Send<i32, Recv<i32, End>>, RoleS<RoleS<RoleEnd>>, NameC>),
+    Done(MeshedChannelsThree<End, Send<i32, End>, RoleS<RoleEnd>, NameC>),
+}
+
+// S
+enum Branching0fromAtoS {
+    Auth(MeshedChannelsThree<End, Recv<i32, Send<i32, End>>, RoleC<RoleC<RoleEnd>>, NameS>),
+    Done(MeshedChannelsThree<End, Recv<i32, End>, RoleC<RoleEnd>, NameS>),
+}
+
+// Creating the MP sessions
+// A
+type EndpointA = MeshedChannelsThree<
+    Recv<i32, Send<i32, Choose0fromAtoC>>,
+    Choose0fromAtoS,
+    RoleC<RoleC<RoleBroadcast>>,
+    NameA,
+>;
+// C
+type EndpointC = MeshedChannelsThree<
+    Send<i32, Recv<i32, <Choose0fromAtoC as Session>::Dual>>,
+    End,
+    RoleA<RoleA<RoleA<RoleEnd>>>,
+    NameC,
+>;
+// S
+type EndpointS =
+    MeshedChannelsThree<<Choose0fromAtoS as Session>::Dual, End, RoleA<RoleEnd>, NameS>;
+
+create_fn_choose_mpst_multi_to_all_bundle!(
+    auth_from_a_to_all, done_from_a_to_all, =>
+    Auth, Done, =>
+    EndpointAAuth, EndpointADone, =>
+    Branching0fromAtoC, Branching0fromAtoS, =>
+    RoleC, RoleS, =>
+    RoleA, MeshedChannelsThree, 1
+);
+
+// Functions
+fn endpoint_a(s: EndpointA, http: bool, server: &HttpServer) -> Result<(), Box<dyn Error>> {
+    let (pwd, s, req, responder) = recv_http_a_from_c(s, http, server)?;
+
+    if http {
+        assert_eq!(req.method(), Method::POST);
+        assert_eq!(req.uri().path(), "/login");
+        assert_eq!(req.body(), pwd.to_string().as_bytes());
+    }
+
+    // Answer the request before telling everyone the outcome
+    if pwd == PASSWORD {
+        let resp = Response::new(Body::from(TOKEN.to_string()));
+        let s = send_http_a_to_c(TOKEN, s, http, responder, resp)?;
+
+        let s = auth_from_a_to_all(s);
+        close_mpst_multi(s)
+    } else {
+        let resp = Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(Body::empty())?;
+        let s = send_http_a_to_c(0, s, http, responder, resp)?;
+
+        let s = done_from_a_to_all(s);
+        close_mpst_multi(s)
+    }
+}
+
+fn endpoint_c(s: EndpointC, http: bool, addr: SocketAddr, pwd: i32) -> Result<(), Box<dyn Error>> {
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri(format!("http://{}/login", addr))
+        .body(Body::from(pwd.to_string()))?;
+    let (s, resp) = send_http_c_to_a(pwd, s, http, req)?;
+
+    // The request is only sent once its response is awaited
+    let futures = if http { vec![resp] } else { Vec::new() };
+    let (token, s, resp) = recv_http_c_to_a(s, http, futures)?;
+
+    if http {
+        match token {
+            0 => assert_eq!(resp.status(), StatusCode::UNAUTHORIZED),
+            _ => {
+                assert_eq!(resp.status(), StatusCode::OK);
+                let body = Runtime::new()?.block_on(hyper::body::to_bytes(resp.into_body()))?;
+                assert_eq!(body, token.to_string());
+            }
+        }
+    }
+
+    offer_http_mpst!(s, recv_http_c_to_a, {
+        Branching0fromAtoC::Auth(s) => {
+            let (s, _req) = send_http_c_to_s(token, s, false, Request::default())?;
+            let (picture, s, _resp) = recv_http_c_to_s(s, false, Vec::new())?;
+
+            assert_eq!(picture, token + 1);
+
+            close_mpst_multi(s)
+        },
+        Branching0fromAtoC::Done(s) => {
+            let (s, _req) = send_http_c_to_s(0, s, false, Request::default())?;
+
+            close_mpst_multi(s)
+        },
+    })
+}
+
+fn endpoint_s(s: EndpointS) -> Result<(), Box<dyn Error>> {
+    offer_http_mpst!(s, recv_http_s_to_a, {
+        Branching0fromAtoS::Auth(s) => {
+            let (token, s, _resp) = recv_http_s_to_c(s, false, Vec::new())?;
+            let (s, _req) = send_http_s_to_c(token + 1, s, false, Request::default())?;
+
+            close_mpst_multi(s)
+        },
+        Branching0fromAtoS::Done(s) => {
+            let (_quit, s, _resp) = recv_http_s_to_c(s, false, Vec::new())?;
+
+            close_mpst_multi(s)
+        },
+    })
+}
+
+/////////////////////////
+
+fn run(http: bool, pwd: i32) {
+    let server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr();
+
+    let (thread_a, thread_c, thread_s) = fork_mpst(
+        move |s| endpoint_a(s, http, &server),
+        move |s| endpoint_c(s, http, addr, pwd),
+        endpoint_s,
+    );
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_c.join().is_ok());
+    assert!(thread_s.join().is_ok());
+}
+
+pub fn accepted() {
+    run(true, PASSWORD);
+}
+
+pub fn refused() {
+    run(true, PASSWORD + 1);
+}
+
+pub fn without_http() {
+    run(false, PASSWORD);
+}
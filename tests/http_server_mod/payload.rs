@@ -0,0 +1,215 @@
+// The payloads are the requests and the responses:
+// the client C logs in on the authenticator A,
+// which answers with a token, or with `401 Unauthorized`.
+
+use hyper::{Method, Request, Response, StatusCode};
+use mpstthree::binary::close::close;
+use mpstthree::binary::fork::fork_with_thread_id;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::role::end::RoleEnd;
+use mpstthree::transport::http::context::HttpContext;
+use mpstthree::transport::http::payload::{
+    recv_http_payload, recv_http_payload_request, send_http_payload, send_http_payload_response,
+    HttpPayload,
+};
+use mpstthree::transport::http::server::HttpServer;
+use mpstthree::{
+    bundle_struct_fork_close_multi, create_multiple_normal_role_short,
+    create_recv_http_payload_request_session, create_recv_http_payload_session,
+    create_send_http_payload_response_session, create_send_http_payload_session,
+};
+use std::error::Error;
+use std::net::SocketAddr;
+
+#[derive(Debug, PartialEq)]
+struct Login {
+    user: String,
+    password: String,
+}
+
+impl HttpPayload for Login {
+    fn path(&self) -> String {
+        format!("/login/{}", self.user)
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        vec![("content-type".to_string(), "text/plain".to_string())]
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(self.password.as_bytes().to_vec())
+    }
+
+    fn decode(_body: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Err("A login needs its path".into())
+    }
+
+    fn from_request(req: &Request<Vec<u8>>) -> Result<Self, Box<dyn Error>> {
+        assert_eq!(req.method(), Method::POST);
+        assert_eq!(req.headers()["content-type"], "text/plain");
+
+        let user = req
+            .uri()
+            .path()
+            .strip_prefix("/login/")
+            .ok_or("Not a login")?;
+
+        Ok(Login {
+            user: user.to_string(),
+            password: String::from_utf8(req.body().clone())?,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Token(Option<String>);
+
+impl HttpPayload for Token {
+    fn status(&self) -> StatusCode {
+        match self.0 {
+            Some(_) => StatusCode::OK,
+            None => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(self.0.clone().unwrap_or_default().into_bytes())
+    }
+
+    fn decode(body: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(Token(Some(String::from_utf8(body.to_vec())?)))
+    }
+
+    fn from_response(resp: &Response<Vec<u8>>) -> Result<Self, Box<dyn Error>> {
+        match resp.status() {
+            StatusCode::UNAUTHORIZED => Ok(Token(None)),
+            _ => Self::decode(resp.body()),
+        }
+    }
+}
+
+fn authenticate(login: &Login) -> Token {
+    match (login.user.as_str(), login.password.as_str()) {
+        ("alice", "secret") => Token(Some("alice-token".to_string())),
+        _ => Token(None),
+    }
+}
+
+// Binary sessions
+type ClientMPST = Send<Login, Recv<Token, End>>;
+type ServerMPST = <ClientMPST as Session>::Dual;
+
+fn binary_run(user: &str, password: &str) -> Token {
+    let http_server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let uri = format!("http://{}", http_server.local_addr());
+    let login = Login {
+        user: user.to_string(),
+        password: password.to_string(),
+    };
+
+    let (sender, receiver) = crossbeam_channel::bounded(1);
+
+    let (thread, s) = fork_with_thread_id(move |s: ClientMPST| {
+        let (s, resp) = send_http_payload(login, s, true, &uri)?;
+        let (token, s) = recv_http_payload(s, true, resp)?;
+        sender.send(token)?;
+        close(s)
+    });
+
+    assert!(|s: ServerMPST| -> Result<(), Box<dyn Error>> {
+        let (login, s, responder) = recv_http_payload_request(s, true, &http_server)?;
+        let s = send_http_payload_response(authenticate(&login), s, true, responder)?;
+        close(s)
+    }(s)
+    .is_ok());
+
+    assert!(thread.join().is_ok());
+    receiver.recv().unwrap()
+}
+
+pub fn binary() {
+    assert_eq!(
+        binary_run("alice", "secret"),
+        Token(Some("alice-token".to_string()))
+    );
+    assert_eq!(binary_run("alice", "guess"), Token(None));
+}
+
+// Multiparty sessions
+bundle_struct_fork_close_multi!(close_mpst_multi, fork_mpst, MeshedChannelsTwo, 2);
+
+create_multiple_normal_role_short!(A, C);
+
+create_recv_http_payload_request_session!(
+    recv_http_a_from_c,
+    RoleC,
+    RoleA,
+    MeshedChannelsTwo,
+    2,
+    1
+);
+create_send_http_payload_response_session!(send_http_a_to_c, RoleC, RoleA, MeshedChannelsTwo, 2, 1);
+create_send_http_payload_session!(send_http_c_to_a, RoleA, RoleC, MeshedChannelsTwo, 2, 1);
+create_recv_http_payload_session!(recv_http_c_from_a, RoleA, RoleC, MeshedChannelsTwo, 2, 1);
+
+type EndpointA =
+    MeshedChannelsTwo<Recv<Login, Send<Token, End>>, RoleC<RoleC<RoleEnd>>, RoleA<RoleEnd>>;
+type EndpointC =
+    MeshedChannelsTwo<Send<Login, Recv<Token, End>>, RoleA<RoleA<RoleEnd>>, RoleC<RoleEnd>>;
+
+fn endpoint_a(s: EndpointA, http: bool, server: &HttpServer) -> Result<(), Box<dyn Error>> {
+    let (login, s, responder) = recv_http_a_from_c(s, http, server)?;
+    let s = send_http_a_to_c(authenticate(&login), s, http, responder)?;
+    close_mpst_multi(s)
+}
+
+fn endpoint_c(
+    s: EndpointC,
+    context: Option<&HttpContext>,
+    addr: SocketAddr,
+    login: Login,
+) -> Result<(), Box<dyn Error>> {
+    let uri = format!("http://{}/", addr);
+
+    let token = match context {
+        Some(context) => {
+            let (s, resp) = send_http_c_to_a(login, s, context, &uri)?;
+            let (token, s) = recv_http_c_from_a(s, context, vec![resp])?;
+            close_mpst_multi(s)?;
+            token
+        }
+        None => {
+            let (s, _resp) = send_http_c_to_a(login, s, false, &uri)?;
+            let (token, s) = recv_http_c_from_a(s, false, Vec::new())?;
+            close_mpst_multi(s)?;
+            token
+        }
+    };
+
+    assert_eq!(token, Token(Some("alice-token".to_string())));
+
+    Ok(())
+}
+
+fn multiparty_run(http: bool) {
+    let http_server = HttpServer::bind("127.0.0.1:0").unwrap();
+    let addr = http_server.local_addr();
+    let context = HttpContext::new().unwrap();
+    let login = Login {
+        user: "alice".to_string(),
+        password: "secret".to_string(),
+    };
+
+    let (thread_a, thread_c) = fork_mpst(
+        move |s| endpoint_a(s, http, &http_server),
+        move |s| endpoint_c(s, Some(&context).filter(|_| http), addr, login),
+    );
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_c.join().is_ok());
+}
+
+pub fn multiparty() {
+    multiparty_run(true);
+    multiparty_run(false);
+}
@@ -0,0 +1,22 @@
+#![allow(clippy::type_complexity)]
+
+mod property_mod;
+
+#[test]
+fn traces() {
+    property_mod::o_auth::traces();
+}
+
+#[test]
+fn stubs() {
+    property_mod::o_auth::endpoint_a_against_stubs();
+    property_mod::o_auth::endpoint_c_against_stubs();
+    property_mod::o_auth::shrink_failing_trace();
+}
+
+#[test]
+fn branches() {
+    property_mod::branches::same_first_message();
+    property_mod::branches::branch_without_sending();
+    property_mod::branches::stubs_follow_the_branches();
+}
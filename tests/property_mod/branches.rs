@@ -0,0 +1,231 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::testing::property::Protocol;
+use mpstthree::testing::{stub, Script};
+use mpstthree::{
+    bundle_impl_with_enum_and_cancel, protocol_concat, stub_bundle, stub_choose, stub_offer,
+    stub_payloads,
+};
+
+use proptest::test_runner::{Config, TestRunner};
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+// A chooses between four branches: First and Second both start with
+// the same message from A to B, A does not do anything in Quiet,
+// and only receives a message from C in Listen
+
+bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, B, C);
+stub_bundle!(MeshedChannelsThree, A, B, C);
+
+// Payloads
+#[derive(Default)]
+struct Data;
+#[derive(Default)]
+struct Ack;
+
+stub_payloads!(default: Data, Ack);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+
+// Types
+
+// A
+type Choose0fromAtoB = <Offer0fromBtoA as Session>::Dual;
+type Choose0fromAtoC = <Offer0fromCtoA as Session>::Dual;
+
+// B
+enum Branching0fromAtoB {
+    First(MeshedChannelsThree<Recv<Data, End>, Send<Data, End>, RoleA<RoleC<RoleEnd>>, NameB>),
+    Second(MeshedChannelsThree<Recv<Data, Send<Ack, End>>, End, RoleA<RoleA<RoleEnd>>, NameB>),
+    Quiet(MeshedChannelsThree<End, Send<Ack, End>, RoleC<RoleEnd>, NameB>),
+    Listen(MeshedChannelsThree<End, End, RoleEnd, NameB>),
+}
+type Offer0fromBtoA = Recv<Branching0fromAtoB, End>;
+
+stub_offer!(Branching0fromAtoB {
+    First,
+    Second,
+    Quiet,
+    Listen
+});
+
+// C
+enum Branching0fromAtoC {
+    First(MeshedChannelsThree<End, Recv<Data, End>, RoleB<RoleEnd>, NameC>),
+    Second(MeshedChannelsThree<End, End, RoleEnd, NameC>),
+    Quiet(MeshedChannelsThree<End, Recv<Ack, End>, RoleB<RoleEnd>, NameC>),
+    Listen(MeshedChannelsThree<Send<Ack, End>, End, RoleA<RoleEnd>, NameC>),
+}
+type Offer0fromCtoA = Recv<Branching0fromAtoC, End>;
+
+stub_offer!(Branching0fromAtoC {
+    First,
+    Second,
+    Quiet,
+    Listen
+});
+
+// Creating the MP sessions
+// A
+type EndpointAFirst = MeshedChannelsThree<Send<Data, End>, End, RoleB<RoleEnd>, NameA>;
+type EndpointASecond =
+    MeshedChannelsThree<Send<Data, Recv<Ack, End>>, End, RoleB<RoleB<RoleEnd>>, NameA>;
+type EndpointAQuiet = MeshedChannelsThree<End, End, RoleEnd, NameA>;
+type EndpointAListen = MeshedChannelsThree<End, Recv<Ack, End>, RoleC<RoleEnd>, NameA>;
+type EndpointA = MeshedChannelsThree<Choose0fromAtoB, Choose0fromAtoC, RoleBroadcast, NameA>;
+
+stub_choose!(
+    MeshedChannelsThree<Choose0fromAtoB, Choose0fromAtoC, RoleBroadcast, NameA>,
+    choose_mpst_a_to_all,
+    {
+        First: EndpointAFirst => Branching0fromAtoB::First, Branching0fromAtoC::First;
+        Second: EndpointASecond => Branching0fromAtoB::Second, Branching0fromAtoC::Second;
+        Quiet: EndpointAQuiet => Branching0fromAtoB::Quiet, Branching0fromAtoC::Quiet;
+        Listen: EndpointAListen => Branching0fromAtoB::Listen, Branching0fromAtoC::Listen;
+    }
+);
+
+// B
+type EndpointB = MeshedChannelsThree<Offer0fromBtoA, End, RoleA<RoleEnd>, NameB>;
+
+// C
+type EndpointC = MeshedChannelsThree<Offer0fromCtoA, End, RoleA<RoleEnd>, NameC>;
+
+fn protocol() -> Protocol {
+    protocol_concat!(
+        EndpointA,
+        EndpointB,
+        EndpointC
+        =>
+        [
+            EndpointAFirst,
+            Branching0fromAtoB, First,
+            Branching0fromAtoC, First,
+        ],
+        [
+            EndpointASecond,
+            Branching0fromAtoB, Second,
+            Branching0fromAtoC, Second,
+        ],
+        [
+            EndpointAQuiet,
+            Branching0fromAtoB, Quiet,
+            Branching0fromAtoC, Quiet,
+        ],
+        [
+            EndpointAListen,
+            Branching0fromAtoB, Listen,
+            Branching0fromAtoC, Listen,
+        ]
+    )
+    .unwrap()
+}
+
+fn trace(steps: &[&str]) -> Vec<String> {
+    steps.iter().map(|step| step.to_string()).collect()
+}
+
+/////////////////////////
+
+pub fn same_first_message() {
+    let protocol = protocol();
+
+    assert_eq!(
+        protocol
+            .choices(
+                &trace(&["RoleA->RoleB: Data", "RoleB->RoleC: Data"]),
+                "RoleA"
+            )
+            .unwrap(),
+        ["First"]
+    );
+    assert_eq!(
+        protocol
+            .choices(
+                &trace(&["RoleA->RoleB: Data", "RoleB->RoleA: Ack"]),
+                "RoleA"
+            )
+            .unwrap(),
+        ["Second"]
+    );
+
+    // The passive roles do not choose anything
+    assert!(protocol
+        .choices(
+            &trace(&["RoleA->RoleB: Data", "RoleB->RoleA: Ack"]),
+            "RoleB"
+        )
+        .unwrap()
+        .is_empty());
+
+    // Not a trace of the protocol
+    assert!(protocol
+        .choices(
+            &trace(&["RoleA->RoleB: Data", "RoleC->RoleA: Ack"]),
+            "RoleA"
+        )
+        .is_err());
+}
+
+pub fn branch_without_sending() {
+    let protocol = protocol();
+
+    assert_eq!(
+        protocol
+            .choices(&trace(&["RoleB->RoleC: Ack"]), "RoleA")
+            .unwrap(),
+        ["Quiet"]
+    );
+    assert_eq!(
+        protocol
+            .choices(&trace(&["RoleC->RoleA: Ack"]), "RoleA")
+            .unwrap(),
+        ["Listen"]
+    );
+}
+
+pub fn stubs_follow_the_branches() {
+    let protocol = protocol();
+
+    let mut runner = TestRunner::new(Config {
+        cases: 64,
+        failure_persistence: None,
+        ..Config::default()
+    });
+
+    let seen = RefCell::new(HashSet::new());
+
+    runner
+        .run(&protocol.traces(4), |trace| {
+            let choices = protocol.choices(&trace, "RoleA").unwrap();
+            assert_eq!(choices.len(), 1);
+
+            let script_a = protocol.script(&trace, "RoleA").unwrap();
+            let trace_a = script_a.trace();
+
+            let (thread_a, thread_b, thread_c) = fork_mpst(
+                stub::<EndpointA>(script_a),
+                stub::<EndpointB>(Script::new()),
+                stub::<EndpointC>(Script::new()),
+            );
+
+            assert!(thread_a.join().is_ok());
+            assert!(thread_b.join().is_ok());
+            assert!(thread_c.join().is_ok());
+
+            assert_eq!(trace_a.steps()[0], format!("RoleA: choose {}", choices[0]));
+
+            seen.borrow_mut().insert(choices[0].clone());
+
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(seen.borrow().len(), 4);
+}
@@ -0,0 +1,2 @@
+pub mod branches;
+pub mod o_auth;
@@ -0,0 +1,353 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::testing::property::Protocol;
+use mpstthree::testing::stub;
+use mpstthree::{
+    bundle_impl_with_enum_and_cancel, protocol_concat, stub_bundle, stub_choose, stub_offer,
+    stub_payloads,
+};
+
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestError, TestRunner};
+
+use std::error::Error;
+
+// The o_auth example, where each role is tested across all the branches of the protocol
+
+bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, C, S);
+stub_bundle!(MeshedChannelsThree, A, C, S);
+
+// Payloads
+#[derive(Default)]
+struct Start;
+#[derive(Default)]
+struct Redirect;
+#[derive(Default)]
+struct Login;
+#[derive(Default)]
+struct Auth;
+#[derive(Default)]
+struct Password(String);
+#[derive(Default)]
+struct Success;
+#[derive(Default)]
+struct Token;
+#[derive(Default)]
+struct Fail;
+#[derive(Default)]
+struct Received;
+
+stub_payloads!(
+    default: Start, Redirect, Login, Auth, Password, Success, Token, Fail, Received
+);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+type NameS = RoleS<RoleEnd>;
+
+// Types
+
+// A
+type Choose0fromAtoC = <Offer0fromCtoA as Session>::Dual;
+type Choose0fromAtoS = <Offer0fromStoA as Session>::Dual;
+
+// C
+enum Branching0fromAtoC {
+    Success(
+        MeshedChannelsThree<
+            Recv<Success, End>,
+            Send<Success, Recv<Token, End>>,
+            RoleA<RoleS<RoleS<RoleEnd>>>,
+            NameC,
+        >,
+    ),
+    Fail(
+        MeshedChannelsThree<
+            Recv<Fail, End>,
+            Send<Fail, Recv<Received, End>>,
+            RoleA<RoleS<RoleS<RoleEnd>>>,
+            NameC,
+        >,
+    ),
+}
+type Offer0fromCtoA = Recv<Branching0fromAtoC, End>;
+
+stub_offer!(Branching0fromAtoC { Success, Fail });
+
+// S
+enum Branching0fromAtoS {
+    Success(
+        MeshedChannelsThree<
+            Send<Token, Recv<Token, End>>,
+            Recv<Success, Send<Token, End>>,
+            RoleC<RoleA<RoleA<RoleC<RoleEnd>>>>,
+            NameS,
+        >,
+    ),
+    Fail(MeshedChannelsThree<End, Recv<Fail, Send<Received, End>>, RoleC<RoleC<RoleEnd>>, NameS>),
+}
+type Offer0fromStoA = Recv<Branching0fromAtoS, End>;
+
+stub_offer!(Branching0fromAtoS { Success, Fail });
+
+// Creating the MP sessions
+// A
+type EndpointASuccess = MeshedChannelsThree<
+    Send<Success, End>,
+    Recv<Token, Send<Token, End>>,
+    RoleC<RoleS<RoleS<RoleEnd>>>,
+    NameA,
+>;
+type EndpointAFail = MeshedChannelsThree<Send<Fail, End>, End, RoleC<RoleEnd>, NameA>;
+type EndpointA = MeshedChannelsThree<
+    Recv<Login, Send<Auth, Recv<Password, Choose0fromAtoC>>>,
+    Choose0fromAtoS,
+    RoleC<RoleC<RoleC<RoleBroadcast>>>,
+    NameA,
+>;
+
+stub_choose!(
+    MeshedChannelsThree<Choose0fromAtoC, Choose0fromAtoS, RoleBroadcast, NameA>,
+    choose_mpst_a_to_all,
+    {
+        Success: EndpointASuccess => Branching0fromAtoC::Success, Branching0fromAtoS::Success;
+        Fail: EndpointAFail => Branching0fromAtoC::Fail, Branching0fromAtoS::Fail;
+    }
+);
+
+// C
+type EndpointC = MeshedChannelsThree<
+    Send<Login, Recv<Auth, Send<Password, Offer0fromCtoA>>>,
+    Send<Start, Recv<Redirect, End>>,
+    RoleS<RoleS<RoleA<RoleA<RoleA<RoleA<RoleEnd>>>>>>,
+    NameC,
+>;
+
+// S
+type EndpointS = MeshedChannelsThree<
+    Offer0fromStoA,
+    Recv<Start, Send<Redirect, End>>,
+    RoleC<RoleC<RoleA<RoleEnd>>>,
+    NameS,
+>;
+
+// Functions
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    let (_, s) = s.recv()?;
+    let s = s.send(Auth {})?;
+    let (password, s) = s.recv()?;
+
+    if password.0 == "secret" {
+        let s: EndpointASuccess =
+            choose_mpst_a_to_all!(s, Branching0fromAtoC::Success, Branching0fromAtoS::Success);
+
+        let s = s.send(Success {})?;
+        let (_, s) = s.recv()?;
+        let s = s.send(Token {})?;
+        s.close()
+    } else {
+        let s: EndpointAFail =
+            choose_mpst_a_to_all!(s, Branching0fromAtoC::Fail, Branching0fromAtoS::Fail);
+
+        let s = s.send(Fail {})?;
+        s.close()
+    }
+}
+
+fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    let s = s.send(Start {})?;
+    let (_, s) = s.recv()?;
+    let s = s.send(Login {})?;
+    let (_, s) = s.recv()?;
+    let s = s.send(Password(String::from("secret")))?;
+
+    offer_mpst!(s, {
+        Branching0fromAtoC::Success(s) => {
+            let (_, s) = s.recv()?;
+            let s = s.send(Success {  })?;
+            let (_,s) = s.recv()?;
+            s.close()
+        },
+        Branching0fromAtoC::Fail(s) => {
+            let (_, s) = s.recv()?;
+            let s = s.send(Fail {  })?;
+            let (_, s) = s.recv()?;
+            s.close()
+        },
+    })
+}
+
+// Fails when A chooses Fail
+fn faulty_endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    let s = s.send(Start {})?;
+    let (_, s) = s.recv()?;
+    let s = s.send(Login {})?;
+    let (_, s) = s.recv()?;
+    let s = s.send(Password(String::from("secret")))?;
+
+    offer_mpst!(s, {
+        Branching0fromAtoC::Success(s) => {
+            let (_, s) = s.recv()?;
+            let s = s.send(Success {  })?;
+            let (_,s) = s.recv()?;
+            s.close()
+        },
+        Branching0fromAtoC::Fail(s) => {
+            let (_, s) = s.recv()?;
+            let s = s.send(Fail {  })?;
+            let (_, s) = s.recv()?;
+            s.close()?;
+            Err("Unexpected failure".into())
+        },
+    })
+}
+
+fn protocol() -> Protocol {
+    protocol_concat!(
+        EndpointA,
+        EndpointC,
+        EndpointS
+        =>
+        [
+            EndpointASuccess,
+            Branching0fromAtoC, Success,
+            Branching0fromAtoS, Success,
+        ],
+        [
+            EndpointAFail,
+            Branching0fromAtoC, Fail,
+            Branching0fromAtoS, Fail,
+        ]
+    )
+    .unwrap()
+}
+
+fn config() -> Config {
+    Config {
+        cases: 32,
+        failure_persistence: None,
+        ..Config::default()
+    }
+}
+
+/////////////////////////
+
+pub fn traces() {
+    let protocol = protocol();
+
+    // Without any decision, the shortest trace
+    let shortest = protocol.follow(&[]);
+
+    assert_eq!(shortest[0], "RoleC->RoleS: Start");
+    assert_eq!(shortest.len(), 8);
+    assert_eq!(protocol.choices(&shortest, "RoleA").unwrap().len(), 1);
+    assert!(protocol.choices(&shortest, "RoleC").unwrap().is_empty());
+
+    // Not a trace of the protocol
+    assert!(protocol
+        .choices(&["RoleA->RoleC: Success".to_string()], "RoleA")
+        .is_err());
+
+    let mut runner = TestRunner::new(config());
+
+    runner
+        .run(&protocol.traces(10), |trace| {
+            let choices = protocol.choices(&trace, "RoleA").unwrap();
+
+            prop_assert!(choices == ["Success"] || choices == ["Fail"]);
+            let step = format!("RoleA->RoleC: {}", choices[0]);
+            prop_assert!(trace.contains(&step));
+            prop_assert!(trace.len() == 8 || trace.len() == 10);
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+pub fn endpoint_a_against_stubs() {
+    let protocol = protocol();
+
+    let mut runner = TestRunner::new(config());
+
+    let passwords = prop_oneof![Just(String::from("secret")), any::<String>()];
+
+    runner
+        .run(&(protocol.traces(10), passwords), |(trace, password)| {
+            let script_c = protocol
+                .script(&trace, "RoleC")
+                .unwrap()
+                .with_values(vec![Password(password.clone())]);
+            let trace_c = script_c.trace();
+
+            let (thread_a, thread_c, thread_s) = fork_mpst(
+                endpoint_a,
+                stub::<EndpointC>(script_c),
+                stub::<EndpointS>(protocol.script(&trace, "RoleS").unwrap()),
+            );
+
+            prop_assert!(thread_a.join().is_ok());
+            prop_assert!(thread_c.join().is_ok());
+            prop_assert!(thread_s.join().is_ok());
+
+            let branch = if password == "secret" {
+                "Branching0fromAtoC::Success"
+            } else {
+                "Branching0fromAtoC::Fail"
+            };
+
+            let step = format!("RoleC?RoleA: {}", branch);
+            prop_assert!(trace_c.steps().contains(&step));
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+fn run_c(
+    protocol: &Protocol,
+    trace: &[String],
+    endpoint: fn(EndpointC) -> Result<(), Box<dyn Error>>,
+) -> Result<(), TestCaseError> {
+    let (thread_a, thread_c, thread_s) = fork_mpst(
+        stub::<EndpointA>(protocol.script(trace, "RoleA").unwrap()),
+        endpoint,
+        stub::<EndpointS>(protocol.script(trace, "RoleS").unwrap()),
+    );
+
+    prop_assert!(thread_a.join().is_ok());
+    prop_assert!(thread_c.join().is_ok());
+    prop_assert!(thread_s.join().is_ok());
+
+    Ok(())
+}
+
+pub fn endpoint_c_against_stubs() {
+    let protocol = protocol();
+
+    let mut runner = TestRunner::new(config());
+
+    runner
+        .run(&protocol.traces(10), |trace| {
+            run_c(&protocol, &trace, endpoint_c)
+        })
+        .unwrap();
+}
+
+pub fn shrink_failing_trace() {
+    let protocol = protocol();
+
+    let mut runner = TestRunner::new(config());
+
+    match runner.run(&protocol.traces(10), |trace| {
+        run_c(&protocol, &trace, faulty_endpoint_c)
+    }) {
+        Err(TestError::Fail(_, trace)) => {
+            assert!(trace.contains(&"RoleA->RoleC: Fail".to_string()));
+            assert_eq!(protocol.choices(&trace, "RoleA").unwrap(), ["Fail"]);
+            assert_eq!(trace.len(), 8);
+        }
+        result => panic!("Expected a failing trace, got {:?}", result),
+    }
+}
@@ -0,0 +1,9 @@
+#![allow(clippy::type_complexity)]
+
+mod recording_mod;
+
+#[test]
+fn recording() {
+    recording_mod::accumulator::entries();
+    recording_mod::accumulator::record_and_replay();
+}
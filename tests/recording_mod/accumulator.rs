@@ -0,0 +1,203 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::testing::recording::{record, Entry, Recorder, Recording};
+use mpstthree::testing::stub;
+use mpstthree::{bundle_impl_with_enum_and_cancel, stub_bundle, stub_choose, stub_offer};
+
+use rand::{thread_rng, Rng};
+
+use std::error::Error;
+use std::fs;
+
+// C sends random numbers to A, which forwards their sum to B
+// and fails once the sum is above LIMIT
+
+bundle_impl_with_enum_and_cancel!(recorded MeshedChannelsThree, A, B, C);
+stub_bundle!(MeshedChannelsThree, A, B, C);
+
+static LIMIT: i32 = 20;
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+
+// Types
+// A
+enum Branching0fromCtoA {
+    More(
+        MeshedChannelsThree<
+            Send<i32, End>,
+            Recv<i32, RecursAtoC>,
+            RoleC<RoleB<RoleC<RoleEnd>>>,
+            NameA,
+        >,
+    ),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameA>),
+}
+type RecursAtoC = Recv<Branching0fromCtoA, End>;
+// B
+enum Branching0fromCtoB {
+    More(MeshedChannelsThree<Recv<i32, End>, RecursBtoC, RoleA<RoleC<RoleEnd>>, NameB>),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameB>),
+}
+type RecursBtoC = Recv<Branching0fromCtoB, End>;
+// C
+type Choose0fromCtoA = Send<Branching0fromCtoA, End>;
+type Choose0fromCtoB = Send<Branching0fromCtoB, End>;
+type EndpointDoneC = MeshedChannelsThree<End, End, RoleEnd, NameC>;
+type EndpointMoreC =
+    MeshedChannelsThree<Send<i32, Choose0fromCtoA>, Choose0fromCtoB, RoleA<RoleBroadcast>, NameC>;
+
+// Creating the MP sessions
+type EndpointA = MeshedChannelsThree<End, RecursAtoC, RoleC<RoleEnd>, NameA>;
+type EndpointB = MeshedChannelsThree<End, RecursBtoC, RoleC<RoleEnd>, NameB>;
+type EndpointC = MeshedChannelsThree<Choose0fromCtoA, Choose0fromCtoB, RoleBroadcast, NameC>;
+
+// Stubs for the replay
+stub_offer!(Branching0fromCtoB { More, Done });
+stub_choose!(
+    EndpointC,
+    choose_mpst_c_to_all,
+    {
+        More: EndpointMoreC => Branching0fromCtoA::More, Branching0fromCtoB::More;
+        Done: EndpointDoneC => Branching0fromCtoA::Done, Branching0fromCtoB::Done;
+    }
+);
+
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    recurs_a(s, 0)
+}
+
+fn recurs_a(s: EndpointA, sum: i32) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromCtoA::Done(s) => {
+            s.close()
+        },
+        Branching0fromCtoA::More(s) => {
+            let (x, s) = s.recv()?;
+            let sum = sum + x;
+            if sum > LIMIT {
+                return Err(format!("{} is above the limit", sum).into());
+            }
+            let s = s.send(sum)?;
+            recurs_a(s, sum)
+        },
+    })
+}
+
+fn endpoint_b(s: EndpointB) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromCtoB::Done(s) => {
+            s.close()
+        },
+        Branching0fromCtoB::More(s) => {
+            let (_, s) = s.recv()?;
+            endpoint_b(s)
+        },
+    })
+}
+
+fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    recurs_c(s, 10)
+}
+
+fn recurs_c(s: EndpointC, loops: i32) -> Result<(), Box<dyn Error>> {
+    if loops == 0 {
+        let s: EndpointDoneC =
+            choose_mpst_c_to_all!(s, Branching0fromCtoA::Done, Branching0fromCtoB::Done);
+
+        s.close()
+    } else {
+        let s: EndpointMoreC =
+            choose_mpst_c_to_all!(s, Branching0fromCtoA::More, Branching0fromCtoB::More);
+
+        let s = s.send(thread_rng().gen_range(5..=10))?;
+
+        recurs_c(s, loops - 1)
+    }
+}
+
+/////////////////////////////////////////
+
+pub fn entries() {
+    let entry = Entry {
+        clock: 3,
+        step: String::from("RoleA!RoleB: String"),
+        payload: Some(String::from("two\nlines = \\")),
+    };
+
+    assert_eq!(
+        entry.to_string(),
+        "3 RoleA!RoleB: String = two\\nlines = \\\\"
+    );
+    assert_eq!(entry.to_string().parse::<Entry>().unwrap(), entry);
+
+    let recording = "1 RoleC: choose More\n2 RoleC!RoleA: i32 = 7\n\n3 RoleA: close\n"
+        .parse::<Recording>()
+        .unwrap();
+
+    assert_eq!(recording.entries().len(), 3);
+    assert_eq!(
+        recording.steps("RoleC"),
+        vec!["RoleC: choose More", "RoleC!RoleA: i32 = 7"]
+    );
+    assert_eq!(recording.branches("RoleC"), vec!["More"]);
+    assert!("RoleA: close".parse::<Recording>().is_err());
+}
+
+pub fn record_and_replay() {
+    let path = std::env::temp_dir().join(format!("mpstthree_recording_{}.log", std::process::id()));
+
+    // The production run, where A fails
+    let recorder = Recorder::create(&path)
+        .unwrap()
+        .with_serializer(|x: &i32| x.to_string());
+
+    let (thread_a, thread_b, thread_c) = fork_mpst(
+        record(&recorder, endpoint_a),
+        record(&recorder, endpoint_b),
+        record(&recorder, endpoint_c),
+    );
+
+    // B and C may end before noticing the failure of A
+    assert!(thread_a.join().is_err());
+    let _ = thread_b.join();
+    let _ = thread_c.join();
+
+    // The clocks follow the order of the steps
+    let recording = Recording::load(&path)
+        .unwrap()
+        .with_deserializer(|x: &str| x.parse::<i32>());
+
+    assert_eq!(recording.entries(), recorder.recording().entries());
+    assert!(recording
+        .entries()
+        .windows(2)
+        .all(|pair| pair[0].clock < pair[1].clock));
+
+    let steps_a = recording.steps("RoleA");
+    assert!(!steps_a.is_empty());
+    assert!(steps_a
+        .iter()
+        .all(|step| step.starts_with("RoleA!RoleB: i32 = ")));
+
+    // The replay of A against the recorded B and C
+    let replay = Recorder::new().with_serializer(|x: &i32| x.to_string());
+
+    let (thread_a, thread_b, thread_c) = fork_mpst(
+        record(&replay, endpoint_a),
+        stub::<EndpointB>(recording.script("RoleB").unwrap()),
+        stub::<EndpointC>(recording.script("RoleC").unwrap()),
+    );
+
+    // B and C may end before noticing the failure of A
+    assert!(thread_a.join().is_err());
+    let _ = thread_b.join();
+    let _ = thread_c.join();
+
+    assert_eq!(replay.recording().steps("RoleA"), steps_a);
+
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,27 @@
+mod rendezvous_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn rendezvous_mesh() {
+    rendezvous_mod::mesh::three_roles();
+    rendezvous_mod::mesh::concurrent_sessions();
+    rendezvous_mod::mesh::role_reconnected();
+    rendezvous_mod::mesh::silent_connection();
+    rendezvous_mod::mesh::forked_endpoints();
+}
+
+#[test]
+#[timeout(60000)]
+fn rendezvous_errors() {
+    rendezvous_mod::errors::unknown_role();
+    rendezvous_mod::errors::other_roles();
+    rendezvous_mod::errors::registered_twice();
+}
+
+#[test]
+#[timeout(60000)]
+fn rendezvous_command_line() {
+    rendezvous_mod::cli::serve();
+}
@@ -0,0 +1,41 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+
+static ROLES: &[&str] = &["A", "B", "C"];
+
+pub fn serve() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mpst"))
+        .args(["rendezvous", "127.0.0.1:0"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let addr = line
+        .trim()
+        .strip_prefix("Listening on ")
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let threads = ROLES
+        .iter()
+        .map(|role| super::join_in_thread(addr, "cli", role, ROLES))
+        .collect::<Vec<_>>();
+    let results = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect::<Vec<_>>();
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    for mesh in results {
+        let mesh = mesh.unwrap();
+        assert_eq!(mesh.peers().len(), 2);
+        assert!(!mesh.peers().contains(&mesh.role()));
+    }
+}
@@ -0,0 +1,59 @@
+use mpstthree::transport::tcp::rendezvous::{join, Coordinator};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::thread::sleep;
+use std::time::Duration;
+
+// Register with a raw connection, returning the answer of the coordinator
+fn register(coordinator: &TcpStream, line: &str) -> String {
+    let mut stream = coordinator;
+    writeln!(stream, "{}", line).unwrap();
+    let mut answer = String::new();
+    BufReader::new(coordinator).read_line(&mut answer).unwrap();
+    answer
+}
+
+pub fn unknown_role() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().spawn().unwrap();
+    let addr = coordinator.local_addr();
+
+    let error = join(addr, "session", "D", &["A", "B"]).unwrap_err();
+    assert_eq!(error.to_string(), "D is not one of the roles");
+
+    let stream = TcpStream::connect(addr).unwrap();
+    assert_eq!(
+        register(&stream, "REGISTER session D A,B 127.0.0.1:1"),
+        "ERROR D is not a role of the session session\n"
+    );
+}
+
+pub fn other_roles() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().spawn().unwrap();
+    let addr = coordinator.local_addr();
+
+    // The registrations are read concurrently, let the first one be registered
+    let first = TcpStream::connect(addr).unwrap();
+    writeln!(&first, "REGISTER session A A,B 127.0.0.1:1").unwrap();
+    sleep(Duration::from_millis(200));
+
+    let second = TcpStream::connect(addr).unwrap();
+    assert_eq!(
+        register(&second, "REGISTER session B A,B,C 127.0.0.1:1"),
+        "ERROR The roles of the session session are A,B\n"
+    );
+}
+
+pub fn registered_twice() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().spawn().unwrap();
+    let addr = coordinator.local_addr();
+
+    let first = TcpStream::connect(addr).unwrap();
+    writeln!(&first, "REGISTER session A A,B 127.0.0.1:1").unwrap();
+    sleep(Duration::from_millis(200));
+
+    let second = TcpStream::connect(addr).unwrap();
+    assert_eq!(
+        register(&second, "REGISTER session A A,B 127.0.0.1:1"),
+        "ERROR A is already registered in the session session\n"
+    );
+}
@@ -0,0 +1,170 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::meshedchannels::MeshedChannels;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::transport::tcp::recv::recv_tcp;
+use mpstthree::transport::tcp::rendezvous::{fork_mesh, Coordinator};
+use mpstthree::transport::tcp::send::send_tcp;
+use std::error::Error;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::thread::spawn;
+use std::time::{Duration, Instant};
+
+static ROLES: &[&str] = &["A", "B", "C"];
+
+pub fn three_roles() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().spawn().unwrap();
+    let addr = coordinator.local_addr();
+
+    let threads = ROLES
+        .iter()
+        .map(|role| super::join_in_thread(addr, "three", role, ROLES))
+        .collect::<Vec<_>>();
+    let meshes = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap().unwrap())
+        .collect::<Vec<_>>();
+
+    // The peers are in the order of the sessions of the MeshedChannels
+    assert_eq!(meshes[0].peers(), vec!["B", "C"]);
+    assert_eq!(meshes[1].peers(), vec!["A", "C"]);
+    assert_eq!(meshes[2].peers(), vec!["A", "B"]);
+
+    let greetings = meshes
+        .into_iter()
+        .map(|mesh| spawn(move || super::greet(&mesh)))
+        .collect::<Vec<_>>();
+    for greeting in greetings {
+        assert!(greeting.join().is_ok());
+    }
+}
+
+// A registers, drops out before B joins, then joins again
+pub fn role_reconnected() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().spawn().unwrap();
+    let addr = coordinator.local_addr();
+
+    let dropped = TcpStream::connect(addr).unwrap();
+    writeln!(&dropped, "REGISTER again A A,B 127.0.0.1:1").unwrap();
+    drop(dropped);
+
+    let thread_a = super::join_in_thread(addr, "again", "A", &["A", "B"]);
+    let thread_b = super::join_in_thread(addr, "again", "B", &["A", "B"]);
+
+    let mesh_a = thread_a.join().unwrap().unwrap();
+    let mesh_b = thread_b.join().unwrap().unwrap();
+
+    let greeting = spawn(move || super::greet(&mesh_a));
+    super::greet(&mesh_b);
+    assert!(greeting.join().is_ok());
+}
+
+// A connection which never registers does not delay the other roles
+pub fn silent_connection() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().spawn().unwrap();
+    let addr = coordinator.local_addr();
+
+    let start = Instant::now();
+    let _silent = TcpStream::connect(addr).unwrap();
+
+    let threads = ROLES
+        .iter()
+        .map(|role| super::join_in_thread(addr, "silent", role, ROLES))
+        .collect::<Vec<_>>();
+    for thread in threads {
+        assert!(thread.join().unwrap().is_ok());
+    }
+
+    // Well before the timeout of the registrations
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+type EndpointMesh = MeshedChannels<End, End, RoleEnd, RoleEnd>;
+
+// Each role runs its endpoint with the streams of its mesh,
+// in the order of its sessions
+pub fn forked_endpoints() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().spawn().unwrap();
+    let addr = coordinator.local_addr();
+
+    let threads = ROLES
+        .iter()
+        .map(|role| super::join_in_thread(addr, "forked", role, ROLES))
+        .collect::<Vec<_>>();
+    let meshes = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap().unwrap())
+        .collect::<Vec<_>>();
+
+    let forks = meshes
+        .into_iter()
+        .map(|mesh| {
+            let role = mesh.role().to_string();
+            let peers = mesh
+                .peers()
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<_>>();
+
+            let p = move |s: EndpointMesh, streams: Vec<TcpStream>| -> Result<(), Box<dyn Error>> {
+                drop(s);
+                for mut stream in &streams {
+                    writeln!(stream, "{}", role)?;
+                }
+                for (stream, peer) in streams.iter().zip(peers) {
+                    let mut line = String::new();
+                    BufReader::new(stream).read_line(&mut line)?;
+                    assert_eq!(line, format!("{}\n", peer));
+                }
+                Ok(())
+            };
+
+            fork_mesh(p, mesh).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    for (thread, _s) in forks {
+        assert!(thread.join().is_ok());
+    }
+}
+
+type Data = (i32, [u8; 128]);
+type BinaryA = Send<Data, Recv<Data, End>>;
+
+pub fn concurrent_sessions() {
+    let coordinator = Coordinator::bind("127.0.0.1:0").unwrap().spawn().unwrap();
+    let addr = coordinator.local_addr();
+
+    // The registrations of both sessions are interleaved
+    let first_a = super::join_in_thread(addr, "first", "A", &["A", "B"]);
+    let second_b = super::join_in_thread(addr, "second", "B", &["A", "B"]);
+    let first_b = super::join_in_thread(addr, "first", "B", &["A", "B"]);
+    let second_a = super::join_in_thread(addr, "second", "A", &["A", "B"]);
+
+    for (a, b) in [(first_a, first_b), (second_a, second_b)] {
+        let mesh_a = a.join().unwrap().unwrap();
+        let mesh_b = b.join().unwrap().unwrap();
+        assert_eq!(mesh_a.session(), mesh_b.session());
+
+        // A binary session over the stream between A and B
+        let stream_a = mesh_a.into_streams().pop().unwrap();
+        let stream_b = mesh_b.into_streams().pop().unwrap();
+        let (s_a, s_b) = BinaryA::new();
+
+        let thread_b = spawn(move || {
+            let (x, s, data, r, stream) = recv_tcp(s_b, stream_b, true).unwrap();
+            assert_eq!(r, 128);
+            let (s, _stream) = send_tcp(x + 1, &data, s, stream, true).unwrap();
+            s
+        });
+
+        let (s, stream) = send_tcp(1, &[7_u8; 128], s_a, stream_a, true).unwrap();
+        let (x, s, data, r, _stream) = recv_tcp(s, stream, true).unwrap();
+        assert_eq!(x, 2);
+        assert_eq!(r, 128);
+        assert_eq!(data, [7_u8; 128]);
+
+        let s_b = thread_b.join().unwrap();
+        drop((s, s_b));
+    }
+}
@@ -0,0 +1,34 @@
+pub mod cli;
+pub mod errors;
+pub mod mesh;
+
+use mpstthree::transport::tcp::rendezvous::{join, Mesh};
+use std::io::{BufRead, BufReader, Write};
+use std::net::SocketAddr;
+use std::thread::{spawn, JoinHandle};
+
+// Join `session` as `role` in another thread, as another process would
+pub fn join_in_thread(
+    coordinator: SocketAddr,
+    session: &'static str,
+    role: &'static str,
+    roles: &'static [&'static str],
+) -> JoinHandle<Result<Mesh, String>> {
+    spawn(move || join(coordinator, session, role, roles).map_err(|e| e.to_string()))
+}
+
+// Send the name of the role to each other role,
+// and check the names received
+pub fn greet(mesh: &Mesh) {
+    for peer in mesh.peers() {
+        let mut stream = mesh.stream(peer).unwrap();
+        writeln!(stream, "{} from {}", mesh.session(), mesh.role()).unwrap();
+    }
+    for peer in mesh.peers() {
+        let mut line = String::new();
+        BufReader::new(mesh.stream(peer).unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        assert_eq!(line, format!("{} from {}\n", mesh.session(), peer));
+    }
+}
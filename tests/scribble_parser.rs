@@ -0,0 +1,28 @@
+mod scribble_parser_mod;
+
+#[test]
+fn scribble_files() {
+    scribble_parser_mod::parse::supported_protocols();
+    scribble_parser_mod::parse::errors();
+    scribble_parser_mod::parse::normalisation();
+    scribble_parser_mod::parse::projection();
+    scribble_parser_mod::parse::generation();
+}
+
+#[test]
+fn generated_code() {
+    scribble_parser_mod::fib::run();
+    scribble_parser_mod::fib::same_graphs();
+    scribble_parser_mod::three_buyers::run();
+}
+
+#[test]
+fn build_script() {
+    scribble_parser_mod::builder::compile();
+    scribble_parser_mod::builder::errors();
+}
+
+#[test]
+fn command_line() {
+    scribble_parser_mod::cli::commands();
+}
@@ -0,0 +1,80 @@
+use mpstthree::scribble::build::Builder;
+use mpstthree::scribble::Protocol;
+
+use std::env::temp_dir;
+use std::fs::{copy, create_dir_all, metadata, read_to_string, remove_dir_all};
+use std::path::PathBuf;
+
+// A folder of protocols, copied from the scribble_protocols folder
+fn protocols(name: &str, files: &[&str]) -> PathBuf {
+    let dir = temp_dir().join(name);
+    let _ = remove_dir_all(&dir);
+    create_dir_all(dir.join("protocols")).unwrap();
+
+    for file in files.iter() {
+        copy(
+            format!("scribble_protocols/{}.scr", file),
+            dir.join("protocols").join(format!("{}.scr", file)),
+        )
+        .unwrap();
+    }
+
+    dir
+}
+
+pub fn compile() {
+    let dir = protocols("mpst_build_compile", &["three_buyers", "fib"]);
+
+    let builder = Builder::new()
+        .protocols(dir.join("protocols"))
+        .out_dir(dir.join("out"))
+        .rerun_if_changed(false);
+
+    let files = builder.compile().unwrap();
+    assert_eq!(
+        files,
+        vec![
+            dir.join("out").join("fib.rs"),
+            dir.join("out").join("three_buyers.rs")
+        ]
+    );
+
+    // Same items as `mpst generate`, without the inner attributes
+    let code = read_to_string(&files[0]).unwrap();
+    let generated = Protocol::load("scribble_protocols/fib.scr")
+        .unwrap()
+        .generate()
+        .unwrap();
+    assert!(code.starts_with("// Generated from "));
+    assert!(!code.contains("#!["));
+    assert!(generated.ends_with(code.split_once("\n\n").unwrap().1));
+
+    // Unchanged files are not rewritten
+    let modified = metadata(&files[0]).unwrap().modified().unwrap();
+    assert_eq!(builder.compile().unwrap(), files);
+    assert_eq!(metadata(&files[0]).unwrap().modified().unwrap(), modified);
+
+    remove_dir_all(dir).unwrap();
+}
+
+pub fn errors() {
+    let dir = protocols("mpst_build_errors", &["fib", "circuit_breaker"]);
+
+    let error = Builder::new()
+        .protocols(dir.join("protocols"))
+        .out_dir(dir.join("out"))
+        .rerun_if_changed(false)
+        .compile()
+        .unwrap_err()
+        .to_string();
+    assert!(error.ends_with("circuit_breaker.scr: line 13: unknown role Api"));
+
+    // Without OUT_DIR, outside of a build script
+    assert!(Builder::new()
+        .protocols(dir.join("protocols"))
+        .rerun_if_changed(false)
+        .compile()
+        .is_err());
+
+    remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,70 @@
+use std::env::temp_dir;
+use std::fs::{read_to_string, remove_dir_all};
+use std::process::{Command, Output};
+
+fn mpst(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mpst"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+pub fn commands() {
+    // project
+    let output = mpst(&["project", "scribble_protocols/fib.scr", "--role", "A"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .starts_with("local protocol Fibonacci_A(self A, role B) {"));
+
+    // generate
+    let output = mpst(&["generate", "scribble_protocols/three_buyers.scr"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, C, S);"));
+
+    // check
+    let output = mpst(&["check", "scribble_protocols/logging.scr"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Protocol Logging: 2 roles, 6 global states, 9 global transitions\n\
+        Deadlock-free: yes\n\
+        Deadlock-free with cancellation: yes\n"
+    );
+
+    // graph
+    let output_dir = temp_dir().join("mpst_cli_graphs");
+    let output = mpst(&[
+        "graph",
+        "scribble_protocols/fib.scr",
+        "--format",
+        "dot",
+        "--format",
+        "json",
+        "--output",
+        output_dir.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 4);
+    assert!(read_to_string(output_dir.join("Fibonacci_RoleA.dot"))
+        .unwrap()
+        .contains("RoleA!RoleB: Fibonacci"));
+    assert!(read_to_string(output_dir.join("Fibonacci.json")).is_ok());
+    remove_dir_all(output_dir).unwrap();
+
+    // errors
+    let output = mpst(&["check", "scribble_protocols/circuit_breaker.scr"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "scribble_protocols/circuit_breaker.scr: line 13: unknown role Api\n"
+    );
+
+    let output = mpst(&["draw"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("Usage:"));
+}
@@ -0,0 +1,131 @@
+// Generated from the Scribble protocol Fibonacci by `mpst generate`
+
+#![allow(clippy::type_complexity, dead_code)]
+
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::bundle_impl_with_enum_and_cancel;
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+
+// Create the new MeshedChannels for 2 participants and the close and fork functions
+bundle_impl_with_enum_and_cancel!(MeshedChannelsTwo, A, B);
+
+// Payload types
+struct Fibonacci(i64);
+struct Stop;
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+
+// Types
+// A
+type Choose0fromAToB = Send<Branching0fromAToB, End>;
+// B
+enum Branching0fromAToB {
+    Fibonacci(
+        MeshedChannelsTwo<
+            Recv<Fibonacci, Send<Fibonacci, Offer0fromAToB>>,
+            RoleA<RoleA<RoleA<RoleEnd>>>,
+            NameB,
+        >,
+    ),
+    Stop(MeshedChannelsTwo<Recv<Stop, End>, RoleA<RoleEnd>, NameB>),
+}
+type Offer0fromAToB = Recv<Branching0fromAToB, End>;
+
+// Creating the MP sessions
+// A
+type EndpointA = MeshedChannelsTwo<Choose0fromAToB, RoleBroadcast, NameA>;
+type EndpointA0Fibonacci = MeshedChannelsTwo<
+    Send<Fibonacci, Recv<Fibonacci, Choose0fromAToB>>,
+    RoleB<RoleB<RoleBroadcast>>,
+    NameA,
+>;
+type EndpointA0Stop = MeshedChannelsTwo<Send<Stop, End>, RoleB<RoleEnd>, NameA>;
+// B
+type EndpointB = MeshedChannelsTwo<Offer0fromAToB, RoleA<RoleEnd>, NameB>;
+
+/////////////////////////////////////////
+
+use mpstthree::checker_concat;
+use mpstthree::checking::product::synchronous_product;
+use mpstthree::scribble::Protocol;
+
+use std::error::Error;
+
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    recurs_a(s, 10, 1)
+}
+
+fn recurs_a(s: EndpointA, index: i64, old: i64) -> Result<(), Box<dyn Error>> {
+    match index {
+        0 => {
+            let s: EndpointA0Stop = choose_mpst_a_to_all!(s, Branching0fromAToB::Stop);
+            let s = s.send(Stop {})?;
+            s.close()
+        }
+        i => {
+            let s: EndpointA0Fibonacci = choose_mpst_a_to_all!(s, Branching0fromAToB::Fibonacci);
+            let s = s.send(Fibonacci(old))?;
+            let (Fibonacci(new), s) = s.recv()?;
+            recurs_a(s, i - 1, new)
+        }
+    }
+}
+
+fn endpoint_b(s: EndpointB) -> Result<(), Box<dyn Error>> {
+    recurs_b(s, 0)
+}
+
+fn recurs_b(s: EndpointB, old: i64) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromAToB::Stop(s) => {
+            let (_, s) = s.recv()?;
+            s.close()
+        },
+        Branching0fromAToB::Fibonacci(s) => {
+            let (Fibonacci(new), s) = s.recv()?;
+            let s = s.send(Fibonacci(new + old))?;
+            recurs_b(s, new + old)
+        },
+    })
+}
+
+pub fn run() {
+    let (thread_a, thread_b) = fork_mpst(endpoint_a, endpoint_b);
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_b.join().is_ok());
+}
+
+pub fn same_graphs() {
+    let (graphs, _) = checker_concat!(
+        EndpointA,
+        EndpointB
+        =>
+        [
+            EndpointA0Fibonacci,
+            Branching0fromAToB, Fibonacci
+        ],
+        [
+            EndpointA0Stop,
+            Branching0fromAToB, Stop
+        ]
+    )
+    .unwrap();
+
+    let protocol = Protocol::load("scribble_protocols/fib.scr").unwrap();
+
+    let labels = |graph: &petgraph::Graph<String, String>| {
+        let mut labels = graph.edge_weights().cloned().collect::<Vec<_>>();
+        labels.sort();
+        labels
+    };
+
+    let global = synchronous_product(&graphs);
+    let global_scribble = synchronous_product(&protocol.graphs());
+
+    assert_eq!(global.node_count(), global_scribble.node_count());
+    assert_eq!(labels(&global), labels(&global_scribble));
+}
@@ -0,0 +1,5 @@
+pub mod builder;
+pub mod cli;
+pub mod fib;
+pub mod parse;
+pub mod three_buyers;
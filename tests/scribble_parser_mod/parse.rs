@@ -0,0 +1,158 @@
+use mpstthree::checking::deadlock::check_interleaved;
+use mpstthree::scribble::{Global, Protocol};
+
+use std::fs::read_to_string;
+
+// The protocols of the scribble_protocols folder within the supported subset
+static SUPPORTED: [&str; 6] = [
+    "fib",
+    "logging",
+    "o_auth",
+    "simple_voting",
+    "three_buyers",
+    "travel_three",
+];
+
+pub fn supported_protocols() {
+    for name in SUPPORTED.iter() {
+        let protocol = Protocol::load(format!("scribble_protocols/{}.scr", name)).unwrap();
+
+        for role in protocol.roles() {
+            assert!(protocol.project(role).is_ok());
+        }
+        assert!(protocol.generate().is_ok());
+
+        let graphs = protocol.graphs();
+        assert_eq!(graphs.len(), protocol.roles().len());
+        assert!(check_interleaved(&[(protocol.name(), &graphs)], &[], 1)
+            .unwrap()
+            .is_none());
+    }
+}
+
+pub fn errors() {
+    let error = |file: &str| {
+        Protocol::parse(&read_to_string(format!("scribble_protocols/{}.scr", file)).unwrap())
+            .unwrap_err()
+            .to_string()
+    };
+
+    assert_eq!(error("circuit_breaker"), "line 13: unknown role Api");
+    assert_eq!(
+        error("distributed_calc"),
+        "line 4: expected `;` after the message, found `element_2`"
+    );
+    assert_eq!(
+        error("video_stream"),
+        "line 15: expected `}` at the end of a block"
+    );
+
+    assert_eq!(
+        Protocol::parse("global protocol P(role A) { }")
+            .unwrap_err()
+            .to_string(),
+        "line 1: a protocol needs at least two roles"
+    );
+    assert_eq!(
+        Protocol::parse("global protocol P(role A, role B) {\n    m() from A to A;\n}")
+            .unwrap_err()
+            .to_string(),
+        "line 2: role A sends a message to itself"
+    );
+    assert_eq!(
+        Protocol::parse(
+            "global protocol P(role A, role B) {\n    rec X {\n        continue Y;\n    }\n}"
+        )
+        .unwrap_err()
+        .to_string(),
+        "line 3: unknown rec Y"
+    );
+    assert_eq!(
+        Protocol::parse(
+            "global protocol P(role A, role B) {\n    rec X {\n        m() from A to B;\n        continue X;\n    }\n}"
+        )
+        .unwrap_err()
+        .to_string(),
+        "rec X: `continue X` is reached without any choice"
+    );
+    assert_eq!(
+        Protocol::parse("global protocol P(role A, role B) {\n    do Q(A, B);\n}")
+            .unwrap_err()
+            .to_string(),
+        "line 2: only `do P(A, B)` is supported"
+    );
+}
+
+pub fn normalisation() {
+    let protocol = Protocol::load("scribble_protocols/simple_voting.scr").unwrap();
+
+    // The last message is copied into both branches of the inner choice
+    match &protocol.body()[1] {
+        Global::Choice { at, branches } => {
+            assert_eq!(at, "SERVER");
+            match branches[0].last() {
+                Some(Global::Choice { branches, .. }) => {
+                    for branch in branches.iter() {
+                        assert!(matches!(
+                            branch.last(),
+                            Some(Global::Message { label, .. }) if label == "Result"
+                        ));
+                    }
+                }
+                _ => panic!("Expected a choice at VOTER"),
+            }
+        }
+        _ => panic!("Expected a choice at SERVER"),
+    }
+
+    // `do Booking(C, A, S)` becomes a recursion
+    let protocol = Protocol::load("scribble_protocols/travel_three.scr").unwrap();
+    assert!(matches!(&protocol.body()[0], Global::Rec { name, .. } if name == "Booking"));
+}
+
+pub fn projection() {
+    let protocol = Protocol::load("scribble_protocols/fib.scr").unwrap();
+
+    assert_eq!(
+        protocol.project("B").unwrap(),
+        "local protocol Fibonacci_B(role A, self B) {\n    \
+            rec Fib {\n        \
+                choice at A {\n            \
+                    fibonacci(Long) from A;\n            \
+                    fibonacci(Long) to A;\n            \
+                    continue Fib;\n        \
+                } or {\n            \
+                    stop() from A;\n        \
+                }\n    \
+            }\n\
+        }\n"
+    );
+
+    assert!(protocol.project("C").is_err());
+}
+
+// Compare the generated code with the one checked into the tests,
+// which has been formatted with rustfmt.
+pub fn generation() {
+    let normalise = |code: &str| {
+        code.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .replace(",>", ">")
+            .replace(",)", ")")
+    };
+
+    for name in ["fib", "three_buyers"].iter() {
+        let generated = Protocol::load(format!("scribble_protocols/{}.scr", name))
+            .unwrap()
+            .generate()
+            .unwrap();
+        let expected = read_to_string(format!("tests/scribble_parser_mod/{}.rs", name)).unwrap();
+        let expected = expected
+            .split("/////////////////////////////////////////")
+            .next()
+            .unwrap();
+
+        assert_eq!(normalise(&generated), normalise(expected));
+    }
+}
@@ -0,0 +1,140 @@
+// Generated from the Scribble protocol ThreeBuyer by `mpst generate`
+
+#![allow(clippy::type_complexity, dead_code)]
+
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::bundle_impl_with_enum_and_cancel;
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+
+// Create the new MeshedChannels for 3 participants and the close and fork functions
+bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, C, S);
+
+// Payload types
+struct Empty1(i32);
+struct Empty2(i32);
+struct Empty3(i32);
+struct Empty4(i32);
+struct OkMessage(i32);
+struct Empty5(i32);
+struct Quit;
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+type NameS = RoleS<RoleEnd>;
+
+// Types
+// A
+enum Branching0fromCToA {
+    Ok(MeshedChannelsThree<Recv<OkMessage, End>, End, RoleC<RoleEnd>, NameA>),
+    Quit(MeshedChannelsThree<Recv<Quit, End>, End, RoleC<RoleEnd>, NameA>),
+}
+type Offer0fromCToA = Recv<Branching0fromCToA, End>;
+// C
+type Choose0fromCToA = Send<Branching0fromCToA, End>;
+type Choose0fromCToS = Send<Branching0fromCToS, End>;
+// S
+enum Branching0fromCToS {
+    Ok(MeshedChannelsThree<End, Recv<OkMessage, Send<Empty5, End>>, RoleC<RoleC<RoleEnd>>, NameS>),
+    Quit(MeshedChannelsThree<End, Recv<Quit, End>, RoleC<RoleEnd>, NameS>),
+}
+type Offer0fromCToS = Recv<Branching0fromCToS, End>;
+
+// Creating the MP sessions
+// A
+type EndpointA = MeshedChannelsThree<
+    Send<Empty4, Offer0fromCToA>,
+    Send<Empty1, Recv<Empty2, End>>,
+    RoleS<RoleS<RoleC<RoleC<RoleEnd>>>>,
+    NameA,
+>;
+// C
+type EndpointC = MeshedChannelsThree<
+    Recv<Empty4, Choose0fromCToA>,
+    Recv<Empty3, Choose0fromCToS>,
+    RoleS<RoleA<RoleBroadcast>>,
+    NameC,
+>;
+type EndpointC0Ok = MeshedChannelsThree<
+    Send<OkMessage, End>,
+    Send<OkMessage, Recv<Empty5, End>>,
+    RoleA<RoleS<RoleS<RoleEnd>>>,
+    NameC,
+>;
+type EndpointC0Quit =
+    MeshedChannelsThree<Send<Quit, End>, Send<Quit, End>, RoleA<RoleS<RoleEnd>>, NameC>;
+// S
+type EndpointS = MeshedChannelsThree<
+    Recv<Empty1, Send<Empty2, End>>,
+    Send<Empty3, Offer0fromCToS>,
+    RoleA<RoleA<RoleC<RoleC<RoleEnd>>>>,
+    NameS,
+>;
+
+/////////////////////////////////////////
+
+use std::error::Error;
+
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    let s = s.send(Empty1(1))?;
+    let (Empty2(price), s) = s.recv()?;
+    let s = s.send(Empty4(price / 2))?;
+
+    offer_mpst!(s, {
+        Branching0fromCToA::Ok(s) => {
+            let (_, s) = s.recv()?;
+            s.close()
+        },
+        Branching0fromCToA::Quit(s) => {
+            let (_, s) = s.recv()?;
+            s.close()
+        },
+    })
+}
+
+fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    let (Empty3(price), s) = s.recv()?;
+    let (Empty4(contribution), s) = s.recv()?;
+
+    if price - contribution <= 50 {
+        let s: EndpointC0Ok =
+            choose_mpst_c_to_all!(s, Branching0fromCToA::Ok, Branching0fromCToS::Ok);
+        let s = s.send(OkMessage(price - contribution))?;
+        let s = s.send(OkMessage(price - contribution))?;
+        let (_, s) = s.recv()?;
+        s.close()
+    } else {
+        let s: EndpointC0Quit =
+            choose_mpst_c_to_all!(s, Branching0fromCToA::Quit, Branching0fromCToS::Quit);
+        let s = s.send(Quit {})?;
+        let s = s.send(Quit {})?;
+        s.close()
+    }
+}
+
+fn endpoint_s(s: EndpointS) -> Result<(), Box<dyn Error>> {
+    let (_, s) = s.recv()?;
+    let s = s.send(Empty2(80))?;
+    let s = s.send(Empty3(80))?;
+
+    offer_mpst!(s, {
+        Branching0fromCToS::Ok(s) => {
+            let (OkMessage(paid), s) = s.recv()?;
+            let s = s.send(Empty5(paid))?;
+            s.close()
+        },
+        Branching0fromCToS::Quit(s) => {
+            let (_, s) = s.recv()?;
+            s.close()
+        },
+    })
+}
+
+pub fn run() {
+    let (thread_a, thread_c, thread_s) = fork_mpst(endpoint_a, endpoint_c, endpoint_s);
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_c.join().is_ok());
+    assert!(thread_s.join().is_ok());
+}
@@ -0,0 +1,12 @@
+#![allow(clippy::type_complexity)]
+
+mod testing_mod;
+
+#[test]
+fn stubs() {
+    testing_mod::o_auth::stub_c_and_s();
+    testing_mod::o_auth::stub_a_and_s();
+    testing_mod::o_auth::stub_missing_branch();
+    testing_mod::o_auth::stub_failing_inspector();
+    testing_mod::send_to_all::stub_send_to_all();
+}
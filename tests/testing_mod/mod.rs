@@ -0,0 +1,2 @@
+pub mod o_auth;
+pub mod send_to_all;
@@ -0,0 +1,282 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::testing::{stub, Script};
+use mpstthree::{
+    bundle_impl_with_enum_and_cancel, stub_bundle, stub_choose, stub_offer, stub_payloads,
+};
+
+use std::error::Error;
+
+// The o_auth example, where each role is tested on its own
+
+bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, C, S);
+stub_bundle!(MeshedChannelsThree, A, C, S);
+
+// Payloads
+#[derive(Default)]
+struct Start;
+#[derive(Default)]
+struct Redirect;
+#[derive(Default)]
+struct Login;
+#[derive(Default)]
+struct Auth;
+struct Password(String);
+#[derive(Default)]
+struct Success;
+#[derive(Default)]
+struct Token;
+#[derive(Default)]
+struct Fail;
+#[derive(Default)]
+struct Received;
+
+stub_payloads!(default: Start, Redirect, Login, Auth, Success, Token, Fail, Received);
+stub_payloads!(Password);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+type NameS = RoleS<RoleEnd>;
+
+// Types
+
+// A
+type Choose0fromAtoC = <Offer0fromCtoA as Session>::Dual;
+type Choose0fromAtoS = <Offer0fromStoA as Session>::Dual;
+
+// C
+enum Branching0fromAtoC {
+    Success(
+        MeshedChannelsThree<
+            Recv<Success, End>,
+            Send<Success, Recv<Token, End>>,
+            RoleA<RoleS<RoleS<RoleEnd>>>,
+            NameC,
+        >,
+    ),
+    Fail(
+        MeshedChannelsThree<
+            Recv<Fail, End>,
+            Send<Fail, Recv<Received, End>>,
+            RoleA<RoleS<RoleS<RoleEnd>>>,
+            NameC,
+        >,
+    ),
+}
+type Offer0fromCtoA = Recv<Branching0fromAtoC, End>;
+
+stub_offer!(Branching0fromAtoC { Success, Fail });
+
+// S
+enum Branching0fromAtoS {
+    Success(
+        MeshedChannelsThree<
+            Send<Token, Recv<Token, End>>,
+            Recv<Success, Send<Token, End>>,
+            RoleC<RoleA<RoleA<RoleC<RoleEnd>>>>,
+            NameS,
+        >,
+    ),
+    Fail(MeshedChannelsThree<End, Recv<Fail, Send<Received, End>>, RoleC<RoleC<RoleEnd>>, NameS>),
+}
+type Offer0fromStoA = Recv<Branching0fromAtoS, End>;
+
+stub_offer!(Branching0fromAtoS { Success, Fail });
+
+// Creating the MP sessions
+// A
+type EndpointASuccess = MeshedChannelsThree<
+    Send<Success, End>,
+    Recv<Token, Send<Token, End>>,
+    RoleC<RoleS<RoleS<RoleEnd>>>,
+    NameA,
+>;
+type EndpointAFail = MeshedChannelsThree<Send<Fail, End>, End, RoleC<RoleEnd>, NameA>;
+type EndpointA = MeshedChannelsThree<
+    Recv<Login, Send<Auth, Recv<Password, Choose0fromAtoC>>>,
+    Choose0fromAtoS,
+    RoleC<RoleC<RoleC<RoleBroadcast>>>,
+    NameA,
+>;
+
+stub_choose!(
+    MeshedChannelsThree<Choose0fromAtoC, Choose0fromAtoS, RoleBroadcast, NameA>,
+    choose_mpst_a_to_all,
+    {
+        Success: EndpointASuccess => Branching0fromAtoC::Success, Branching0fromAtoS::Success;
+        Fail: EndpointAFail => Branching0fromAtoC::Fail, Branching0fromAtoS::Fail;
+    }
+);
+
+// C
+type EndpointC = MeshedChannelsThree<
+    Send<Login, Recv<Auth, Send<Password, Offer0fromCtoA>>>,
+    Send<Start, Recv<Redirect, End>>,
+    RoleS<RoleS<RoleA<RoleA<RoleA<RoleA<RoleEnd>>>>>>,
+    NameC,
+>;
+
+// S
+type EndpointS = MeshedChannelsThree<
+    Offer0fromStoA,
+    Recv<Start, Send<Redirect, End>>,
+    RoleC<RoleC<RoleA<RoleEnd>>>,
+    NameS,
+>;
+
+// Functions
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    let (_, s) = s.recv()?;
+    let s = s.send(Auth {})?;
+    let (password, s) = s.recv()?;
+
+    if password.0 == "secret" {
+        let s: EndpointASuccess =
+            choose_mpst_a_to_all!(s, Branching0fromAtoC::Success, Branching0fromAtoS::Success);
+
+        let s = s.send(Success {})?;
+        let (_, s) = s.recv()?;
+        let s = s.send(Token {})?;
+        s.close()
+    } else {
+        let s: EndpointAFail =
+            choose_mpst_a_to_all!(s, Branching0fromAtoC::Fail, Branching0fromAtoS::Fail);
+
+        let s = s.send(Fail {})?;
+        s.close()
+    }
+}
+
+fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    let s = s.send(Start {})?;
+    let (_, s) = s.recv()?;
+    let s = s.send(Login {})?;
+    let (_, s) = s.recv()?;
+    let s = s.send(Password(String::from("secret")))?;
+
+    offer_mpst!(s, {
+        Branching0fromAtoC::Success(s) => {
+            let (_, s) = s.recv()?;
+            let s = s.send(Success {  })?;
+            let (_,s) = s.recv()?;
+            s.close()
+        },
+        Branching0fromAtoC::Fail(s) => {
+            let (_, s) = s.recv()?;
+            let s = s.send(Fail {  })?;
+            let (_, s) = s.recv()?;
+            s.close()
+        },
+    })
+}
+
+/////////////////////////
+
+pub fn stub_c_and_s() {
+    for (password, branch) in [("secret", "Success"), ("wrong", "Fail")] {
+        let script_c = Script::new().with_payload(move || Password(String::from(password)));
+        let trace_c = script_c.trace();
+
+        let script_s = Script::new();
+        let trace_s = script_s.trace();
+
+        let (thread_a, thread_c, thread_s) = fork_mpst(
+            endpoint_a,
+            stub::<EndpointC>(script_c),
+            stub::<EndpointS>(script_s),
+        );
+
+        assert!(thread_a.join().is_ok());
+        assert!(thread_c.join().is_ok());
+        assert!(thread_s.join().is_ok());
+
+        let steps_c = trace_c.steps();
+
+        assert_eq!(
+            steps_c[..5],
+            [
+                "RoleC!RoleS: Start",
+                "RoleC?RoleS: Redirect",
+                "RoleC!RoleA: Login",
+                "RoleC?RoleA: Auth",
+                "RoleC!RoleA: Password",
+            ]
+        );
+        assert_eq!(
+            steps_c[5],
+            format!("RoleC?RoleA: Branching0fromAtoC::{}", branch)
+        );
+        assert_eq!(steps_c.last().unwrap(), "RoleC: close");
+
+        assert_eq!(
+            trace_s.steps()[2],
+            format!("RoleS?RoleA: Branching0fromAtoS::{}", branch)
+        );
+    }
+}
+
+pub fn stub_a_and_s() {
+    for branch in ["Success", "Fail"] {
+        let script_a = Script::new()
+            .with_inspector(|password: &Password| {
+                if password.0 == "secret" {
+                    Ok(())
+                } else {
+                    Err("Wrong password".into())
+                }
+            })
+            .with_branches(vec![branch]);
+        let trace_a = script_a.trace();
+
+        let (thread_a, thread_c, thread_s) = fork_mpst(
+            stub::<EndpointA>(script_a),
+            endpoint_c,
+            stub::<EndpointS>(Script::new()),
+        );
+
+        assert!(thread_a.join().is_ok());
+        assert!(thread_c.join().is_ok());
+        assert!(thread_s.join().is_ok());
+
+        let steps_a = trace_a.steps();
+
+        assert_eq!(steps_a[3], format!("RoleA: choose {}", branch));
+        assert_eq!(steps_a.last().unwrap(), "RoleA: close");
+    }
+}
+
+pub fn stub_missing_branch() {
+    let (thread_a, thread_c, thread_s) = fork_mpst(
+        stub::<EndpointA>(Script::new()),
+        stub::<EndpointC>(Script::new().with_payload(|| Password(String::from("secret")))),
+        stub::<EndpointS>(Script::new()),
+    );
+
+    assert!(thread_a.join().is_err());
+    assert!(thread_c.join().is_err());
+    assert!(thread_s.join().is_err());
+}
+
+pub fn stub_failing_inspector() {
+    let script_a = Script::new()
+        .with_inspector(|password: &Password| {
+            if password.0 == "secret" {
+                Ok(())
+            } else {
+                Err("Wrong password".into())
+            }
+        })
+        .with_branches(vec!["Success"]);
+
+    let (thread_a, thread_c, thread_s) = fork_mpst(
+        stub::<EndpointA>(script_a),
+        stub::<EndpointC>(Script::new().with_payload(|| Password(String::from("wrong")))),
+        stub::<EndpointS>(Script::new()),
+    );
+
+    assert!(thread_a.join().is_err());
+    assert!(thread_c.join().is_err());
+    assert!(thread_s.join().is_err());
+}
@@ -0,0 +1,57 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::role::end::RoleEnd;
+use mpstthree::role::to_all::RoleToAll;
+use mpstthree::testing::{stub, Script};
+use mpstthree::{bundle_impl_with_enum_and_cancel, stub_bundle, stub_payloads};
+
+// A announces a note to all the other roles, each role is a stub
+
+bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, B, C);
+stub_bundle!(MeshedChannelsThree, A, B, C);
+
+// Payloads
+#[derive(Clone)]
+struct Note(String);
+
+stub_payloads!(Note);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+
+// Types
+type EndpointA = MeshedChannelsThree<Send<Note, End>, Send<Note, End>, RoleToAll<RoleEnd>, NameA>;
+type EndpointB = MeshedChannelsThree<Recv<Note, End>, End, RoleA<RoleEnd>, NameB>;
+type EndpointC = MeshedChannelsThree<Recv<Note, End>, End, RoleA<RoleEnd>, NameC>;
+
+pub fn stub_send_to_all() {
+    let script_a = Script::new().with_payload(|| Note(String::from("hello")));
+    let trace_a = script_a.trace();
+
+    let script_b = Script::new().with_inspector(|note: &Note| {
+        if note.0 == "hello" {
+            Ok(())
+        } else {
+            Err("Wrong note".into())
+        }
+    });
+    let script_c = Script::new();
+    let trace_c = script_c.trace();
+
+    let (thread_a, thread_b, thread_c) = fork_mpst(
+        stub::<EndpointA>(script_a),
+        stub::<EndpointB>(script_b),
+        stub::<EndpointC>(script_c),
+    );
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_b.join().is_ok());
+    assert!(thread_c.join().is_ok());
+
+    assert_eq!(
+        trace_a.steps(),
+        vec!["RoleA!RoleB: Note", "RoleA!RoleC: Note", "RoleA: close"]
+    );
+    assert_eq!(trace_c.steps(), vec!["RoleC?RoleA: Note", "RoleC: close"]);
+}
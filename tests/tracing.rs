@@ -0,0 +1,8 @@
+#![allow(clippy::type_complexity)]
+
+mod tracing_mod;
+
+#[test]
+fn tracing_spans() {
+    tracing_mod::recurs::spans_and_events();
+}
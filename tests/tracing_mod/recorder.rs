@@ -0,0 +1,97 @@
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+thread_local! {
+    // The names of the spans entered on this thread
+    static ENTERED: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+// A subscriber keeping each span and event as a line such as
+// "send bundle=MeshedChannelsThree role=RoleC peer=RoleA payload=()",
+// and each span opened inside another one as a line such as "recv in offer"
+#[derive(Clone, Default)]
+pub struct Recorder {
+    lines: Arc<Mutex<Vec<String>>>,
+    names: Arc<Mutex<HashMap<u64, &'static str>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+struct Line(String);
+
+impl Visit for Line {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.push_str(&format!(" {}", value));
+        } else {
+            self.0.push_str(&format!(" {}={}", field.name(), value));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.0.push_str(&format!(" {:?}", value));
+        } else {
+            self.0.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+}
+
+impl Recorder {
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().clone()
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let name = span.metadata().name();
+        let mut line = Line(name.to_string());
+        span.record(&mut line);
+        self.lines.lock().unwrap().push(line.0);
+
+        if let Some(parent) = ENTERED.with(|entered| entered.borrow().last().copied()) {
+            self.lines
+                .lock()
+                .unwrap()
+                .push(format!("{} in {}", name, parent));
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        self.names.lock().unwrap().insert(id, name);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = Line(format!(
+            "{} {}:",
+            event.metadata().level(),
+            event.metadata().target()
+        ));
+        event.record(&mut line);
+        self.lines.lock().unwrap().push(line.0);
+    }
+
+    fn enter(&self, id: &Id) {
+        let name = self.names.lock().unwrap()[&id.into_u64()];
+        ENTERED.with(|entered| entered.borrow_mut().push(name));
+    }
+
+    fn exit(&self, _: &Id) {
+        ENTERED.with(|entered| entered.borrow_mut().pop());
+    }
+}
@@ -0,0 +1,135 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send};
+use mpstthree::bundle_impl_with_enum_and_cancel;
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+
+use std::error::Error;
+
+use super::recorder::Recorder;
+
+// Create new roles
+bundle_impl_with_enum_and_cancel!(MeshedChannelsThree, A, B, C);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameB = RoleB<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+
+// Types
+// A
+enum Branching0fromCtoA {
+    More(MeshedChannelsThree<End, Recv<i32, RecursAtoC>, RoleC<RoleC<RoleEnd>>, NameA>),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameA>),
+}
+type RecursAtoC = Recv<Branching0fromCtoA, End>;
+// B
+enum Branching0fromCtoB {
+    More(MeshedChannelsThree<End, RecursBtoC, RoleC<RoleEnd>, NameB>),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameB>),
+}
+type RecursBtoC = Recv<Branching0fromCtoB, End>;
+// C
+type Choose0fromCtoA = Send<Branching0fromCtoA, End>;
+type Choose0fromCtoB = Send<Branching0fromCtoB, End>;
+type EndpointDoneC = MeshedChannelsThree<End, End, RoleEnd, NameC>;
+type EndpointMoreC =
+    MeshedChannelsThree<Send<i32, Choose0fromCtoA>, Choose0fromCtoB, RoleA<RoleBroadcast>, NameC>;
+
+// Creating the MP sessions
+type EndpointA = MeshedChannelsThree<End, RecursAtoC, RoleC<RoleEnd>, NameA>;
+type EndpointB = MeshedChannelsThree<End, RecursBtoC, RoleC<RoleEnd>, NameB>;
+type EndpointC = MeshedChannelsThree<Choose0fromCtoA, Choose0fromCtoB, RoleBroadcast, NameC>;
+
+fn endpoint_a(s: EndpointA) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromCtoA::Done(s) => {
+            s.close()
+        },
+        Branching0fromCtoA::More(s) => {
+            let (_, s) = s.recv()?;
+            endpoint_a(s)
+        },
+    })
+}
+
+fn endpoint_b(s: EndpointB) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, {
+        Branching0fromCtoB::Done(s) => {
+            s.close()
+        },
+        Branching0fromCtoB::More(s) => {
+            endpoint_b(s)
+        },
+    })
+}
+
+fn endpoint_c(s: EndpointC) -> Result<(), Box<dyn Error>> {
+    let s: EndpointMoreC =
+        choose_mpst_c_to_all!(s, Branching0fromCtoA::More, Branching0fromCtoB::More);
+
+    let s = s.send(1)?;
+
+    let s: EndpointDoneC =
+        choose_mpst_c_to_all!(s, Branching0fromCtoA::Done, Branching0fromCtoB::Done);
+
+    s.close()
+}
+
+/////////////////////////////////////////
+
+pub fn spans_and_events() {
+    let recorder = Recorder::default();
+    tracing::subscriber::set_global_default(recorder.clone()).unwrap();
+
+    let (thread_a, thread_b, thread_c) = fork_mpst(endpoint_a, endpoint_b, endpoint_c);
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_b.join().is_ok());
+    assert!(thread_c.join().is_ok());
+
+    let lines = recorder.lines();
+
+    for line in [
+        // The spans of the bundles
+        "choose bundle=MeshedChannelsThree role=RoleC branch=More",
+        "choose bundle=MeshedChannelsThree role=RoleC branch=Done",
+        "offer bundle=MeshedChannelsThree role=RoleA branch=More",
+        "offer bundle=MeshedChannelsThree role=RoleB branch=Done",
+        "send bundle=MeshedChannelsThree role=RoleC peer=RoleA payload=i32",
+        "recv bundle=MeshedChannelsThree role=RoleA peer=RoleC payload=i32",
+        "close bundle=MeshedChannelsThree role=RoleA",
+        "close bundle=MeshedChannelsThree role=RoleC",
+        // The branches run inside the spans of the offers
+        "recv in offer",
+        "close in offer",
+        // The events of the bundles
+        "DEBUG mpstthree::instrument: RoleC sends i32 to RoleA",
+        "DEBUG mpstthree::instrument: RoleA follows More",
+        // The events of the binary layer
+        "TRACE mpstthree::binary::send: send payload=i32",
+        "TRACE mpstthree::binary::recv: recv payload=i32",
+    ] {
+        assert!(
+            lines.iter().any(|l| l == line),
+            "Missing {} in {:#?}",
+            line,
+            lines
+        );
+    }
+
+    // B follows both branches, and closes once
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|l| l.starts_with("offer bundle=MeshedChannelsThree role=RoleB"))
+            .count(),
+        2
+    );
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|l| *l == "close bundle=MeshedChannelsThree role=RoleB")
+            .count(),
+        1
+    );
+}
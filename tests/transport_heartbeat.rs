@@ -0,0 +1,23 @@
+mod transport_heartbeat_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn heartbeat_options() {
+    transport_heartbeat_mod::options::invalid();
+    transport_heartbeat_mod::options::enabled();
+}
+
+#[test]
+#[timeout(60000)]
+fn heartbeat_watched() {
+    transport_heartbeat_mod::watched::exchange();
+}
+
+#[test]
+#[timeout(60000)]
+fn heartbeat_lost() {
+    transport_heartbeat_mod::lost::reset();
+    transport_heartbeat_mod::lost::closed();
+}
@@ -0,0 +1,81 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, session::Session};
+use mpstthree::transport::tcp::heartbeat::recv_tcp_watched;
+use mpstthree::transport::tcp::send::send_tcp;
+use socket2::SockRef;
+use std::io::Read;
+use std::net::TcpStream;
+use std::thread::{sleep, spawn, JoinHandle};
+use std::time::{Duration, Instant};
+
+type Data = (i32, [u8; 128]);
+type BinaryB = Recv<Data, End>;
+
+// B waits for a payload from C, which never sends it,
+// while A is lost or cancels
+fn wait_for_c(stream_a: TcpStream, stream_c: TcpStream) -> JoinHandle<(String, Duration)> {
+    spawn(move || {
+        let heartbeat = super::heartbeat();
+        let watchdog = heartbeat.watch(&[&stream_a, &stream_c]).unwrap();
+
+        let (s_b, s_c) = BinaryB::new();
+        let copy_stream = stream_c.try_clone().unwrap();
+        let (_s, _stream) = send_tcp(1, &[0_u8; 128], s_c, copy_stream, false).unwrap();
+
+        let start = Instant::now();
+        let error = recv_tcp_watched(s_b, stream_c, true, &watchdog).unwrap_err();
+        assert!(start.elapsed() < heartbeat.bound());
+        (error.to_string(), start.elapsed())
+    })
+}
+
+// C is notified of the cancellation
+fn notified(mut peer_c: TcpStream) {
+    let mut data = [0_u8; 128];
+    assert_eq!(peer_c.read(&mut data).unwrap(), 0);
+}
+
+pub fn reset() {
+    let (stream_a, peer_a) = super::pair();
+    let (stream_c, peer_c) = super::pair();
+    let addr_a = stream_a.peer_addr().unwrap();
+
+    let thread_b = wait_for_c(stream_a, stream_c);
+    let thread_c = spawn(move || notified(peer_c));
+
+    // A crashes, resetting its connection
+    sleep(Duration::from_millis(50));
+    SockRef::from(&peer_a)
+        .set_linger(Some(Duration::ZERO))
+        .unwrap();
+    drop(peer_a);
+
+    let (error, _elapsed) = thread_b.join().unwrap();
+    assert!(error.starts_with(&format!(
+        "The connection to {} was lost, the session is cancelled",
+        addr_a
+    )));
+    thread_c.join().unwrap();
+}
+
+pub fn closed() {
+    let (stream_a, peer_a) = super::pair();
+    let (stream_c, peer_c) = super::pair();
+    let addr_c = stream_c.peer_addr().unwrap();
+
+    let thread_b = wait_for_c(stream_a, stream_c);
+    let thread_a = spawn(move || notified(peer_a));
+
+    // C cancels its session
+    sleep(Duration::from_millis(50));
+    peer_c.shutdown(std::net::Shutdown::Both).unwrap();
+
+    let (error, _elapsed) = thread_b.join().unwrap();
+    assert_eq!(
+        error,
+        format!(
+            "The connection to {} was closed, the session is cancelled",
+            addr_c
+        )
+    );
+    thread_a.join().unwrap();
+}
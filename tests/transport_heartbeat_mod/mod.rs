@@ -0,0 +1,20 @@
+pub mod lost;
+pub mod options;
+pub mod watched;
+
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+use mpstthree::transport::tcp::heartbeat::Heartbeat;
+
+pub fn heartbeat() -> Heartbeat {
+    Heartbeat::new(Duration::from_secs(1), Duration::from_secs(3)).unwrap()
+}
+
+// A connected pair of streams: the role, and its peer
+pub fn pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (peer, _addr) = listener.accept().unwrap();
+    (stream, peer)
+}
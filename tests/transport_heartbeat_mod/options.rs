@@ -0,0 +1,32 @@
+use mpstthree::transport::tcp::heartbeat::Heartbeat;
+use socket2::SockRef;
+use std::time::Duration;
+
+pub fn invalid() {
+    assert!(Heartbeat::new(Duration::ZERO, Duration::from_secs(1)).is_err());
+    assert!(Heartbeat::new(Duration::from_millis(100), Duration::from_secs(1)).is_err());
+    assert!(Heartbeat::new(Duration::from_secs(2), Duration::from_secs(1)).is_err());
+
+    let heartbeat = Heartbeat::default();
+    assert!(heartbeat.interval() <= heartbeat.timeout());
+}
+
+pub fn enabled() {
+    let heartbeat = super::heartbeat();
+    let (stream, _peer) = super::pair();
+    heartbeat.enable(&stream).unwrap();
+
+    let socket = SockRef::from(&stream);
+    assert!(socket.keepalive().unwrap());
+    assert_eq!(socket.keepalive_time().unwrap(), heartbeat.interval());
+
+    #[cfg(target_os = "linux")]
+    {
+        assert_eq!(socket.keepalive_interval().unwrap(), heartbeat.interval());
+        assert_eq!(socket.keepalive_retries().unwrap(), 3);
+        assert_eq!(
+            socket.tcp_user_timeout().unwrap(),
+            Some(heartbeat.timeout())
+        );
+    }
+}
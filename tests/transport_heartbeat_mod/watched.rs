@@ -0,0 +1,39 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::tcp::heartbeat::{recv_tcp_watched, send_tcp_watched};
+use mpstthree::transport::tcp::{recv::recv_tcp, send::send_tcp};
+use mpstthree::transport::wire::frame::{read_frame, write_frame};
+use std::thread::spawn;
+
+type Data = (i32, [u8; 128]);
+type BinaryB = Send<Data, Recv<Data, End>>;
+
+pub fn exchange() {
+    let (stream, mut peer) = super::pair();
+    let watchdog = super::heartbeat().watch(&[&stream]).unwrap();
+
+    // The peer echoes the payload
+    let echo = spawn(move || {
+        let frame = read_frame(&mut peer).unwrap();
+        write_frame(&mut peer, &frame).unwrap();
+        peer
+    });
+
+    // The dual endpoint stays in the process, as with fork_tcp
+    let (s_b, s_a) = BinaryB::new();
+    let copy_stream = stream.try_clone().unwrap();
+    let proxy = spawn(move || {
+        let (x, s, data, _r, stream) = recv_tcp(s_a, copy_stream, false).unwrap();
+        let (s, _stream) = send_tcp(x + 1, &data, s, stream, false).unwrap();
+        s
+    });
+
+    let (s, stream) = send_tcp_watched(1, &[7_u8; 128], s_b, stream, true, &watchdog).unwrap();
+    let (x, s, data, r, _stream) = recv_tcp_watched(s, stream, true, &watchdog).unwrap();
+
+    assert_eq!(x, 2);
+    assert_eq!(r, 128);
+    assert_eq!(data, [7_u8; 128]);
+    assert!(watchdog.lost().is_none());
+
+    drop((s, proxy.join().unwrap(), echo.join().unwrap()));
+}
@@ -0,0 +1,23 @@
+mod transport_resume_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn resume_payloads() {
+    transport_resume_mod::payloads::ping_pong();
+    transport_resume_mod::payloads::one_way();
+}
+
+#[test]
+#[timeout(60000)]
+fn resume_session() {
+    transport_resume_mod::session::main();
+}
+
+#[test]
+#[timeout(60000)]
+fn resume_failed() {
+    transport_resume_mod::failed::peer_gone();
+    transport_resume_mod::failed::other_session();
+}
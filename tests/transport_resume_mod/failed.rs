@@ -0,0 +1,50 @@
+use mpstthree::transport::tcp::resume::ResumableStream;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread::spawn;
+use std::time::{Duration, Instant};
+
+pub fn peer_gone() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let thread_b = spawn(move || {
+        let b = ResumableStream::accept(&listener, "gone", super::TIMEOUT).unwrap();
+        // The role and its listener are gone
+        b.cancel();
+    });
+
+    let timeout = Duration::from_millis(500);
+    let mut a = ResumableStream::connect(addr, "gone", timeout).unwrap();
+    thread_b.join().unwrap();
+
+    let start = Instant::now();
+    let error = a.recv().unwrap_err().to_string();
+    assert!(start.elapsed() >= timeout);
+    assert!(
+        error.starts_with("The session gone could not be resumed within 500ms"),
+        "{}",
+        error
+    );
+}
+
+pub fn other_session() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let thread_b = spawn(move || {
+        ResumableStream::accept(&listener, "expected", Duration::from_millis(500))
+            .unwrap_err()
+            .to_string()
+    });
+
+    // A connection for another session is refused
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    writeln!(stream, "RESUME other 0").unwrap();
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line).unwrap();
+    assert_eq!(line, "ERROR Unexpected resumption: RESUME other 0\n");
+
+    assert!(thread_b
+        .join()
+        .unwrap()
+        .starts_with("The session expected could not be resumed within 500ms"));
+}
@@ -0,0 +1,34 @@
+pub mod failed;
+pub mod payloads;
+pub mod session;
+
+use mpstthree::transport::tcp::resume::ResumableStream;
+use std::net::TcpListener;
+use std::thread::{spawn, JoinHandle};
+use std::time::Duration;
+
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+// Accept the session in another thread, and connect to it
+pub fn pair(session: &'static str) -> (ResumableStream, JoinHandle<ResumableStream>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let accepted = spawn(move || ResumableStream::accept(&listener, session, TIMEOUT).unwrap());
+    let connected = ResumableStream::connect(addr, session, TIMEOUT).unwrap();
+    (connected, accepted)
+}
+
+pub fn payload(i: u64) -> [u8; 128] {
+    let mut data = [0_u8; 128];
+    data[..8].copy_from_slice(&i.to_be_bytes());
+    data
+}
+
+// Drop the current connection, as the network would
+pub fn interrupt(stream: &ResumableStream) {
+    stream
+        .tcp_stream()
+        .unwrap()
+        .shutdown(std::net::Shutdown::Both)
+        .unwrap();
+}
@@ -0,0 +1,60 @@
+use std::thread::spawn;
+
+static LOOPS: u64 = 200;
+
+pub fn ping_pong() {
+    let (mut a, thread_b) = super::pair("ping_pong");
+
+    let echo = spawn(move || {
+        let mut b = thread_b.join().unwrap();
+        b.set_window(4);
+        for i in 0..LOOPS {
+            let data = b.recv().unwrap();
+            assert_eq!(data, super::payload(i));
+            b.send(&data).unwrap();
+        }
+        let resumptions = b.resumptions();
+        b.close().unwrap();
+        resumptions
+    });
+
+    for i in 0..LOOPS {
+        if i % 50 == 25 {
+            super::interrupt(&a);
+        }
+        a.send(&super::payload(i)).unwrap();
+        assert_eq!(a.recv().unwrap(), super::payload(i));
+    }
+
+    assert_eq!(a.resumptions(), 4);
+    a.close().unwrap();
+    assert_eq!(echo.join().unwrap(), 4);
+}
+
+pub fn one_way() {
+    let (mut a, thread_b) = super::pair("one_way");
+
+    let receiver = spawn(move || {
+        let mut b = thread_b.join().unwrap();
+        for i in 0..LOOPS {
+            if i == LOOPS / 2 {
+                super::interrupt(&b);
+            }
+            assert_eq!(b.recv().unwrap(), super::payload(i));
+        }
+        b.close().unwrap();
+    });
+
+    // The payloads not acknowledged are sent again
+    a.set_window(8);
+    for i in 0..LOOPS {
+        if i == LOOPS / 4 {
+            super::interrupt(&a);
+        }
+        a.send(&super::payload(i)).unwrap();
+    }
+    assert!(a.resumptions() >= 1);
+    a.close().unwrap();
+
+    receiver.join().unwrap();
+}
@@ -0,0 +1,41 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::tcp::resume::{
+    close_resumable, recv_resumable, send_resumable, ResumableStream,
+};
+use std::error::Error;
+use std::thread::spawn;
+
+type Data = (i32, [u8; 128]);
+
+type BinaryA = Send<Data, Recv<Data, Send<Data, End>>>;
+type BinaryB = <BinaryA as Session>::Dual;
+
+fn binary_a(s: BinaryA, stream: ResumableStream) -> Result<(), Box<dyn Error>> {
+    let (s, stream) = send_resumable(1, &super::payload(1), s, stream, true)?;
+    let (x, s, data, _r, stream) = recv_resumable(s, stream, true)?;
+    assert_eq!((x, data), (2, super::payload(2)));
+    super::interrupt(&stream);
+    let (s, stream) = send_resumable(x + 1, &super::payload(3), s, stream, true)?;
+    assert_eq!(stream.resumptions(), 1);
+    close_resumable(s, stream, true)
+}
+
+fn binary_b(s: BinaryB, stream: ResumableStream) -> Result<(), Box<dyn Error>> {
+    let (x, s, data, _r, stream) = recv_resumable(s, stream, true)?;
+    assert_eq!((x, data), (1, super::payload(1)));
+    let (s, stream) = send_resumable(x + 1, &super::payload(2), s, stream, true)?;
+    let (x, s, data, _r, stream) = recv_resumable(s, stream, true)?;
+    assert_eq!((x, data), (3, super::payload(3)));
+    close_resumable(s, stream, true)
+}
+
+pub fn main() {
+    let (stream_a, thread_b) = super::pair("session");
+    let stream_b = thread_b.join().unwrap();
+
+    let (s_a, s_b) = BinaryA::new();
+
+    let role_b = spawn(move || binary_b(s_b, stream_b).map_err(|e| e.to_string()));
+    binary_a(s_a, stream_a).unwrap();
+    role_b.join().unwrap().unwrap();
+}
@@ -0,0 +1,48 @@
+mod transport_shm_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn shm_channel() {
+    transport_shm_mod::channel::full();
+    transport_shm_mod::channel::names();
+}
+
+#[test]
+#[timeout(60000)]
+fn shm_binary() {
+    transport_shm_mod::binary::main();
+}
+
+#[test]
+#[timeout(60000)]
+fn shm_cancel() {
+    transport_shm_mod::cancel::main();
+}
+
+#[test]
+#[timeout(60000)]
+fn shm_spawn() {
+    transport_shm_mod::spawn::main();
+}
+
+#[test]
+#[timeout(60000)]
+fn shm_lost() {
+    transport_shm_mod::lost::main();
+}
+
+// The role spawned by `shm_spawn`, which does nothing when run on its own
+#[test]
+#[ignore]
+fn shm_spawned_role() {
+    transport_shm_mod::spawn::spawned_role();
+}
+
+// The role spawned by `shm_lost`, which does nothing when run on its own
+#[test]
+#[ignore]
+fn shm_lost_role() {
+    transport_shm_mod::lost::lost_role();
+}
@@ -0,0 +1,85 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::shm::channel::ShmChannel;
+use mpstthree::transport::shm::{close::close_shm, fork::fork_shm, recv::recv_shm, send::send_shm};
+use mpstthree::{choose_shm, offer_shm};
+
+use std::error::Error;
+use std::thread::spawn;
+
+type Data = (i32, [u8; 128]);
+
+/////////////////////////
+
+// Types
+// A
+enum BinaryA {
+    More(Recv<Data, Send<Data, RecursA>>),
+    Done(End),
+}
+
+type RecursA = Recv<([u8; 128], BinaryA), End>;
+
+// B
+type RecursB = <RecursA as Session>::Dual;
+
+// Functions
+// A
+fn binary_a_to_b(s: RecursA, channel: ShmChannel) -> Result<(), Box<dyn Error>> {
+    offer_shm!(s, {
+        BinaryA::Done(s) => {
+            close_shm(s, channel, false)
+        },
+        BinaryA::More(s) => {
+            let (x, s, data, _r, channel) = recv_shm(s, channel, false)?;
+            let (s, channel) = send_shm(x + 1, &data, s, channel, false)?;
+            binary_a_to_b(s, channel)
+        },
+    })
+}
+
+// B
+fn binary_b_to_a(s: RecursB, channel: ShmChannel, index: i32) -> Result<(), Box<dyn Error>> {
+    if index >= LOOPS {
+        let s = choose_shm!(BinaryA::Done, s, [0_u8; 128]);
+        return close_shm(s, channel, true);
+    }
+
+    let s = choose_shm!(BinaryA::More, s, [0_u8; 128]);
+    let (s, channel) = send_shm(index, &[index as u8; 128], s, channel, true)?;
+    let (x, s, data, r, channel) = recv_shm(s, channel, true)?;
+
+    assert_eq!(x, index + 1);
+    assert_eq!(r, 128);
+    assert_eq!(data, [index as u8; 128]);
+
+    binary_b_to_a(s, channel, index + 1)
+}
+
+static LOOPS: i32 = 100;
+
+/////////////////////////
+
+// Echo everything until B closes the channel
+fn echo_server(channel: ShmChannel) -> usize {
+    let mut data = [0_u8; 128];
+    let mut echoed = 0;
+    while let Ok(size) = channel.recv(&mut data) {
+        channel.send(&data[..size]).unwrap();
+        echoed += size;
+    }
+    echoed
+}
+
+/////////////////////////
+
+pub fn main() {
+    let name = super::channel_name("binary");
+    let channel = ShmChannel::create(&name).unwrap();
+    let server = spawn(move || echo_server(channel));
+
+    let (thread, s, channel) = fork_shm(binary_a_to_b, &name).unwrap();
+
+    assert!(binary_b_to_a(s, channel, 0).is_ok());
+    assert!(thread.join().is_ok());
+    assert_eq!(server.join().unwrap(), 128 * LOOPS as usize);
+}
@@ -0,0 +1,43 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::shm::channel::ShmChannel;
+use mpstthree::transport::shm::{cancel::cancel_shm, recv::recv_shm, send::send_shm};
+
+use std::error::Error;
+use std::thread::spawn;
+
+type Data = (i32, [u8; 128]);
+
+type BinaryA = Recv<Data, Send<Data, End>>;
+type BinaryB = <BinaryA as Session>::Dual;
+
+// A cancels the session after receiving the first message
+fn binary_a(s: BinaryA, channel: ShmChannel) -> Result<(), Box<dyn Error>> {
+    let (_x, s, _data, r, channel) = recv_shm(s, channel, true)?;
+    assert_eq!(r, 128);
+    cancel_shm(s, channel);
+    Ok(())
+}
+
+fn binary_b(s: BinaryB, channel: ShmChannel) -> Result<(), Box<dyn Error>> {
+    let (s, channel) = send_shm(1, &[1_u8; 128], s, channel, true)?;
+    let (_x, _s, _data, _r, _channel) = recv_shm(s, channel, true)?;
+    Ok(())
+}
+
+pub fn main() {
+    let (channel_a, channel_b) = ShmChannel::pair().unwrap();
+    let copy_channel_b = channel_b.try_clone().unwrap();
+    let (s_a, s_b) = BinaryA::new();
+
+    let thread_a = spawn(move || binary_a(s_a, channel_a).unwrap());
+
+    // The session and the channel of B both see the cancellation
+    assert!(binary_b(s_b, channel_b).is_err());
+    assert!(thread_a.join().is_ok());
+
+    let mut data = [0_u8; 128];
+    assert_eq!(
+        copy_channel_b.recv(&mut data).unwrap_err().to_string(),
+        "The shared memory channel was closed, the session is cancelled"
+    );
+}
@@ -0,0 +1,55 @@
+use mpstthree::transport::shm::channel::{ShmChannel, SLOTS};
+
+use std::thread::spawn;
+
+// The sender blocks on the full ring buffer until the receiver catches up
+pub fn full() {
+    let (a, b) = ShmChannel::pair().unwrap();
+    let messages = 10 * SLOTS;
+
+    let sender = spawn(move || {
+        for i in 0..messages {
+            a.send(&[i as u8; 100]).unwrap();
+        }
+        a
+    });
+
+    let mut data = [0_u8; 128];
+    for i in 0..messages {
+        assert_eq!(b.recv(&mut data).unwrap(), 100);
+        assert_eq!(data[..100], [i as u8; 100]);
+    }
+
+    // The pending data is received before the shutdown
+    let a = sender.join().unwrap();
+    a.send(&[1, 2, 3]).unwrap();
+    a.shutdown();
+    assert_eq!(b.recv(&mut data).unwrap(), 3);
+    assert_eq!(data[..3], [1, 2, 3]);
+    assert!(b.recv(&mut data).is_err());
+    assert!(b.send(&data).is_err());
+    assert!(a.send(&[0_u8; 129]).is_err());
+}
+
+pub fn names() {
+    let name = super::channel_name("names");
+
+    assert!(ShmChannel::open(&name).is_err());
+    assert!(ShmChannel::create("a/b").is_err());
+
+    let created = ShmChannel::create(&name).unwrap();
+    assert!(ShmChannel::create(&name).is_err());
+    assert!(!created.is_connected());
+
+    let opened = ShmChannel::open(&name).unwrap();
+    assert!(created.is_connected());
+    assert!(ShmChannel::open(&name).is_err());
+
+    opened.send(&[42]).unwrap();
+    let mut data = [0_u8; 128];
+    assert_eq!(created.recv(&mut data).unwrap(), 1);
+
+    // The name is removed with the channel
+    drop((created, opened));
+    assert!(ShmChannel::open(&name).is_err());
+}
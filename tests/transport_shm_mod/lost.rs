@@ -0,0 +1,51 @@
+// The process of the peer exits without shutting down the channel,
+// which is detected by the role waiting for it.
+
+use mpstthree::transport::shm::channel::ShmChannel;
+use mpstthree::transport::shm::fork::{connect_spawner, SHM_NAME_ENV};
+
+use std::env;
+use std::mem;
+use std::process::{self, Command, Stdio};
+use std::thread::sleep;
+use std::time::Duration;
+
+pub fn lost_role() {
+    if env::var_os(SHM_NAME_ENV).is_none() {
+        return;
+    }
+
+    let channel = connect_spawner().unwrap();
+    channel.send(&[1]).unwrap();
+    // Crash, without dropping the channel
+    mem::forget(channel);
+    process::exit(1);
+}
+
+pub fn main() {
+    let name = super::channel_name("lost");
+    let channel = ShmChannel::create(&name).unwrap();
+
+    let mut child = Command::new(env::current_exe().unwrap())
+        .args(["shm_lost_role", "--exact", "--ignored"])
+        .env(SHM_NAME_ENV, &name)
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    while !channel.is_connected() {
+        sleep(Duration::from_millis(10));
+    }
+
+    // The data sent before the crash is received,
+    // even while the exited process is not waited for
+    let mut data = [0_u8; 128];
+    assert_eq!(channel.recv(&mut data).unwrap(), 1);
+    assert_eq!(
+        channel.recv(&mut data).unwrap_err().to_string(),
+        "The process of the peer exited, the session is cancelled"
+    );
+    assert!(channel.send(&data).is_err());
+
+    assert!(!child.wait().unwrap().success());
+}
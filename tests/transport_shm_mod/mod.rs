@@ -0,0 +1,12 @@
+pub mod binary;
+pub mod cancel;
+pub mod channel;
+pub mod lost;
+pub mod spawn;
+
+use std::process;
+
+// A name of channel unique to the test
+pub fn channel_name(name: &str) -> String {
+    format!("/mpst_{}_{}", process::id(), name)
+}
@@ -0,0 +1,70 @@
+// The role B spawns the process of the role A,
+// which is this test binary running `shm_spawned_role`.
+
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::shm::channel::ShmChannel;
+use mpstthree::transport::shm::fork::{connect_spawner, fork_shm_spawn, SHM_NAME_ENV};
+use mpstthree::transport::shm::{close::close_shm, recv::recv_shm, send::send_shm};
+
+use std::env;
+use std::error::Error;
+use std::process::{Command, Stdio};
+
+type Data = (i32, [u8; 128]);
+
+type BinaryA = Recv<Data, Send<Data, End>>;
+type BinaryB = <BinaryA as Session>::Dual;
+
+// The session of A, kept in the process of B
+fn binary_a(s: BinaryA, channel: ShmChannel) -> Result<(), Box<dyn Error>> {
+    let (x, s, data, _r, channel) = recv_shm(s, channel, false)?;
+    let (s, channel) = send_shm(x * 2, &data, s, channel, false)?;
+    close_shm(s, channel, false)
+}
+
+fn binary_b(s: BinaryB, channel: ShmChannel) -> Result<(), Box<dyn Error>> {
+    let (s, channel) = send_shm(21, &[21_u8; 128], s, channel, true)?;
+    let (x, s, data, r, channel) = recv_shm(s, channel, true)?;
+
+    assert_eq!(x, 42);
+    assert_eq!(r, 128);
+    // The spawned process doubles each byte
+    assert_eq!(data, [42_u8; 128]);
+
+    close_shm(s, channel, true)
+}
+
+pub fn spawned_role() {
+    if env::var_os(SHM_NAME_ENV).is_none() {
+        return;
+    }
+
+    let channel = connect_spawner().unwrap();
+    let mut data = [0_u8; 128];
+    let size = channel.recv(&mut data).unwrap();
+    for byte in data.iter_mut() {
+        *byte *= 2;
+    }
+    channel.send(&data[..size]).unwrap();
+
+    // Wait for B to close the channel
+    assert!(channel.recv(&mut data).is_err());
+}
+
+pub fn main() {
+    let name = super::channel_name("spawn");
+
+    let mut command = Command::new(env::current_exe().unwrap());
+    command
+        .args(["shm_spawned_role", "--exact", "--ignored"])
+        .stdout(Stdio::null());
+
+    let (thread, s, channel, mut child) = fork_shm_spawn(binary_a, &name, &mut command).unwrap();
+
+    // The name is removed once the role is connected
+    assert!(ShmChannel::open(&name).is_err());
+
+    assert!(binary_b(s, channel).is_ok());
+    assert!(thread.join().is_ok());
+    assert!(child.wait().unwrap().success());
+}
@@ -0,0 +1,31 @@
+mod transport_tls_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn tls_binary() {
+    transport_tls_mod::binary::main();
+}
+
+#[test]
+#[timeout(60000)]
+fn tls_full_duplex() {
+    transport_tls_mod::binary::full_duplex();
+}
+
+#[test]
+#[timeout(60000)]
+fn tls_alert() {
+    transport_tls_mod::binary::alert_sent();
+}
+
+#[test]
+#[timeout(60000)]
+fn tls_authentication() {
+    transport_tls_mod::authentication::roles_bound();
+    transport_tls_mod::authentication::wrong_client_role();
+    transport_tls_mod::authentication::wrong_server_role();
+    transport_tls_mod::authentication::without_client_certificate();
+    transport_tls_mod::authentication::other_authority();
+}
@@ -0,0 +1,114 @@
+// A connection is only opened between roles whose certificates
+// are signed by the same authority and are bound to the expected roles.
+
+use super::certificates::Authority;
+use mpstthree::transport::tls::config::rustls::crypto::ring;
+use mpstthree::transport::tls::config::rustls::pki_types::pem::PemObject;
+use mpstthree::transport::tls::config::rustls::pki_types::CertificateDer;
+use mpstthree::transport::tls::config::rustls::{ClientConfig, RootCertStore};
+use mpstthree::transport::tls::config::TlsConfig;
+use mpstthree::transport::tls::stream::TlsStream;
+
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread::{spawn, JoinHandle};
+
+// A server for `server`, expecting a client bound to `client_role`,
+// which reads a single byte and answers it
+fn server(
+    config: TlsConfig,
+    client_role: &'static str,
+) -> (String, JoinHandle<Result<(), String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+
+    let thread = spawn(move || {
+        let (stream, _addr) = listener.accept().map_err(|e| e.to_string())?;
+        let mut stream =
+            TlsStream::accept(stream, &config, client_role).map_err(|e| e.to_string())?;
+        let mut data = [0_u8; 1];
+        stream.read_exact(&mut data).map_err(|e| e.to_string())?;
+        stream.write_all(&data).map_err(|e| e.to_string())?;
+        stream.flush().map_err(|e| e.to_string())?;
+        Ok(())
+    });
+
+    (address, thread)
+}
+
+// Connect and exchange a single byte
+fn client(address: &str, config: &TlsConfig, server_role: &str) -> Result<(), Box<dyn Error>> {
+    let mut stream = TlsStream::connect(address, config, server_role)?;
+    stream.write_all(&[42])?;
+    stream.flush()?;
+    let mut data = [0_u8; 1];
+    stream.read_exact(&mut data)?;
+    assert_eq!(data, [42]);
+    stream.shutdown()?;
+    Ok(())
+}
+
+pub fn roles_bound() {
+    let authority = Authority::new();
+
+    let (address, thread) = server(authority.role("RoleA"), "RoleB");
+
+    client(&address, &authority.role("RoleB"), "RoleA").unwrap();
+    thread.join().unwrap().unwrap();
+}
+
+pub fn wrong_client_role() {
+    let authority = Authority::new();
+
+    let (address, thread) = server(authority.role("RoleA"), "RoleB");
+
+    // RoleC pretends to be RoleB
+    assert!(client(&address, &authority.role("RoleC"), "RoleA").is_err());
+
+    let error = thread.join().unwrap().unwrap_err();
+    assert!(error.contains("not bound to the role RoleB"), "{}", error);
+}
+
+pub fn wrong_server_role() {
+    let authority = Authority::new();
+
+    let (address, thread) = server(authority.role("RoleC"), "RoleB");
+
+    // RoleC answers instead of RoleA
+    assert!(client(&address, &authority.role("RoleB"), "RoleA").is_err());
+    assert!(thread.join().unwrap().is_err());
+}
+
+pub fn without_client_certificate() {
+    let authority = Authority::new();
+    let config_b = authority.role("RoleB");
+
+    let mut roots = RootCertStore::empty();
+    for ca in CertificateDer::pem_slice_iter(authority.pem().as_bytes()) {
+        roots.add(ca.unwrap()).unwrap();
+    }
+    let client_config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let anonymous = TlsConfig::from_configs(Arc::new(client_config), config_b.server().clone());
+
+    let (address, thread) = server(authority.role("RoleA"), "RoleB");
+
+    assert!(client(&address, &anonymous, "RoleA").is_err());
+    assert!(thread.join().unwrap().is_err());
+}
+
+pub fn other_authority() {
+    let authority = Authority::new();
+    let other = Authority::new();
+
+    let (address, thread) = server(authority.role("RoleA"), "RoleB");
+
+    // The certificate of RoleB is signed by another authority
+    assert!(client(&address, &other.role("RoleB"), "RoleA").is_err());
+    assert!(thread.join().unwrap().is_err());
+}
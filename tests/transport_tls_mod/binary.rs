@@ -0,0 +1,158 @@
+// The client B sends two messages to A, through the session and
+// through TLS, to an echo server holding the certificate of A.
+
+use super::certificates::Authority;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::tls::config::TlsConfig;
+use mpstthree::transport::tls::{
+    close::close_tls, fork::fork_tls, recv::recv_tls, send::send_tls, stream::TlsStream,
+};
+
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread::{sleep, spawn};
+use std::time::Duration;
+
+type Data = (i32, [u8; 128]);
+
+// Types
+type BinaryA = Recv<Data, Send<Data, Recv<Data, Send<Data, End>>>>;
+type BinaryB = <BinaryA as Session>::Dual;
+
+// Functions
+fn binary_a(s: BinaryA, stream: TlsStream) -> Result<(), Box<dyn Error>> {
+    let (x, s, data, _r, stream) = recv_tls(s, stream, false)?;
+    let (s, stream) = send_tls(x + 1, &data, s, stream, false)?;
+    let (x, s, data, _r, stream) = recv_tls(s, stream, false)?;
+    let (s, stream) = send_tls(x + 1, &data, s, stream, false)?;
+    close_tls(s, stream, false)
+}
+
+fn binary_b(s: BinaryB, stream: TlsStream) -> Result<(), Box<dyn Error>> {
+    assert_eq!(stream.peer_role(), "RoleA");
+
+    let (s, stream) = send_tls(1, &[1_u8; 128], s, stream, true)?;
+    let (x, s, data, r, stream) = recv_tls(s, stream, true)?;
+
+    assert_eq!(x, 2);
+    assert_eq!(r, 128);
+    assert_eq!(data, [1_u8; 128]);
+
+    let (s, stream) = send_tls(x, &[2_u8; 128], s, stream, true)?;
+    let (x, s, data, r, stream) = recv_tls(s, stream, true)?;
+
+    assert_eq!(x, 3);
+    assert_eq!(r, 128);
+    assert_eq!(data, [2_u8; 128]);
+
+    close_tls(s, stream, true)
+}
+
+/////////////////////////
+
+fn echo_server(listener: TcpListener, config: TlsConfig) -> Result<usize, Box<dyn Error>> {
+    let (stream, _addr) = listener.accept()?;
+    let mut stream = TlsStream::accept(stream, &config, "RoleB")?;
+
+    let mut data = [0_u8; 128];
+    let mut echoed = 0;
+    loop {
+        match stream.read(&mut data)? {
+            0 => break,
+            size => {
+                stream.write_all(&data[0..size])?;
+                stream.flush()?;
+                echoed += size;
+            }
+        }
+    }
+    Ok(echoed)
+}
+
+/////////////////////////
+
+pub fn main() {
+    let authority = Authority::new();
+    let config_a = authority.role("RoleA");
+    let config_b = authority.role("RoleB");
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let server = spawn(move || echo_server(listener, config_a).unwrap());
+
+    let (thread, s, stream) = fork_tls(binary_a, &address, &config_b, "RoleA").unwrap();
+
+    assert!(binary_b(s, stream).is_ok());
+    assert!(thread.join().is_ok());
+    // Two payload frames, each with a header of 10 bytes
+    assert_eq!(server.join().unwrap(), 2 * (10 + 128));
+}
+
+// One clone of the stream waits for the echoes
+// while the other one keeps on writing
+pub fn full_duplex() {
+    let authority = Authority::new();
+    let config_a = authority.role("RoleA");
+    let config_b = authority.role("RoleB");
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let server = spawn(move || echo_server(listener, config_a).unwrap());
+
+    let mut writer = TlsStream::connect(&address, &config_b, "RoleA").unwrap();
+    let mut reader = writer.try_clone().unwrap();
+
+    let echoes = spawn(move || -> Result<Vec<u8>, std::io::Error> {
+        let mut echoes = vec![0_u8; 4 * 128];
+        reader.read_exact(&mut echoes)?;
+        Ok(echoes)
+    });
+
+    // Let the reader wait for the socket first
+    sleep(Duration::from_millis(100));
+
+    for i in 0..4 {
+        writer.write_all(&[i; 128]).unwrap();
+        writer.flush().unwrap();
+    }
+
+    let echoes = echoes.join().unwrap().unwrap();
+    for (i, chunk) in echoes.chunks(128).enumerate() {
+        assert_eq!(chunk, [i as u8; 128]);
+    }
+
+    writer.shutdown().unwrap();
+    assert_eq!(server.join().unwrap(), 4 * 128);
+}
+
+// The alert queued by the reader of the server,
+// on a corrupted record, is sent to the client
+pub fn alert_sent() {
+    let authority = Authority::new();
+    let config_a = authority.role("RoleA");
+    let config_b = authority.role("RoleB");
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let server = spawn(move || {
+        let (stream, _addr) = listener.accept().unwrap();
+        let mut stream = TlsStream::accept(stream, &config_a, "RoleB").unwrap();
+        let error = stream.read(&mut [0_u8; 128]).unwrap_err();
+        // Keep the connection open until the client has read the alert
+        sleep(Duration::from_millis(500));
+        error
+    });
+
+    let mut client = TlsStream::connect(&address, &config_b, "RoleA").unwrap();
+
+    // An application data record which cannot be decrypted
+    let mut record = vec![0x17, 0x03, 0x03, 0x00, 0x20];
+    record.extend_from_slice(&[0_u8; 32]);
+    client.tcp_stream().unwrap().write_all(&record).unwrap();
+
+    let error = client.read(&mut [0_u8; 128]).unwrap_err();
+    assert!(error.to_string().contains("alert"), "{}", error);
+
+    assert!(server.join().unwrap().to_string().contains("decrypt"));
+}
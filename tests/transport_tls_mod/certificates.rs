@@ -0,0 +1,38 @@
+// Self-signed certificates for running the roles on localhost:
+// an authority signs a certificate for each role,
+// whose subject alternative name is the name of the role.
+
+use mpstthree::transport::tls::config::TlsConfig;
+use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa, KeyPair};
+
+pub struct Authority {
+    cert: Certificate,
+    key: KeyPair,
+}
+
+impl Authority {
+    pub fn new() -> Self {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let cert = params.self_signed(&key).unwrap();
+        Authority { cert, key }
+    }
+
+    pub fn pem(&self) -> String {
+        self.cert.pem()
+    }
+
+    // The PEM encoded certificate and private key of `role`
+    pub fn role_pem(&self, role: &str) -> (String, String) {
+        let key = KeyPair::generate().unwrap();
+        let params = CertificateParams::new(vec![role.to_string()]).unwrap();
+        let cert = params.signed_by(&key, &self.cert, &self.key).unwrap();
+        (cert.pem(), key.serialize_pem())
+    }
+
+    pub fn role(&self, role: &str) -> TlsConfig {
+        let (cert, key) = self.role_pem(role);
+        TlsConfig::new(cert, key, self.pem()).unwrap()
+    }
+}
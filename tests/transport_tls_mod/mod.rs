@@ -0,0 +1,3 @@
+pub mod authentication;
+pub mod binary;
+pub mod certificates;
@@ -0,0 +1,31 @@
+mod transport_unix_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn unix_binary() {
+    transport_unix_mod::binary::main();
+}
+
+#[test]
+#[timeout(60000)]
+fn unix_cancel() {
+    transport_unix_mod::cancel::main();
+    transport_unix_mod::cancel::peer_closed();
+}
+
+#[test]
+#[timeout(60000)]
+fn unix_spawn() {
+    transport_unix_mod::spawn::main();
+    transport_unix_mod::spawn::not_a_socket();
+    transport_unix_mod::spawn::timeout();
+}
+
+// The role spawned by `unix_spawn`, which does nothing when run on its own
+#[test]
+#[ignore]
+fn unix_spawned_role() {
+    transport_unix_mod::spawn::spawned_role();
+}
@@ -0,0 +1,101 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::unix::{
+    close::close_unix, fork::fork_unix, recv::recv_unix, send::send_unix,
+};
+use mpstthree::{choose_unix, offer_unix};
+
+use std::error::Error;
+use std::fs;
+use std::io::{Read, Write};
+use std::net::Shutdown;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::thread::spawn;
+
+type Data = (i32, [u8; 128]);
+
+/////////////////////////
+
+// Types
+// A
+enum BinaryA {
+    More(Recv<Data, Send<Data, RecursA>>),
+    Done(End),
+}
+
+type RecursA = Recv<([u8; 128], BinaryA), End>;
+
+// B
+type RecursB = <RecursA as Session>::Dual;
+
+// Functions
+// A
+fn binary_a_to_b(s: RecursA, stream: UnixStream) -> Result<(), Box<dyn Error>> {
+    offer_unix!(s, {
+        BinaryA::Done(s) => {
+            close_unix(s, stream, false)
+        },
+        BinaryA::More(s) => {
+            let (x, s, data, _r, stream) = recv_unix(s, stream, false)?;
+            let (s, stream) = send_unix(x + 1, &data, s, stream, false)?;
+            binary_a_to_b(s, stream)
+        },
+    })
+}
+
+// B
+fn binary_b_to_a(s: RecursB, stream: UnixStream, index: i32) -> Result<(), Box<dyn Error>> {
+    if index >= LOOPS {
+        let s = choose_unix!(BinaryA::Done, s, [0_u8; 128]);
+        return close_unix(s, stream, true);
+    }
+
+    let s = choose_unix!(BinaryA::More, s, [0_u8; 128]);
+    let (s, stream) = send_unix(index, &[index as u8; 128], s, stream, true)?;
+    let (x, s, data, r, stream) = recv_unix(s, stream, true)?;
+
+    assert_eq!(x, index + 1);
+    assert_eq!(r, 128);
+    assert_eq!(data, [index as u8; 128]);
+
+    binary_b_to_a(s, stream, index + 1)
+}
+
+static LOOPS: i32 = 5;
+
+/////////////////////////
+
+// Echo everything until B closes the stream
+fn echo_server(listener: UnixListener) -> Result<usize, Box<dyn Error>> {
+    let (mut stream, _addr) = listener.accept()?;
+    let mut data = [0_u8; 128];
+    let mut echoed = 0;
+    loop {
+        match stream.read(&mut data)? {
+            0 => break,
+            size => {
+                stream.write_all(&data[0..size])?;
+                echoed += size;
+            }
+        }
+    }
+    stream.shutdown(Shutdown::Both).unwrap_or(());
+    Ok(echoed)
+}
+
+/////////////////////////
+
+pub fn main() {
+    let path = super::socket_path("binary");
+    fs::remove_file(&path).unwrap_or(());
+    let listener = UnixListener::bind(&path).unwrap();
+    let server = spawn(move || echo_server(listener).unwrap());
+
+    let (thread, s, stream) = fork_unix(binary_a_to_b, &path).unwrap();
+
+    assert!(binary_b_to_a(s, stream, 0).is_ok());
+    assert!(thread.join().is_ok());
+    // A payload frame for each loop, each with a header of 10 bytes
+    assert_eq!(server.join().unwrap(), (10 + 128) * LOOPS as usize);
+
+    fs::remove_file(&path).unwrap();
+}
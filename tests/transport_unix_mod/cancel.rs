@@ -0,0 +1,50 @@
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::unix::{cancel::cancel_unix, recv::recv_unix, send::send_unix};
+
+use std::error::Error;
+use std::io::Read;
+use std::os::unix::net::UnixStream;
+use std::thread::spawn;
+
+type Data = (i32, [u8; 128]);
+
+type BinaryA = Recv<Data, Send<Data, End>>;
+type BinaryB = <BinaryA as Session>::Dual;
+
+// A cancels the session after receiving the first message
+fn binary_a(s: BinaryA, stream: UnixStream) -> Result<(), Box<dyn Error>> {
+    let (_x, s, _data, r, stream) = recv_unix(s, stream, true)?;
+    assert_eq!(r, 128);
+    cancel_unix(s, stream);
+    Ok(())
+}
+
+fn binary_b(s: BinaryB, stream: UnixStream) -> Result<(), Box<dyn Error>> {
+    let (s, stream) = send_unix(1, &[1_u8; 128], s, stream, true)?;
+    let (_x, _s, _data, _r, _stream) = recv_unix(s, stream, true)?;
+    Ok(())
+}
+
+pub fn main() {
+    let (stream_a, stream_b) = UnixStream::pair().unwrap();
+    let mut copy_stream_b = stream_b.try_clone().unwrap();
+    let (s_a, s_b) = BinaryA::new();
+
+    let thread_a = spawn(move || binary_a(s_a, stream_a).unwrap());
+
+    // The session and the stream of B both see the cancellation
+    assert!(binary_b(s_b, stream_b).is_err());
+    assert!(thread_a.join().is_ok());
+
+    let mut data = [0_u8; 128];
+    assert_eq!(copy_stream_b.read(&mut data).unwrap(), 0);
+}
+
+// Cancelling after the peer has closed the stream does not panic
+pub fn peer_closed() {
+    let (stream_a, stream_b) = UnixStream::pair().unwrap();
+    let (s_a, _s_b) = BinaryA::new();
+
+    drop(stream_b);
+    cancel_unix(s_a, stream_a);
+}
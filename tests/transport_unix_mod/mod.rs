@@ -0,0 +1,11 @@
+pub mod binary;
+pub mod cancel;
+pub mod spawn;
+
+use std::path::PathBuf;
+use std::process;
+
+// A socket path unique to the test
+pub fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mpst_{}_{}.sock", process::id(), name))
+}
@@ -0,0 +1,116 @@
+// The role B spawns the process of the role A,
+// which is this test binary running `unix_spawned_role`.
+
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::unix::fork::{connect_spawner, fork_unix_spawn, SOCKET_PATH_ENV};
+use mpstthree::transport::unix::{close::close_unix, recv::recv_unix, send::send_unix};
+use mpstthree::transport::wire::frame::{read_frame, write_frame};
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::os::unix::net::UnixStream;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+type Data = (i32, [u8; 128]);
+
+type BinaryA = Recv<Data, Send<Data, End>>;
+type BinaryB = <BinaryA as Session>::Dual;
+
+// The session of A, kept in the process of B
+fn binary_a(s: BinaryA, stream: UnixStream) -> Result<(), Box<dyn Error>> {
+    let (x, s, data, _r, stream) = recv_unix(s, stream, false)?;
+    let (s, stream) = send_unix(x * 2, &data, s, stream, false)?;
+    close_unix(s, stream, false)
+}
+
+fn binary_b(s: BinaryB, stream: UnixStream) -> Result<(), Box<dyn Error>> {
+    let (s, stream) = send_unix(21, &[21_u8; 128], s, stream, true)?;
+    let (x, s, data, r, stream) = recv_unix(s, stream, true)?;
+
+    assert_eq!(x, 42);
+    assert_eq!(r, 128);
+    // The spawned process doubles each byte
+    assert_eq!(data, [42_u8; 128]);
+
+    close_unix(s, stream, true)
+}
+
+pub fn spawned_role() {
+    if env::var_os(SOCKET_PATH_ENV).is_none() {
+        return;
+    }
+
+    let mut stream = connect_spawner().unwrap();
+    let mut frame = read_frame(&mut stream).unwrap();
+    for byte in frame.body.iter_mut() {
+        *byte *= 2;
+    }
+    write_frame(&mut stream, &frame).unwrap();
+}
+
+pub fn main() {
+    let path = super::socket_path("spawn");
+
+    let mut command = Command::new(env::current_exe().unwrap());
+    command
+        .args(["unix_spawned_role", "--exact", "--ignored"])
+        .stdout(Stdio::null());
+
+    let (thread, s, stream, mut child) =
+        fork_unix_spawn(binary_a, &path, &mut command, Duration::from_secs(30)).unwrap();
+
+    assert!(binary_b(s, stream).is_ok());
+    assert!(thread.join().is_ok());
+    assert!(child.wait().unwrap().success());
+
+    // The socket is removed once the role is connected
+    assert!(!path.exists());
+}
+
+// A file which is not a socket is left untouched
+pub fn not_a_socket() {
+    let path = super::socket_path("not_a_socket");
+    fs::write(&path, "data").unwrap();
+
+    let mut command = Command::new("true");
+    let error =
+        fork_unix_spawn::<BinaryA, _, _>(binary_a, &path, &mut command, Duration::from_secs(30))
+            .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        format!("{} is not a socket", path.display())
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "data");
+
+    fs::remove_file(&path).unwrap();
+}
+
+// A process which does not connect in time is killed
+pub fn timeout() {
+    let path = super::socket_path("timeout");
+    let pid_path = super::socket_path("timeout_pid");
+
+    let mut command = Command::new("sh");
+    command
+        .args(["-c", "echo $$ > \"$0\"; exec sleep 30"])
+        .arg(&pid_path);
+
+    let error =
+        fork_unix_spawn::<BinaryA, _, _>(binary_a, &path, &mut command, Duration::from_millis(500))
+            .unwrap_err();
+
+    assert_eq!(error.to_string(), "The role did not connect within 500 ms");
+    assert!(!path.exists());
+
+    let pid = fs::read_to_string(&pid_path).unwrap();
+    fs::remove_file(&pid_path).unwrap();
+    let alive = Command::new("kill")
+        .args(["-0", pid.trim()])
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(!alive.success());
+}
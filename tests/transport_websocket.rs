@@ -0,0 +1,27 @@
+#![allow(clippy::type_complexity)]
+
+mod transport_websocket_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn websocket_binary() {
+    transport_websocket_mod::binary::main();
+}
+
+#[test]
+#[timeout(60000)]
+fn websocket_o_auth() {
+    transport_websocket_mod::o_auth::accepted();
+    transport_websocket_mod::o_auth::refused();
+    transport_websocket_mod::o_auth::without_websocket();
+    transport_websocket_mod::o_auth::wrong_label();
+}
+
+#[test]
+#[timeout(60000)]
+fn websocket_failures() {
+    transport_websocket_mod::failures::cancelled();
+    transport_websocket_mod::failures::closed_early();
+}
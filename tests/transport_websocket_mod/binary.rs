@@ -0,0 +1,45 @@
+// The client increments a counter held by the server,
+// over a WebSocket.
+
+use mpstthree::binary::close::close;
+use mpstthree::binary::fork::fork_with_thread_id;
+use mpstthree::binary::recv::recv;
+use mpstthree::binary::send::send;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::websocket::close::close_ws;
+use mpstthree::transport::websocket::recv::recv_ws;
+use mpstthree::transport::websocket::send::send_ws;
+use mpstthree::transport::websocket::stream::WsStream;
+
+use std::error::Error;
+
+type ClientMPST = Send<i32, Recv<i32, Send<i32, Recv<i32, End>>>>;
+type ServerMPST = <ClientMPST as Session>::Dual;
+
+fn client(s: ClientMPST, mut ws: WsStream) -> Result<(), Box<dyn Error>> {
+    let s = send_ws(1, s, send, &mut ws, true)?;
+    let (x, s) = recv_ws(s, recv, &mut ws, true)?;
+    let s = send_ws(x, s, send, &mut ws, true)?;
+    let (x, s) = recv_ws(s, recv, &mut ws, true)?;
+
+    assert_eq!(x, 3);
+
+    close_ws(s, close, ws, true)
+}
+
+fn server(s: ServerMPST, mut ws: WsStream) -> Result<(), Box<dyn Error>> {
+    let (x, s) = recv_ws(s, recv, &mut ws, true)?;
+    let s = send_ws(x + 1, s, send, &mut ws, true)?;
+    let (x, s) = recv_ws(s, recv, &mut ws, true)?;
+    let s = send_ws(x + 1, s, send, &mut ws, true)?;
+    close_ws(s, close, ws, true)
+}
+
+pub fn main() {
+    let (ws_server, ws_client) = super::pair();
+
+    let (thread, s) = fork_with_thread_id(move |s: ClientMPST| client(s, ws_client));
+
+    assert!(server(s, ws_server).is_ok());
+    assert!(thread.join().is_ok());
+}
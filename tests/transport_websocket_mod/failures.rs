@@ -0,0 +1,64 @@
+// A connection lost, or closed, before the end of the session
+// cancels the session.
+
+use mpstthree::binary::close::close;
+use mpstthree::binary::fork::fork_with_thread_id;
+use mpstthree::binary::recv::recv;
+use mpstthree::binary::send::send;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::transport::websocket::cancel::cancel_ws;
+use mpstthree::transport::websocket::recv::recv_ws;
+use mpstthree::transport::websocket::send::send_ws;
+use mpstthree::transport::websocket::stream::WsStream;
+
+use crossbeam_channel::{bounded, Sender};
+use std::error::Error;
+
+type ClientMPST = Send<i32, Recv<i32, End>>;
+type ServerMPST = <ClientMPST as Session>::Dual;
+
+fn client(s: ClientMPST, mut ws: WsStream, errors: Sender<String>) -> Result<(), Box<dyn Error>> {
+    let s = send_ws(1, s, send, &mut ws, true)?;
+    match recv_ws(s, recv, &mut ws, true) {
+        Ok((_x, s)) => close(s),
+        Err(e) => {
+            errors.send(e.to_string())?;
+            Err(e)
+        }
+    }
+}
+
+// The error of the client when the server ends the session with `end`
+fn client_error(end: fn(End, WsStream)) -> String {
+    let (mut ws_server, ws_client) = super::pair();
+    let (sender, receiver) = bounded(1);
+
+    let (thread, s) = fork_with_thread_id(move |s: ClientMPST| client(s, ws_client, sender));
+
+    let s: ServerMPST = s;
+    let (x, s) = recv_ws(s, recv, &mut ws_server, true).unwrap();
+    assert_eq!(x, 1);
+
+    // The server does not answer
+    let (here, there) = End::new();
+    drop(there);
+    drop(s);
+    end(here, ws_server);
+
+    assert!(thread.join().is_err());
+    receiver.recv().unwrap()
+}
+
+pub fn cancelled() {
+    let error = client_error(cancel_ws);
+    assert!(error.contains("closed abnormally"), "{}", error);
+}
+
+pub fn closed_early() {
+    let error = client_error(|s, mut ws| {
+        drop(s);
+        ws.close(None).unwrap();
+        ws.flush().unwrap_or(());
+    });
+    assert!(error.contains("closed before the end"), "{}", error);
+}
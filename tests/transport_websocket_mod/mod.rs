@@ -0,0 +1,26 @@
+pub mod binary;
+pub mod failures;
+pub mod o_auth;
+
+use mpstthree::transport::websocket::stream::{accept_ws, connect_ws, WsStream};
+use std::net::TcpListener;
+use std::thread::{spawn, JoinHandle};
+
+// A WebSocket server on localhost accepting a single client,
+// and the URL of the server
+pub fn server() -> (JoinHandle<WsStream>, String) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}/role", listener.local_addr().unwrap());
+    let thread = spawn(move || {
+        let (stream, _addr) = listener.accept().unwrap();
+        accept_ws(stream).unwrap()
+    });
+    (thread, url)
+}
+
+// Both sides of a WebSocket on localhost
+pub fn pair() -> (WsStream, WsStream) {
+    let (thread, url) = server();
+    let client = connect_ws(&url).unwrap();
+    (thread.join().unwrap(), client)
+}
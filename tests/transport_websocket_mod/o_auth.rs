@@ -0,0 +1,216 @@
+// The authenticator A serves the client C over a WebSocket,
+// as if C was running in a browser, and tells
+// both C and the server S whether C is logged in.
+
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
+use mpstthree::role::broadcast::RoleBroadcast;
+use mpstthree::role::end::RoleEnd;
+use mpstthree::transport::websocket::choose::choose_ws;
+use mpstthree::transport::websocket::close::close_ws;
+use mpstthree::transport::websocket::recv::recv_ws;
+use mpstthree::transport::websocket::send::send_ws;
+use mpstthree::transport::websocket::stream::WsStream;
+use mpstthree::{
+    bundle_struct_fork_close_multi, create_fn_choose_mpst_multi_to_all_bundle,
+    create_multiple_normal_role_short, create_recv_mpst_session_bundle,
+    create_send_mpst_session_bundle, offer_mpst, offer_ws_mpst,
+};
+
+use crossbeam_channel::bounded;
+use std::error::Error;
+
+static PASSWORD: i32 = 1234;
+static TOKEN: i32 = 42;
+
+// Create the new MeshedChannels for three participants and the close and fork functions
+bundle_struct_fork_close_multi!(close_mpst_multi, fork_mpst, MeshedChannelsThree, 3);
+
+// Create new roles
+create_multiple_normal_role_short!(A, C, S);
+
+// Create new send functions
+// A
+create_send_mpst_session_bundle!(
+    send_mpst_a_to_c, RoleC, 1 | =>
+    RoleA, MeshedChannelsThree, 3
+);
+// C
+create_send_mpst_session_bundle!(
+    send_mpst_c_to_a, RoleA, 1 | =>
+    RoleC, MeshedChannelsThree, 3
+);
+
+// Create new recv functions and related types
+// A
+create_recv_mpst_session_bundle!(
+    recv_mpst_a_from_c, RoleC, 1 | =>
+    RoleA, MeshedChannelsThree, 3
+);
+// C
+create_recv_mpst_session_bundle!(
+    recv_mpst_c_from_a, RoleA, 1 | =>
+    RoleC, MeshedChannelsThree, 3
+);
+// S
+create_recv_mpst_session_bundle!(
+    recv_mpst_s_from_a, RoleA, 1 | =>
+    RoleS, MeshedChannelsThree, 3
+);
+
+// Names
+type NameA = RoleA<RoleEnd>;
+type NameC = RoleC<RoleEnd>;
+type NameS = RoleS<RoleEnd>;
+
+// Types
+// A
+type Choose0fromAtoC = Send<Branching0fromAtoC, End>;
+type Choose0fromAtoS = Send<Branching0fromAtoS, End>;
+
+type EndpointAAuth = MeshedChannelsThree<Send<i32, End>, End, RoleC<RoleEnd>, NameA>;
+type EndpointADone = MeshedChannelsThree<End, End, RoleEnd, NameA>;
+
+// C
+enum Branching0fromAtoC {
+    Auth(MeshedChannelsThree<Recv<i32, End>, End, RoleA<RoleEnd>, NameC>),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameC>),
+}
+
+// S
+enum Branching0fromAtoS {
+    Auth(MeshedChannelsThree<End, End, RoleEnd, NameS>),
+    Done(MeshedChannelsThree<End, End, RoleEnd, NameS>),
+}
+
+// Creating the MP sessions
+type EndpointA =
+    MeshedChannelsThree<Recv<i32, Choose0fromAtoC>, Choose0fromAtoS, RoleC<RoleBroadcast>, NameA>;
+type EndpointC = MeshedChannelsThree<
+    Send<i32, <Choose0fromAtoC as Session>::Dual>,
+    End,
+    RoleA<RoleA<RoleEnd>>,
+    NameC,
+>;
+type EndpointS =
+    MeshedChannelsThree<<Choose0fromAtoS as Session>::Dual, End, RoleA<RoleEnd>, NameS>;
+
+create_fn_choose_mpst_multi_to_all_bundle!(
+    auth_from_a_to_all, done_from_a_to_all, =>
+    Auth, Done, =>
+    EndpointAAuth, EndpointADone, =>
+    Branching0fromAtoC, Branching0fromAtoS, =>
+    RoleC, RoleS, =>
+    RoleA, MeshedChannelsThree, 1
+);
+
+// Functions
+fn endpoint_a(s: EndpointA, mut ws: WsStream, websocket: bool) -> Result<(), Box<dyn Error>> {
+    let (pwd, s) = recv_ws(s, recv_mpst_a_from_c, &mut ws, websocket)?;
+
+    if pwd == PASSWORD {
+        let s = choose_ws(s, auth_from_a_to_all, "Auth", &mut ws, websocket)?;
+        let s = send_ws(TOKEN, s, send_mpst_a_to_c, &mut ws, websocket)?;
+        close_ws(s, close_mpst_multi, ws, websocket)
+    } else {
+        let s = choose_ws(s, done_from_a_to_all, "Done", &mut ws, websocket)?;
+        close_ws(s, close_mpst_multi, ws, websocket)
+    }
+}
+
+fn endpoint_c(
+    s: EndpointC,
+    mut ws: WsStream,
+    websocket: bool,
+    pwd: i32,
+) -> Result<(), Box<dyn Error>> {
+    let s = send_ws(pwd, s, send_mpst_c_to_a, &mut ws, websocket)?;
+
+    offer_ws_mpst!(s, recv_mpst_c_from_a, &mut ws, websocket, {
+        Branching0fromAtoC::Auth(s) => {
+            let (token, s) = recv_ws(s, recv_mpst_c_from_a, &mut ws, websocket)?;
+            assert_eq!(pwd, PASSWORD);
+            assert_eq!(token, TOKEN);
+            close_ws(s, close_mpst_multi, ws, websocket)
+        },
+        Branching0fromAtoC::Done(s) => {
+            assert_ne!(pwd, PASSWORD);
+            close_ws(s, close_mpst_multi, ws, websocket)
+        },
+    })
+}
+
+fn endpoint_s(s: EndpointS) -> Result<(), Box<dyn Error>> {
+    offer_mpst!(s, recv_mpst_s_from_a, {
+        Branching0fromAtoS::Auth(s) => {
+            close_mpst_multi(s)
+        },
+        Branching0fromAtoS::Done(s) => {
+            close_mpst_multi(s)
+        },
+    })
+}
+
+/////////////////////////
+
+fn run(websocket: bool, pwd: i32) {
+    let (ws_a, ws_c) = super::pair();
+
+    let (thread_a, thread_c, thread_s) = fork_mpst(
+        move |s| endpoint_a(s, ws_a, websocket),
+        move |s| endpoint_c(s, ws_c, websocket, pwd),
+        endpoint_s,
+    );
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_c.join().is_ok());
+    assert!(thread_s.join().is_ok());
+}
+
+pub fn accepted() {
+    run(true, PASSWORD);
+}
+
+pub fn refused() {
+    run(true, PASSWORD + 1);
+}
+
+pub fn without_websocket() {
+    run(false, PASSWORD);
+}
+
+// A sends the label Done while choosing Auth, then stops
+fn endpoint_a_lying(s: EndpointA, mut ws: WsStream) -> Result<(), Box<dyn Error>> {
+    let (_pwd, s) = recv_ws(s, recv_mpst_a_from_c, &mut ws, true)?;
+    let s = choose_ws(s, auth_from_a_to_all, "Done", &mut ws, true)?;
+    drop(s);
+    Ok(())
+}
+
+pub fn wrong_label() {
+    let (ws_a, ws_c) = super::pair();
+    let (sender, receiver) = bounded(1);
+
+    let (thread_a, thread_c, thread_s) = fork_mpst(
+        move |s| endpoint_a_lying(s, ws_a),
+        move |s| match endpoint_c(s, ws_c, true, PASSWORD) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                sender.send(e.to_string())?;
+                Err(e)
+            }
+        },
+        endpoint_s,
+    );
+
+    assert!(thread_a.join().is_ok());
+    assert!(thread_c.join().is_err());
+    // S cannot close the session with A
+    assert!(thread_s.join().is_err());
+
+    let error = receiver.recv().unwrap();
+    assert!(
+        error.contains("The label Done does not match the branch Auth"),
+        "{}",
+        error
+    );
+}
@@ -1,6 +1,7 @@
 use mpstthree::binary::close::close;
 use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session::Session};
 use mpstthree::transport::udp::{fork::fork_udp, recv::recv_udp, send::send_udp};
+use mpstthree::transport::wire::frame::{encode, Frame};
 use mpstthree::{choose_udp, offer_udp};
 
 use std::error::Error;
@@ -112,9 +113,10 @@ fn udp_server() -> Result<(), Box<dyn Error>> {
     let socket = UdpSocket::bind("127.0.0.1:8080")?;
 
     for _ in 0..LOOPS {
-        let mut buf = [0; 128];
+        let mut buf = [0; 1024];
         let _ = socket.recv_from(&mut buf)?;
-        let _ = socket.send_to(&[0, 1, 2], "127.0.0.1:8081")?;
+        let frame = encode(&Frame::payload("", "", vec![0, 1, 2]))?;
+        let _ = socket.send_to(&frame, "127.0.0.1:8081")?;
     }
 
     // close the socket server
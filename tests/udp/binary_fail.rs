@@ -3,6 +3,7 @@ use mpstthree::binary::struct_trait::{end::End, recv::Recv, send::Send, session:
 use mpstthree::transport::udp::{
     cancel::cancel_udp, fork::fork_udp, recv::recv_udp, send::send_udp,
 };
+use mpstthree::transport::wire::frame::{encode, Frame};
 use mpstthree::{choose_udp, offer_udp};
 
 use std::error::Error;
@@ -124,9 +125,10 @@ fn udp_server() -> Result<(), Box<dyn Error>> {
     let _ = socket.set_read_timeout(Some(Duration::new(0, 500)))?;
 
     for _ in 0..LOOPS {
-        let mut buf = [0; 128];
+        let mut buf = [0; 1024];
         let _ = socket.recv_from(&mut buf)?;
-        let _ = socket.send_to(&[0, 1, 2], "127.0.0.1:8081")?;
+        let frame = encode(&Frame::payload("", "", vec![0, 1, 2]))?;
+        let _ = socket.send_to(&frame, "127.0.0.1:8081")?;
     }
 
     // close the socket server
@@ -0,0 +1,25 @@
+mod wire_mod;
+
+use ntest::timeout;
+
+#[test]
+#[timeout(60000)]
+fn wire_frames() {
+    wire_mod::frames::encoded();
+    wire_mod::frames::streamed();
+    wire_mod::frames::invalid();
+}
+
+#[test]
+#[timeout(60000)]
+fn wire_payloads() {
+    wire_mod::payloads::encoded();
+    wire_mod::payloads::invalid();
+}
+
+#[test]
+#[timeout(60000)]
+fn wire_choices() {
+    wire_mod::choices::streamed();
+    wire_mod::choices::mismatched();
+}
@@ -0,0 +1,96 @@
+use mpstthree::binary::cancel::cancel;
+use mpstthree::binary::struct_trait::{end::End, recv::Recv, session::Session};
+use mpstthree::{choose_tcp, offer_tcp};
+
+use std::error::Error;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+type Data = [u8; 128];
+
+enum BinaryA {
+    More(End),
+    Done(End),
+}
+
+type OfferA = Recv<(Data, BinaryA), End>;
+
+type ChooseA = <OfferA as Session>::Dual;
+
+// A connected pair of TCP streams
+fn pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("localhost:0").unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (peer, _addr) = listener.accept().unwrap();
+    (stream, peer)
+}
+
+// The branch offered by `s`, whose label is read from `stream`
+fn offer(s: OfferA, stream: &mut TcpStream) -> Result<&'static str, Box<dyn Error>> {
+    offer_tcp!(s, stream, true, {
+        BinaryA::More(s) => {
+            cancel(s);
+            Ok("BinaryA::More")
+        },
+        BinaryA::Done(s) => {
+            cancel(s);
+            Ok("BinaryA::Done")
+        },
+    })
+}
+
+// Choose with `choose` over `stream`, then check the bytes received by `peer`
+// and that the same bytes, written by another implementation, are offered
+fn check<F>(branch: &str, bytes: &[u8], choose: F)
+where
+    F: FnOnce(ChooseA, &mut TcpStream) -> Result<End, Box<dyn Error>>,
+{
+    let (mut stream, mut peer) = pair();
+
+    let (s, dual) = OfferA::new();
+    cancel(choose(dual, &mut stream).unwrap());
+
+    let mut written = vec![0_u8; bytes.len()];
+    peer.read_exact(&mut written).unwrap();
+    assert_eq!(written, bytes);
+
+    peer.write_all(bytes).unwrap();
+    assert_eq!(offer(s, &mut stream).unwrap(), branch);
+}
+
+pub fn streamed() {
+    let vectors = super::vectors("choice", 2);
+    assert_eq!(vectors.len(), 2);
+    for fields in vectors {
+        let bytes = super::bytes(fields[1]);
+        match fields[0] {
+            "BinaryA::More" => check(fields[0], &bytes, |s, stream| {
+                choose_tcp!(BinaryA::More, s, [0_u8; 128], stream, true)
+            }),
+            "BinaryA::Done" => check(fields[0], &bytes, |s, stream| {
+                choose_tcp!(BinaryA::Done, s, [0_u8; 128], stream, true)
+            }),
+            branch => panic!("Unknown branch {}", branch),
+        }
+    }
+}
+
+pub fn mismatched() {
+    let (mut stream, mut peer) = pair();
+
+    // The session chooses More, but the stream carries Done
+    let (s, dual) = OfferA::new();
+    let end: End = choose_tcp!(BinaryA::More, dual, [0_u8; 128]);
+    cancel(end);
+
+    let done = super::vectors("choice", 2)
+        .into_iter()
+        .find(|fields| fields[0] == "BinaryA::Done")
+        .unwrap();
+    peer.write_all(&super::bytes(done[1])).unwrap();
+
+    assert_eq!(
+        offer(s, &mut stream).unwrap_err().to_string(),
+        "The label Done does not match the branch More"
+    );
+}
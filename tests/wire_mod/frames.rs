@@ -0,0 +1,93 @@
+use mpstthree::transport::wire::frame::{decode, encode, read_frame, write_frame, Frame};
+
+use std::io::ErrorKind;
+
+fn frames() -> Vec<(Frame, Vec<u8>)> {
+    let vectors = super::vectors("frame", 5);
+    assert_eq!(vectors.len(), 7);
+    vectors
+        .iter()
+        .map(|fields| {
+            let (from, to, body) = (super::text(fields[1]), super::text(fields[2]), fields[3]);
+            let frame = match fields[0] {
+                "payload" => Frame::payload(from, to, super::bytes(body)),
+                "label" => Frame::label(from, to, &String::from_utf8(super::bytes(body)).unwrap()),
+                kind => panic!("Unknown kind {}", kind),
+            };
+            (frame, super::bytes(fields[4]))
+        })
+        .collect()
+}
+
+pub fn encoded() {
+    for (frame, bytes) in frames() {
+        assert_eq!(encode(&frame).unwrap(), bytes);
+        assert_eq!(decode(&bytes).unwrap(), (frame.clone(), bytes.len()));
+
+        // The trailing bytes belong to the next frame
+        let mut longer = bytes.clone();
+        longer.extend_from_slice(b"MP");
+        assert_eq!(decode(&longer).unwrap(), (frame, bytes.len()));
+    }
+}
+
+pub fn streamed() {
+    let frames = frames();
+
+    let mut stream = Vec::new();
+    for (frame, _bytes) in &frames {
+        write_frame(&mut stream, frame).unwrap();
+    }
+    assert_eq!(
+        stream,
+        frames
+            .iter()
+            .flat_map(|(_frame, bytes)| bytes.clone())
+            .collect::<Vec<_>>()
+    );
+
+    let mut reader = &stream[..];
+    for (frame, _bytes) in frames {
+        assert_eq!(read_frame(&mut reader).unwrap(), frame);
+    }
+
+    // The end of the stream is an io error, as for a closed connection
+    let error = read_frame(&mut reader).unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<std::io::Error>().unwrap().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+pub fn invalid() {
+    let vectors = super::vectors("invalid", 2);
+    assert_eq!(vectors.len(), 8);
+    for fields in vectors {
+        let error = decode(&super::bytes(fields[0])).unwrap_err().to_string();
+        assert!(
+            error.starts_with(fields[1]),
+            "{} does not start with {}",
+            error,
+            fields[1]
+        );
+    }
+
+    // The frames which cannot be encoded
+    let role = "R".repeat(256);
+    assert!(encode(&Frame::label(&role, "B", "More")).is_err());
+    assert!(encode(&Frame::label("A", &role, "More")).is_err());
+    assert_eq!(
+        Frame::payload("A", "B", Vec::new())
+            .as_label()
+            .unwrap_err()
+            .to_string(),
+        "Expected a label, received a payload"
+    );
+    assert_eq!(
+        Frame::label("A", "B", "More")
+            .as_payload()
+            .unwrap_err()
+            .to_string(),
+        "Expected a payload, received the label More"
+    );
+}
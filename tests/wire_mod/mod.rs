@@ -0,0 +1,36 @@
+pub mod choices;
+pub mod frames;
+pub mod payloads;
+
+// The conformance vectors, shared with the implementations in other languages
+static VECTORS: &str = include_str!("vectors.txt");
+
+// The fields of the vectors of `kind`, the last field holding the rest of the line
+pub fn vectors(kind: &str, fields: usize) -> Vec<Vec<&'static str>> {
+    VECTORS
+        .lines()
+        .filter_map(|line| line.strip_prefix(kind)?.strip_prefix(' '))
+        .map(|line| {
+            let fields = line.splitn(fields, ' ').collect::<Vec<_>>();
+            assert!(!fields.is_empty());
+            fields
+        })
+        .collect()
+}
+
+// An empty field
+pub fn text(field: &str) -> &str {
+    match field {
+        "-" => "",
+        field => field,
+    }
+}
+
+pub fn bytes(field: &str) -> Vec<u8> {
+    let field = text(field);
+    assert_eq!(field.len() % 2, 0, "Odd number of digits in {}", field);
+    (0..field.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&field[i..i + 2], 16).unwrap())
+        .collect()
+}
@@ -0,0 +1,56 @@
+use mpstthree::transport::wire::payload::Payload;
+
+use std::fmt::Debug;
+use std::str::FromStr;
+
+// Check the encoding of `value`, parsed from its text
+fn check<T>(value: &str, body: &[u8])
+where
+    T: Payload + FromStr + PartialEq + Debug,
+    <T as FromStr>::Err: Debug,
+{
+    let value = value.parse::<T>().unwrap();
+    assert_eq!(value.encode().unwrap(), body);
+    assert_eq!(T::decode(body).unwrap(), value);
+}
+
+pub fn encoded() {
+    let vectors = super::vectors("payload", 3);
+    assert_eq!(vectors.len(), 13);
+    for fields in vectors {
+        let (value, body) = (super::text(fields[1]), super::bytes(fields[2]));
+        match fields[0] {
+            "bool" => check::<bool>(value, &body),
+            "i8" => check::<i8>(value, &body),
+            "i32" => check::<i32>(value, &body),
+            "u8" => check::<u8>(value, &body),
+            "u64" => check::<u64>(value, &body),
+            "usize" => check::<usize>(value, &body),
+            "f32" => check::<f32>(value, &body),
+            "f64" => check::<f64>(value, &body),
+            "String" => check::<String>(value, &body),
+            "()" => {
+                assert_eq!(().encode().unwrap(), body);
+                <()>::decode(&body).unwrap();
+            }
+            "Vec<u8>" => {
+                let value = super::bytes(value);
+                assert_eq!(value.encode().unwrap(), body);
+                assert_eq!(Vec::<u8>::decode(&body).unwrap(), value);
+            }
+            kind => panic!("Unknown type {}", kind),
+        }
+    }
+}
+
+pub fn invalid() {
+    assert!(bool::decode(b"yes").is_err());
+    assert!(u8::decode(b"256").is_err());
+    assert!(i32::decode(b"-").is_err());
+    assert!(String::decode(&[0xff]).is_err());
+    assert_eq!(
+        <[u8; 128]>::decode(&[0; 127]).unwrap_err().to_string(),
+        "Expected 128 bytes, received 127"
+    );
+    assert_eq!(<[u8; 128]>::decode(&[7; 128]).unwrap(), [7; 128]);
+}